@@ -194,6 +194,118 @@ fn main() -> Result<()> {
         )
     }
 
+    if command_line_args.elder_size.is_some() {
+        assert_eq!(command_line_args.elder_size, config.elder_size)
+    } else {
+        assert_eq!(file_config.elder_size, config.elder_size)
+    }
+
+    if command_line_args.recommended_section_size.is_some() {
+        assert_eq!(
+            command_line_args.recommended_section_size,
+            config.recommended_section_size
+        )
+    } else {
+        assert_eq!(
+            file_config.recommended_section_size,
+            config.recommended_section_size
+        )
+    }
+
+    if command_line_args.reward_policy.is_some() {
+        assert_eq!(command_line_args.reward_policy, config.reward_policy)
+    } else {
+        assert_eq!(file_config.reward_policy, config.reward_policy)
+    }
+
+    if command_line_args.faucet.is_some() {
+        assert_eq!(command_line_args.faucet, config.faucet)
+    } else {
+        assert_eq!(file_config.faucet, config.faucet)
+    }
+
+    assert_eq!(
+        config.trace_messages,
+        file_config.trace_messages || command_line_args.trace_messages
+    );
+
+    assert_eq!(
+        config.reward_statement,
+        file_config.reward_statement || command_line_args.reward_statement
+    );
+
+    if command_line_args.max_elder_dkg_failures.is_some() {
+        assert_eq!(
+            command_line_args.max_elder_dkg_failures,
+            config.max_elder_dkg_failures
+        )
+    } else {
+        assert_eq!(
+            file_config.max_elder_dkg_failures,
+            config.max_elder_dkg_failures
+        )
+    }
+
+    if command_line_args.max_elder_invalid_shares.is_some() {
+        assert_eq!(
+            command_line_args.max_elder_invalid_shares,
+            config.max_elder_invalid_shares
+        )
+    } else {
+        assert_eq!(
+            file_config.max_elder_invalid_shares,
+            config.max_elder_invalid_shares
+        )
+    }
+
+    if command_line_args.max_elder_missed_signatures.is_some() {
+        assert_eq!(
+            command_line_args.max_elder_missed_signatures,
+            config.max_elder_missed_signatures
+        )
+    } else {
+        assert_eq!(
+            file_config.max_elder_missed_signatures,
+            config.max_elder_missed_signatures
+        )
+    }
+
+    if command_line_args.max_elder_late_signatures.is_some() {
+        assert_eq!(
+            command_line_args.max_elder_late_signatures,
+            config.max_elder_late_signatures
+        )
+    } else {
+        assert_eq!(
+            file_config.max_elder_late_signatures,
+            config.max_elder_late_signatures
+        )
+    }
+
+    if command_line_args.elder_late_signature_msec.is_some() {
+        assert_eq!(
+            command_line_args.elder_late_signature_msec,
+            config.elder_late_signature_msec
+        )
+    } else {
+        assert_eq!(
+            file_config.elder_late_signature_msec,
+            config.elder_late_signature_msec
+        )
+    }
+
+    if command_line_args.elder_signature_timeout_msec.is_some() {
+        assert_eq!(
+            command_line_args.elder_signature_timeout_msec,
+            config.elder_signature_timeout_msec
+        )
+    } else {
+        assert_eq!(
+            file_config.elder_signature_timeout_msec,
+            config.elder_signature_timeout_msec
+        )
+    }
+
     clear_disk_config()?;
 
     Ok(())
@@ -15,8 +15,9 @@ use crate::messaging::client::{ChunkRead, ChunkWrite, DataCmd, DataQuery, Query,
 use crate::types::{Chunk, ChunkAddress, PrivateChunk, PublicChunk, PublicKey};
 use bincode::{deserialize, serialize};
 use log::{info, trace};
-use self_encryption::{DataMap, SelfEncryptor};
+use self_encryption::{DataMap, SelfEncryptionError, SelfEncryptor, Storage};
 use serde::{Deserialize, Serialize};
+use xor_name::{XorName, XOR_NAME_LEN};
 
 #[derive(Serialize, Deserialize)]
 enum DataMapLevel {
@@ -103,7 +104,7 @@ impl Client {
     /// // Let's use an existing client, with a pre-existing balance to be used for write payments.
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(None, None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let data = b"some data".to_vec();
     /// // grab the random head of the blob from the network
     /// let _address = client.store_public_blob(&data).await?;
@@ -111,7 +112,7 @@ impl Client {
     /// # let balance_after_write = client.get_local_balance().await; assert_ne!(initial_balance, balance_after_write); Ok(()) } ); }
     /// ```
     pub async fn store_public_blob(&self, data: &[u8]) -> Result<ChunkAddress, Error> {
-        self.create_new_blob(data, true).await
+        self.create_new_blob(data, true, None).await
     }
 
     /// Store data in private chunks on the network.
@@ -134,7 +135,7 @@ impl Client {
     /// // Let's use an existing client, with a pre-existing balance to be used for write payments.
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(None, None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let data = b"some data".to_vec();
     /// // grab the random head of the blob from the network
     /// let fetched_data = client.store_private_blob(&data).await?;
@@ -143,17 +144,43 @@ impl Client {
     /// # let balance_after_write = client.get_local_balance().await; assert_ne!(initial_balance, balance_after_write); Ok(()) } ); }
     /// ```
     pub async fn store_private_blob(&self, data: &[u8]) -> Result<ChunkAddress, Error> {
-        self.create_new_blob(data, false).await
+        self.create_new_blob(data, false, None).await
     }
 
-    async fn create_new_blob(&self, data: &[u8], public: bool) -> Result<ChunkAddress, Error> {
-        let data_map = self.write_to_network(data, public).await?;
+    /// Store Private Blob which expires
+    ///
+    /// Like `store_private_blob`, but all the chunks of the Blob are deleted from the network
+    /// once `expiry`, in seconds since the Unix epoch, has passed, unless the expiry is extended.
+    pub async fn store_private_blob_with_expiry(
+        &self,
+        data: &[u8],
+        expiry: u64,
+    ) -> Result<ChunkAddress, Error> {
+        self.create_new_blob(data, false, Some(expiry)).await
+    }
 
-        let chunk_content = serialize(&DataMapLevel::Root(data_map))?;
-        let chunk = self.pack(chunk_content, public).await?;
+    async fn create_new_blob(
+        &self,
+        data: &[u8],
+        public: bool,
+        expiry: Option<u64>,
+    ) -> Result<ChunkAddress, Error> {
+        let blob_storage = match expiry {
+            Some(expiry) => BlobStorage::expiring(self.clone(), expiry),
+            None => BlobStorage::new(self.clone(), public),
+        };
+        let owner = if public {
+            None
+        } else {
+            Some(self.public_key())
+        };
+        let (_, chunk, _) = Self::encrypt_blob(data, owner, blob_storage).await?;
         let blob_head = *chunk.address();
 
-        self.store_chunk_on_network(chunk).await?;
+        match expiry {
+            Some(expiry) => self.store_expiring_chunk_on_network(chunk, expiry).await?,
+            None => self.store_chunk_on_network(chunk).await?,
+        }
 
         Ok(blob_head)
     }
@@ -196,6 +223,58 @@ impl Client {
         Ok(())
     }
 
+    // Like `store_chunk_on_network`, for a private chunk which expires.
+    pub(crate) async fn store_expiring_chunk_on_network(
+        &self,
+        chunk: Chunk,
+        expiry: u64,
+    ) -> Result<(), Error> {
+        if !chunk.validate_size() {
+            return Err(Error::NetworkDataError(crate::types::Error::ExceededSize));
+        }
+        let cmd = DataCmd::Blob(ChunkWrite::NewExpiring { chunk, expiry });
+        self.pay_and_send_data_command(cmd).await?;
+        Ok(())
+    }
+
+    /// Extend the expiry of a private Blob stored with one.
+    ///
+    /// Postpones the expiry of every chunk of the Blob, which only its owner can do.
+    pub async fn extend_blob_expiry(
+        &self,
+        head_chunk: ChunkAddress,
+        expiry: u64,
+    ) -> Result<(), Error> {
+        info!(
+            "Extending expiry of blob at given address: {:?} to {}",
+            head_chunk, expiry
+        );
+
+        let mut chunk = self.fetch_blob_from_network(head_chunk).await?;
+        self.extend_chunk_expiry(head_chunk, expiry).await?;
+
+        loop {
+            match deserialize(chunk.value())? {
+                DataMapLevel::Root(data_map) => {
+                    self.extend_expiry_using_data_map(&data_map, expiry).await?;
+                    return Ok(());
+                }
+                DataMapLevel::Child(data_map) => {
+                    let serialized_chunk = self
+                        .read_using_data_map(data_map.clone(), false, None, None)
+                        .await?;
+                    self.extend_expiry_using_data_map(&data_map, expiry).await?;
+                    chunk = deserialize(&serialized_chunk)?;
+                }
+            }
+        }
+    }
+
+    async fn extend_chunk_expiry(&self, address: ChunkAddress, expiry: u64) -> Result<(), Error> {
+        let cmd = DataCmd::Blob(ChunkWrite::ExtendExpiry { address, expiry });
+        self.pay_and_send_data_command(cmd).await
+    }
+
     /// Delete blob can only be performed on private chunks. But on those private chunks this will remove the data
     /// from the network.
     ///
@@ -213,7 +292,7 @@ impl Client {
     /// // Let's use an existing client, with a pre-existing balance to be used for write payments.
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(None, None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let data = b"some private data".to_vec();
     /// let address = client.store_private_blob(&data).await?;
     ///
@@ -253,45 +332,12 @@ impl Client {
     /// Uses self_encryption to generate an encrypted Blob serialized data map,
     /// without connecting and/or writing to the network.
     pub async fn blob_data_map(
-        mut data: Vec<u8>,
+        data: Vec<u8>,
         privately_owned: Option<PublicKey>,
     ) -> Result<(DataMap, ChunkAddress), Error> {
-        // We generate a random public key as owner since this is used for dry-run only
-        let mut is_original_data = true;
-
-        let (data_map, head_chunk) = loop {
-            let blob_storage = BlobStorageDryRun::new(privately_owned);
-            let self_encryptor =
-                SelfEncryptor::new(blob_storage, DataMap::None).map_err(Error::SelfEncryption)?;
-            self_encryptor
-                .write(&data, 0)
-                .await
-                .map_err(Error::SelfEncryption)?;
-            let (data_map, _) = self_encryptor
-                .close()
-                .await
-                .map_err(Error::SelfEncryption)?;
-
-            let chunk_content = if is_original_data {
-                is_original_data = false;
-                serialize(&DataMapLevel::Root(data_map.clone()))?
-            } else {
-                serialize(&DataMapLevel::Child(data_map.clone()))?
-            };
-
-            let chunk: Chunk = if let Some(owner) = privately_owned {
-                PrivateChunk::new(chunk_content, owner).into()
-            } else {
-                PublicChunk::new(chunk_content).into()
-            };
-
-            // If the chunk (data map) is bigger than 1MB we need to break it down
-            if chunk.validate_size() {
-                break (data_map, chunk);
-            } else {
-                data = serialize(&chunk)?;
-            }
-        };
+        let blob_storage = BlobStorageDryRun::new(privately_owned);
+        let (data_map, head_chunk, _) =
+            Self::encrypt_blob(&data, privately_owned, blob_storage).await?;
 
         Ok((data_map, *head_chunk.address()))
     }
@@ -300,24 +346,6 @@ impl Client {
     // ---------- Private helpers -----------------
     // --------------------------------------------
 
-    // Writes raw data to the network into immutable data chunks
-    async fn write_to_network(&self, data: &[u8], public: bool) -> Result<DataMap, Error> {
-        let blob_storage = BlobStorage::new(self.clone(), public);
-        let self_encryptor = SelfEncryptor::new(blob_storage.clone(), DataMap::None)
-            .map_err(Error::SelfEncryption)?;
-
-        self_encryptor
-            .write(data, 0)
-            .await
-            .map_err(Error::SelfEncryption)?;
-
-        let (data_map, _) = self_encryptor
-            .close()
-            .await
-            .map_err(Error::SelfEncryption)?;
-        Ok(data_map)
-    }
-
     // This function reads raw data from the network using the data map
     async fn read_using_data_map(
         &self,
@@ -354,29 +382,76 @@ impl Client {
         }
     }
 
-    /// Takes the "Root data map" and returns a chunk that is acceptable by the network
+    // The data map holds the names of the chunks, so their expiry is extended without reading them
+    async fn extend_expiry_using_data_map(
+        &self,
+        data_map: &DataMap,
+        expiry: u64,
+    ) -> Result<(), Error> {
+        if let DataMap::Chunks(chunks) = data_map {
+            for details in chunks {
+                if details.hash.len() != XOR_NAME_LEN {
+                    return Err(Error::SelfEncryption(SelfEncryptionError::Generic(
+                        "Chunk name in data map is incorrect size.".to_owned(),
+                    )));
+                }
+                let mut name = [0_u8; XOR_NAME_LEN];
+                name.clone_from_slice(&details.hash);
+                self.extend_chunk_expiry(ChunkAddress::Private(XorName(name)), expiry)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Self encrypts the data into `storage` and puts the "Root data map" in a chunk that is
+    /// acceptable by the network.
     ///
     /// If the root data map chunk is too big, it is self-encrypted and the resulting data map is put into a chunk.
     /// The above step is repeated as many times as required until the chunk size is valid.
-    async fn pack(&self, mut contents: Vec<u8>, public: bool) -> Result<Chunk, Error> {
+    /// Returns the data map held by the head chunk, the head chunk and the storage.
+    pub(super) async fn encrypt_blob<S>(
+        data: &[u8],
+        owner: Option<PublicKey>,
+        storage: S,
+    ) -> Result<(DataMap, Chunk, S), Error>
+    where
+        S: Storage + Send + Sync + Clone + 'static,
+    {
+        let (mut data_map, mut storage) = Self::encrypt_into(data, storage).await?;
+        let mut contents = serialize(&DataMapLevel::Root(data_map.clone()))?;
         loop {
-            let chunk: Chunk = if public {
-                PublicChunk::new(contents).into()
-            } else {
-                PrivateChunk::new(contents, self.public_key()).into()
+            let chunk: Chunk = match owner {
+                Some(owner) => PrivateChunk::new(contents, owner).into(),
+                None => PublicChunk::new(contents).into(),
             };
 
             // If data map chunk is less thatn 1MB return it so it can be directly sent to the network
             if chunk.validate_size() {
-                return Ok(chunk);
-            } else {
-                let serialized_chunk = serialize(&chunk)?;
-                let data_map = self.write_to_network(&serialized_chunk, public).await?;
-                contents = serialize(&DataMapLevel::Child(data_map))?;
+                return Ok((data_map, chunk, storage));
             }
+            let (child_data_map, encrypted) =
+                Self::encrypt_into(&serialize(&chunk)?, storage).await?;
+            storage = encrypted;
+            contents = serialize(&DataMapLevel::Child(child_data_map.clone()))?;
+            data_map = child_data_map;
         }
     }
 
+    // Writes raw data into immutable data chunks of the storage
+    async fn encrypt_into<S>(data: &[u8], storage: S) -> Result<(DataMap, S), Error>
+    where
+        S: Storage + Send + Sync + Clone + 'static,
+    {
+        let self_encryptor =
+            SelfEncryptor::new(storage, DataMap::None).map_err(Error::SelfEncryption)?;
+        self_encryptor
+            .write(data, 0)
+            .await
+            .map_err(Error::SelfEncryption)?;
+        self_encryptor.close().await.map_err(Error::SelfEncryption)
+    }
+
     /// Takes a chunk and fetches the data map from it.
     /// If the data map is not the root data map of the user's contents,
     /// the process repeats itself until it obtains the root data map.
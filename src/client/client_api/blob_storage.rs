@@ -11,6 +11,7 @@ use crate::types::{Chunk, ChunkAddress, PrivateChunk, PublicChunk, PublicKey};
 use async_trait::async_trait;
 use log::trace;
 use self_encryption::{SelfEncryptionError, Storage};
+use std::collections::BTreeMap;
 use xor_name::{XorName, XOR_NAME_LEN};
 
 /// Network storage is the concrete type which self_encryption crate will use
@@ -19,12 +20,26 @@ use xor_name::{XorName, XOR_NAME_LEN};
 pub struct BlobStorage {
     client: Client,
     public: bool,
+    expiry: Option<u64>,
 }
 
 impl BlobStorage {
     /// Create a new BlobStorage instance.
     pub fn new(client: Client, public: bool) -> Self {
-        Self { client, public }
+        Self {
+            client,
+            public,
+            expiry: None,
+        }
+    }
+
+    /// Create a new BlobStorage instance storing private chunks which expire.
+    pub fn expiring(client: Client, expiry: u64) -> Self {
+        Self {
+            client,
+            public: false,
+            expiry: Some(expiry),
+        }
     }
 }
 
@@ -92,10 +107,15 @@ impl Storage for BlobStorage {
             PrivateChunk::new(data, self.client.public_key()).into()
         };
         trace!("Self encrypt invoked StoreChunk({:?})", &chunk);
-        self.client
-            .store_chunk_on_network(chunk)
-            .await
-            .map_err(|err| SelfEncryptionError::Generic(format!("{}", err)))
+        let result = match self.expiry {
+            Some(expiry) => {
+                self.client
+                    .store_expiring_chunk_on_network(chunk, expiry)
+                    .await
+            }
+            None => self.client.store_chunk_on_network(chunk).await,
+        };
+        result.map_err(|err| SelfEncryptionError::Generic(format!("{}", err)))
     }
 
     async fn generate_address(&self, data: &[u8]) -> Result<Vec<u8>, SelfEncryptionError> {
@@ -154,3 +174,77 @@ impl Storage for BlobStorageDryRun {
         Ok(chunk.name().0.to_vec())
     }
 }
+
+/// Storage which keeps the chunks self_encryption puts in memory, by name, so they can be
+/// compared against the network before being uploaded.
+#[derive(Clone)]
+pub struct BlobStorageCollector {
+    privately_owned: Option<PublicKey>,
+    chunks: BTreeMap<XorName, Chunk>,
+}
+
+impl BlobStorageCollector {
+    /// Create a new BlobStorageCollector instance.
+    pub fn new(privately_owned: Option<PublicKey>) -> Self {
+        Self {
+            privately_owned,
+            chunks: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the chunks collected so far, by name.
+    pub fn into_chunks(self) -> BTreeMap<XorName, Chunk> {
+        self.chunks
+    }
+
+    fn to_chunk(&self, data: Vec<u8>) -> Chunk {
+        if let Some(owner) = self.privately_owned {
+            PrivateChunk::new(data, owner).into()
+        } else {
+            PublicChunk::new(data).into()
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for BlobStorageCollector {
+    async fn get(&mut self, name: &[u8]) -> Result<Vec<u8>, SelfEncryptionError> {
+        trace!("Self encrypt invoked GetChunk from collected chunks.");
+        if name.len() != XOR_NAME_LEN {
+            return Err(SelfEncryptionError::Generic(
+                "Requested `name` is incorrect size.".to_owned(),
+            ));
+        }
+        let mut temp = [0_u8; XOR_NAME_LEN];
+        temp.clone_from_slice(name);
+
+        self.chunks
+            .get(&XorName(temp))
+            .map(|chunk| chunk.value().clone())
+            .ok_or_else(|| SelfEncryptionError::Generic("Chunk not collected.".to_owned()))
+    }
+
+    async fn put(&mut self, _: Vec<u8>, data: Vec<u8>) -> Result<(), SelfEncryptionError> {
+        let chunk = self.to_chunk(data);
+        trace!(
+            "Self encrypt invoked StoreChunk({:?}) to collect.",
+            chunk.address()
+        );
+        let _ = self.chunks.insert(*chunk.name(), chunk);
+        Ok(())
+    }
+
+    async fn delete(&mut self, name: &[u8]) -> Result<(), SelfEncryptionError> {
+        trace!("Self encrypt invoked DeleteChunk from collected chunks.");
+        if name.len() == XOR_NAME_LEN {
+            let mut temp = [0_u8; XOR_NAME_LEN];
+            temp.clone_from_slice(name);
+            let _ = self.chunks.remove(&XorName(temp));
+        }
+        Ok(())
+    }
+
+    async fn generate_address(&self, data: &[u8]) -> Result<Vec<u8>, SelfEncryptionError> {
+        Ok(self.to_chunk(data.to_vec()).name().0.to_vec())
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{blob_storage::BlobStorageCollector, Client};
+use crate::client::{Error, ErrorMessage};
+use crate::messaging::client::{ChunkRead, DataQuery, Query, QueryResponse};
+use crate::types::ChunkAddress;
+use log::{debug, trace};
+
+/// Outcome of syncing a Blob with `Client::sync_blob`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlobSyncReport {
+    /// Address of the Blob's head chunk.
+    pub head: ChunkAddress,
+    /// Number of chunks which were missing, and so were paid for and uploaded.
+    pub chunks_uploaded: usize,
+    /// Number of chunks which already existed on the network.
+    pub chunks_existing: usize,
+    /// Bytes of the chunks which were uploaded.
+    pub bytes_uploaded: u64,
+    /// Bytes of the chunks which already existed, and so weren't uploaded again.
+    pub bytes_saved: u64,
+}
+
+impl Client {
+    /// Store a Blob, uploading only the chunks which aren't on the network yet.
+    ///
+    /// Self encryption is deterministic, so storing a slightly changed file again yields mostly
+    /// the same chunks. The data map is computed offline, as `Client::blob_data_map` does, then
+    /// each chunk address is looked up and only the missing chunks are paid for and uploaded.
+    /// The head chunk is uploaded last, so the Blob is only readable once all its chunks are.
+    pub async fn sync_blob(&self, data: &[u8], public: bool) -> Result<BlobSyncReport, Error> {
+        let owner = if public {
+            None
+        } else {
+            Some(self.public_key())
+        };
+
+        // Self encrypt without touching the network, collecting the chunks
+        let storage = BlobStorageCollector::new(owner);
+        let (_, head, storage) = Self::encrypt_blob(data, owner, storage).await?;
+        let chunks = storage.into_chunks();
+        trace!(
+            "Syncing Blob at {:?} made of {} chunks",
+            head.address(),
+            chunks.len() + 1
+        );
+
+        let mut report = BlobSyncReport {
+            head: *head.address(),
+            chunks_uploaded: 0,
+            chunks_existing: 0,
+            bytes_uploaded: 0,
+            bytes_saved: 0,
+        };
+        for chunk in chunks.into_values().chain(Some(head)) {
+            let size = chunk.value().len() as u64;
+            if self.chunk_exists(*chunk.address()).await? {
+                report.chunks_existing += 1;
+                report.bytes_saved += size;
+            } else {
+                self.store_chunk_on_network(chunk).await?;
+                report.chunks_uploaded += 1;
+                report.bytes_uploaded += size;
+            }
+        }
+
+        debug!(
+            "Synced Blob at {:?}: uploaded {} bytes, saved {} bytes",
+            report.head, report.bytes_uploaded, report.bytes_saved
+        );
+
+        Ok(report)
+    }
+
+    // Asks the holders whether they store the chunk, without downloading it. A chunk is missing
+    // if the network answers it wasn't found; any other error is returned.
+    async fn chunk_exists(&self, address: ChunkAddress) -> Result<bool, Error> {
+        let res = self
+            .send_query(Query::Data(DataQuery::Blob(ChunkRead::Exists(address))))
+            .await?;
+        let msg_id = res.msg_id;
+        match res.response {
+            QueryResponse::ChunkExists(Ok(())) => Ok(true),
+            QueryResponse::ChunkExists(Err(ErrorMessage::DataNotFound(_))) => Ok(false),
+            QueryResponse::ChunkExists(Err(error)) => Err(Error::from((error, msg_id))),
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Client;
+    use crate::client::utils::{generate_random_vector, test_utils::create_test_client};
+    use crate::retry_loop;
+    use anyhow::Result;
+
+    #[tokio::test]
+    pub async fn sync_blob_uploads_only_missing_chunks() -> Result<()> {
+        let client = create_test_client().await?;
+        // Big enough for the chunks in the middle not to depend on the last one.
+        let mut data = generate_random_vector::<u8>(5 * 1024 * 1024);
+        let (_, expected_head) = Client::blob_data_map(data.clone(), None).await?;
+
+        let first = client.sync_blob(&data, true).await?;
+        assert_eq!(first.head, expected_head);
+        assert_eq!(first.chunks_existing, 0);
+        assert_eq!(first.bytes_saved, 0);
+        assert_eq!(retry_loop!(client.read_blob(first.head, None, None)), data);
+
+        // Syncing the same data again uploads nothing.
+        let again = client.sync_blob(&data, true).await?;
+        assert_eq!(again.head, first.head);
+        assert_eq!(again.chunks_uploaded, 0);
+        assert_eq!(again.bytes_saved, first.bytes_uploaded);
+
+        // Changing the end of the data only re-uploads the chunks depending on it.
+        let last = data.len() - 1;
+        data[last] = data[last].wrapping_add(1);
+        let changed = client.sync_blob(&data, true).await?;
+        assert_ne!(changed.head, first.head);
+        assert!(changed.chunks_existing > 0);
+        assert!(changed.bytes_uploaded < first.bytes_uploaded);
+        assert_eq!(
+            retry_loop!(client.read_blob(changed.head, None, None)),
+            data
+        );
+
+        Ok(())
+    }
+}
@@ -8,7 +8,10 @@
 
 use super::Client;
 use crate::client::Error;
-use crate::messaging::client::{ClientSig, Cmd};
+use crate::messaging::{
+    client::{ClientSig, Cmd},
+    SectionAuthorityProvider,
+};
 use crate::types::{PublicKey, Signature};
 use log::debug;
 use std::net::SocketAddr;
@@ -40,4 +43,22 @@ impl Client {
         self.send_signed_command(cmd, client_pk, signature, target)
             .await
     }
+
+    // Send a Cmd to the Elders of the given section, which need not be our own,
+    // without awaiting for a response.
+    pub(crate) async fn send_cmd_to_section(
+        &self,
+        cmd: Cmd,
+        section: &SectionAuthorityProvider,
+    ) -> Result<(), Error> {
+        debug!("Sending Cmd to section {:?}: {:?}", section.prefix, cmd);
+        let client_sig = ClientSig {
+            public_key: self.public_key(),
+            signature: self.keypair.sign(b"TODO"),
+        };
+
+        self.session
+            .send_cmd_to_section(cmd, client_sig, section)
+            .await
+    }
 }
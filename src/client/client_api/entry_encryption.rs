@@ -0,0 +1,230 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::{
+    utils::{symmetric_decrypt, symmetric_encrypt, SymEncKey},
+    Error,
+};
+use crate::types::{
+    register, Keypair, MapAddress, MapEntryActions, MapSeqEntryAction, MapSeqEntryActions,
+    MapUnseqEntryAction, MapUnseqEntryActions, MapValue, SequenceAddress,
+};
+use bincode::serialize;
+use serde::Serialize;
+use tiny_keccak::{Hasher, Sha3};
+
+// Domain separating the signatures entry keys are derived from from any other signature made
+// with the client's keypair.
+const ENTRY_KEY_DOMAIN: &[u8] = b"safe_network::client::entry_encryption::v1";
+
+// Address of data whose entries are encrypted when entry encryption is enabled.
+pub(super) trait EntryAddress: Serialize {
+    fn encrypts_entries(&self) -> bool;
+}
+
+impl EntryAddress for register::Address {
+    fn encrypts_entries(&self) -> bool {
+        self.is_private()
+    }
+}
+
+impl EntryAddress for SequenceAddress {
+    fn encrypts_entries(&self) -> bool {
+        self.is_private()
+    }
+}
+
+// Maps have no public kind, they're only ever permission-gated.
+impl EntryAddress for MapAddress {
+    fn encrypts_entries(&self) -> bool {
+        true
+    }
+}
+
+impl Client {
+    /// Enable or disable client-side encryption of the entries of private Registers, private
+    /// Sequences and Maps.
+    ///
+    /// When enabled, the entries written through `write_to_register`, `append_to_sequence`,
+    /// `edit_map_entries` and the `store_*` functions taking initial entries are encrypted with a
+    /// key derived from this client's keypair and the address of the data, and the entries read
+    /// through the `read_*`, `get_*_entry`, `get_sequence_range`, `get_map_value` and
+    /// `list_*_map_*` functions are decrypted. Map keys stay in plaintext, as they are looked up
+    /// by the nodes. Functions returning the whole data, e.g. `get_register`, return the entries
+    /// as stored.
+    ///
+    /// The entries can then only be read by clients using the same keypair, and not by the nodes
+    /// storing them. For the same reason, the owners of private Registers and Sequences can't be
+    /// changed while entry encryption is enabled: the new owners couldn't decrypt the existing
+    /// entries, while this client still could. Such policy updates fail with
+    /// `Error::EncryptedOwnershipTransfer`.
+    pub fn with_entry_encryption(mut self, enabled: bool) -> Self {
+        self.encrypt_entries = enabled;
+        self
+    }
+
+    /// Returns whether client-side encryption of entries is enabled.
+    pub fn entry_encryption(&self) -> bool {
+        self.encrypt_entries
+    }
+
+    // Encrypts an entry of the data at `address`, if entry encryption is enabled and applies to
+    // the data.
+    pub(super) fn encrypt_entry(
+        &self,
+        address: &impl EntryAddress,
+        entry: Vec<u8>,
+    ) -> Result<Vec<u8>, Error> {
+        if !self.encrypts_entries_of(address) {
+            return Ok(entry);
+        }
+        symmetric_encrypt(&entry, &entry_key(&self.keypair, address)?, None)
+    }
+
+    // Decrypts an entry of the data at `address`, if entry encryption is enabled and applies to
+    // the data.
+    pub(super) fn decrypt_entry(
+        &self,
+        address: &impl EntryAddress,
+        entry: Vec<u8>,
+    ) -> Result<Vec<u8>, Error> {
+        if !self.encrypts_entries_of(address) {
+            return Ok(entry);
+        }
+        symmetric_decrypt(&entry, &entry_key(&self.keypair, address)?)
+    }
+
+    // Refuses an update of the policy changing the owners of the data at `address`, if entry
+    // encryption applies to the data, as its entries are encrypted with a key only we can derive.
+    pub(super) fn check_owners_kept(
+        &self,
+        address: &impl EntryAddress,
+        owners_changed: bool,
+    ) -> Result<(), Error> {
+        if owners_changed && self.encrypts_entries_of(address) {
+            return Err(Error::EncryptedOwnershipTransfer);
+        }
+        Ok(())
+    }
+
+    pub(super) fn encrypts_entries_of(&self, address: &impl EntryAddress) -> bool {
+        self.encrypt_entries && address.encrypts_entries()
+    }
+
+    // Encrypts the values written by the Map entry actions, if entry encryption is enabled.
+    pub(super) fn encrypt_map_actions(
+        &self,
+        address: &MapAddress,
+        actions: MapEntryActions,
+    ) -> Result<MapEntryActions, Error> {
+        if !self.encrypt_entries {
+            return Ok(actions);
+        }
+
+        Ok(match actions {
+            MapEntryActions::Seq(actions) => {
+                let mut encrypted = MapSeqEntryActions::new();
+                for (key, action) in actions.into_actions() {
+                    encrypted = match action {
+                        MapSeqEntryAction::Ins(value) => encrypted.ins(
+                            key,
+                            self.encrypt_entry(address, value.data)?,
+                            value.version,
+                        ),
+                        MapSeqEntryAction::Update(value) => encrypted.update(
+                            key,
+                            self.encrypt_entry(address, value.data)?,
+                            value.version,
+                        ),
+                        MapSeqEntryAction::Del(version) => encrypted.del(key, version),
+                    };
+                }
+                MapEntryActions::Seq(encrypted)
+            }
+            MapEntryActions::Unseq(actions) => {
+                let mut encrypted = MapUnseqEntryActions::new();
+                for (key, action) in actions.into_actions() {
+                    encrypted = match action {
+                        MapUnseqEntryAction::Ins(data) => {
+                            encrypted.ins(key, self.encrypt_entry(address, data)?)
+                        }
+                        MapUnseqEntryAction::Update(data) => {
+                            encrypted.update(key, self.encrypt_entry(address, data)?)
+                        }
+                        MapUnseqEntryAction::Del => encrypted.del(key),
+                    };
+                }
+                MapEntryActions::Unseq(encrypted)
+            }
+        })
+    }
+
+    // Decrypts a Map value, if entry encryption is enabled.
+    pub(super) fn decrypt_map_value(
+        &self,
+        address: &MapAddress,
+        value: MapValue,
+    ) -> Result<MapValue, Error> {
+        Ok(match value {
+            MapValue::Seq(mut value) => {
+                value.data = self.decrypt_entry(address, value.data)?;
+                MapValue::Seq(value)
+            }
+            MapValue::Unseq(data) => MapValue::Unseq(self.decrypt_entry(address, data)?),
+        })
+    }
+}
+
+// Derives the key encrypting the entries of the data at `address` from the signature of the
+// address with the keypair. Signatures of both Ed25519 and BLS keys are deterministic, so the
+// same key is derived every time.
+fn entry_key(keypair: &Keypair, address: &impl Serialize) -> Result<SymEncKey, Error> {
+    let mut message = ENTRY_KEY_DOMAIN.to_vec();
+    message.extend(serialize(address)?);
+    let signature = serialize(&keypair.sign(&message))?;
+
+    let mut hasher = Sha3::v256();
+    let mut key = SymEncKey::default();
+    hasher.update(ENTRY_KEY_DOMAIN);
+    hasher.update(&signature);
+    hasher.finalize(&mut key);
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use xor_name::XorName;
+
+    #[test]
+    fn entry_keys() -> Result<()> {
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let address = register::Address::Private {
+            name: XorName::random(),
+            tag: 15000,
+        };
+        let other_address = register::Address::Private {
+            name: XorName::random(),
+            tag: 15000,
+        };
+
+        let key = entry_key(&keypair, &address)?;
+        assert_eq!(key, entry_key(&keypair, &address)?);
+        assert_ne!(key, entry_key(&keypair, &other_address)?);
+        assert_ne!(key, entry_key(&Keypair::new_ed25519(&mut OsRng), &address)?);
+
+        let cipher_text = symmetric_encrypt(b"private entry", &key, None)?;
+        assert_eq!(symmetric_decrypt(&cipher_text, &key)?, b"private entry");
+        assert!(symmetric_decrypt(&cipher_text, &entry_key(&keypair, &other_address)?).is_err());
+
+        Ok(())
+    }
+}
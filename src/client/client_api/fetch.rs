@@ -0,0 +1,242 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use super::{FilesMap, MultimapEntries, ResolvedPath};
+use crate::client::Error;
+use crate::types::{
+    register::{Address as RegisterAddress, Entry as RegisterEntry, EntryHash},
+    ChunkAddress, Map, MapAddress, SequenceAddress, SequenceEntry,
+};
+use crate::url::{SafeContentType, SafeDataType, SafeUrl};
+use log::trace;
+use std::collections::BTreeSet;
+use xor_name::XorName;
+
+/// Content fetched from a `SafeUrl`, as returned by `Client::fetch`.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SafeContent {
+    /// The URL of a key, which has no content stored on the network.
+    SafeKey(XorName),
+    /// The data of a Blob.
+    Blob {
+        /// Address of the Blob's head chunk.
+        address: ChunkAddress,
+        /// The data.
+        data: Vec<u8>,
+    },
+    /// The current entries of a Register.
+    Register {
+        /// Address of the Register.
+        address: RegisterAddress,
+        /// The current entries, as there may be several concurrent ones.
+        entries: BTreeSet<(EntryHash, RegisterEntry)>,
+    },
+    /// An entry of a Sequence.
+    Sequence {
+        /// Address of the Sequence.
+        address: SequenceAddress,
+        /// Version of the entry, i.e. its index in the Sequence.
+        version: u64,
+        /// The entry.
+        entry: SequenceEntry,
+    },
+    /// A Map.
+    Map(Map),
+    /// The current values of a Multimap.
+    Multimap {
+        /// Address of the Multimap's Register.
+        address: RegisterAddress,
+        /// The current values of its keys.
+        entries: MultimapEntries,
+    },
+    /// A directory of a FilesContainer.
+    FilesContainer {
+        /// Version of the FilesContainer's map.
+        version: u64,
+        /// The files under the directory.
+        files: FilesMap,
+    },
+}
+
+impl Client {
+    /// Fetch the content a `SafeUrl` points to.
+    ///
+    /// NRS URLs are first resolved to the XOR-URL they point to, see `Client::nrs_resolve`. The
+    /// content is then fetched with the API of the URL's data type. A content version (`?v=`)
+    /// selects the entry of a Sequence or the version of a FilesContainer, and must match the
+    /// current version of a Map. Blobs and other Registers aren't versioned by index, so a content
+    /// version is rejected for them.
+    ///
+    /// Registers with the `Multimap` content type are read as Multimaps, see
+    /// `Client::multimap_entries`. The path of a FilesContainer URL is resolved against its files,
+    /// fetching the file it points to, or listing the directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate tokio; use anyhow::Result;
+    /// # use safe_network::client::utils::test_utils::read_network_conn_info;
+    /// use safe_network::client::{Client, SafeContent};
+    /// use safe_network::url::{SafeContentType, SafeUrl, XorUrlBase};
+    /// # #[tokio::main] async fn main() { let _: Result<()> = futures::executor::block_on( async {
+    /// # let bootstrap_contacts = Some(read_network_conn_info()?);
+    /// let client = Client::new(None, None, bootstrap_contacts).await?;
+    /// let address = client.store_public_blob(b"Hello").await?;
+    /// let url = SafeUrl::encode_blob(*address.name(), SafeContentType::Raw, XorUrlBase::Base32z)?;
+    ///
+    /// if let SafeContent::Blob { data, .. } = client.fetch(&url).await? {
+    ///     assert_eq!(data, b"Hello".to_vec());
+    /// }
+    /// # Ok(()) } ); }
+    /// ```
+    pub async fn fetch(&self, url: &str) -> Result<SafeContent, Error> {
+        trace!("Fetch content at {}", url);
+
+        let url = self.nrs_resolve(url).await?;
+
+        let name = url.xorname();
+        let tag = url.type_tag();
+        let version = url.content_version();
+        let data_type = url.data_type();
+        let files_container = data_type == SafeDataType::PublicRegister
+            && url.content_type() == SafeContentType::FilesContainer;
+        let versioned = files_container
+            || matches!(
+                data_type,
+                SafeDataType::PublicSequence
+                    | SafeDataType::PrivateSequence
+                    | SafeDataType::SeqMap
+                    | SafeDataType::UnseqMap
+            );
+        if let (Some(version), false) = (version, versioned) {
+            return Err(Error::UnsupportedUrl(format!(
+                "content version {} requested for unversioned data type {}",
+                version, data_type
+            )));
+        }
+
+        match data_type {
+            SafeDataType::SafeKey => Ok(SafeContent::SafeKey(name)),
+            SafeDataType::PublicBlob | SafeDataType::PrivateBlob => {
+                self.fetch_blob(url.blob_address()?).await
+            }
+            SafeDataType::PublicRegister if files_container => {
+                self.fetch_files_container(&url).await
+            }
+            SafeDataType::PublicRegister | SafeDataType::PrivateRegister
+                if url.content_type() == SafeContentType::Multimap =>
+            {
+                let address = url.register_address()?;
+                let entries = self.multimap_entries(address).await?;
+                Ok(SafeContent::Multimap { address, entries })
+            }
+            SafeDataType::PublicRegister | SafeDataType::PrivateRegister => {
+                let address = url.register_address()?;
+                let entries = self.read_register(address).await?;
+                Ok(SafeContent::Register { address, entries })
+            }
+            SafeDataType::PublicSequence => {
+                self.fetch_sequence(SequenceAddress::Public { name, tag }, version)
+                    .await
+            }
+            SafeDataType::PrivateSequence => {
+                self.fetch_sequence(SequenceAddress::Private { name, tag }, version)
+                    .await
+            }
+            SafeDataType::SeqMap => self.fetch_map(MapAddress::Seq { name, tag }, version).await,
+            SafeDataType::UnseqMap => {
+                self.fetch_map(MapAddress::Unseq { name, tag }, version)
+                    .await
+            }
+        }
+    }
+
+    async fn fetch_blob(&self, address: ChunkAddress) -> Result<SafeContent, Error> {
+        let data = self.read_blob(address, None, None).await?;
+        Ok(SafeContent::Blob { address, data })
+    }
+
+    async fn fetch_files_container(&self, url: &SafeUrl) -> Result<SafeContent, Error> {
+        let (version, files) = self
+            .get_versioned_blob::<FilesMap>(url.register_address()?, url.content_version())
+            .await?;
+
+        let path = url.path_decoded()?;
+        match files.resolve(&path) {
+            Some(ResolvedPath::File(item)) => {
+                let link = SafeUrl::from_url(&item.link)?;
+                self.fetch_blob(link.blob_address()?).await
+            }
+            Some(ResolvedPath::Directory(files)) => {
+                Ok(SafeContent::FilesContainer { version, files })
+            }
+            None => Err(Error::PathNotFound(path)),
+        }
+    }
+
+    async fn fetch_sequence(
+        &self,
+        address: SequenceAddress,
+        version: Option<u64>,
+    ) -> Result<SafeContent, Error> {
+        let (version, entry) = match version {
+            Some(version) => (version, self.get_sequence_entry(address, version).await?),
+            None => self.get_sequence_last_entry(address).await?,
+        };
+        Ok(SafeContent::Sequence {
+            address,
+            version,
+            entry,
+        })
+    }
+
+    async fn fetch_map(
+        &self,
+        address: MapAddress,
+        version: Option<u64>,
+    ) -> Result<SafeContent, Error> {
+        let map = self.get_map(address).await?;
+        // Maps don't keep their history, so only their current version can be served.
+        match version {
+            Some(version) if version != map.version() => {
+                Err(Error::ContentVersionNotFound(version))
+            }
+            _ => Ok(SafeContent::Map(map)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::retry_loop;
+    use crate::url::XorUrlBase;
+    use anyhow::{bail, Result};
+
+    #[tokio::test]
+    pub async fn fetch_public_blob() -> Result<()> {
+        let client = create_test_client().await?;
+        let address = client.store_public_blob(b"fetched").await?;
+
+        let url = SafeUrl::encode_blob(*address.name(), SafeContentType::Raw, XorUrlBase::Base32z)?;
+        match retry_loop!(client.fetch(&url)) {
+            SafeContent::Blob { data, .. } => assert_eq!(data, b"fetched".to_vec()),
+            other => bail!("Unexpected content fetched: {:?}", other),
+        }
+
+        let mut versioned = SafeUrl::from_url(&url)?;
+        versioned.set_content_version(Some(1));
+        match client.fetch(&versioned.to_string()).await {
+            Err(Error::UnsupportedUrl(_)) => Ok(()),
+            other => bail!("Unexpected result fetching a versioned Blob: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,484 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::url::{
+    SafeContentType, SafeDataType, SafeUrl, DEFAULT_XORURL_BASE, FILES_CONTAINER_TYPE_TAG,
+};
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+use xor_name::XorName;
+
+/// Media type of the files whose type can't be guessed from their extension.
+const DEFAULT_MEDIA_TYPE: &str = "application/octet-stream";
+
+/// A file of a `FilesContainer`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileItem {
+    /// XOR-URL of the Blob holding the content of the file.
+    pub link: String,
+    /// Size of the file, in bytes.
+    pub size: u64,
+    /// Last modification time of the local file it was uploaded from, in seconds since the UNIX
+    /// epoch.
+    pub modified: u64,
+    /// Media type of the file, guessed from its extension.
+    pub media_type: String,
+}
+
+/// Manifest of a `FilesContainer`: its files by path.
+///
+/// Paths are absolute within the container, e.g. `/dir/file.txt`. Directories are implied by the
+/// paths of the files they contain.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilesMap {
+    files: BTreeMap<String, FileItem>,
+}
+
+/// What a path resolves to within a `FilesMap`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResolvedPath {
+    /// A file.
+    File(FileItem),
+    /// A directory, with the files under it by their full path.
+    Directory(FilesMap),
+}
+
+impl FilesMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the file at the given path, if any.
+    pub fn get(&self, path: &str) -> Option<&FileItem> {
+        self.files.get(&normalise_path(path))
+    }
+
+    /// Adds a file at the given path, returning the file it replaced, if any.
+    pub fn insert(&mut self, path: &str, item: FileItem) -> Option<FileItem> {
+        self.files.insert(normalise_path(path), item)
+    }
+
+    /// Removes the file at the given path, returning it if it was there.
+    pub fn remove(&mut self, path: &str) -> Option<FileItem> {
+        self.files.remove(&normalise_path(path))
+    }
+
+    /// Removes the files under the given directory, returning how many were removed.
+    pub fn remove_dir(&mut self, path: &str) -> usize {
+        let dir = dir_prefix(path);
+        let count = self.files.len();
+        self.files
+            .retain(|file_path, _| !file_path.starts_with(&dir));
+        count - self.files.len()
+    }
+
+    /// Resolves a path, as returned by `SafeUrl::path_decoded`, to the file or directory at it.
+    pub fn resolve(&self, path: &str) -> Option<ResolvedPath> {
+        if let Some(item) = self.get(path) {
+            return Some(ResolvedPath::File(item.clone()));
+        }
+
+        let dir = dir_prefix(path);
+        let files: BTreeMap<_, _> = self
+            .files
+            .iter()
+            .filter(|(file_path, _)| file_path.starts_with(&dir))
+            .map(|(file_path, item)| (file_path.clone(), item.clone()))
+            .collect();
+        if files.is_empty() && dir != "/" {
+            None
+        } else {
+            Some(ResolvedPath::Directory(Self { files }))
+        }
+    }
+
+    /// Iterates over the files and their paths.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FileItem)> {
+        self.files.iter().map(|(path, item)| (path.as_str(), item))
+    }
+
+    /// Number of files in the map.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns whether the map has no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl Client {
+    /// Create a FilesContainer holding the local file, or directory tree, at `local_path`.
+    ///
+    /// The files are uploaded as public Blobs, and the `FilesMap` of the container is versioned
+    /// in a public Register owned by this client. Returns the XOR-URL of the container along with
+    /// its map, which is version 0.
+    pub async fn files_container_create(
+        &self,
+        local_path: &Path,
+    ) -> Result<(String, FilesMap), Error> {
+        trace!("Create FilesContainer from {}", local_path.display());
+
+        let mut map = FilesMap::new();
+        for (path, local_file) in local_files(local_path)? {
+            let _ = map.insert(&path, self.upload_file(&local_file).await?);
+        }
+
+        let name = XorName::random();
+        let _ = self
+            .create_versioned_blob(name, FILES_CONTAINER_TYPE_TAG, &map)
+            .await?;
+        let url = SafeUrl::encode_register(
+            name,
+            FILES_CONTAINER_TYPE_TAG,
+            SafeContentType::FilesContainer,
+            DEFAULT_XORURL_BASE,
+            false,
+        )?;
+
+        Ok((url, map))
+    }
+
+    /// Get the `FilesMap` of a FilesContainer at the URL's content version, or at its latest one,
+    /// along with that version.
+    pub async fn files_container_get(&self, url: &str) -> Result<(u64, FilesMap), Error> {
+        let url = self.files_container_url(url).await?;
+        self.get_versioned_blob(url.register_address()?, url.content_version())
+            .await
+    }
+
+    /// Add the local file, or directory tree, at `local_path` to a FilesContainer, under the
+    /// directory the URL's path points to. Fails if any of the files is already in the container.
+    ///
+    /// Returns the new version of the container's map, along with the map.
+    pub async fn files_container_add(
+        &self,
+        url: &str,
+        local_path: &Path,
+    ) -> Result<(u64, FilesMap), Error> {
+        trace!("Add {} to FilesContainer {}", local_path.display(), url);
+
+        let url = self.files_container_url(url).await?;
+        let address = url.register_address()?;
+        let dest = url.path_decoded()?;
+        let (_, current) = self.get_versioned_blob::<FilesMap>(address, None).await?;
+
+        let local = local_files(local_path)?;
+        let mut added = BTreeMap::new();
+        for (path, local_file) in local {
+            let path = join_paths(&dest, &path);
+            if current.get(&path).is_some() {
+                return Err(Error::PathAlreadyExists(path));
+            }
+            let item = self.upload_file(&local_file).await?;
+            let _ = added.insert(path, item);
+        }
+
+        self.update_versioned_blob(address, |map: &mut FilesMap| {
+            for (path, item) in added {
+                if map.get(&path).is_some() {
+                    return Err(Error::PathAlreadyExists(path));
+                }
+                let _ = map.insert(&path, item);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Sync the local file, or directory tree, at `local_path` to the directory the URL's path
+    /// points to in a FilesContainer.
+    ///
+    /// Only the files which are new, or whose size or modification time changed, are uploaded.
+    /// If `delete` is set, the files of the directory which aren't found locally are removed from
+    /// the container. Returns the new version of the container's map, along with the map.
+    pub async fn files_container_sync(
+        &self,
+        url: &str,
+        local_path: &Path,
+        delete: bool,
+    ) -> Result<(u64, FilesMap), Error> {
+        trace!("Sync {} to FilesContainer {}", local_path.display(), url);
+
+        let url = self.files_container_url(url).await?;
+        let address = url.register_address()?;
+        let dest = url.path_decoded()?;
+        let (_, current) = self.get_versioned_blob::<FilesMap>(address, None).await?;
+
+        let mut local_paths = BTreeSet::new();
+        let mut changed = BTreeMap::new();
+        for (path, local_file) in local_files(local_path)? {
+            let path = join_paths(&dest, &path);
+            let (size, modified) = file_stats(&local_file)?;
+            let unchanged = match current.get(&path) {
+                Some(item) => item.size == size && item.modified == modified,
+                None => false,
+            };
+            if !unchanged {
+                debug!("Uploading changed file {}", path);
+                let _ = changed.insert(path.clone(), self.upload_file(&local_file).await?);
+            }
+            let _ = local_paths.insert(path);
+        }
+
+        let dir = dir_prefix(&dest);
+        self.update_versioned_blob(address, |map: &mut FilesMap| {
+            if delete {
+                map.files.retain(|path, _| {
+                    !path.starts_with(&dir) || local_paths.contains(path.as_str())
+                });
+            }
+            for (path, item) in changed {
+                let _ = map.insert(&path, item);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Remove the file the URL's path points to from a FilesContainer or, if `recursive` is set,
+    /// the directory it points to along with all the files under it.
+    ///
+    /// Returns the new version of the container's map, along with the map.
+    pub async fn files_container_remove(
+        &self,
+        url: &str,
+        recursive: bool,
+    ) -> Result<(u64, FilesMap), Error> {
+        trace!("Remove {} from FilesContainer", url);
+
+        let url = self.files_container_url(url).await?;
+        let path = url.path_decoded()?;
+
+        self.update_versioned_blob(url.register_address()?, |map: &mut FilesMap| {
+            let removed = map.remove(&path).is_some() || (recursive && map.remove_dir(&path) > 0);
+            if removed {
+                Ok(())
+            } else {
+                Err(Error::PathNotFound(path.clone()))
+            }
+        })
+        .await
+    }
+
+    // Resolves the URL, checking it points to a FilesContainer.
+    async fn files_container_url(&self, url: &str) -> Result<SafeUrl, Error> {
+        let url = self.nrs_resolve(url).await?;
+        if url.data_type() != SafeDataType::PublicRegister
+            || url.content_type() != SafeContentType::FilesContainer
+        {
+            return Err(Error::UnsupportedUrl(format!(
+                "not a FilesContainer URL: {}",
+                url
+            )));
+        }
+        Ok(url)
+    }
+
+    async fn upload_file(&self, local_file: &Path) -> Result<FileItem, Error> {
+        let (size, modified) = file_stats(local_file)?;
+        let data = fs::read(local_file)?;
+        // Only the chunks which changed since a previous upload of the file are paid for
+        let report = self.sync_blob(&data, true).await?;
+        let address = report.head;
+
+        let media_type = mime_guess::from_path(local_file)
+            .first_raw()
+            .unwrap_or(DEFAULT_MEDIA_TYPE)
+            .to_string();
+        let content_type = if SafeUrl::is_media_type_supported(&media_type) {
+            SafeContentType::MediaType(media_type.clone())
+        } else {
+            SafeContentType::Raw
+        };
+        let link = SafeUrl::encode_blob(*address.name(), content_type, DEFAULT_XORURL_BASE)?;
+
+        Ok(FileItem {
+            link,
+            size,
+            modified,
+            media_type,
+        })
+    }
+}
+
+// Lists the file at `local_path`, or the files of the directory tree at it, along with their path
+// relative to it (or the file name for a file).
+fn local_files(local_path: &Path) -> Result<Vec<(String, PathBuf)>, Error> {
+    let mut files = vec![];
+    if fs::metadata(local_path)?.is_file() {
+        let name = local_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        files.push((format!("/{}", name), local_path.to_path_buf()));
+    } else {
+        list_dir(local_path, "", &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn list_dir(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        let metadata = fs::metadata(entry.path())?;
+        if metadata.is_dir() {
+            list_dir(&entry.path(), &path, files)?;
+        } else if metadata.is_file() {
+            files.push((path, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+// Size and modification time of a local file, in seconds since the UNIX epoch.
+fn file_stats(local_file: &Path) -> Result<(u64, u64), Error> {
+    let metadata = fs::metadata(local_file)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    Ok((metadata.len(), modified))
+}
+
+// Makes the path absolute, without trailing separator.
+fn normalise_path(path: &str) -> String {
+    format!("/{}", path.trim_matches('/'))
+}
+
+// Prefix of the paths of the files under the directory at `path`.
+fn dir_prefix(path: &str) -> String {
+    match path.trim_matches('/') {
+        "" => "/".to_string(),
+        dir => format!("/{}/", dir),
+    }
+}
+
+fn join_paths(dir: &str, path: &str) -> String {
+    normalise_path(&format!(
+        "{}/{}",
+        dir.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::retry_loop;
+    use anyhow::{anyhow, bail, Result};
+    use std::env::temp_dir;
+
+    fn item(link: &str) -> FileItem {
+        FileItem {
+            link: link.to_string(),
+            size: 1,
+            modified: 0,
+            media_type: DEFAULT_MEDIA_TYPE.to_string(),
+        }
+    }
+
+    #[test]
+    fn resolve_paths() {
+        let mut map = FilesMap::new();
+        let _ = map.insert("index.html", item("safe://index"));
+        let _ = map.insert("/img/logo.png", item("safe://logo"));
+        let _ = map.insert("/img/icons/up.png", item("safe://up"));
+
+        assert_eq!(
+            map.resolve("/index.html"),
+            Some(ResolvedPath::File(item("safe://index")))
+        );
+        match map.resolve("/img/") {
+            Some(ResolvedPath::Directory(dir)) => assert_eq!(
+                dir.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+                vec!["/img/icons/up.png", "/img/logo.png"]
+            ),
+            other => panic!("Unexpected resolution of /img/: {:?}", other),
+        }
+        assert_eq!(map.resolve("/im"), None);
+        assert_eq!(map.resolve(""), Some(ResolvedPath::Directory(map.clone())));
+
+        assert_eq!(map.remove_dir("/img"), 2);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn list_local_files() -> Result<()> {
+        let root = temp_dir().join(format!("files_container_{}", rand::random::<u64>()));
+        fs::create_dir_all(root.join("sub/dir"))?;
+        fs::write(root.join("a.txt"), b"a")?;
+        fs::write(root.join("sub/dir/b.txt"), b"b")?;
+
+        let paths: Vec<_> = local_files(&root)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(paths, vec!["/a.txt", "/sub/dir/b.txt"]);
+
+        let paths: Vec<_> = local_files(&root.join("a.txt"))?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(paths, vec!["/a.txt"]);
+
+        assert_eq!(join_paths("/dest/", "/sub/b.txt"), "/dest/sub/b.txt");
+        assert_eq!(join_paths("", "/b.txt"), "/b.txt");
+
+        fs::remove_dir_all(root)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn files_container_versions() -> Result<()> {
+        let client = create_test_client().await?;
+        let root = temp_dir().join(format!("files_container_{}", rand::random::<u64>()));
+        fs::create_dir_all(&root)?;
+        fs::write(root.join("index.html"), b"<html></html>")?;
+
+        let (url, map) = client.files_container_create(&root).await?;
+        let item = map
+            .get("/index.html")
+            .ok_or_else(|| anyhow!("index.html not uploaded"))?;
+        assert_eq!(item.media_type, "text/html");
+
+        fs::write(root.join("style.css"), b"body {}")?;
+        let (version, map) = retry_loop!(client.files_container_sync(&url, &root, false));
+        assert_eq!(version, 1);
+        assert_eq!(map.len(), 2);
+
+        match retry_loop!(client.fetch(&format!("{}/style.css", url))) {
+            crate::client::SafeContent::Blob { data, .. } => assert_eq!(data, b"body {}".to_vec()),
+            other => bail!("Unexpected content fetched: {:?}", other),
+        }
+
+        let mut first_version = SafeUrl::from_url(&url)?;
+        first_version.set_content_version(Some(0));
+        let (_, map) = client
+            .files_container_get(&first_version.to_string())
+            .await?;
+        assert_eq!(map.len(), 1);
+
+        fs::remove_dir_all(root)?;
+        Ok(())
+    }
+}
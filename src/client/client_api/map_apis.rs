@@ -49,7 +49,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 15001;
     /// let mut entries = MapSeqEntries::default();
@@ -70,14 +70,22 @@ impl Client {
         entries: Option<MapSeqEntries>,
         permissions: Option<BTreeMap<PublicKey, MapPermissionSet>>,
     ) -> Result<MapAddress, Error> {
+        let address = MapAddress::Seq { name, tag };
+        let entries = entries
+            .unwrap_or_else(MapSeqEntries::default)
+            .into_iter()
+            .map(|(key, mut value)| {
+                value.data = self.encrypt_entry(&address, value.data)?;
+                Ok((key, value))
+            })
+            .collect::<Result<_, Error>>()?;
         let data = Map::Seq(SeqMap::new_with_data(
             name,
             tag,
-            entries.unwrap_or_else(MapSeqEntries::default),
+            entries,
             permissions.unwrap_or_else(BTreeMap::default),
             owner,
         ));
-        let address = *data.address();
         let cmd = DataCmd::Map(MapWrite::New(data));
 
         self.pay_and_send_data_command(cmd).await?;
@@ -103,7 +111,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 15001;
     /// let mut entries = MapUnseqEntries::default();
@@ -124,14 +132,19 @@ impl Client {
         entries: Option<MapUnseqEntries>,
         permissions: Option<BTreeMap<PublicKey, MapPermissionSet>>,
     ) -> Result<MapAddress, Error> {
+        let address = MapAddress::Unseq { name, tag };
+        let entries = entries
+            .unwrap_or_else(MapUnseqEntries::default)
+            .into_iter()
+            .map(|(key, value)| Ok((key, self.encrypt_entry(&address, value)?)))
+            .collect::<Result<_, Error>>()?;
         let data = Map::Unseq(UnseqMap::new_with_data(
             name,
             tag,
-            entries.unwrap_or_else(MapUnseqEntries::default),
+            entries,
             permissions.unwrap_or_else(BTreeMap::default),
             owner,
         ));
-        let address = *data.address();
 
         let cmd = DataCmd::Map(MapWrite::New(data));
 
@@ -158,7 +171,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 15001;
     /// let mut entries = MapUnseqEntries::default();
@@ -219,6 +232,7 @@ impl Client {
         address: MapAddress,
         changes: MapEntryActions,
     ) -> Result<(), Error> {
+        let changes = self.encrypt_map_actions(&address, changes)?;
         let cmd = DataCmd::Map(MapWrite::Edit { address, changes });
 
         self.pay_and_send_data_command(cmd).await
@@ -246,7 +260,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 15001;
     /// let mut entries = MapUnseqEntries::default();
@@ -294,7 +308,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 15001;
     /// let mut entries = MapUnseqEntries::default();
@@ -324,7 +338,9 @@ impl Client {
             .await?;
         let msg_id = query_result.msg_id;
         match query_result.response {
-            QueryResponse::GetMapValue(res) => res.map_err(|err| Error::from((err, msg_id))),
+            QueryResponse::GetMapValue(res) => res
+                .map_err(|err| Error::from((err, msg_id)))
+                .and_then(|value| self.decrypt_map_value(&address, value)),
             _ => Err(Error::ReceivedUnexpectedEvent),
         }
     }
@@ -364,7 +380,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 15001;
     /// let mut entries = MapUnseqEntries::default();
@@ -447,18 +463,19 @@ impl Client {
     {
         trace!("ListMapEntries for {:?}", name);
 
+        let address = MapAddress::Unseq { name, tag };
         let query_result = self
-            .send_query(wrap_map_read(MapRead::ListEntries(MapAddress::Unseq {
-                name,
-                tag,
-            })))
+            .send_query(wrap_map_read(MapRead::ListEntries(address)))
             .await?;
         let msg_id = query_result.msg_id;
         match query_result.response {
             QueryResponse::ListMapEntries(res) => res
                 .map_err(|err| Error::from((err, msg_id)))
                 .and_then(|entries| match entries {
-                    MapEntries::Unseq(data) => Ok(data),
+                    MapEntries::Unseq(data) => data
+                        .into_iter()
+                        .map(|(key, value)| Ok((key, self.decrypt_entry(&address, value)?)))
+                        .collect(),
                     MapEntries::Seq(_) => Err(Error::ReceivedUnexpectedData),
                 }),
             _ => Err(Error::ReceivedUnexpectedEvent),
@@ -476,18 +493,22 @@ impl Client {
     {
         trace!("ListSeqMapEntries for {:?}", name);
 
+        let address = MapAddress::Seq { name, tag };
         let query_result = self
-            .send_query(wrap_map_read(MapRead::ListEntries(MapAddress::Seq {
-                name,
-                tag,
-            })))
+            .send_query(wrap_map_read(MapRead::ListEntries(address)))
             .await?;
         let msg_id = query_result.msg_id;
         match query_result.response {
             QueryResponse::ListMapEntries(res) => res
                 .map_err(|err| Error::from((err, msg_id)))
                 .and_then(|entries| match entries {
-                    MapEntries::Seq(data) => Ok(data),
+                    MapEntries::Seq(data) => data
+                        .into_iter()
+                        .map(|(key, mut value)| {
+                            value.data = self.decrypt_entry(&address, value.data)?;
+                            Ok((key, value))
+                        })
+                        .collect(),
                     MapEntries::Unseq(_) => Err(Error::ReceivedUnexpectedData),
                 }),
             _ => Err(Error::ReceivedUnexpectedEvent),
@@ -522,18 +543,22 @@ impl Client {
     {
         trace!("List MapValues for {:?}", name);
 
+        let address = MapAddress::Seq { name, tag };
         let query_result = self
-            .send_query(wrap_map_read(MapRead::ListValues(MapAddress::Seq {
-                name,
-                tag,
-            })))
+            .send_query(wrap_map_read(MapRead::ListValues(address)))
             .await?;
         let msg_id = query_result.msg_id;
         match query_result.response {
             QueryResponse::ListMapValues(res) => res
                 .map_err(|err| Error::from((err, msg_id)))
                 .and_then(|values| match values {
-                    MapValues::Seq(data) => Ok(data),
+                    MapValues::Seq(data) => data
+                        .into_iter()
+                        .map(|mut value| {
+                            value.data = self.decrypt_entry(&address, value.data)?;
+                            Ok(value)
+                        })
+                        .collect(),
                     MapValues::Unseq(_) => Err(Error::ReceivedUnexpectedData),
                 }),
             _ => Err(Error::ReceivedUnexpectedEvent),
@@ -551,18 +576,19 @@ impl Client {
     {
         trace!("List MapValues for {:?}", name);
 
+        let address = MapAddress::Unseq { name, tag };
         let query_result = self
-            .send_query(wrap_map_read(MapRead::ListValues(MapAddress::Unseq {
-                name,
-                tag,
-            })))
+            .send_query(wrap_map_read(MapRead::ListValues(address)))
             .await?;
         let msg_id = query_result.msg_id;
         match query_result.response {
             QueryResponse::ListMapValues(res) => res
                 .map_err(|err| Error::from((err, msg_id)))
                 .and_then(|values| match values {
-                    MapValues::Unseq(data) => Ok(data),
+                    MapValues::Unseq(data) => data
+                        .into_iter()
+                        .map(|value| self.decrypt_entry(&address, value))
+                        .collect(),
                     MapValues::Seq(_) => Err(Error::ReceivedUnexpectedData),
                 }),
             _ => Err(Error::ReceivedUnexpectedEvent),
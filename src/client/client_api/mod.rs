@@ -8,17 +8,33 @@
 
 mod blob_apis;
 mod blob_storage;
+mod blob_sync;
 mod commands;
+mod entry_encryption;
+mod fetch;
+mod files_container;
 mod map_apis;
+mod multimap;
+mod nrs;
 mod queries;
 mod register_apis;
 mod sequence_apis;
+mod topology;
 mod transfers;
+mod versioned_blob;
+
+pub use blob_sync::BlobSyncReport;
+pub use fetch::SafeContent;
+pub use files_container::{FileItem, FilesMap, ResolvedPath};
+pub use multimap::{MultimapEntries, MultimapEntry, MultimapKey, MultimapValue};
+pub use nrs::NrsMap;
+pub use transfers::MultisigWallet;
 
 use crate::client::{config_handler::Config, connections::Session, errors::Error};
 use crate::messaging::client::{Cmd, CmdError, DataCmd, Error as ErrorMessage, TransferCmd};
 use crate::transfers::TransferActor;
 use crate::types::{Keypair, PublicKey, SectionElders, Token};
+#[cfg(feature = "simulated-payouts")]
 use crdts::Dot;
 use log::{debug, info, trace, warn};
 use rand::rngs::OsRng;
@@ -28,7 +44,7 @@ use std::{
     {collections::HashSet, net::SocketAddr, sync::Arc},
 };
 use tokio::sync::mpsc::Receiver;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 
 // Number of attempts to make when trying to bootstrap to the network
 const NUM_OF_BOOTSTRAPPING_ATTEMPTS: u8 = 1;
@@ -38,9 +54,14 @@ const NUM_OF_BOOTSTRAPPING_ATTEMPTS: u8 = 1;
 pub struct Client {
     keypair: Keypair,
     transfer_actor: Arc<RwLock<TransferActor<Keypair>>>,
+    #[cfg(feature = "simulated-payouts")]
     simulated_farming_payout_dot: Dot<PublicKey>,
     incoming_errors: Arc<RwLock<Receiver<CmdError>>>,
+    // Notified when a debit in flight is registered or abandoned,
+    // waking up the transfers waiting for earlier debits.
+    transfers_progressed: Arc<Notify>,
     session: Session,
+    encrypt_entries: bool,
 }
 
 /// Easily manage connections to/from The Safe Network with the client and its APIs.
@@ -112,10 +133,8 @@ impl Client {
         attempt_bootstrap(&mut session, client_pk).await?;
 
         // Random PK used for from payment
-        let random_payment_id = Keypair::new_ed25519(&mut rng);
-        let random_payment_pk = random_payment_id.public_key();
-
-        let simulated_farming_payout_dot = Dot::new(random_payment_pk, 0);
+        #[cfg(feature = "simulated-payouts")]
+        let simulated_farming_payout_dot = Dot::new(Keypair::new_ed25519(&mut rng).public_key(), 0);
 
         let key_set = session
             .section_key_set
@@ -140,9 +159,12 @@ impl Client {
         let mut client = Self {
             keypair,
             transfer_actor,
+            #[cfg(feature = "simulated-payouts")]
             simulated_farming_payout_dot,
             session,
             incoming_errors: Arc::new(RwLock::new(err_receiver)),
+            transfers_progressed: Arc::default(),
+            encrypt_entries: false,
         };
 
         Self::handle_anti_entropy_errors(client.clone(), transfer_err_receiver);
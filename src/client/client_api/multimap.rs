@@ -0,0 +1,334 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::types::register::{Address as RegisterAddress, EntryHash, Kind, Register};
+use bincode::{deserialize, serialize};
+use log::trace;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use xor_name::XorName;
+
+/// Key of a Multimap.
+pub type MultimapKey = Vec<u8>;
+
+/// Value of a Multimap.
+pub type MultimapValue = Vec<u8>;
+
+/// The current values of the keys of a Multimap, along with the hashes of the Register entries
+/// holding them.
+pub type MultimapEntries = BTreeMap<MultimapKey, BTreeSet<(EntryHash, MultimapValue)>>;
+
+/// Entry of the Register a Multimap is stored in.
+///
+/// Each entry is written on top of the entries it supersedes, so the current values of a key are
+/// the `Insert` entries of the key which are current entries of the Register.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MultimapEntry {
+    /// A value inserted under a key.
+    Insert {
+        /// The key.
+        key: MultimapKey,
+        /// The value.
+        value: MultimapValue,
+    },
+    /// A tombstone, removing the values of the key it was written on top of.
+    Remove {
+        /// The key.
+        key: MultimapKey,
+    },
+}
+
+impl MultimapEntry {
+    /// Returns the key the entry is about.
+    pub fn key(&self) -> &MultimapKey {
+        match self {
+            Self::Insert { key, .. } | Self::Remove { key } => key,
+        }
+    }
+}
+
+impl Client {
+    /// Create a Multimap, i.e. a Register whose entries map keys to values, writable only by this
+    /// client.
+    ///
+    /// Any Register can be used as a Multimap through the `multimap_*` functions, e.g. one
+    /// created with `store_public_register` to share it with other writers.
+    pub async fn store_multimap(
+        &self,
+        name: XorName,
+        tag: u64,
+        kind: Kind,
+    ) -> Result<RegisterAddress, Error> {
+        trace!("Store {:?} Multimap {:?}", kind, name);
+        let register = match kind {
+            Kind::Public => Register::new_public(self.public_key(), name, tag, None),
+            Kind::Private => Register::new_private(self.public_key(), name, tag, None),
+        };
+        let address = *register.address();
+
+        self.pay_and_write_register_to_network(register).await?;
+
+        Ok(address)
+    }
+
+    /// Insert a value under a key of a Multimap, alongside the values the key already has,
+    /// returning the hash of the entry holding the value.
+    pub async fn multimap_insert(
+        &self,
+        address: RegisterAddress,
+        key: MultimapKey,
+        value: MultimapValue,
+    ) -> Result<EntryHash, Error> {
+        trace!("Insert value into Multimap {:?}", address.name());
+        let heads = self.multimap_heads(address).await?;
+        // The tombstones of the key are superseded by the new value.
+        let parents = heads
+            .iter()
+            .filter(|(_, entry)| matches!(entry, MultimapEntry::Remove { key: removed } if *removed == key))
+            .map(|(hash, _)| *hash)
+            .collect();
+
+        self.write_multimap_entry(address, MultimapEntry::Insert { key, value }, parents)
+            .await
+    }
+
+    /// Replace the values of a key of a Multimap with a single value, returning the hash of the
+    /// entry holding it.
+    ///
+    /// Only the values currently known are replaced, values inserted concurrently by other
+    /// writers are kept alongside the new one.
+    pub async fn multimap_replace(
+        &self,
+        address: RegisterAddress,
+        key: MultimapKey,
+        value: MultimapValue,
+    ) -> Result<EntryHash, Error> {
+        trace!("Replace values in Multimap {:?}", address.name());
+        let heads = self.multimap_heads(address).await?;
+        let parents = key_heads(&heads, &key);
+
+        self.write_multimap_entry(address, MultimapEntry::Insert { key, value }, parents)
+            .await
+    }
+
+    /// Remove all the values of a key of a Multimap, returning the hash of the tombstone entry.
+    pub async fn multimap_remove(
+        &self,
+        address: RegisterAddress,
+        key: MultimapKey,
+    ) -> Result<EntryHash, Error> {
+        trace!("Remove key from Multimap {:?}", address.name());
+        let heads = self.multimap_heads(address).await?;
+        if !current_entries(&heads).contains_key(&key) {
+            return Err(Error::from(crate::types::Error::NoSuchEntry));
+        }
+        let parents = key_heads(&heads, &key);
+
+        self.write_multimap_entry(address, MultimapEntry::Remove { key }, parents)
+            .await
+    }
+
+    /// Remove a single value of a Multimap, given the hash of the entry holding it, returning the
+    /// hash of the tombstone entry. The other values of its key are kept, which allows resolving
+    /// conflicting values.
+    pub async fn multimap_remove_value(
+        &self,
+        address: RegisterAddress,
+        hash: EntryHash,
+    ) -> Result<EntryHash, Error> {
+        trace!("Remove value from Multimap {:?}", address.name());
+        let heads = self.multimap_heads(address).await?;
+        let key = heads
+            .into_iter()
+            .find_map(|(head, entry)| match entry {
+                MultimapEntry::Insert { key, .. } if head == hash => Some(key),
+                _ => None,
+            })
+            .ok_or_else(|| Error::from(crate::types::Error::NoSuchEntry))?;
+
+        let parents = vec![hash].into_iter().collect();
+        self.write_multimap_entry(address, MultimapEntry::Remove { key }, parents)
+            .await
+    }
+
+    /// Get the current values of a key of a Multimap, along with the hashes of the entries
+    /// holding them.
+    ///
+    /// A key has several values when they were inserted alongside each other, or when they were
+    /// written concurrently, e.g. by different writers replacing the same value. Such conflicts
+    /// can be resolved with `multimap_replace` or `multimap_remove_value`.
+    pub async fn multimap_get_by_key(
+        &self,
+        address: RegisterAddress,
+        key: &[u8],
+    ) -> Result<BTreeSet<(EntryHash, MultimapValue)>, Error> {
+        let heads = self.multimap_heads(address).await?;
+        Ok(current_entries(&heads).remove(key).unwrap_or_default())
+    }
+
+    /// Get the current values of all the keys of a Multimap.
+    pub async fn multimap_entries(
+        &self,
+        address: RegisterAddress,
+    ) -> Result<MultimapEntries, Error> {
+        let heads = self.multimap_heads(address).await?;
+        Ok(current_entries(&heads))
+    }
+
+    /// List all the entries ever written to a Multimap, including the superseded values and the
+    /// tombstones, from the current entries back to the first ones.
+    pub async fn multimap_history(
+        &self,
+        address: RegisterAddress,
+    ) -> Result<Vec<(EntryHash, MultimapEntry)>, Error> {
+        trace!("Get history of Multimap {:?}", address.name());
+        let register = self.get_register(address).await?;
+
+        let mut history = Vec::new();
+        let mut visited = BTreeSet::new();
+        let mut pending: VecDeque<_> = register.read(None)?.into_iter().collect();
+        while let Some((hash, entry)) = pending.pop_front() {
+            if !visited.insert(hash) {
+                continue;
+            }
+            pending.extend(register.parents(hash, None)?);
+            let entry = self.decrypt_entry(&address, entry)?;
+            history.push((hash, deserialize(&entry)?));
+        }
+
+        Ok(history)
+    }
+
+    // Returns the current entries of the Multimap's Register, values and tombstones.
+    async fn multimap_heads(
+        &self,
+        address: RegisterAddress,
+    ) -> Result<BTreeSet<(EntryHash, MultimapEntry)>, Error> {
+        self.read_register(address)
+            .await?
+            .into_iter()
+            .map(|(hash, entry)| Ok((hash, deserialize(&entry)?)))
+            .collect()
+    }
+
+    async fn write_multimap_entry(
+        &self,
+        address: RegisterAddress,
+        entry: MultimapEntry,
+        parents: BTreeSet<EntryHash>,
+    ) -> Result<EntryHash, Error> {
+        self.write_to_register(address, serialize(&entry)?, parents)
+            .await
+    }
+}
+
+// Returns the hashes of the current entries about `key`, values and tombstones.
+fn key_heads(heads: &BTreeSet<(EntryHash, MultimapEntry)>, key: &[u8]) -> BTreeSet<EntryHash> {
+    heads
+        .iter()
+        .filter(|(_, entry)| entry.key().as_slice() == key)
+        .map(|(hash, _)| *hash)
+        .collect()
+}
+
+// Groups the current values by key, skipping the tombstones.
+fn current_entries(heads: &BTreeSet<(EntryHash, MultimapEntry)>) -> MultimapEntries {
+    let mut entries = MultimapEntries::new();
+    for (hash, entry) in heads {
+        if let MultimapEntry::Insert { key, value } = entry {
+            let _ = entries
+                .entry(key.clone())
+                .or_default()
+                .insert((*hash, value.clone()));
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::{retry_loop, retry_loop_for_pattern};
+    use anyhow::Result;
+
+    #[test]
+    fn current_entries_skip_tombstones() {
+        let insert = |key: &[u8], value: &[u8]| MultimapEntry::Insert {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        };
+        let heads: BTreeSet<_> = vec![
+            ([1; 32], insert(b"a", b"1")),
+            ([2; 32], insert(b"a", b"2")),
+            ([3; 32], insert(b"b", b"3")),
+            ([4; 32], MultimapEntry::Remove { key: b"c".to_vec() }),
+        ]
+        .into_iter()
+        .collect();
+
+        let entries = current_entries(&heads);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries.get(b"a".as_ref()),
+            Some(
+                &vec![([1; 32], b"1".to_vec()), ([2; 32], b"2".to_vec())]
+                    .into_iter()
+                    .collect()
+            )
+        );
+        assert_eq!(key_heads(&heads, b"c"), vec![[4; 32]].into_iter().collect());
+    }
+
+    #[tokio::test]
+    pub async fn multimap_operations() -> Result<()> {
+        let client = create_test_client().await?;
+        let address = client
+            .store_multimap(XorName::random(), 15000, Kind::Public)
+            .await?;
+
+        let key = b"key".to_vec();
+        let first = retry_loop!(client.multimap_insert(address, key.clone(), b"first".to_vec()));
+        let _ = retry_loop_for_pattern!(client.multimap_get_by_key(address, &key), Ok(values) if values.len() == 1)?;
+
+        // Inserting keeps the value already there, so the key has two values.
+        let second = client
+            .multimap_insert(address, key.clone(), b"second".to_vec())
+            .await?;
+        let values = retry_loop_for_pattern!(client.multimap_get_by_key(address, &key), Ok(values) if values.len() == 2)?;
+        let expected: BTreeSet<_> = vec![(first, b"first".to_vec()), (second, b"second".to_vec())]
+            .into_iter()
+            .collect();
+        assert_eq!(values, expected);
+
+        // Removing a single value keeps the other one.
+        let _ = client.multimap_remove_value(address, first).await?;
+        let values = retry_loop_for_pattern!(client.multimap_get_by_key(address, &key), Ok(values) if values.len() == 1)?;
+        assert_eq!(
+            values,
+            vec![(second, b"second".to_vec())].into_iter().collect()
+        );
+
+        // Replacing supersedes the remaining value, and removing the key its replacement.
+        let _ = client
+            .multimap_replace(address, key.clone(), b"third".to_vec())
+            .await?;
+        let _ = retry_loop_for_pattern!(client.multimap_get_by_key(address, &key), Ok(values) if values.iter().any(|(_, value)| value == b"third"))?;
+        let _ = client.multimap_remove(address, key.clone()).await?;
+        let _ = retry_loop_for_pattern!(client.multimap_entries(address), Ok(entries) if entries.is_empty())?;
+
+        // The history has the three values, and the two tombstones.
+        let history = client.multimap_history(address).await?;
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[0].1, MultimapEntry::Remove { key });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::types::register::Address as RegisterAddress;
+use crate::url::{SafeUrl, NRS_MAP_TYPE_TAG};
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Map of the sub-names of an NRS top name to the URLs they resolve to.
+///
+/// The top name itself is keyed by the empty string, e.g. `safe://a.b.name` resolves through the
+/// `a.b` entry of the map of `name`, and `safe://name` through the `` entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NrsMap {
+    sub_names: BTreeMap<String, String>,
+}
+
+impl NrsMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the URL the sub-names resolve to, if any.
+    pub fn get(&self, sub_names: &str) -> Option<&str> {
+        self.sub_names.get(sub_names).map(String::as_str)
+    }
+
+    /// Points the sub-names to the target URL, returning the URL they pointed to before, if any.
+    pub fn insert(&mut self, sub_names: &str, target: &str) -> Option<String> {
+        self.sub_names
+            .insert(sub_names.to_string(), target.to_string())
+    }
+
+    /// Removes the sub-names, returning the URL they pointed to, if any.
+    pub fn remove(&mut self, sub_names: &str) -> Option<String> {
+        self.sub_names.remove(sub_names)
+    }
+
+    /// Iterates over the sub-names and the URLs they resolve to.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sub_names
+            .iter()
+            .map(|(sub_names, target)| (sub_names.as_str(), target.as_str()))
+    }
+
+    /// Returns whether the map has no sub-names.
+    pub fn is_empty(&self) -> bool {
+        self.sub_names.is_empty()
+    }
+}
+
+impl Client {
+    /// Register an NRS top name, with an empty `NrsMap` as its version 0.
+    ///
+    /// The versions of the top name's map are kept in a public Register owned by this client, at
+    /// the address the top name hashes to.
+    pub async fn nrs_create(&self, top_name: &str) -> Result<RegisterAddress, Error> {
+        trace!("Create NRS top name {}", top_name);
+
+        let url = parse_nrs_name(top_name)?;
+        if !url.sub_names().is_empty() {
+            return Err(Error::UnsupportedUrl(format!(
+                "NRS top names can't have sub-names: {}",
+                top_name
+            )));
+        }
+
+        self.create_versioned_blob(url.xorname(), NRS_MAP_TYPE_TAG, &NrsMap::new())
+            .await
+    }
+
+    /// Point an NRS name, e.g. `a.b.name`, to the target URL, returning the new version of the
+    /// top name's map. The target may itself be an NRS URL.
+    pub async fn nrs_add(&self, public_name: &str, target: &str) -> Result<u64, Error> {
+        trace!("Add NRS name {} pointing to {}", public_name, target);
+
+        let url = parse_nrs_name(public_name)?;
+        let _ = SafeUrl::from_url(target)?;
+
+        self.update_nrs_map(&url, |map| {
+            let _ = map.insert(url.sub_names(), target);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Remove an NRS name, e.g. `a.b.name`, from its top name's map, returning the new version of
+    /// the map.
+    pub async fn nrs_remove(&self, public_name: &str) -> Result<u64, Error> {
+        trace!("Remove NRS name {}", public_name);
+
+        let url = parse_nrs_name(public_name)?;
+
+        self.update_nrs_map(&url, |map| match map.remove(url.sub_names()) {
+            Some(_) => Ok(()),
+            None => Err(Error::NrsNameNotFound(public_name.to_string())),
+        })
+        .await
+    }
+
+    /// Get the `NrsMap` of an NRS top name at the given version, or at its latest one, along
+    /// with that version.
+    pub async fn nrs_get_map(
+        &self,
+        top_name: &str,
+        version: Option<u64>,
+    ) -> Result<(u64, NrsMap), Error> {
+        let url = parse_nrs_name(top_name)?;
+        self.get_versioned_blob(nrs_address(&url), version).await
+    }
+
+    /// Resolve an NRS URL to the XOR-URL it points to.
+    ///
+    /// NRS names pointing to other NRS URLs are followed until a XOR-URL is reached, failing if
+    /// they loop. The content version (`?v=`) of an NRS URL selects the version of its top name's
+    /// map, and its path is appended to the path of the URL it resolves to. XOR-URLs are returned
+    /// as they are.
+    pub async fn nrs_resolve(&self, url: &str) -> Result<SafeUrl, Error> {
+        trace!("Resolve NRS URL {}", url);
+
+        let mut url = SafeUrl::from_url(url)?;
+        let mut visited = BTreeSet::new();
+
+        while url.is_nrsurl() {
+            if !visited.insert((url.public_name().to_string(), url.content_version())) {
+                return Err(Error::NrsResolutionLoop(url.to_string()));
+            }
+
+            let (_, map) = self
+                .nrs_get_map(url.top_name(), url.content_version())
+                .await?;
+            let target = map
+                .get(url.sub_names())
+                .ok_or_else(|| Error::NrsNameNotFound(url.public_name().to_string()))?;
+            debug!("NRS name {} resolved to {}", url.public_name(), target);
+
+            let mut resolved = SafeUrl::from_url(target)?;
+            let path = url.path_decoded()?;
+            if !path.is_empty() {
+                let target_path = resolved.path_decoded()?;
+                resolved.set_path(&format!("{}{}", target_path.trim_end_matches('/'), path));
+            }
+            url = resolved;
+        }
+
+        Ok(url)
+    }
+
+    // Applies `update` to the latest map of the top name of `url`, returning the new version.
+    async fn update_nrs_map(
+        &self,
+        url: &SafeUrl,
+        update: impl FnOnce(&mut NrsMap) -> Result<(), Error>,
+    ) -> Result<u64, Error> {
+        let (version, _) = self.update_versioned_blob(nrs_address(url), update).await?;
+        Ok(version)
+    }
+}
+
+// Parses an NRS name such as `a.b.name`, with or without the `safe://` scheme.
+fn parse_nrs_name(name: &str) -> Result<SafeUrl, Error> {
+    let url = if name.starts_with("safe://") {
+        name.to_string()
+    } else {
+        format!("safe://{}", name)
+    };
+    Ok(SafeUrl::from_nrsurl(&url)?)
+}
+
+fn nrs_address(url: &SafeUrl) -> RegisterAddress {
+    RegisterAddress::Public {
+        name: url.xorname(),
+        tag: NRS_MAP_TYPE_TAG,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::retry_loop;
+    use crate::url::{SafeContentType, XorUrlBase};
+    use anyhow::{bail, Result};
+
+    #[test]
+    fn nrs_map_edits() {
+        let mut map = NrsMap::new();
+        assert!(map.is_empty());
+
+        assert_eq!(map.insert("", "safe://top-target"), None);
+        assert_eq!(map.insert("a.b", "safe://sub-target"), None);
+        assert_eq!(
+            map.insert("a.b", "safe://new-target"),
+            Some("safe://sub-target".to_string())
+        );
+        assert_eq!(map.get(""), Some("safe://top-target"));
+        assert_eq!(map.get("a.b"), Some("safe://new-target"));
+        assert_eq!(map.get("b"), None);
+
+        assert_eq!(map.remove(""), Some("safe://top-target".to_string()));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![("a.b", "safe://new-target")]
+        );
+    }
+
+    #[tokio::test]
+    pub async fn nrs_resolution() -> Result<()> {
+        let client = create_test_client().await?;
+        let blob = client.store_public_blob(b"nrs target").await?;
+        let blob_url =
+            SafeUrl::encode_blob(*blob.name(), SafeContentType::Raw, XorUrlBase::Base32z)?;
+
+        let top_name = format!("nrs-test-{}", rand::random::<u64>());
+        let _ = client.nrs_create(&top_name).await?;
+        let version = retry_loop!(client.nrs_add(&format!("sub.{}", top_name), &blob_url));
+        assert_eq!(version, 1);
+
+        // The top name points to the sub-name, which points to the Blob.
+        let sub_name_url = format!("safe://sub.{}", top_name);
+        let version = retry_loop!(client.nrs_add(&top_name, &sub_name_url));
+        assert_eq!(version, 2);
+
+        let resolved = retry_loop!(client.nrs_resolve(&format!("safe://{}", top_name)));
+        assert_eq!(resolved.to_string(), blob_url);
+
+        // Version 1 of the map didn't have the top name.
+        match client
+            .nrs_resolve(&format!("safe://{}?v=1", top_name))
+            .await
+        {
+            Err(Error::NrsNameNotFound(_)) => (),
+            other => bail!("Unexpected resolution of version 1: {:?}", other),
+        }
+
+        // Pointing the sub-name back to the top name makes a loop.
+        let _ = client
+            .nrs_add(
+                &format!("sub.{}", top_name),
+                &format!("safe://{}", top_name),
+            )
+            .await?;
+        match retry_loop!(async {
+            match client.nrs_resolve(&sub_name_url).await {
+                Err(Error::NrsResolutionLoop(url)) => Ok(url),
+                other => Err(other),
+            }
+        }) {
+            url if url.contains(&top_name) => Ok(()),
+            url => bail!("Unexpected loop detected at {}", url),
+        }
+    }
+}
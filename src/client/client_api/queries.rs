@@ -8,7 +8,10 @@
 
 use super::Client;
 use crate::client::{connections::QueryResult, errors::Error};
-use crate::messaging::client::{ClientSig, Query};
+use crate::messaging::{
+    client::{ClientSig, Query},
+    SectionAuthorityProvider,
+};
 use crate::types::{PublicKey, Signature};
 use log::debug;
 
@@ -37,4 +40,22 @@ impl Client {
 
         self.send_signed_query(query, client_pk, signature).await
     }
+
+    // Send a Query to the Elders of the given section, which need not be our own,
+    // and await a response.
+    pub(crate) async fn send_query_to_section(
+        &self,
+        query: Query,
+        section: &SectionAuthorityProvider,
+    ) -> Result<QueryResult, Error> {
+        debug!("Sending Query to section {:?}: {:?}", section.prefix, query);
+        let client_sig = ClientSig {
+            public_key: self.public_key(),
+            signature: self.keypair.sign(b"TODO"),
+        };
+
+        self.session
+            .send_query_to_section(query, client_sig, section)
+            .await
+    }
 }
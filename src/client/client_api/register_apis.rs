@@ -13,10 +13,10 @@ use crate::messaging::client::{
 };
 use crate::types::{
     register::{
-        Address, Entry, EntryHash, Permissions, Policy, PrivatePermissions, PrivatePolicy,
-        PublicPermissions, PublicPolicy, Register, User,
+        Address, BranchDiff, Entry, EntryHash, EntryNode, Permissions, Policy, PolicyUpdate,
+        PrivatePermissions, PrivatePolicy, PublicPermissions, PublicPolicy, Register, User,
     },
-    PublicKey,
+    Ownership, PublicKey,
 };
 use log::{debug, trace};
 use std::collections::{BTreeMap, BTreeSet};
@@ -53,6 +53,45 @@ impl Client {
         Ok(address)
     }
 
+    /// Create a Private Register which expires
+    ///
+    /// Like `store_private_register`, but the Register is deleted from the network once
+    /// `expiry`, in seconds since the Unix epoch, has passed, unless the expiry is extended.
+    pub async fn store_private_register_with_expiry(
+        &self,
+        name: XorName,
+        tag: u64,
+        owner: PublicKey,
+        permissions: BTreeMap<PublicKey, PrivatePermissions>,
+        expiry: u64,
+    ) -> Result<Address, Error> {
+        trace!(
+            "Store Private Register data {:?} expiring at {}",
+            name,
+            expiry
+        );
+        let pk = self.public_key();
+        let policy = PrivatePolicy { owner, permissions };
+        let mut priv_register = Register::new_private(pk, name, tag, Some(policy));
+        priv_register.set_expiry(expiry)?;
+        let address = *priv_register.address();
+
+        self.pay_and_write_register_to_network(priv_register)
+            .await?;
+
+        Ok(address)
+    }
+
+    /// Extend the expiry of a Private Register
+    ///
+    /// Only the owner can postpone the expiry, and only of a Register created with one.
+    pub async fn extend_register_expiry(&self, address: Address, expiry: u64) -> Result<(), Error> {
+        trace!("Extend expiry of Register data at {:?}", address.name());
+        let cmd = DataCmd::Register(RegisterWrite::ExtendExpiry { address, expiry });
+
+        self.pay_and_send_data_command(cmd).await
+    }
+
     /// Create a Public Register onto the Network
     ///
     /// Creates a public Register on the network which can then be written to.
@@ -112,6 +151,7 @@ impl Client {
         let mut register = self.get_register(address).await?;
 
         // We can now write the entry to the Register
+        let entry = self.encrypt_entry(&address, entry)?;
         let (hash, mut op) = register.write(entry, parents)?;
         let bytes = bincode::serialize(&op.crdt_op)?;
         let signature = self.keypair.sign(&bytes);
@@ -125,6 +165,35 @@ impl Client {
         Ok(hash)
     }
 
+    /// Merge branches of a Register
+    ///
+    /// Writes an entry on top of the given heads, resolving the fork between them. The heads
+    /// must be at least two of the current entries of the Register, as returned by
+    /// `read_register`, so branches aren't merged accidentally from a stale view.
+    pub async fn merge_register_branches(
+        &self,
+        address: Address,
+        entry: Entry,
+        heads: BTreeSet<EntryHash>,
+    ) -> Result<EntryHash, Error> {
+        trace!(
+            "Merge {} branches of Register data at {:?}",
+            heads.len(),
+            address.name()
+        );
+
+        let current = self.read_register(address).await?;
+        let merged = current
+            .iter()
+            .filter(|(hash, _)| heads.contains(hash))
+            .count();
+        if merged < 2 || merged != heads.len() {
+            return Err(Error::InvalidRegisterMerge(merged));
+        }
+
+        self.write_to_register(address, entry, heads).await
+    }
+
     /// Store a new Register data object
     /// Wraps msg_contents for payment validation and mutation
     pub(crate) async fn pay_and_write_register_to_network(
@@ -165,9 +234,11 @@ impl Client {
         );
 
         let register = self.get_register(address).await?;
-        let last = register.read(None)?;
-
-        Ok(last)
+        register
+            .read(None)?
+            .into_iter()
+            .map(|(hash, entry)| Ok((hash, self.decrypt_entry(&address, entry)?)))
+            .collect()
     }
 
     /// Get an entry from a Register on the Network by its hash
@@ -187,7 +258,143 @@ impl Client {
             .get(hash, None)?
             .ok_or_else(|| Error::from(crate::types::Error::NoSuchEntry))?;
 
-        Ok(entry.to_vec())
+        self.decrypt_entry(&address, entry.to_vec())
+    }
+
+    //----------------------
+    // History
+    //---------------------
+
+    /// Get all the entries of a Register, along with the hashes of the entries each of them was
+    /// written on top of, i.e. the whole history of the Register.
+    pub async fn get_register_entries(
+        &self,
+        address: Address,
+    ) -> Result<BTreeMap<EntryHash, EntryNode>, Error> {
+        trace!("Get all entries from Register data at {:?}", address.name());
+
+        let query = Query::Data(DataQuery::Register(RegisterRead::GetEntries(address)));
+        let query_result = self.send_query(query).await?;
+        let msg_id = query_result.msg_id;
+        let entries = match query_result.response {
+            QueryResponse::GetRegisterEntries(res) => {
+                res.map_err(|err| Error::from((err, msg_id)))?
+            }
+            _ => return Err(Error::ReceivedUnexpectedEvent),
+        };
+
+        entries
+            .into_iter()
+            .map(|(hash, node)| Ok((hash, self.decrypt_entry_node(&address, node)?)))
+            .collect()
+    }
+
+    /// Walk the history of a Register back from the entry with the given hash, breadth-first,
+    /// starting with the entry itself and ending with the first entries of the Register.
+    pub async fn get_register_ancestry(
+        &self,
+        address: Address,
+        hash: EntryHash,
+    ) -> Result<Vec<(EntryHash, EntryNode)>, Error> {
+        trace!(
+            "Get ancestry of entry {:?} from Register data at {:?}",
+            hash,
+            address.name()
+        );
+
+        let query = Query::Data(DataQuery::Register(RegisterRead::GetAncestry {
+            address,
+            hash,
+        }));
+        let query_result = self.send_query(query).await?;
+        let msg_id = query_result.msg_id;
+        let ancestry = match query_result.response {
+            QueryResponse::GetRegisterAncestry(res) => {
+                res.map_err(|err| Error::from((err, msg_id)))?
+            }
+            _ => return Err(Error::ReceivedUnexpectedEvent),
+        };
+
+        ancestry
+            .into_iter()
+            .map(|(hash, node)| Ok((hash, self.decrypt_entry_node(&address, node)?)))
+            .collect()
+    }
+
+    /// Compare the branches of a Register ending at the entries with the given hashes, e.g. two
+    /// of its current heads, returning the entries of each branch which aren't in the other's
+    /// history.
+    pub async fn diff_register_branches(
+        &self,
+        address: Address,
+        first: EntryHash,
+        second: EntryHash,
+    ) -> Result<BranchDiff, Error> {
+        trace!("Diff branches of Register data at {:?}", address.name());
+
+        let query = Query::Data(DataQuery::Register(RegisterRead::DiffBranches {
+            address,
+            first,
+            second,
+        }));
+        let query_result = self.send_query(query).await?;
+        let msg_id = query_result.msg_id;
+        let diff = match query_result.response {
+            QueryResponse::DiffRegisterBranches(res) => {
+                res.map_err(|err| Error::from((err, msg_id)))?
+            }
+            _ => return Err(Error::ReceivedUnexpectedEvent),
+        };
+
+        let decrypt = |entries: BTreeMap<EntryHash, EntryNode>| {
+            entries
+                .into_iter()
+                .map(|(hash, node)| Ok((hash, self.decrypt_entry_node(&address, node)?)))
+                .collect::<Result<_, Error>>()
+        };
+        Ok(BranchDiff {
+            first: decrypt(diff.first)?,
+            second: decrypt(diff.second)?,
+        })
+    }
+
+    /// Bring a locally cached replica of a Register up to date
+    ///
+    /// Instead of fetching the whole Register again, only the entries of the network's replica
+    /// which aren't in the history of the cached replica's current entries are fetched, as the
+    /// signed operations they were written with, and merged into it. Each operation's signature
+    /// and write permission are checked before it's merged. Returns the number of entries fetched.
+    pub async fn sync_register(&self, register: &mut Register) -> Result<usize, Error> {
+        let address = *register.address();
+        trace!("Sync Register data at {:?}", address.name());
+
+        let heads = register
+            .read(None)?
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        let query = Query::Data(DataQuery::Register(RegisterRead::GetOpsSince {
+            address,
+            heads,
+        }));
+        let query_result = self.send_query(query).await?;
+        let msg_id = query_result.msg_id;
+        let ops = match query_result.response {
+            QueryResponse::GetRegisterOps(res) => res.map_err(|err| Error::from((err, msg_id)))?,
+            _ => return Err(Error::ReceivedUnexpectedEvent),
+        };
+
+        let count = ops.len();
+        register.merge_ops(ops)?;
+
+        Ok(count)
+    }
+
+    fn decrypt_entry_node(&self, address: &Address, node: EntryNode) -> Result<EntryNode, Error> {
+        Ok(EntryNode {
+            entry: self.decrypt_entry(address, node.entry)?,
+            parents: node.parents,
+        })
     }
 
     //----------------------
@@ -204,6 +411,83 @@ impl Client {
         Ok(owner)
     }
 
+    /// Get the owners of a Register, and how many of them must sign updates of its policy.
+    pub async fn get_register_ownership(&self, address: Address) -> Result<Ownership, Error> {
+        trace!("Get ownership of the Register data at {:?}", address.name());
+
+        let register = self.get_register(address).await?;
+
+        Ok(register.ownership().clone())
+    }
+
+    /// Prepare an update of the policy and ownership of a Register, replacing its current
+    /// policy version, signed by this client.
+    ///
+    /// When the Register has several owners, the update must then be signed by enough of them,
+    /// with `PolicyUpdate::sign` or `PolicyUpdate::add_signature`, before being sent with
+    /// `update_register_policy`.
+    pub async fn register_policy_update(
+        &self,
+        address: Address,
+        policy: Policy,
+        ownership: Ownership,
+    ) -> Result<PolicyUpdate, Error> {
+        let register = self.get_register(address).await?;
+        let owners_changed =
+            policy.owner() != &register.owner() || &ownership != register.ownership();
+        self.check_owners_kept(&address, owners_changed)?;
+        let mut update = PolicyUpdate::new(address, register.policy_version(), policy, ownership);
+        update.sign(&self.keypair)?;
+
+        Ok(update)
+    }
+
+    /// Update the policy and ownership of a Register.
+    ///
+    /// The update must be signed by enough of the current owners, and fails if another update
+    /// was applied since it was prepared. It can't change the owners of a private Register while
+    /// entry encryption is enabled, see `Client::with_entry_encryption`.
+    pub async fn update_register_policy(&self, update: PolicyUpdate) -> Result<(), Error> {
+        trace!(
+            "Update policy of the Register data at {:?}",
+            update.address.name()
+        );
+        if self.encrypts_entries_of(&update.address) {
+            let register = self.get_register(update.address).await?;
+            let owners_changed = update.policy.owner() != &register.owner()
+                || &update.ownership != register.ownership();
+            self.check_owners_kept(&update.address, owners_changed)?;
+        }
+        let cmd = DataCmd::Register(RegisterWrite::UpdatePolicy(update));
+
+        self.pay_and_send_data_command(cmd).await
+    }
+
+    /// Hand a Register owned by this client over to a new owner, keeping its permissions.
+    pub async fn change_register_owner(
+        &self,
+        address: Address,
+        new_owner: PublicKey,
+    ) -> Result<(), Error> {
+        let policy = match self.get_register_policy(address).await? {
+            Policy::Public(policy) => PublicPolicy {
+                owner: new_owner,
+                ..policy
+            }
+            .into(),
+            Policy::Private(policy) => PrivatePolicy {
+                owner: new_owner,
+                ..policy
+            }
+            .into(),
+        };
+        let update = self
+            .register_policy_update(address, policy, Ownership::single(new_owner))
+            .await?;
+
+        self.update_register_policy(update).await
+    }
+
     //----------------------
     // Permissions
     //---------------------
@@ -245,7 +529,7 @@ mod tests {
     use crate::messaging::client::Error as ErrorMessage;
     use crate::types::{
         register::{Action, EntryHash, Permissions, PrivatePermissions, PublicPermissions, User},
-        Error as DtError, PublicKey, Token,
+        Error as DtError, Ownership, PublicKey, Token,
     };
     use crate::{retry_loop, retry_loop_for_pattern};
     use anyhow::{anyhow, bail, Result};
@@ -427,6 +711,105 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    pub async fn register_history_and_merge() -> Result<()> {
+        let name = XorName(rand::random());
+        let tag = 10;
+        let client = create_test_client().await?;
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<User, PublicPermissions>::new();
+        let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+        let address = client
+            .store_public_register(name, tag, owner, perms)
+            .await?;
+
+        // write two concurrent entries, creating a fork
+        let first =
+            retry_loop!(client.write_to_register(address, b"FIRST".to_vec(), BTreeSet::new()));
+        let second = client
+            .write_to_register(address, b"SECOND".to_vec(), BTreeSet::new())
+            .await?;
+        let _ =
+            retry_loop_for_pattern!(client.read_register(address), Ok(heads) if heads.len() == 2)?;
+
+        let diff = client
+            .diff_register_branches(address, first, second)
+            .await?;
+        assert_eq!(diff.first.keys().collect::<Vec<_>>(), vec![&first]);
+        assert_eq!(diff.second.keys().collect::<Vec<_>>(), vec![&second]);
+
+        // merging needs at least two current heads
+        match client
+            .merge_register_branches(
+                address,
+                b"MERGE".to_vec(),
+                vec![first].into_iter().collect(),
+            )
+            .await
+        {
+            Err(Error::InvalidRegisterMerge(1)) => {}
+            other => bail!("Unexpected result merging a single head: {:?}", other),
+        }
+
+        let heads: BTreeSet<_> = vec![first, second].into_iter().collect();
+        let merge = client
+            .merge_register_branches(address, b"MERGE".to_vec(), heads.clone())
+            .await?;
+        let _ =
+            retry_loop_for_pattern!(client.read_register(address), Ok(heads) if heads.len() == 1)?;
+
+        let entries = client.get_register_entries(address).await?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries.get(&merge).map(|node| &node.parents), Some(&heads));
+
+        let ancestry = client.get_register_ancestry(address, merge).await?;
+        assert_eq!(ancestry.len(), 3);
+        assert_eq!(ancestry[0].1.entry, b"MERGE".to_vec());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn sync_cached_register() -> Result<()> {
+        let name = XorName(rand::random());
+        let tag = 10;
+        let client = create_test_client().await?;
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<User, PublicPermissions>::new();
+        let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+        let address = client
+            .store_public_register(name, tag, owner, perms)
+            .await?;
+
+        let first =
+            retry_loop!(client.write_to_register(address, b"VALUE1".to_vec(), BTreeSet::new()));
+        let mut cached = retry_loop_for_pattern!(client.get_register(address), Ok(register) if register.size(None)? == 1)?;
+
+        let second = client
+            .write_to_register(
+                address,
+                b"VALUE2".to_vec(),
+                vec![first].into_iter().collect(),
+            )
+            .await?;
+
+        let mut synced = 0;
+        while synced == 0 {
+            synced += client.sync_register(&mut cached).await?;
+        }
+
+        assert_eq!(synced, 1);
+        let heads: Vec<_> = cached.read(None)?.into_iter().collect();
+        assert_eq!(heads, vec![(second, b"VALUE2".to_vec())]);
+
+        // nothing is left to sync
+        assert_eq!(client.sync_register(&mut cached).await?, 0);
+
+        Ok(())
+    }
+
     #[tokio::test]
     pub async fn register_owner() -> Result<()> {
         let name = XorName(rand::random());
@@ -448,6 +831,83 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn register_ownership_transfer() -> Result<()> {
+        let client = create_test_client().await?;
+        let new_owner = gen_ed_keypair().public_key();
+        let address = client
+            .store_public_register(XorName::random(), 10, client.public_key(), BTreeMap::new())
+            .await?;
+        let _ = retry_loop!(client.get_register(address));
+
+        client.change_register_owner(address, new_owner).await?;
+        let _ = retry_loop_for_pattern!(client.get_register_owner(address), Ok(owner) if *owner == new_owner)?;
+        assert_eq!(
+            client.get_register_ownership(address).await?,
+            Ownership::single(new_owner)
+        );
+
+        // The former owner can neither write nor hand the Register over again.
+        match client
+            .write_to_register(address, b"entry".to_vec(), BTreeSet::new())
+            .await
+        {
+            Err(Error::NetworkDataError(DtError::AccessDenied(_))) => (),
+            other => bail!("Unexpected write by the former owner: {:?}", other),
+        }
+        match client
+            .change_register_owner(address, client.public_key())
+            .await
+        {
+            Err(Error::ErrorMessage {
+                source: ErrorMessage::AccessDenied(_),
+                ..
+            })
+            | Ok(()) => (),
+            Err(err) => bail!("Unexpected error changing the owner: {:?}", err),
+        }
+        assert_eq!(client.get_register_owner(address).await?, new_owner);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn register_expiry_extension() -> Result<()> {
+        let client = create_test_client().await?;
+        let owner = client.public_key();
+        let expiry = u64::MAX / 2;
+        let address = client
+            .store_private_register_with_expiry(
+                XorName::random(),
+                15000,
+                owner,
+                BTreeMap::new(),
+                expiry,
+            )
+            .await?;
+        let register = retry_loop!(client.get_register(address));
+        assert_eq!(register.expiry(), Some(expiry));
+
+        client.extend_register_expiry(address, expiry + 1).await?;
+        let _ = retry_loop_for_pattern!(client.get_register(address), Ok(register) if register.expiry() == Some(expiry + 1))?;
+
+        // The expiry can't be brought forward.
+        match client.extend_register_expiry(address, expiry).await {
+            Err(Error::ErrorMessage {
+                source: ErrorMessage::InvalidOperation(_),
+                ..
+            })
+            | Ok(()) => (),
+            Err(err) => bail!("Unexpected error extending the expiry: {:?}", err),
+        }
+        assert_eq!(
+            client.get_register(address).await?.expiry(),
+            Some(expiry + 1)
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     pub async fn register_can_delete_private() -> Result<()> {
         let client = create_test_client().await?;
@@ -548,4 +1008,50 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    pub async fn register_policy_updates_should_cost_put_price() -> Result<()> {
+        let client = create_test_client().await?;
+        let address = client
+            .store_public_register(XorName::random(), 10, client.public_key(), BTreeMap::new())
+            .await?;
+        let _ = retry_loop!(client.get_register(address));
+
+        let balance_before_update = retry_loop_for_pattern!(client.get_balance(),
+            Ok(balance) if *balance != Token::from_str("0")? && *balance != Token::from_str("10")?)?;
+
+        client
+            .change_register_owner(address, gen_ed_keypair().public_key())
+            .await?;
+
+        // now let's ensure we've paid _something_
+        let _ = retry_loop_for_pattern!(client.get_balance(),
+            Ok(balance) if *balance != balance_before_update)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn encrypted_register_refuses_ownership_transfer() -> Result<()> {
+        let client = create_test_client().await?.with_entry_encryption(true);
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<PublicKey, PrivatePermissions>::new();
+        let _ = perms.insert(owner, PrivatePermissions::new(true, true));
+        let address = client
+            .store_private_register(XorName::random(), 10, owner, perms)
+            .await?;
+        let _ = retry_loop!(client.get_register(address));
+
+        // The new owner couldn't decrypt the entries, so the transfer is refused.
+        match client
+            .change_register_owner(address, gen_ed_keypair().public_key())
+            .await
+        {
+            Err(Error::EncryptedOwnershipTransfer) => (),
+            other => bail!("Unexpected result of the ownership transfer: {:?}", other),
+        }
+        assert_eq!(client.get_register_owner(address).await?, owner);
+
+        Ok(())
+    }
 }
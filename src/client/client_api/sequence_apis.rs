@@ -12,9 +12,9 @@ use crate::messaging::client::{
     Cmd, DataCmd, DataQuery, Query, QueryResponse, SequenceRead, SequenceWrite,
 };
 use crate::types::{
-    PublicKey, Sequence, SequenceAddress, SequenceEntries, SequenceEntry, SequenceIndex,
-    SequencePermissions, SequencePrivatePermissions, SequencePrivatePolicy,
-    SequencePublicPermissions, SequencePublicPolicy, SequenceUser,
+    Ownership, PublicKey, Sequence, SequenceAddress, SequenceEntries, SequenceEntry, SequenceIndex,
+    SequencePermissions, SequencePolicy, SequencePolicyUpdate, SequencePrivatePermissions,
+    SequencePrivatePolicy, SequencePublicPermissions, SequencePublicPolicy, SequenceUser,
 };
 use log::{debug, trace};
 use std::collections::BTreeMap;
@@ -55,7 +55,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -80,13 +80,66 @@ impl Client {
         permissions: BTreeMap<PublicKey, SequencePrivatePermissions>,
     ) -> Result<SequenceAddress, Error> {
         trace!("Store Private Sequence Data {:?}", name);
+        self.store_private_sequence_expiring(sequence, name, tag, owner, permissions, None)
+            .await
+    }
+
+    /// Create Private Sequence Data which expires
+    ///
+    /// Like `store_private_sequence`, but the Sequence is deleted from the network once
+    /// `expiry`, in seconds since the Unix epoch, has passed, unless the expiry is extended.
+    pub async fn store_private_sequence_with_expiry(
+        &self,
+        sequence: Option<SequenceEntries>,
+        name: XorName,
+        tag: u64,
+        owner: PublicKey,
+        permissions: BTreeMap<PublicKey, SequencePrivatePermissions>,
+        expiry: u64,
+    ) -> Result<SequenceAddress, Error> {
+        trace!(
+            "Store Private Sequence Data {:?} expiring at {}",
+            name,
+            expiry
+        );
+        self.store_private_sequence_expiring(sequence, name, tag, owner, permissions, Some(expiry))
+            .await
+    }
+
+    /// Extend the expiry of Private Sequence Data
+    ///
+    /// Only the owner can postpone the expiry, and only of a Sequence created with one.
+    pub async fn extend_sequence_expiry(
+        &self,
+        address: SequenceAddress,
+        expiry: u64,
+    ) -> Result<(), Error> {
+        trace!("Extend expiry of Sequence Data at {:?}", address.name());
+        let cmd = DataCmd::Sequence(SequenceWrite::ExtendExpiry { address, expiry });
+
+        self.pay_and_send_data_command(cmd).await
+    }
+
+    async fn store_private_sequence_expiring(
+        &self,
+        sequence: Option<SequenceEntries>,
+        name: XorName,
+        tag: u64,
+        owner: PublicKey,
+        permissions: BTreeMap<PublicKey, SequencePrivatePermissions>,
+        expiry: Option<u64>,
+    ) -> Result<SequenceAddress, Error> {
         let pk = self.public_key();
         let policy = SequencePrivatePolicy { owner, permissions };
         let mut data = Sequence::new_private(pk, pk.to_string(), name, tag, Some(policy));
+        if let Some(expiry) = expiry {
+            data.set_expiry(expiry)?;
+        }
         let address = *data.address();
 
         if let Some(entries) = sequence {
             for entry in entries {
+                let entry = self.encrypt_entry(&address, entry)?;
                 let mut op = data.create_unsigned_append_op(entry)?;
                 let bytes = bincode::serialize(&op.crdt_op)?;
                 let signature = self.keypair.sign(&bytes);
@@ -126,7 +179,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -158,6 +211,7 @@ impl Client {
 
         if let Some(entries) = sequence {
             for entry in entries {
+                let entry = self.encrypt_entry(&address, entry)?;
                 let mut op = data.create_unsigned_append_op(entry)?;
                 let bytes = bincode::serialize(&op.crdt_op)?;
                 let signature = self.keypair.sign(&bytes);
@@ -193,7 +247,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -246,7 +300,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -274,6 +328,7 @@ impl Client {
         let mut sequence = self.get_sequence(address).await?;
 
         // We can now append the entry to the Sequence
+        let entry = self.encrypt_entry(&address, entry)?;
         let mut op = sequence.create_unsigned_append_op(entry)?;
         let bytes = bincode::serialize(&op.crdt_op)?;
         let signature = self.keypair.sign(&bytes);
@@ -319,7 +374,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -368,7 +423,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -404,7 +459,10 @@ impl Client {
         let sequence = self.get_sequence(address).await?;
         // TODO: do we need to query with some specific PK?
         match sequence.last_entry(None)? {
-            Some(entry) => Ok((sequence.len(None)? - 1, entry.to_vec())),
+            Some(entry) => Ok((
+                sequence.len(None)? - 1,
+                self.decrypt_entry(&address, entry.to_vec())?,
+            )),
             None => Err(Error::from(crate::types::Error::NoSuchEntry)),
         }
     }
@@ -426,7 +484,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -463,7 +521,7 @@ impl Client {
         let sequence = self.get_sequence(address).await?;
         let index = SequenceIndex::FromStart(index_from_start);
         match sequence.get(index, None)? {
-            Some(entry) => Ok(entry.to_vec()),
+            Some(entry) => self.decrypt_entry(&address, entry.to_vec()),
             None => Err(Error::from(crate::types::Error::NoSuchEntry)),
         }
     }
@@ -485,7 +543,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -525,7 +583,39 @@ impl Client {
         // TODO: do we need to query with some specific PK?
         sequence
             .in_range(range.0, range.1, None)?
-            .ok_or_else(|| Error::from(crate::types::Error::NoSuchEntry))
+            .ok_or_else(|| Error::from(crate::types::Error::NoSuchEntry))?
+            .into_iter()
+            .map(|entry| self.decrypt_entry(&address, entry))
+            .collect()
+    }
+
+    /// Bring a locally cached replica of a Sequence up to date
+    ///
+    /// Instead of fetching the whole Sequence again, only the entries of the network's replica
+    /// which aren't covered by the cached one's clock are fetched, and merged into it. Returns
+    /// the number of entries fetched.
+    pub async fn sync_sequence(&self, sequence: &mut Sequence) -> Result<usize, Error> {
+        let address = *sequence.address();
+        let clock = sequence.clock(None)?;
+        trace!(
+            "Sync Sequence Data at {:?} from clock {}",
+            address.name(),
+            clock
+        );
+
+        let query_result = self
+            .send_query(wrap_seq_read(SequenceRead::GetOpsSince { address, clock }))
+            .await?;
+        let msg_id = query_result.msg_id;
+        let ops = match query_result.response {
+            QueryResponse::GetSequenceOps(res) => res.map_err(|err| Error::from((err, msg_id)))?,
+            _ => return Err(Error::ReceivedUnexpectedEvent),
+        };
+
+        let count = ops.len();
+        sequence.merge_ops(ops);
+
+        Ok(count)
     }
 
     //----------------------
@@ -549,7 +639,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -584,6 +674,89 @@ impl Client {
         Ok(owner)
     }
 
+    /// Get the owners of a Sequence, and how many of them must sign updates of its policy.
+    pub async fn get_sequence_ownership(
+        &self,
+        address: SequenceAddress,
+    ) -> Result<Ownership, Error> {
+        trace!("Get ownership of the Sequence Data at {:?}", address.name());
+
+        let sequence = self.get_sequence(address).await?;
+
+        Ok(sequence.ownership().clone())
+    }
+
+    /// Prepare an update of the policy and ownership of a Sequence, replacing its current
+    /// policy version, signed by this client.
+    ///
+    /// When the Sequence has several owners, the update must then be signed by enough of them,
+    /// with `PolicyUpdate::sign` or `PolicyUpdate::add_signature`, before being sent with
+    /// `update_sequence_policy`.
+    pub async fn sequence_policy_update(
+        &self,
+        address: SequenceAddress,
+        policy: SequencePolicy,
+        ownership: Ownership,
+    ) -> Result<SequencePolicyUpdate, Error> {
+        let sequence = self.get_sequence(address).await?;
+        let owners_changed =
+            policy.owner() != &sequence.owner() || &ownership != sequence.ownership();
+        self.check_owners_kept(&address, owners_changed)?;
+        let mut update =
+            SequencePolicyUpdate::new(address, sequence.policy_version(), policy, ownership);
+        update.sign(&self.keypair)?;
+
+        Ok(update)
+    }
+
+    /// Update the policy and ownership of a Sequence.
+    ///
+    /// The update must be signed by enough of the current owners, and fails if another update
+    /// was applied since it was prepared. It can't change the owners of a private Sequence while
+    /// entry encryption is enabled, see `Client::with_entry_encryption`.
+    pub async fn update_sequence_policy(&self, update: SequencePolicyUpdate) -> Result<(), Error> {
+        trace!(
+            "Update policy of the Sequence Data at {:?}",
+            update.address.name()
+        );
+        if self.encrypts_entries_of(&update.address) {
+            let sequence = self.get_sequence(update.address).await?;
+            let owners_changed = update.policy.owner() != &sequence.owner()
+                || &update.ownership != sequence.ownership();
+            self.check_owners_kept(&update.address, owners_changed)?;
+        }
+        let cmd = DataCmd::Sequence(SequenceWrite::UpdatePolicy(update));
+
+        self.pay_and_send_data_command(cmd).await
+    }
+
+    /// Hand a Sequence owned by this client over to a new owner, keeping its permissions.
+    pub async fn change_sequence_owner(
+        &self,
+        address: SequenceAddress,
+        new_owner: PublicKey,
+    ) -> Result<(), Error> {
+        let sequence = self.get_sequence(address).await?;
+        let policy = if sequence.is_public() {
+            SequencePublicPolicy {
+                owner: new_owner,
+                ..sequence.public_policy()?.clone()
+            }
+            .into()
+        } else {
+            SequencePrivatePolicy {
+                owner: new_owner,
+                ..sequence.private_policy(None)?.clone()
+            }
+            .into()
+        };
+        let update = self
+            .sequence_policy_update(address, policy, Ownership::single(new_owner))
+            .await?;
+
+        self.update_sequence_policy(update).await
+    }
+
     //----------------------
     // Permissions
     //---------------------
@@ -606,7 +779,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -667,7 +840,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -726,7 +899,7 @@ impl Client {
 
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
     /// let mut client = Client::new(Some(id), None, bootstrap_contacts).await?;
-    /// # let initial_balance = Token::from_str("100")?; client.trigger_simulated_farming_payout(initial_balance).await?;
+    /// # let initial_balance = Token::from_str("100")?; safe_network::client::utils::test_utils::fund_from_faucet(&client, initial_balance).await?;
     /// let name = XorName::random();
     /// let tag = 10;
     /// let owner = client.public_key();
@@ -797,6 +970,28 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn sequence_policy_updates_should_cost_put_price() -> Result<()> {
+        let name = XorName(rand::random());
+        let tag = 10;
+        let client = create_test_client().await?;
+        let owner = client.public_key();
+        let perms = BTreeMap::<PublicKey, SequencePrivatePermissions>::new();
+        let sequence_address =
+            retry_loop!(client.store_private_sequence(None, name, tag, owner, perms.clone()));
+
+        let balance_before_update = retry_loop_for_pattern!(client.get_balance(), Ok(bal) if *bal != Token::from_str("10")? && *bal != Token::from_str("0")?)?;
+
+        let new_owner = gen_ed_keypair().public_key();
+        retry_loop!(client.change_sequence_owner(sequence_address, new_owner));
+
+        // now let's ensure we've paid _something_
+        let _ =
+            retry_loop_for_pattern!(client.get_balance(), Ok(bal) if *bal != balance_before_update);
+
+        Ok(())
+    }
+
     /// Sequence data tests ///
 
     #[tokio::test]
@@ -1040,6 +1235,48 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    pub async fn sync_cached_sequence() -> Result<()> {
+        let name = XorName(rand::random());
+        let tag = 10;
+        let client = create_test_client().await?;
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<SequenceUser, SequencePublicPermissions>::new();
+        let _ = perms.insert(
+            SequenceUser::Key(owner),
+            SequencePublicPermissions::new(true),
+        );
+        let address = client
+            .store_public_sequence(None, name, tag, owner, perms)
+            .await?;
+
+        retry_loop!(client.append_to_sequence(address, b"VALUE1".to_vec()));
+        let mut cached = retry_loop_for_pattern!(client.get_sequence(address), Ok(sequence) if sequence.len(None)? == 1)?;
+        assert_eq!(cached.clock(None)?.dots.values().sum::<u64>(), 1);
+
+        client
+            .append_to_sequence(address, b"VALUE2".to_vec())
+            .await?;
+        client
+            .append_to_sequence(address, b"VALUE3".to_vec())
+            .await?;
+
+        let mut synced = 0;
+        while synced < 2 {
+            synced += client.sync_sequence(&mut cached).await?;
+        }
+
+        assert_eq!(synced, 2);
+        assert_eq!(cached.len(None)?, 3);
+        assert_eq!(
+            cached.last_entry(None)?.map(|entry| entry.as_slice()),
+            Some(b"VALUE3".as_ref())
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     pub async fn sequence_owner() -> Result<()> {
         let name = XorName(rand::random());
@@ -1061,6 +1298,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn sequence_ownership_transfer() -> Result<()> {
+        let client = create_test_client().await?;
+        let owner = client.public_key();
+        let new_owners = [gen_ed_keypair(), gen_ed_keypair()];
+        let address = client
+            .store_public_sequence(None, XorName::random(), 10, owner, BTreeMap::new())
+            .await?;
+        let _ = retry_loop!(client.get_sequence(address));
+
+        // Hand the Sequence over to two owners, who must both sign the next update.
+        let ownership = Ownership::multi(
+            new_owners
+                .iter()
+                .map(|keypair| keypair.public_key())
+                .collect(),
+            2,
+        )?;
+        let policy = SequencePublicPolicy {
+            owner: new_owners[0].public_key(),
+            permissions: BTreeMap::new(),
+        };
+        let update = client
+            .sequence_policy_update(address, policy.clone().into(), ownership.clone())
+            .await?;
+        client.update_sequence_policy(update).await?;
+        let _ = retry_loop_for_pattern!(client.get_sequence_ownership(address), Ok(current) if *current == ownership)?;
+
+        // Handing it back takes the signatures of both of them.
+        let policy = SequencePublicPolicy { owner, ..policy };
+        let mut update =
+            SequencePolicyUpdate::new(address, 1, policy.into(), Ownership::single(owner));
+        update.sign(&new_owners[0])?;
+        match client.update_sequence_policy(update.clone()).await {
+            Err(Error::ErrorMessage {
+                source: ErrorMessage::InvalidOperation(_),
+                ..
+            })
+            | Ok(()) => (),
+            Err(err) => bail!("Unexpected error updating the policy: {:?}", err),
+        }
+        assert_eq!(client.get_sequence_ownership(address).await?, ownership);
+
+        update.sign(&new_owners[1])?;
+        client.update_sequence_policy(update).await?;
+        let _ = retry_loop_for_pattern!(client.get_sequence_owner(address), Ok(current) if *current == owner)?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     pub async fn sequence_can_delete_private() -> Result<()> {
         let client = create_test_client().await?;
@@ -0,0 +1,148 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::{
+    topology::{NetworkCrawler, NetworkTopology, QuicKnowledgeSource, SupplyAudit},
+    Error, QuicP2pConfig,
+};
+use crate::messaging::{
+    client::{ClientSig, Query, QueryResponse, TransferQuery},
+    SectionAuthorityProvider,
+};
+use bls::PublicKey as BlsPublicKey;
+use log::debug;
+use std::{collections::BTreeMap, time::Duration};
+use xor_name::XorName;
+
+// How long to wait for each Elder to answer a `SectionKnowledgeQuery`.
+const SECTION_KNOWLEDGE_TIMEOUT: Duration = Duration::from_secs(10);
+// How long to wait for the Elders of a section to answer a `GetSectionSupply` query.
+const SECTION_SUPPLY_TIMEOUT: Duration = Duration::from_secs(20);
+
+impl Client {
+    /// Crawl the whole network, starting from the Elders of our section, and return its topology.
+    ///
+    /// Every section is verified against `genesis_key`. If none is given, the genesis key reported
+    /// by our own section is trusted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate tokio; use anyhow::Result;
+    /// # use safe_network::client::utils::test_utils::read_network_conn_info;
+    /// use safe_network::client::Client;
+    /// # #[tokio::main] async fn main() { let _: Result<()> = futures::executor::block_on( async {
+    /// # let bootstrap_contacts = Some(read_network_conn_info()?);
+    /// let client = Client::new(None, None, bootstrap_contacts).await?;
+    /// let topology = client.network_topology(None).await?;
+    /// println!("{}", topology.to_dot());
+    /// # Ok(()) } ); }
+    /// ```
+    pub async fn network_topology(
+        &self,
+        genesis_key: Option<BlsPublicKey>,
+    ) -> Result<NetworkTopology, Error> {
+        let source = self.knowledge_source().await?;
+        let seeds = self.session.known_elders_addrs().await;
+
+        NetworkCrawler::new(source, genesis_key).crawl(seeds).await
+    }
+
+    // Finds the section responsible for `name`, e.g. the one holding the wallet of a key,
+    // walking the network from our section. Returns `None` if that is our own section.
+    pub(crate) async fn section_of(
+        &self,
+        name: XorName,
+    ) -> Result<Option<SectionAuthorityProvider>, Error> {
+        let prefix = self
+            .session
+            .section_prefix()
+            .await
+            .ok_or(Error::NoSectionPrefixKnown)?;
+        if prefix.matches(&name) {
+            return Ok(None);
+        }
+
+        let source = self.knowledge_source().await?;
+        let seeds = self.session.known_elders_addrs().await;
+        let section = NetworkCrawler::new(source, None)
+            .find_section(seeds, name)
+            .await?;
+        debug!("{} is in section {:b}", name, section.prefix);
+
+        Ok(Some(section))
+    }
+
+    // A source of section knowledge on a fresh endpoint, next to our own.
+    async fn knowledge_source(&self) -> Result<QuicKnowledgeSource, Error> {
+        let qp2p_config = QuicP2pConfig {
+            local_ip: Some(self.session.endpoint()?.local_addr().ip()),
+            local_port: Some(0),
+            forward_port: false,
+            ..Default::default()
+        };
+        QuicKnowledgeSource::new(qp2p_config, SECTION_KNOWLEDGE_TIMEOUT).await
+    }
+
+    /// Audit the token supply of the whole network.
+    ///
+    /// The network is crawled as by `network_topology`, then the Elders of every section are
+    /// asked for the token totals of their section, and the totals each section signed with its
+    /// verified key are checked against the maximum supply and against each other.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate tokio; use anyhow::Result;
+    /// # use safe_network::client::utils::test_utils::read_network_conn_info;
+    /// use safe_network::client::Client;
+    /// # #[tokio::main] async fn main() { let _: Result<()> = futures::executor::block_on( async {
+    /// # let bootstrap_contacts = Some(read_network_conn_info()?);
+    /// let client = Client::new(None, None, bootstrap_contacts).await?;
+    /// let audit = client.audit_supply(None).await?;
+    /// for inconsistency in &audit.inconsistencies {
+    ///     println!("{}", inconsistency);
+    /// }
+    /// # Ok(()) } ); }
+    /// ```
+    pub async fn audit_supply(
+        &self,
+        genesis_key: Option<BlsPublicKey>,
+    ) -> Result<SupplyAudit, Error> {
+        let topology = self.network_topology(genesis_key).await?;
+
+        let mut shares = BTreeMap::new();
+        for (prefix, section) in &topology.sections {
+            let query = Query::Transfer(TransferQuery::GetSectionSupply {
+                section: prefix.name(),
+            });
+            let client_sig = ClientSig {
+                public_key: self.public_key(),
+                signature: self.keypair.sign(b"TODO"),
+            };
+            let elders = section.elders.values().copied().collect();
+            let section_shares = self
+                .session
+                .send_query_to_elders(query, client_sig, elders, SECTION_SUPPLY_TIMEOUT)
+                .await?
+                .into_iter()
+                .filter_map(|response| match response {
+                    QueryResponse::GetSectionSupply(Ok(share)) => Some(share),
+                    response => {
+                        debug!("Unexpected response to supply query: {:?}", response);
+                        None
+                    }
+                })
+                .collect();
+            let _ = shares.insert(*prefix, section_shares);
+        }
+
+        Ok(SupplyAudit::new(&topology, shares))
+    }
+}
@@ -6,13 +6,17 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::messaging::client::{Cmd, Event, Query, QueryResponse, TransferCmd, TransferQuery};
-use crate::transfers::{ActorEvent, TransferInitiated};
-use crate::types::{PublicKey, SignedTransfer, Token, TransferAgreementProof};
+use crate::messaging::{
+    client::{Cmd, Event, Query, QueryResponse, TransferCmd, TransferQuery},
+    SectionAuthorityProvider,
+};
+use crate::transfers::{ActorEvent, Error as TransfersError, TransferActor, TransferInitiated};
+use crate::types::{PublicKey, SignedTransfer, Signing, Token, TransferAgreementProof};
 
 use crate::client::{Client, Error};
 
 use log::{debug, info, trace};
+use tokio::sync::RwLock;
 
 /// Handle all token transfers and Write API requests for a given ClientId.
 impl Client {
@@ -97,10 +101,10 @@ impl Client {
     /// # Examples
     ///
     /// Send token to a PublickKey.
-    /// (This test uses "simulated payouts" to generate test token. This of course would not be avaiable on a live network.)
+    /// (This test uses the local testnet faucet to generate test token. This of course would not be avaiable on a live network.)
     /// ```no_run
     /// # extern crate tokio;use anyhow::Result;
-    /// # use safe_network::client::utils::test_utils::read_network_conn_info;
+    /// # use safe_network::client::utils::test_utils::{fund_from_faucet, read_network_conn_info};
     /// use safe_network::client::Client;
     /// use safe_network::types::{PublicKey, Token};
     /// use std::str::FromStr;
@@ -110,10 +114,10 @@ impl Client {
     /// let pk = PublicKey::from(sk.public_key());
     /// // Next we create a random client.
     /// # let bootstrap_contacts = Some(read_network_conn_info()?);
-    /// let mut client = Client::new(None, None, bootstrap_contacts).await?;
+    /// let client = Client::new(None, None, bootstrap_contacts).await?;
     /// let target_balance = Token::from_str("100")?;
-    /// // And trigger a simulated payout to our client's PublicKey, so we have token to send.
-    /// let _ = client.trigger_simulated_farming_payout(target_balance).await?;
+    /// // And get token from the faucet to our client's PublicKey, so we have token to send.
+    /// fund_from_faucet(&client, target_balance).await?;
     ///
     /// // Now we have 100 token at our balance, we can send it elsewhere:
     /// let (count, sending_pk) = client.send_tokens( pk, target_balance ).await?;
@@ -145,43 +149,100 @@ impl Client {
             .transfer(amount, to, "".to_string())?
             .ok_or(Error::NoTransferGenerated)?;
 
+        let dot = self
+            .send_initiated_transfer(&self.transfer_actor, None, initiated)
+            .await?
+            .id();
+        Ok((dot.counter, dot.actor))
+    }
+
+    /// Send token to several recipients at once, from a single debit of our wallet.
+    ///
+    /// Either all the recipients are credited, or none of them is.
+    /// Returns the same debit version and sender key as `Client::send_tokens`.
+    pub async fn send_tokens_batch(
+        &self,
+        payouts: Vec<(PublicKey, Token)>,
+    ) -> Result<(u64, PublicKey), Error> {
+        info!("Sending token to {} recipients", payouts.len());
+
+        let payouts = payouts
+            .into_iter()
+            .map(|(to, amount)| (amount, to))
+            .collect();
+        let initiated = self
+            .transfer_actor
+            .read()
+            .await
+            .transfer_batch(payouts, "".to_string())?
+            .ok_or(Error::NoTransferGenerated)?;
+
+        let dot = self
+            .send_initiated_transfer(&self.transfer_actor, None, initiated)
+            .await?
+            .id();
+        Ok((dot.counter, dot.actor))
+    }
+
+    // Validates and registers a transfer initiated at the given local actor, with the Replicas
+    // of the given section if any, or else of ours.
+    pub(super) async fn send_initiated_transfer<S: Signing>(
+        &self,
+        transfer_actor: &RwLock<TransferActor<S>>,
+        section: Option<&SectionAuthorityProvider>,
+        initiated: TransferInitiated,
+    ) -> Result<TransferAgreementProof, Error> {
         let signed_transfer = SignedTransfer {
             debit: initiated.signed_debit,
             credit: initiated.signed_credit,
+            batch: initiated.batch,
+            escrow: initiated.escrow,
         };
-        let dot = signed_transfer.id();
-        let cmd = Cmd::Transfer(TransferCmd::ValidateTransfer(signed_transfer.clone()));
-
-        self.transfer_actor
+        transfer_actor
             .write()
             .await
             .apply(ActorEvent::TransferInitiated(TransferInitiated {
                 signed_debit: signed_transfer.debit.clone(),
                 signed_credit: signed_transfer.credit.clone(),
+                batch: signed_transfer.batch.clone(),
+                escrow: signed_transfer.escrow.clone(),
             }))?;
 
-        let transfer_proof: TransferAgreementProof =
-            self.await_validation(cmd, signed_transfer.id()).await?;
-
-        // Register the transfer on the network.
-        let cmd = Cmd::Transfer(TransferCmd::RegisterTransfer(transfer_proof.clone()));
+        let transfer_proof: TransferAgreementProof = self
+            .validate_initiated_transfer(transfer_actor, section, signed_transfer)
+            .await?;
 
         trace!(
             "Transfer proof received and to be sent in RegisterTransfer req: {:?}",
             transfer_proof
         );
 
-        self.send_cmd(cmd, None).await?;
-
-        let mut actor = self.transfer_actor.write().await;
-        // First register with local actor, then reply.
-        let register_event = actor
-            .register(transfer_proof)?
-            .ok_or(Error::NoTransferEventsForLocalActor)?;
+        // Debits are registered in order, so wait for any earlier debit in flight.
+        loop {
+            let mut actor = transfer_actor.write().await;
+            let register_event = match actor.register(transfer_proof.clone()) {
+                Err(TransfersError::DebitPending) => {
+                    // Created under the lock, so that no registration is missed.
+                    let progressed = self.transfers_progressed.notified();
+                    drop(actor);
+                    progressed.await;
+                    continue;
+                }
+                result => result?.ok_or(Error::NoTransferEventsForLocalActor)?,
+            };
+
+            // Register the transfer on the network, while holding the lock to keep the order.
+            let cmd = Cmd::Transfer(TransferCmd::RegisterTransfer(transfer_proof.clone()));
+            match section {
+                Some(section) => self.send_cmd_to_section(cmd, section).await?,
+                None => self.send_cmd(cmd, None).await?,
+            }
 
-        actor.apply(ActorEvent::TransferRegistrationSent(register_event))?;
+            actor.apply(ActorEvent::TransferRegistrationSent(register_event))?;
+            self.transfers_progressed.notify_waiters();
 
-        Ok((dot.counter, dot.actor))
+            return Ok(transfer_proof);
+        }
     }
 }
 
@@ -194,6 +255,7 @@ mod tests {
     use super::*;
     use crate::client::utils::{
         generate_random_vector, test_utils::calculate_new_balance, test_utils::create_test_client,
+        test_utils::fund_test_client,
     };
     use crate::client::TransfersError;
     use crate::types::{Keypair, Token};
@@ -248,6 +310,29 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn transfer_actor_can_send_batched_transfers() -> Result<()> {
+        let first = Keypair::new_ed25519(&mut OsRng).public_key();
+        let second = Keypair::new_ed25519(&mut OsRng).public_key();
+
+        let client = create_test_client().await?;
+
+        let _ = client
+            .send_tokens_batch(vec![
+                (first, Token::from_str("1")?),
+                (second, Token::from_str("2")?),
+            ])
+            .await?;
+
+        // Initial 10 token on creation from farming simulation minus 3
+        assert_eq!(client.get_local_balance().await, Token::from_str("7")?);
+        let _ = retry_loop_for_pattern!( client.get_balance(), Ok(bal) if *bal == Token::from_str("7")?);
+        let _ = retry_loop_for_pattern!( client.get_balance_for(first), Ok(bal) if *bal == Token::from_str("1")?);
+        let _ = retry_loop_for_pattern!( client.get_balance_for(second), Ok(bal) if *bal == Token::from_str("2")?);
+
+        Ok(())
+    }
+
     #[tokio::test]
     pub async fn transfer_actor_can_send_many_many_transfers() -> Result<()> {
         let keypair2 = Keypair::new_ed25519(&mut OsRng);
@@ -311,7 +396,7 @@ mod tests {
         Ok(())
     }
 
-    // 1. Create a client A and allocate 100 token to it. (Clients start with 10 token by default on simulated-farming)
+    // 1. Create a client A and allocate 100 token to it. (Test clients start with 10 token)
     // 2. Get the balance and verify it.
     // 3. Create another client B with a wallet holding 10 token on start.
     // 4. Transfer 11 token from client A to client B and verify the new balances.
@@ -322,9 +407,7 @@ mod tests {
 
         let wallet1 = receiving_client.public_key();
 
-        client
-            .trigger_simulated_farming_payout(Token::from_str("100.0")?)
-            .await?;
+        fund_test_client(&mut client, Token::from_str("100.0")?).await?;
 
         let mut balance = client.get_balance().await?;
 
@@ -0,0 +1,130 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Client, Error};
+use crate::messaging::client::{Cmd, TransferCmd};
+use crate::types::{DebitId, PublicKey, Token, TransferAgreementProof, TransferCondition};
+use log::info;
+
+impl Client {
+    /// Send token to a PublicKey, held in escrow by the Replicas of our wallet.
+    ///
+    /// The token is debited from our wallet right away, but only credited to the recipient
+    /// once the secret of the condition is revealed with `Client::release_escrow`.
+    /// If it isn't revealed before the deadline, the token can be refunded to us
+    /// with `Client::refund_escrow`.
+    pub async fn send_tokens_in_escrow(
+        &self,
+        to: PublicKey,
+        amount: Token,
+        condition: TransferCondition,
+    ) -> Result<TransferAgreementProof, Error> {
+        info!("Sending token in escrow");
+
+        let initiated = self
+            .transfer_actor
+            .read()
+            .await
+            .transfer_in_escrow(amount, to, condition, "".to_string())?
+            .ok_or(Error::NoTransferGenerated)?;
+
+        self.send_initiated_transfer(&self.transfer_actor, None, initiated)
+            .await
+    }
+
+    /// Release a transfer held in escrow to its recipient, by revealing the secret of its condition.
+    ///
+    /// Anyone knowing the secret can release it, until the deadline of the condition has passed.
+    pub async fn release_escrow(&self, id: DebitId, secret: Vec<u8>) -> Result<(), Error> {
+        info!("Releasing escrow of transfer {:?}", id);
+        let cmd = Cmd::Transfer(TransferCmd::ReleaseEscrow { id, secret });
+        self.send_cmd(cmd, None).await
+    }
+
+    /// Refund a transfer held in escrow to its sender, once the deadline of its condition has passed.
+    ///
+    /// The Elders refund it only `ESCROW_GRACE_SECS` after the deadline, as their clocks may differ.
+    pub async fn refund_escrow(&self, id: DebitId) -> Result<(), Error> {
+        info!("Refunding escrow of transfer {:?}", id);
+        let cmd = Cmd::Transfer(TransferCmd::RefundEscrow(id));
+        self.send_cmd(cmd, None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::utils::test_utils::{create_test_client, create_test_client_with};
+    use crate::transfers::ESCROW_GRACE_SECS;
+    use crate::types::{Keypair, Token, TransferCondition};
+    use anyhow::{anyhow, Result};
+    use rand::rngs::OsRng;
+    use std::str::FromStr;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use tokio::time::{sleep, Duration};
+
+    fn now() -> Result<u64> {
+        Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+    }
+
+    #[tokio::test]
+    pub async fn escrowed_transfer_is_credited_once_released() -> Result<()> {
+        let client = create_test_client().await?;
+        let recipient_keypair = Keypair::new_ed25519(&mut OsRng);
+        let recipient = recipient_keypair.public_key();
+        let initial_balance = client.get_balance().await?;
+
+        let secret = b"the secret".to_vec();
+        let condition = TransferCondition::new(&secret, now()? + 3600);
+        let proof = client
+            .send_tokens_in_escrow(recipient, Token::from_str("2")?, condition)
+            .await?;
+
+        // The token has left our wallet, but is not yet at the recipient.
+        let expected = initial_balance
+            .checked_sub(Token::from_str("2")?)
+            .ok_or_else(|| anyhow!("Not enough token"))?;
+        assert_eq!(client.get_balance().await?, expected);
+        assert_eq!(client.get_balance_for(recipient).await?, Token::zero());
+
+        client.release_escrow(proof.id(), secret).await?;
+
+        let recipient_client = create_test_client_with(Some(recipient_keypair)).await?;
+        let mut balance = recipient_client.get_balance().await?;
+        while balance != Token::from_str("2")? {
+            sleep(Duration::from_millis(200)).await;
+            balance = recipient_client.get_balance().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn escrowed_transfer_is_refunded_after_the_deadline() -> Result<()> {
+        let client = create_test_client().await?;
+        let recipient = Keypair::new_ed25519(&mut OsRng).public_key();
+        let initial_balance = client.get_balance().await?;
+
+        // The Elders refund only after the grace for their clock differences.
+        let deadline = now()? - ESCROW_GRACE_SECS - 2;
+        let condition = TransferCondition::new(b"never revealed", deadline);
+        let proof = client
+            .send_tokens_in_escrow(recipient, Token::from_str("2")?, condition)
+            .await?;
+
+        client.refund_escrow(proof.id()).await?;
+
+        let mut balance = client.get_balance().await?;
+        while balance != initial_balance {
+            sleep(Duration::from_millis(200)).await;
+            balance = client.get_balance().await?;
+        }
+        assert_eq!(client.get_balance_for(recipient).await?, Token::zero());
+
+        Ok(())
+    }
+}
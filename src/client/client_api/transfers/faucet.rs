@@ -0,0 +1,61 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Client, Error};
+use crate::messaging::{
+    client::{Cmd, TransferCmd},
+    FaucetParams,
+};
+use crate::types::{Credit, CreditId, SignedCredit, Token};
+use bincode::serialize;
+use log::info;
+use xor_name::XorName;
+
+impl Client {
+    /// Request a payout from the faucet of a test network to our wallet.
+    ///
+    /// The Replicas of the faucet wallet pay it out once enough of them agree it is within
+    /// the cap per key and the payouts per minute, by their own clocks. Returns the id
+    /// of the credit, which shows up in our history once it has been paid out.
+    pub async fn request_faucet_payout(&self, amount: Token) -> Result<CreditId, Error> {
+        let genesis_key = self
+            .session
+            .genesis_key()
+            .await
+            .ok_or(Error::NotBootstrapped)?;
+        let faucet = FaucetParams::wallet(genesis_key);
+        info!("Requesting a payout of {} from faucet {:?}", amount, faucet);
+
+        let credit = Credit {
+            id: rand::random(),
+            amount,
+            recipient: self.public_key(),
+            msg: "Faucet payout".to_string(),
+        };
+        let actor_signature = self.keypair.sign(&serialize(&credit)?);
+        let id = *credit.id();
+        let payout = SignedCredit {
+            credit,
+            actor_signature,
+        };
+
+        let cmd = Cmd::Transfer(TransferCmd::RequestFaucetPayout { faucet, payout });
+        match self.section_of(XorName::from(faucet)).await? {
+            Some(section) => self.send_cmd_to_section(cmd, &section).await?,
+            None => self.send_cmd(cmd, None).await?,
+        }
+
+        Ok(id)
+    }
+
+    // Whether a credit is in our history, as last synced with `Client::get_history`.
+    pub(crate) async fn has_credit(&self, id: &CreditId) -> bool {
+        let credits = self.transfer_actor.read().await.history().credits;
+        credits.iter().any(|proof| proof.id() == id)
+    }
+}
@@ -10,20 +10,39 @@
 mod balance_management;
 // Module for simulating token for testing
 mod simulated_payouts;
+// Module for wallets owned by a threshold key set
+mod multisig;
+// Module for transfers held in escrow
+mod escrow;
+// Module for payouts from the faucet of a test network
+mod faucet;
+// Module for transfers signed offline
+mod offline;
+// Module for receipts of payments, verifiable by third parties
+mod receipts;
+// Module for statements of the rewards paid to node operators
+mod rewards;
 // Module containing all PUT apis
 mod write_apis;
 
-use crate::client::{Client, Error};
-use crate::messaging::client::{
-    ClientSig, Cmd, DataCmd, Query, QueryResponse, TransferCmd, TransferQuery,
+pub use multisig::MultisigWallet;
+
+use crate::client::{connections::super_majority, Client, Error};
+use crate::messaging::{
+    client::{ClientSig, Cmd, DataCmd, Query, QueryResponse, TransferCmd, TransferQuery},
+    SectionAuthorityProvider,
 };
-use crate::transfers::{ActorEvent, TransferInitiated};
+use crate::transfers::{ActorEvent, TransferActor};
 use crate::types::{
-    DebitId, PublicKey, SignedTransfer, Token, TransferAgreementProof, TransferValidated,
+    DebitId, PublicKey, SectionElders, SignedTransfer, Signing, Token, TransferAgreementProof,
+    TransferValidated,
 };
 use bincode::serialize;
 use log::{debug, error, info, trace, warn};
-use tokio::sync::mpsc::channel;
+use tokio::sync::{mpsc::channel, RwLock};
+
+// The number of times a transfer is sent for validation, before it is abandoned.
+const VALIDATION_ATTEMPTS: usize = 3;
 
 impl Client {
     /// Get the client's current coin balance from the network
@@ -116,7 +135,29 @@ impl Client {
     /// # Ok(()) } ); }
     /// ```
     pub async fn get_history(&self) -> Result<(), Error> {
-        let public_key = self.public_key();
+        self.sync_history(&self.transfer_actor, None).await
+    }
+
+    // The Replicas of a wallet held by the given section, or else by ours.
+    async fn wallet_replicas(&self, section: Option<&SectionAuthorityProvider>) -> SectionElders {
+        match section {
+            Some(section) => SectionElders {
+                prefix: section.prefix,
+                names: section.elders.keys().copied().collect(),
+                key_set: section.public_key_set.clone(),
+            },
+            None => self.transfer_actor.read().await.replicas(),
+        }
+    }
+
+    // Retrieves the history of the actor's wallet from the network and applies it to the actor.
+    // The history is held by the given section if any, or else by ours.
+    async fn sync_history<S: Signing>(
+        &self,
+        transfer_actor: &RwLock<TransferActor<S>>,
+        section: Option<&SectionAuthorityProvider>,
+    ) -> Result<(), Error> {
+        let public_key = transfer_actor.read().await.id();
         info!("Getting SnTransfers history for pk: {:?}", public_key);
 
         let query = Query::Transfer(TransferQuery::GetHistory {
@@ -125,7 +166,10 @@ impl Client {
         });
 
         // This is a normal response manager request. We want quorum on this for now...
-        let query_result = self.send_query(query).await?;
+        let query_result = match section {
+            Some(section) => self.send_query_to_section(query, section).await?,
+            None => self.send_query(query).await?,
+        };
         let msg_id = query_result.msg_id;
 
         let history = match query_result.response {
@@ -133,7 +177,7 @@ impl Client {
             _ => Err(Error::UnexpectedHistoryResponse(query_result.response)),
         }?;
 
-        let mut actor = self.transfer_actor.write().await;
+        let mut actor = transfer_actor.write().await;
         match actor.from_history(history) {
             Ok(synced_transfer_outcome) => {
                 if let Some(transfers) = synced_transfer_outcome {
@@ -206,40 +250,84 @@ impl Client {
             bytes, section_key, cost_of_put
         );
 
-        let initiated = self
-            .transfer_actor
-            .read()
-            .await
-            .transfer(cost_of_put, section_key, "".to_string())?
-            .ok_or(Error::NoTransferEventsForLocalActor)?;
+        // Initiated and applied under the same lock, as further payments
+        // may be initiated meanwhile, each with the next debit.
+        let initiated = {
+            let mut actor = self.transfer_actor.write().await;
+            let initiated = actor
+                .transfer(cost_of_put, section_key, "".to_string())?
+                .ok_or(Error::NoTransferEventsForLocalActor)?;
+            actor.apply(ActorEvent::TransferInitiated(initiated.clone()))?;
+            initiated
+        };
 
         let signed_transfer = SignedTransfer {
             debit: initiated.signed_debit,
             credit: initiated.signed_credit,
+            batch: initiated.batch,
+            escrow: initiated.escrow,
         };
 
-        let cmd = Cmd::Transfer(TransferCmd::ValidateTransfer(signed_transfer.clone()));
-
         debug!("Transfer to be sent: {:?}", &signed_transfer);
 
-        self.transfer_actor
-            .write()
-            .await
-            .apply(ActorEvent::TransferInitiated(TransferInitiated {
-                signed_debit: signed_transfer.debit.clone(),
-                signed_credit: signed_transfer.credit.clone(),
-            }))?;
-
-        let payment_proof: TransferAgreementProof =
-            self.await_validation(cmd, signed_transfer.id()).await?;
+        let payment_proof: TransferAgreementProof = self
+            .validate_initiated_transfer(&self.transfer_actor, None, signed_transfer)
+            .await?;
 
         debug!("Payment proof retrieved");
         Ok(payment_proof)
     }
 
     /// Send message and await validation and constructing of TransferAgreementProof
-    async fn await_validation(
+    // Validates a transfer in flight at the local actor. If validation fails, the identical
+    // transfer is sent again, as Replicas may have validated it with the validations getting lost:
+    // those answer with the validation they made, and never validate another debit in its place.
+    // If it still fails, the Replicas rejected it, and it is abandoned along with the later
+    // transfers in flight.
+    // The Replicas are the Elders of the given section if any, or else of ours.
+    async fn validate_initiated_transfer<S: Signing>(
         &self,
+        transfer_actor: &RwLock<TransferActor<S>>,
+        section: Option<&SectionAuthorityProvider>,
+        signed_transfer: SignedTransfer,
+    ) -> Result<TransferAgreementProof, Error> {
+        let id = signed_transfer.id();
+        let cmd = Cmd::Transfer(TransferCmd::ValidateTransfer(signed_transfer));
+        let mut result = self
+            .await_validation(transfer_actor, section, cmd.clone(), id)
+            .await;
+        for _ in 1..VALIDATION_ATTEMPTS {
+            match result {
+                Err(error) => {
+                    warn!("Sending transfer {:?} again, after: {:?}", id, error);
+                    result = self
+                        .await_validation(transfer_actor, section, cmd.clone(), id)
+                        .await;
+                }
+                Ok(_) => break,
+            }
+        }
+        match result {
+            Ok(proof) => Ok(proof),
+            Err(error) => {
+                let mut actor = transfer_actor.write().await;
+                if let Some(abandoned) = actor.abandon(id)? {
+                    warn!(
+                        "Abandoning {} transfers in flight",
+                        abandoned.transfers().len()
+                    );
+                    actor.apply(ActorEvent::TransfersAbandoned(abandoned))?;
+                    self.transfers_progressed.notify_waiters();
+                }
+                Err(error)
+            }
+        }
+    }
+
+    async fn await_validation<S: Signing>(
+        &self,
+        transfer_actor: &RwLock<TransferActor<S>>,
+        section: Option<&SectionAuthorityProvider>,
         cmd: Cmd,
         _id: DebitId,
     ) -> Result<TransferAgreementProof, Error> {
@@ -256,19 +344,22 @@ impl Client {
 
         let msg_id = self
             .session
-            .send_transfer_validation(cmd, client_sig, sender)
+            .send_transfer_validation(cmd, client_sig, section, sender)
             .await?;
 
         let mut returned_errors = vec![];
         let mut response_count: usize = 0;
-        let supermajority = self.session.super_majority().await;
+        let supermajority = match section {
+            Some(section) => super_majority(section.elders.len()),
+            None => self.session.super_majority().await,
+        };
 
         loop {
             match receiver.recv().await {
                 Some(event) => match event {
                     Ok(transfer_validated) => {
                         response_count += 1;
-                        let mut actor = self.transfer_actor.write().await;
+                        let mut actor = transfer_actor.write().await;
                         // pass the received validation in to our actor
                         match actor.receive(transfer_validated) {
                             Ok(result) => {
@@ -328,7 +419,9 @@ impl Client {
 
 #[cfg(test)]
 mod tests {
-    use crate::client::utils::test_utils::{create_test_client, create_test_client_with};
+    use crate::client::utils::test_utils::{
+        create_test_client, create_test_client_with, fund_test_client,
+    };
     use crate::retry_loop_for_pattern;
     use crate::types::Token;
     use anyhow::{anyhow, Result};
@@ -377,9 +470,7 @@ mod tests {
 
         {
             let mut initial_actor = create_test_client_with(Some(keypair.clone())).await?;
-            let _ = initial_actor
-                .trigger_simulated_farming_payout(Token::from_str("100")?)
-                .await?;
+            fund_test_client(&mut initial_actor, Token::from_str("100")?).await?;
         }
 
         let client_res = create_test_client_with(Some(keypair.clone())).await;
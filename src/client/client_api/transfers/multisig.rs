@@ -0,0 +1,164 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Client, Error, TransfersError};
+use crate::messaging::SectionAuthorityProvider;
+use crate::transfers::TransferActor;
+use crate::types::{Keypair, PublicKey, SignedTransferShare, Token};
+use log::info;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use xor_name::XorName;
+
+/// A wallet owned by a threshold key set, of which we hold one share.
+///
+/// No single owner can spend from it: one owner proposes a transfer, the co-owners approve it
+/// with `SignedTransferShare::co_sign`, which only needs their keypair share and so can be done
+/// offline, and once more than the threshold of shares is collected the transfer is sent
+/// with `Client::send_multisig_transfer`.
+#[derive(Clone, Debug)]
+pub struct MultisigWallet {
+    actor: Arc<RwLock<TransferActor<Keypair>>>,
+    // The section holding the wallet, when it is not our own.
+    section: Option<SectionAuthorityProvider>,
+}
+
+impl MultisigWallet {
+    /// Get the public key of the wallet, i.e. the public key of the owners' key set.
+    pub async fn public_key(&self) -> PublicKey {
+        self.actor.read().await.id()
+    }
+
+    /// Get the balance of the wallet as currently known locally. (ie. Without querying the network)
+    pub async fn balance(&self) -> Token {
+        self.actor.read().await.balance()
+    }
+
+    /// Propose a transfer from the wallet, signed with our share of the owners' key set.
+    /// The returned share is handed to the co-owners for approval.
+    pub async fn propose_transfer(
+        &self,
+        to: PublicKey,
+        amount: Token,
+    ) -> Result<SignedTransferShare, Error> {
+        info!("Proposing multisig transfer of {} to {}", amount, to);
+        self.actor
+            .read()
+            .await
+            .propose_transfer(amount, to, "".to_string())?
+            .ok_or(Error::NoTransferGenerated)
+    }
+}
+
+impl Client {
+    /// Open the multisig wallet owned by the key set of the given keypair share,
+    /// retrieving its history from the network.
+    ///
+    /// The wallet is held by the section of the owners' key, which is looked up on the network
+    /// when it is not our own: its Elders are the Replicas validating transfers from the wallet.
+    ///
+    /// The client keeps signing its messages with its own keypair;
+    /// the share is only used to sign transfers from the wallet.
+    pub async fn multisig_wallet(&self, keypair_share: Keypair) -> Result<MultisigWallet, Error> {
+        let owners = match &keypair_share {
+            Keypair::BlsShare(share) => PublicKey::Bls(share.public_key_set.public_key()),
+            _ => return Err(Error::Transfer(TransfersError::InvalidOwner)),
+        };
+        let section = self.section_of(XorName::from(owners)).await?;
+        let replicas = self.wallet_replicas(section.as_ref()).await;
+        let wallet = MultisigWallet {
+            actor: Arc::new(RwLock::new(TransferActor::new(keypair_share, replicas))),
+            section,
+        };
+        self.sync_multisig_wallet(&wallet).await?;
+        Ok(wallet)
+    }
+
+    /// Retrieve the history of a multisig wallet from the network and apply it locally.
+    pub async fn sync_multisig_wallet(&self, wallet: &MultisigWallet) -> Result<(), Error> {
+        self.sync_history(&wallet.actor, wallet.section.as_ref())
+            .await
+    }
+
+    /// Combine the shares of a proposed transfer from a multisig wallet, and send it.
+    /// The proposal itself must be among the shares, and together they must exceed the
+    /// threshold of the owners' key set.
+    ///
+    /// Returns the debit version and the wallet key, as `Client::send_tokens` does.
+    pub async fn send_multisig_transfer(
+        &self,
+        wallet: &MultisigWallet,
+        shares: &[SignedTransferShare],
+    ) -> Result<(u64, PublicKey), Error> {
+        info!(
+            "Sending multisig transfer combining {} shares",
+            shares.len()
+        );
+        let initiated = wallet
+            .actor
+            .read()
+            .await
+            .combine_transfer_shares(shares)?
+            .ok_or(Error::NoTransferGenerated)?;
+
+        let dot = self
+            .send_initiated_transfer(&wallet.actor, wallet.section.as_ref(), initiated)
+            .await?
+            .id();
+        Ok((dot.counter, dot.actor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::retry_loop_for_pattern;
+    use crate::types::{Keypair, Token};
+    use anyhow::Result;
+    use bls::SecretKeySet;
+    use rand::rngs::OsRng;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    pub async fn multisig_wallet_transfer_needs_co_owners() -> Result<()> {
+        let client = create_test_client().await?;
+        let owners = SecretKeySet::random(1, &mut OsRng);
+        let owner_share = |index| {
+            Keypair::new_bls_share(index, owners.secret_key_share(index), owners.public_keys())
+        };
+
+        let wallet = client.multisig_wallet(owner_share(0)).await?;
+        let _ = client
+            .send_tokens(wallet.public_key().await, Token::from_str("5")?)
+            .await?;
+        let _ = retry_loop_for_pattern!(client.get_balance_for(wallet.public_key().await), Ok(bal) if *bal == Token::from_str("5")?);
+        client.sync_multisig_wallet(&wallet).await?;
+        assert_eq!(wallet.balance().await, Token::from_str("5")?);
+
+        let recipient = Keypair::new_ed25519(&mut OsRng).public_key();
+        let proposal = wallet
+            .propose_transfer(recipient, Token::from_str("2")?)
+            .await?;
+
+        // The proposal alone is not enough.
+        assert!(client
+            .send_multisig_transfer(&wallet, std::slice::from_ref(&proposal))
+            .await
+            .is_err());
+
+        let approval = proposal.co_sign(&owner_share(2))?;
+        let _ = client
+            .send_multisig_transfer(&wallet, &[proposal, approval])
+            .await?;
+
+        assert_eq!(wallet.balance().await, Token::from_str("3")?);
+        let _ = retry_loop_for_pattern!(client.get_balance_for(recipient), Ok(bal) if *bal == Token::from_str("2")?);
+
+        Ok(())
+    }
+}
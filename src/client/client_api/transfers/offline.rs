@@ -0,0 +1,129 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Client, Error};
+use crate::messaging::SectionAuthorityProvider;
+use crate::transfers::{
+    sign_transfer, TransferActor, TransferInitiated, WalletSnapshot, WatchOnly,
+};
+use crate::types::{Keypair, OwnerType, PublicKey, SignedTransfer, Token, TransferAgreementProof};
+use bincode::{deserialize, serialize};
+use log::info;
+use tokio::sync::RwLock;
+use xor_name::XorName;
+
+type WatchedWallet = (
+    RwLock<TransferActor<WatchOnly>>,
+    Option<SectionAuthorityProvider>,
+);
+
+impl Client {
+    /// Get a snapshot of the wallet of any key, as currently known by the section holding it.
+    ///
+    /// This is what a transfer is signed offline from, with `Client::sign_transfer_offline`,
+    /// so that the key itself can stay on an air-gapped machine.
+    pub async fn wallet_snapshot_for(
+        &self,
+        public_key: PublicKey,
+    ) -> Result<WalletSnapshot, Error> {
+        let (actor, _) = self.watch_only_actor(public_key).await?;
+        let snapshot = actor.read().await.wallet_snapshot();
+        Ok(snapshot)
+    }
+
+    /// Sign a transfer from a wallet snapshot, without any connection to the network.
+    ///
+    /// Returns the serialized `SignedTransfer`, which any client can later submit
+    /// with `Client::submit_signed_transfer`. As debits are ordered, only one transfer
+    /// can be signed from a given snapshot.
+    pub fn sign_transfer_offline(
+        keypair: &Keypair,
+        wallet: &WalletSnapshot,
+        to: PublicKey,
+        amount: Token,
+    ) -> Result<Vec<u8>, Error> {
+        let signed_transfer = sign_transfer(keypair, wallet, amount, to, "".to_string())?;
+        Ok(serialize(&signed_transfer)?)
+    }
+
+    /// Submit a transfer signed offline, and drive it to agreement and registration.
+    /// The client doesn't need the key of the wallet the transfer is from.
+    pub async fn submit_signed_transfer(
+        &self,
+        signed_transfer: &[u8],
+    ) -> Result<TransferAgreementProof, Error> {
+        let signed_transfer: SignedTransfer = deserialize(signed_transfer)?;
+        info!(
+            "Submitting transfer signed offline, from {}",
+            signed_transfer.sender()
+        );
+
+        let (actor, section) = self.watch_only_actor(signed_transfer.sender()).await?;
+        let initiated = TransferInitiated {
+            signed_debit: signed_transfer.debit,
+            signed_credit: signed_transfer.credit,
+            batch: signed_transfer.batch,
+            escrow: signed_transfer.escrow,
+        };
+        self.send_initiated_transfer(&actor, section.as_ref(), initiated)
+            .await
+    }
+
+    // An actor following the wallet of the given key, synched with the network, along with the
+    // section holding the wallet when it is not our own.
+    async fn watch_only_actor(&self, public_key: PublicKey) -> Result<WatchedWallet, Error> {
+        let section = self.section_of(XorName::from(public_key)).await?;
+        let replicas = self.wallet_replicas(section.as_ref()).await;
+        let signing = WatchOnly(OwnerType::Single(public_key));
+        let actor = RwLock::new(TransferActor::new(signing, replicas));
+        self.sync_history(&actor, section.as_ref()).await?;
+        Ok((actor, section))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Client;
+    use crate::client::utils::test_utils::{create_test_client, create_test_client_with};
+    use crate::retry_loop_for_pattern;
+    use crate::types::{Keypair, Token};
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    pub async fn transfer_signed_offline_can_be_submitted_by_another_client() -> Result<()> {
+        // The cold key only ever receives tokens while online.
+        let cold_keypair = Keypair::new_ed25519(&mut OsRng);
+        let cold_key = cold_keypair.public_key();
+        let client = create_test_client().await?;
+        let _ = client.send_tokens(cold_key, Token::from_str("4")?).await?;
+        let _ = retry_loop_for_pattern!(client.get_balance_for(cold_key), Ok(bal) if *bal == Token::from_str("4")?);
+
+        let snapshot = client.wallet_snapshot_for(cold_key).await?;
+        assert_eq!(snapshot.balance, Token::from_str("4")?);
+
+        let recipient = Keypair::new_ed25519(&mut OsRng).public_key();
+        let signed_transfer = Client::sign_transfer_offline(
+            &cold_keypair,
+            &snapshot,
+            recipient,
+            Token::from_str("3")?,
+        )?;
+
+        // Submitted by a client with a different key.
+        let submitter = create_test_client_with(None).await?;
+        let proof = submitter.submit_signed_transfer(&signed_transfer).await?;
+        assert_eq!(proof.sender(), cold_key);
+
+        let _ = retry_loop_for_pattern!(client.get_balance_for(recipient), Ok(bal) if *bal == Token::from_str("3")?);
+        let _ = retry_loop_for_pattern!(client.get_balance_for(cold_key), Ok(bal) if *bal == Token::from_str("1")?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Client, Error};
+use crate::messaging::client::{Query, QueryResponse, TransferQuery};
+use crate::types::{CreditId, PaymentReceipt};
+use log::info;
+
+impl Client {
+    /// Export the receipt of a payment made from our wallet, identified by the id of its credit.
+    ///
+    /// The receipt holds the proof of the credit along with the section chain proving the key
+    /// of the replicas which validated it, so that anyone can verify it offline with
+    /// `PaymentReceipt::verify`, knowing only the genesis key.
+    ///
+    /// A payment held in escrow has no such proof, as the Replicas sign the held credit instead,
+    /// so `Error::EscrowedPayment` is returned for it.
+    pub async fn payment_receipt(&self, credit_id: &CreditId) -> Result<PaymentReceipt, Error> {
+        info!("Exporting receipt of payment {:?}", credit_id);
+
+        let debits = self.transfer_actor.read().await.history().debits;
+        let (debit, credit_proof) = debits
+            .iter()
+            .flat_map(|debit| {
+                debit
+                    .credit_proofs()
+                    .into_iter()
+                    .map(move |credit_proof| (debit, credit_proof))
+            })
+            .find(|(_, credit_proof)| credit_proof.id() == credit_id)
+            .ok_or(Error::NoSuchPayment)?;
+        if debit.is_escrowed() {
+            return Err(Error::EscrowedPayment);
+        }
+
+        let query = Query::Transfer(TransferQuery::GetSectionChain {
+            at: self.public_key(),
+            key: credit_proof.debiting_replicas_keys.public_key(),
+        });
+        let query_result = self.send_query(query).await?;
+        let msg_id = query_result.msg_id;
+
+        let section_chain = match query_result.response {
+            QueryResponse::GetSectionChain(chain) => {
+                chain.map_err(|err| Error::from((err, msg_id)))
+            }
+            _ => Err(Error::UnexpectedQueryResponse(query_result.response)),
+        }?;
+
+        Ok(PaymentReceipt {
+            credit_proof,
+            section_chain,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{utils::test_utils::create_test_client, Error};
+    use crate::types::{Keypair, PaymentReceipt, Token, TransferCondition};
+    use anyhow::{anyhow, bail, Result};
+    use rand::rngs::OsRng;
+    use std::str::FromStr;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[tokio::test]
+    pub async fn payment_receipt_can_be_verified_offline() -> Result<()> {
+        let client = create_test_client().await?;
+        let recipient = Keypair::new_ed25519(&mut OsRng).public_key();
+        let _ = client.send_tokens(recipient, Token::from_str("2")?).await?;
+
+        let debit = client.transfer_actor.read().await.history().debits;
+        let credit_id = *debit[debit.len() - 1].credit_proof().id();
+        let receipt = client.payment_receipt(&credit_id).await?;
+
+        // The receipt survives the trip to a third party.
+        let receipt: PaymentReceipt = bincode::deserialize(&bincode::serialize(&receipt)?)?;
+        assert_eq!(receipt.recipient(), recipient);
+        assert_eq!(receipt.amount(), Token::from_str("2")?);
+
+        // Verified against the genesis key we learned from the network, not from the receipt.
+        let genesis_key = client
+            .network_topology(None)
+            .await?
+            .genesis_key
+            .ok_or_else(|| anyhow!("The network didn't report its genesis key"))?;
+        receipt.verify(vec![&genesis_key])?;
+
+        let foreign_key = bls::SecretKey::random().public_key();
+        assert!(receipt.verify(vec![&foreign_key]).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn payment_receipt_is_refused_for_escrowed_payment() -> Result<()> {
+        let client = create_test_client().await?;
+        let recipient = Keypair::new_ed25519(&mut OsRng).public_key();
+        let deadline = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+        let condition = TransferCondition::new(b"the secret", deadline);
+        let proof = client
+            .send_tokens_in_escrow(recipient, Token::from_str("2")?, condition)
+            .await?;
+
+        match client.payment_receipt(proof.credit_proof().id()).await {
+            Err(Error::EscrowedPayment) => Ok(()),
+            other => bail!("Unexpected receipt of an escrowed payment: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{connections::super_majority, Client, Error};
+use crate::messaging::client::{ClientSig, Query, QueryResponse, TransferQuery};
+use crate::types::{PublicKey, RewardStatement};
+use log::{debug, info};
+use std::time::Duration;
+use xor_name::XorName;
+
+// How long to wait for the Elders to answer a `GetRewardStatement` query.
+const REWARD_STATEMENT_TIMEOUT: Duration = Duration::from_secs(20);
+
+impl Client {
+    /// Get the statement of the rewards paid to a wallet, as recorded by the Elders of the
+    /// section holding the given node.
+    ///
+    /// Each entry of the statement covers one reward round of that section, including the
+    /// rounds in which the wallet was paid nothing, along with the reason why.
+    ///
+    /// All the Elders are asked, and the statement returned only once a supermajority of them
+    /// gave the very same one.
+    pub async fn get_reward_statement(
+        &self,
+        node: XorName,
+        wallet: PublicKey,
+    ) -> Result<RewardStatement, Error> {
+        info!("Getting reward statement of wallet {:?}", wallet);
+
+        let (elders, supermajority) = match self.section_of(node).await? {
+            Some(section) => (
+                section.elders.values().copied().collect(),
+                super_majority(section.elders.len()),
+            ),
+            None => (
+                self.session.known_elders_addrs().await,
+                self.session.super_majority().await,
+            ),
+        };
+
+        let query = Query::Transfer(TransferQuery::GetRewardStatement { node, wallet });
+        let client_sig = ClientSig {
+            public_key: self.public_key(),
+            signature: self.keypair.sign(b"TODO"),
+        };
+        let responses = self
+            .session
+            .send_query_to_elders(query, client_sig, elders, REWARD_STATEMENT_TIMEOUT)
+            .await?;
+
+        let mut statements: Vec<(RewardStatement, usize)> = vec![];
+        for response in responses {
+            match response {
+                QueryResponse::GetRewardStatement(Ok(statement)) => {
+                    match statements.iter_mut().find(|(known, _)| *known == statement) {
+                        Some((_, count)) => *count += 1,
+                        None => statements.push((statement, 1)),
+                    }
+                }
+                response => debug!(
+                    "Unexpected response to reward statement query: {:?}",
+                    response
+                ),
+            }
+        }
+
+        statements
+            .into_iter()
+            .find(|(_, count)| *count >= supermajority)
+            .map(|(statement, _)| statement)
+            .ok_or(Error::NoAgreedResponse(supermajority))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::types::{Keypair, Token};
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use xor_name::XorName;
+
+    #[tokio::test]
+    pub async fn unknown_wallet_has_empty_reward_statement() -> Result<()> {
+        let client = create_test_client().await?;
+        let wallet = Keypair::new_ed25519(&mut OsRng).public_key();
+
+        let statement = client
+            .get_reward_statement(XorName::random(), wallet)
+            .await?;
+
+        assert_eq!(statement.wallet, wallet);
+        assert!(statement.entries.is_empty());
+        assert_eq!(statement.total(), Some(Token::zero()));
+
+        Ok(())
+    }
+}
@@ -88,7 +88,7 @@ impl Client {
 // Tests
 // ---------------------------------
 
-#[cfg(test)]
+#[cfg(all(test, feature = "simulated-payouts"))]
 mod tests {
     use super::*;
     use crate::client::utils::test_utils::create_test_client;
@@ -97,7 +97,6 @@ mod tests {
     use tokio::time::{sleep, Duration};
 
     #[tokio::test]
-    #[cfg(feature = "simulated-payouts")]
     async fn transfer_actor_can_receive_simulated_farming_payout() -> Result<()> {
         let mut client = create_test_client().await?;
 
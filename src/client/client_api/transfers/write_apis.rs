@@ -39,7 +39,6 @@ mod tests {
     use rand::rngs::OsRng;
     use xor_name::XorName;
 
-    #[cfg(feature = "simulated-payouts")]
     #[tokio::test]
     pub async fn transfer_actor_with_no_balance_cannot_store_data() -> Result<()> {
         let keypair = Keypair::new_ed25519(&mut OsRng);
@@ -0,0 +1,147 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::types::{
+    register::{Address as RegisterAddress, EntryHash, Register},
+    ChunkAddress,
+};
+use bincode::{deserialize, serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeSet;
+use xor_name::XorName;
+
+// Entry of a Register versioning content stored in public Blobs, pointing to the Blob of a
+// version. Each entry links to the one of the version before, so older versions can be found from
+// the current one.
+#[derive(Debug, Serialize, Deserialize)]
+struct BlobVersion {
+    version: u64,
+    blob: XorName,
+    previous: Option<EntryHash>,
+}
+
+impl Client {
+    // Stores `content` as version 0 of a new public Register owned by this client.
+    pub(super) async fn create_versioned_blob<T: Serialize>(
+        &self,
+        name: XorName,
+        tag: u64,
+        content: &T,
+    ) -> Result<RegisterAddress, Error> {
+        let entry = serialize(&BlobVersion {
+            version: 0,
+            blob: self.store_versioned_content(content).await?,
+            previous: None,
+        })?;
+
+        let mut register = Register::new_public(self.public_key(), name, tag, None);
+        let _ = register.write(entry, BTreeSet::new())?;
+        let address = *register.address();
+
+        self.pay_and_write_register_to_network(register).await?;
+
+        Ok(address)
+    }
+
+    // Returns the content at the given version, or at the latest one, along with that version.
+    pub(super) async fn get_versioned_blob<T: DeserializeOwned>(
+        &self,
+        address: RegisterAddress,
+        version: Option<u64>,
+    ) -> Result<(u64, T), Error> {
+        let register = self.get_register(address).await?;
+
+        let (_, latest) = latest_version(&register)?;
+        let blob_version = match version {
+            Some(version) => find_version(&register, latest, version)?,
+            None => latest,
+        };
+
+        let content = self.read_versioned_content(blob_version.blob).await?;
+        Ok((blob_version.version, content))
+    }
+
+    // Applies `update` to the latest content, storing the result as the next version. Concurrent
+    // versions are merged by linking the new one to all of them.
+    pub(super) async fn update_versioned_blob<T: Serialize + DeserializeOwned>(
+        &self,
+        address: RegisterAddress,
+        update: impl FnOnce(&mut T) -> Result<(), Error>,
+    ) -> Result<(u64, T), Error> {
+        let register = self.get_register(address).await?;
+        let (latest_hash, latest) = latest_version(&register)?;
+
+        let mut content = self.read_versioned_content(latest.blob).await?;
+        update(&mut content)?;
+
+        let version = latest.version + 1;
+        let entry = serialize(&BlobVersion {
+            version,
+            blob: self.store_versioned_content(&content).await?,
+            previous: Some(latest_hash),
+        })?;
+        let parents = register
+            .read(None)?
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        let _ = self.write_to_register(address, entry, parents).await?;
+
+        Ok((version, content))
+    }
+
+    async fn store_versioned_content<T: Serialize>(&self, content: &T) -> Result<XorName, Error> {
+        let address = self.store_public_blob(&serialize(content)?).await?;
+        Ok(*address.name())
+    }
+
+    async fn read_versioned_content<T: DeserializeOwned>(&self, name: XorName) -> Result<T, Error> {
+        let bytes = self
+            .read_blob(ChunkAddress::Public(name), None, None)
+            .await?;
+        Ok(deserialize(&bytes)?)
+    }
+}
+
+// Returns the latest version, picking the highest one when there are concurrent versions.
+fn latest_version(register: &Register) -> Result<(EntryHash, BlobVersion), Error> {
+    let mut latest: Option<(EntryHash, BlobVersion)> = None;
+    for (hash, entry) in register.read(None)? {
+        let version: BlobVersion = deserialize(&entry)?;
+        latest = match latest {
+            Some(current) if current.1.version >= version.version => Some(current),
+            _ => Some((hash, version)),
+        };
+    }
+    latest.ok_or_else(|| Error::from(crate::types::Error::NoSuchEntry))
+}
+
+// Walks back the versions from `latest` to the requested one.
+fn find_version(
+    register: &Register,
+    latest: BlobVersion,
+    version: u64,
+) -> Result<BlobVersion, Error> {
+    let mut current = latest;
+    while current.version > version {
+        let previous = current
+            .previous
+            .and_then(|hash| register.get(hash, None).transpose())
+            .transpose()?
+            .ok_or(Error::ContentVersionNotFound(version))?;
+        current = deserialize(previous)?;
+    }
+
+    if current.version == version {
+        Ok(current)
+    } else {
+        Err(Error::ContentVersionNotFound(version))
+    }
+}
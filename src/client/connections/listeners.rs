@@ -14,6 +14,7 @@ use crate::messaging::{
     MessageId, MessageType, SectionAuthorityProvider, WireMsg,
 };
 use crate::types::PublicKey;
+use bytes::Bytes;
 use log::{debug, error, info, trace, warn};
 use qp2p::IncomingMessages;
 use std::{
@@ -67,32 +68,43 @@ impl Session {
         client_pk: PublicKey,
     ) -> Result<bool, Error> {
         if let Some((src, message)) = incoming_messages.next().await {
-            let message_type = WireMsg::deserialize(message)?;
-            trace!("Incoming message from {:?}", &src);
-            match message_type {
-                MessageType::SectionInfo { msg, .. } => {
-                    if let Err(error) = self.handle_section_info_msg(msg, src, client_pk).await {
-                        error!("Error handling network info message: {:?}", error);
-                    }
+            self.handle_incoming_message(src, message, client_pk)
+                .await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    pub(crate) async fn handle_incoming_message(
+        &mut self,
+        src: SocketAddr,
+        message: Bytes,
+        client_pk: PublicKey,
+    ) -> Result<(), Error> {
+        let message_type = WireMsg::deserialize(message)?;
+        trace!("Incoming message from {:?}", &src);
+        match message_type {
+            MessageType::SectionInfo { msg, .. } => {
+                if let Err(error) = self.handle_section_info_msg(msg, src, client_pk).await {
+                    error!("Error handling network info message: {:?}", error);
                 }
-                MessageType::Client { msg, .. } => {
-                    match msg {
-                        ClientMsg::Process(msg) => self.handle_client_msg(msg, src).await,
-                        ClientMsg::ProcessingError(error) => {
-                            warn!("Processing error received. {:?}", error);
-                            // TODO: Handle lazy message errors
-                        }
-                        msg => warn!("SupportingInfo received: {:?}", msg),
+            }
+            MessageType::Client { msg, .. } => {
+                match msg {
+                    ClientMsg::Process(msg) => self.handle_client_msg(msg, src).await,
+                    ClientMsg::ProcessingError(error) => {
+                        warn!("Processing error received. {:?}", error);
+                        // TODO: Handle lazy message errors
                     }
-                }
-                msg_type => {
-                    warn!("Unexpected message type received: {:?}", msg_type);
+                    msg => warn!("SupportingInfo received: {:?}", msg),
                 }
             }
-            Ok(true)
-        } else {
-            Ok(false)
+            msg_type => {
+                warn!("Unexpected message type received: {:?}", msg_type);
+            }
         }
+        Ok(())
     }
 
     // Private helpers
@@ -107,9 +119,13 @@ impl Session {
         trace!("Handling network info message {:?}", msg);
 
         match &msg {
-            SectionInfoMsg::GetSectionResponse(GetSectionResponse::Success(info)) => {
+            SectionInfoMsg::GetSectionResponse(GetSectionResponse::Success {
+                section_auth,
+                genesis_key,
+            }) => {
                 debug!("GetSectionResponse::Success!");
-                self.update_session_info(info).await
+                *self.genesis_key.write().await = Some(*genesis_key);
+                self.update_session_info(section_auth).await
             }
             SectionInfoMsg::GetSectionResponse(GetSectionResponse::SectionInfoUpdate(
                 SectionInfoError::InvalidBootstrap(err),
@@ -136,6 +152,7 @@ impl Session {
                 let endpoint = self.endpoint()?.clone();
                 let new_elders_addrs: Vec<SocketAddr> =
                     sap.elders.iter().map(|(_, addr)| *addr).collect();
+                self.refresh_contacts(sap).await;
                 self.qp2p
                     .update_bootstrap_contacts(new_elders_addrs.as_slice());
                 let boostrapped_peer = self
@@ -156,8 +173,13 @@ impl Session {
                 }
                 Ok(())
             }
+            SectionInfoMsg::SectionKnowledgeResponse(_) => {
+                trace!("SectionKnowledgeResponse received outside of a crawl, ignoring it");
+                Ok(())
+            }
             SectionInfoMsg::GetSectionResponse(GetSectionResponse::SectionInfoUpdate(_))
-            | SectionInfoMsg::GetSectionQuery { .. } => {
+            | SectionInfoMsg::GetSectionQuery { .. }
+            | SectionInfoMsg::SectionKnowledgeQuery => {
                 Err(Error::UnexpectedMessageOnJoin(format!(
                     "bootstrapping failed since an invalid response ({:?}) was received",
                     msg
@@ -199,6 +221,8 @@ impl Session {
             *prefix = Some(sap.prefix);
         }
 
+        self.refresh_contacts(sap).await;
+
         {
             // Update session elders
             let mut session_elders = self.all_known_elders.write().await;
@@ -11,17 +11,22 @@ use crate::client::Error;
 use crate::messaging::{
     client::{ChunkRead, ClientMsg, ClientSig, Cmd, DataQuery, ProcessMsg, Query, QueryResponse},
     section_info::SectionInfoMsg,
-    MessageId,
+    MessageId, SectionAuthorityProvider,
 };
 use crate::types::{Chunk, PrivateChunk, PublicChunk, PublicKey, TransferValidated};
 use futures::{future::join_all, stream::FuturesUnordered, StreamExt};
 use itertools::Itertools;
 use log::{debug, error, info, trace, warn};
-use std::{collections::BTreeSet, net::SocketAddr, time::Duration};
+use qp2p::IncomingMessages;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+    time::Duration,
+};
 use tokio::{
     sync::mpsc::{channel, Sender},
     task::JoinHandle,
-    time::timeout,
+    time::{timeout, timeout_at, Instant},
 };
 use xor_name::XorName;
 
@@ -29,25 +34,28 @@ use xor_name::XorName;
 const NUMBER_OF_RETRIES: usize = 3;
 // Number of Elders subset to send queries to
 const NUM_OF_ELDERS_SUBSET_FOR_QUERIES: usize = 3;
+// Overall time allowed to bootstrap to the network
+const BOOTSTRAP_DEADLINE: Duration = Duration::from_secs(60);
+// Time given to each round of contacts to respond before querying the next ones
+const BOOTSTRAP_ROUND_TIMEOUT: Duration = Duration::from_secs(10);
+// Number of contacts raced in each bootstrap round
+const BOOTSTRAP_PARALLELISM: usize = 4;
 
 impl Session {
     /// Bootstrap to the network maintaining connections to several nodes.
+    ///
+    /// Contacts are raced in rounds, best scored first, until one of them responds with our
+    /// section info or `BOOTSTRAP_DEADLINE` passes.
     pub async fn bootstrap(&mut self, client_pk: PublicKey) -> Result<(), Error> {
         trace!(
             "Trying to bootstrap to the network with public_key: {:?}",
             client_pk
         );
 
-        let (endpoint, _, mut incoming_messages, mut disconnections, mut bootstrapped_peer) =
-            self.qp2p.bootstrap().await?;
+        let (endpoint, _, mut incoming_messages, mut disconnections) =
+            self.qp2p.new_endpoint().await?;
 
         self.endpoint = Some(endpoint.clone());
-        let mut bootstrap_nodes = endpoint
-            .clone()
-            .bootstrap_nodes()
-            .to_vec()
-            .into_iter()
-            .collect::<BTreeSet<_>>();
 
         let cloned_endpoint = endpoint.clone();
         let _ = tokio::spawn(async move {
@@ -71,31 +79,26 @@ impl Session {
             }
         });
 
-        self.send_get_section_query(client_pk, &bootstrapped_peer)
-            .await?;
-
-        // Bootstrap and send a handshake request to the bootstrapped peer
-        let mut we_have_keyset = false;
-        while !we_have_keyset {
-            // This means that the peer we bootstrapped to
-            // has responded with a SectionInfo Message
-            if let Ok(Ok(true)) = timeout(
-                Duration::from_secs(30),
-                self.process_incoming_message(&mut incoming_messages, client_pk),
-            )
+        let rounds = self
+            .contact_cache
+            .read()
             .await
-            {
-                we_have_keyset = self.section_key_set.read().await.is_some();
-            } else {
-                // Remove the unresponsive peer we boostrapped to and bootstrap again
-                let _ = bootstrap_nodes.remove(&bootstrapped_peer);
-                bootstrapped_peer = self
-                    .qp2p
-                    .rebootstrap(
-                        &endpoint,
-                        &bootstrap_nodes.iter().cloned().collect::<Vec<_>>(),
-                    )
-                    .await?;
+            .bootstrap_rounds(&self.hard_coded_contacts, BOOTSTRAP_PARALLELISM);
+        let result = timeout(
+            BOOTSTRAP_DEADLINE,
+            self.bootstrap_through(rounds, &mut incoming_messages, client_pk),
+        )
+        .await;
+        self.store_contacts().await;
+
+        match result {
+            Ok(result) => result?,
+            Err(_) => {
+                warn!(
+                    "Could not bootstrap to the network within {:?}",
+                    BOOTSTRAP_DEADLINE
+                );
+                return Err(Error::BootstrapFailed);
             }
         }
 
@@ -110,6 +113,76 @@ impl Session {
         Ok(())
     }
 
+    // Query the contacts for our section info one round at a time, until we have the section key
+    // set. Each contact is scored on whether and how fast it responded.
+    async fn bootstrap_through(
+        &mut self,
+        rounds: Vec<Vec<SocketAddr>>,
+        incoming_messages: &mut IncomingMessages,
+        client_pk: PublicKey,
+    ) -> Result<(), Error> {
+        for round in &rounds {
+            trace!("Querying section info from contacts {:?}", round);
+            let sent_at = Instant::now();
+            let results = join_all(
+                round
+                    .iter()
+                    .map(|contact| self.send_get_section_query(client_pk, contact)),
+            )
+            .await;
+
+            let mut pending = BTreeSet::new();
+            for (contact, result) in round.iter().zip(results) {
+                if let Err(error) = result {
+                    debug!("Could not query contact {:?}: {:?}", contact, error);
+                    self.contact_cache.write().await.record_failure(*contact);
+                } else {
+                    let _ = pending.insert(*contact);
+                }
+            }
+
+            if pending.is_empty() {
+                continue;
+            }
+
+            let round_deadline = sent_at + BOOTSTRAP_ROUND_TIMEOUT;
+            loop {
+                let (src, message) =
+                    match timeout_at(round_deadline, incoming_messages.next()).await {
+                        Ok(Some(incoming)) => incoming,
+                        Ok(None) => return Err(Error::NoElderListenerEstablished),
+                        Err(_) => break,
+                    };
+
+                if pending.remove(&src) {
+                    self.contact_cache
+                        .write()
+                        .await
+                        .record_success(src, sent_at.elapsed());
+                }
+
+                if let Err(error) = self.handle_incoming_message(src, message, client_pk).await {
+                    warn!("Error handling message from {:?}: {:?}", src, error);
+                }
+
+                if self.section_key_set.read().await.is_some() {
+                    return Ok(());
+                }
+            }
+
+            for contact in pending {
+                debug!("Contact {:?} did not respond in time", contact);
+                self.contact_cache.write().await.record_failure(contact);
+            }
+        }
+
+        warn!(
+            "None of the {} contacts responded with our section info",
+            rounds.iter().map(Vec::len).sum::<usize>()
+        );
+        Err(Error::BootstrapFailed)
+    }
+
     /// Send a `ClientMsg` to the network without awaiting for a response.
     pub async fn send_cmd(
         &self,
@@ -117,9 +190,6 @@ impl Session {
         client_sig: ClientSig,
         send_to_specific_elder: Option<SocketAddr>,
     ) -> Result<(), Error> {
-        let msg_id = MessageId::new();
-        let endpoint = self.endpoint()?.clone();
-
         let elders = if let Some(socket) = send_to_specific_elder {
             vec![socket]
         } else {
@@ -130,6 +200,38 @@ impl Session {
                 .cloned()
                 .collect::<Vec<SocketAddr>>()
         };
+        let section_pk = self
+            .section_key()
+            .await?
+            .bls()
+            .ok_or(Error::NoBlsSectionKey)?;
+
+        self.send_cmd_to(cmd, client_sig, elders, section_pk).await
+    }
+
+    /// Send a `ClientMsg` to the Elders of the given section, which need not be our own,
+    /// without awaiting for a response.
+    pub async fn send_cmd_to_section(
+        &self,
+        cmd: Cmd,
+        client_sig: ClientSig,
+        section: &SectionAuthorityProvider,
+    ) -> Result<(), Error> {
+        let elders = section.elders.values().copied().collect();
+        let section_pk = section.public_key_set.public_key();
+
+        self.send_cmd_to(cmd, client_sig, elders, section_pk).await
+    }
+
+    async fn send_cmd_to(
+        &self,
+        cmd: Cmd,
+        client_sig: ClientSig,
+        elders: Vec<SocketAddr>,
+        section_pk: bls::PublicKey,
+    ) -> Result<(), Error> {
+        let msg_id = MessageId::new();
+        let endpoint = self.endpoint()?.clone();
 
         debug!(
             "Sending command w/id {:?}, to {} Elders",
@@ -145,11 +247,6 @@ impl Session {
             msg_id
         );
 
-        let section_pk = self
-            .section_key()
-            .await?
-            .bls()
-            .ok_or(Error::NoBlsSectionKey)?;
         let dst_section_name = XorName::from(client_sig.public_key);
 
         let msg = ClientMsg::Process(ProcessMsg::Cmd {
@@ -195,11 +292,13 @@ impl Session {
         Ok(())
     }
 
-    /// Send a transfer validation message to all Elder without awaiting for a response.
+    /// Send a transfer validation message to all Elders, of the given section or else of ours,
+    /// without awaiting for a response.
     pub async fn send_transfer_validation(
         &self,
         cmd: Cmd,
         client_sig: ClientSig,
+        section: Option<&SectionAuthorityProvider>,
         sender: Sender<Result<TransferValidated, Error>>,
     ) -> Result<MessageId, Error> {
         let msg_id = MessageId::new();
@@ -208,14 +307,21 @@ impl Session {
             cmd, msg_id
         );
         let endpoint = self.endpoint()?.clone();
-        let elders: Vec<SocketAddr> = self.connected_elders.read().await.keys().cloned().collect();
         let pending_transfers = self.pending_transfers.clone();
 
-        let section_pk = self
-            .section_key()
-            .await?
-            .bls()
-            .ok_or(Error::NoBlsSectionKey)?;
+        let (elders, section_pk): (Vec<SocketAddr>, _) = match section {
+            Some(section) => (
+                section.elders.values().copied().collect(),
+                section.public_key_set.public_key(),
+            ),
+            None => (
+                self.connected_elders.read().await.keys().cloned().collect(),
+                self.section_key()
+                    .await?
+                    .bls()
+                    .ok_or(Error::NoBlsSectionKey)?,
+            ),
+        };
         let dst_section_name = XorName::from(client_sig.public_key);
 
         let msg = ClientMsg::Process(ProcessMsg::Cmd {
@@ -257,6 +363,43 @@ impl Session {
         &self,
         query: Query,
         client_sig: ClientSig,
+    ) -> Result<QueryResult, Error> {
+        let elders = self.connected_elders.read().await.clone();
+        let section_pk = self
+            .section_key()
+            .await?
+            .bls()
+            .ok_or(Error::NoBlsSectionKey)?;
+
+        self.send_query_to(query, client_sig, elders, section_pk)
+            .await
+    }
+
+    /// Send a Query `ClientMsg` to the Elders of the given section, which need not be our own,
+    /// awaiting for the response.
+    pub(crate) async fn send_query_to_section(
+        &self,
+        query: Query,
+        client_sig: ClientSig,
+        section: &SectionAuthorityProvider,
+    ) -> Result<QueryResult, Error> {
+        let elders = section
+            .elders
+            .iter()
+            .map(|(name, addr)| (*addr, *name))
+            .collect();
+        let section_pk = section.public_key_set.public_key();
+
+        self.send_query_to(query, client_sig, elders, section_pk)
+            .await
+    }
+
+    async fn send_query_to(
+        &self,
+        query: Query,
+        client_sig: ClientSig,
+        elders: BTreeMap<SocketAddr, XorName>,
+        section_pk: bls::PublicKey,
     ) -> Result<QueryResult, Error> {
         let data_name = query.dst_address();
 
@@ -269,11 +412,6 @@ impl Session {
             None
         };
 
-        let section_pk = self
-            .section_key()
-            .await?
-            .bls()
-            .ok_or(Error::NoBlsSectionKey)?;
         let dst_section_name = XorName::from(client_sig.public_key);
 
         let msg_id = MessageId::new();
@@ -286,12 +424,8 @@ impl Session {
         let msg_bytes = msg.serialize(dst_section_name, section_pk)?;
 
         // We select the NUM_OF_ELDERS_SUBSET_FOR_QUERIES closest
-        // connected Elders to the data we are querying
-        let elders: Vec<SocketAddr> = self
-            .connected_elders
-            .read()
-            .await
-            .clone()
+        // Elders to the data we are querying
+        let elders: Vec<SocketAddr> = elders
             .into_iter()
             .sorted_by(|(_, lhs_name), (_, rhs_name)| data_name.cmp_distance(&lhs_name, &rhs_name))
             .take(NUM_OF_ELDERS_SUBSET_FOR_QUERIES)
@@ -417,17 +551,26 @@ impl Session {
                 // Saving error, but not returning until we have more responses in
                 // (note, this will overwrite prior errors, so we'll just return whicever was last received)
                 (response @ Some(QueryResponse::GetChunk(Err(_))), Some(_))
+                | (response @ Some(QueryResponse::ChunkExists(Err(_))), None)
                 | (response @ Some(QueryResponse::GetBalance(Err(_))), None)
                 | (response @ Some(QueryResponse::GetMap(Err(_))), None)
                 | (response @ Some(QueryResponse::GetRegister(Err(_))), None)
                 | (response @ Some(QueryResponse::GetSequence(Err(_))), None)
                 | (response @ Some(QueryResponse::GetStoreCost(Err(_))), None)
+                | (response @ Some(QueryResponse::GetSectionChain(Err(_))), None)
+                | (response @ Some(QueryResponse::GetRewardStatement(Err(_))), None)
+                | (response @ Some(QueryResponse::GetSectionSupply(Err(_))), None)
                 | (response @ Some(QueryResponse::GetMapShell(Err(_))), None)
                 | (response @ Some(QueryResponse::GetMapValue(Err(_))), None)
                 | (response @ Some(QueryResponse::GetMapVersion(Err(_))), None)
                 | (response @ Some(QueryResponse::GetRegisterPolicy(Err(_))), None)
                 | (response @ Some(QueryResponse::GetRegisterOwner(Err(_))), None)
                 | (response @ Some(QueryResponse::GetRegisterUserPermissions(Err(_))), None)
+                | (response @ Some(QueryResponse::GetRegisterEntries(Err(_))), None)
+                | (response @ Some(QueryResponse::GetRegisterAncestry(Err(_))), None)
+                | (response @ Some(QueryResponse::DiffRegisterBranches(Err(_))), None)
+                | (response @ Some(QueryResponse::GetRegisterOps(Err(_))), None)
+                | (response @ Some(QueryResponse::GetSequenceOps(Err(_))), None)
                 | (response @ Some(QueryResponse::GetSequenceLastEntry(Err(_))), None)
                 | (response @ Some(QueryResponse::GetSequencePrivatePolicy(Err(_))), None)
                 | (response @ Some(QueryResponse::GetSequencePublicPolicy(Err(_))), None)
@@ -466,6 +609,85 @@ impl Session {
             .ok_or(Error::NoResponse)
     }
 
+    /// Send a Query `ClientMsg` to each of the given Elders, which need not be Elders of our
+    /// section, and return all the responses received before `response_timeout` passes.
+    pub(crate) async fn send_query_to_elders(
+        &self,
+        query: Query,
+        client_sig: ClientSig,
+        elders: Vec<SocketAddr>,
+        response_timeout: Duration,
+    ) -> Result<Vec<QueryResponse>, Error> {
+        let endpoint = self.endpoint()?.clone();
+        let section_pk = self
+            .section_key()
+            .await?
+            .bls()
+            .ok_or(Error::NoBlsSectionKey)?;
+        let dst_section_name = XorName::from(client_sig.public_key);
+
+        let msg_id = MessageId::new();
+        let msg = ClientMsg::Process(ProcessMsg::Query {
+            id: msg_id,
+            query,
+            client_sig,
+        });
+        let msg_bytes = msg.serialize(dst_section_name, section_pk)?;
+
+        info!(
+            "Sending query message {:?}, to the Elders: {:?}",
+            msg, elders
+        );
+
+        let (sender, mut receiver) = channel::<QueryResponse>(elders.len().max(1));
+        let _ = self.pending_queries.write().await.insert(msg_id, sender);
+
+        let tasks = elders.iter().map(|socket| {
+            let endpoint = endpoint.clone();
+            let msg_bytes = msg_bytes.clone();
+            async move {
+                endpoint.connect_to(socket).await?;
+                endpoint.send_message(msg_bytes, socket).await?;
+                trace!("ClientMsg with id: {:?}, sent to {}", &msg_id, socket);
+                Ok::<_, Error>(())
+            }
+        });
+        let sent = join_all(tasks)
+            .await
+            .into_iter()
+            .filter(|result| match result {
+                Ok(()) => true,
+                Err(err) => {
+                    error!("Error sending Query to elder: {:?} ", err);
+                    false
+                }
+            })
+            .count();
+
+        let mut responses = Vec::new();
+        let deadline = Instant::now() + response_timeout;
+        while responses.len() < sent {
+            match timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(response)) => responses.push(response),
+                Ok(None) => break,
+                Err(_) => {
+                    warn!(
+                        "Only {} of {} Elders responded to query w/id {:?}",
+                        responses.len(),
+                        sent,
+                        msg_id
+                    );
+                    break;
+                }
+            }
+        }
+
+        trace!("Removing channel for {:?}", msg_id);
+        let _ = self.pending_queries.write().await.remove(&msg_id);
+
+        Ok(responses)
+    }
+
     // Get section info from the peer we have bootstrapped with.
     pub(crate) async fn send_get_section_query(
         &self,
@@ -11,15 +11,18 @@ mod messaging;
 
 use crate::client::Error;
 use crate::messaging::client::CmdError;
-use crate::messaging::{client::Error as ErrorMessage, client::QueryResponse, MessageId};
-use crate::types::{PublicKey, TransferValidated};
-use bls::PublicKeySet;
-use log::{debug, trace};
+use crate::messaging::{
+    client::Error as ErrorMessage, client::QueryResponse, MessageId, SectionAuthorityProvider,
+};
+use crate::types::{ContactCache, PublicKey, TransferValidated, CONTACT_CACHE_FILE};
+use bls::{PublicKey as BlsPublicKey, PublicKeySet};
+use log::{debug, trace, warn};
 use qp2p::{Config as QuicP2pConfig, Endpoint, QuicP2p};
 use std::{
     borrow::Borrow,
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     net::SocketAddr,
+    path::PathBuf,
     sync::Arc,
 };
 use tokio::sync::mpsc::Sender;
@@ -33,6 +36,11 @@ type QueryResponseSender = Sender<QueryResponse>;
 type PendingTransferValidations = Arc<RwLock<HashMap<MessageId, TransferValidationSender>>>;
 type PendingQueryResponses = Arc<RwLock<HashMap<MessageId, QueryResponseSender>>>;
 
+/// The SuperMajority count of a section with the given number of elders
+pub(crate) fn super_majority(elder_count: usize) -> usize {
+    1 + elder_count * 2 / 3
+}
+
 pub(crate) struct QueryResult {
     pub response: QueryResponse,
     pub msg_id: MessageId,
@@ -54,6 +62,14 @@ pub struct Session {
     pub section_key_set: Arc<RwLock<Option<PublicKeySet>>>,
     section_prefix: Arc<RwLock<Option<Prefix>>>,
     is_connecting_to_new_elders: bool,
+    /// genesis key of the network, as reported with our section info
+    genesis_key: Arc<RwLock<Option<BlsPublicKey>>>,
+    /// contacts to bootstrap through, scored by how well they served us before
+    contact_cache: Arc<RwLock<ContactCache>>,
+    /// where the contact cache is persisted, if anywhere
+    contact_cache_path: Option<PathBuf>,
+    /// contacts from the config, always tried first
+    hard_coded_contacts: HashSet<SocketAddr>,
 }
 
 impl Session {
@@ -64,6 +80,13 @@ impl Session {
     ) -> Result<Self, Error> {
         debug!("QP2p config: {:?}", qp2p_config);
 
+        let contact_cache_path = contact_cache_path(&qp2p_config);
+        let contact_cache = contact_cache_path
+            .as_deref()
+            .map(ContactCache::load_or_default)
+            .unwrap_or_default();
+        let hard_coded_contacts = qp2p_config.hard_coded_contacts.clone();
+
         let qp2p = qp2p::QuicP2p::with_config(Some(qp2p_config), Default::default(), true)?;
         Ok(Self {
             qp2p,
@@ -77,12 +100,16 @@ impl Session {
             all_known_elders: Arc::new(RwLock::new(Default::default())),
             section_prefix: Arc::new(RwLock::new(None)),
             is_connecting_to_new_elders: false,
+            genesis_key: Arc::new(RwLock::new(None)),
+            contact_cache: Arc::new(RwLock::new(contact_cache)),
+            contact_cache_path,
+            hard_coded_contacts,
         })
     }
 
     /// Get the SuperMajority count based on number of known elders
     pub async fn super_majority(&self) -> usize {
-        1 + self.known_elders_count().await * 2 / 3
+        super_majority(self.known_elders_count().await)
     }
 
     pub async fn get_elder_names(&self) -> BTreeSet<XorName> {
@@ -95,6 +122,11 @@ impl Session {
         self.all_known_elders.read().await.len()
     }
 
+    /// Get the addresses of our section elders as provided by SectionInfo
+    pub async fn known_elders_addrs(&self) -> Vec<SocketAddr> {
+        self.all_known_elders.read().await.keys().copied().collect()
+    }
+
     pub fn endpoint(&self) -> Result<&Endpoint, Error> {
         match self.endpoint.borrow() {
             Some(endpoint) => Ok(endpoint),
@@ -121,4 +153,54 @@ impl Session {
     pub async fn section_prefix(&self) -> Option<Prefix> {
         *self.section_prefix.read().await
     }
+
+    /// Get the genesis key of the network we're connected to
+    pub async fn genesis_key(&self) -> Option<BlsPublicKey> {
+        *self.genesis_key.read().await
+    }
+
+    /// Refresh the contact cache with the elders of a section we've been told about, once we know
+    /// which network they are of
+    pub(crate) async fn refresh_contacts(&self, sap: &SectionAuthorityProvider) {
+        let genesis_key = match *self.genesis_key.read().await {
+            Some(genesis_key) => genesis_key,
+            None => {
+                trace!(
+                    "Network not known yet, not caching the elders of {:?}",
+                    sap.prefix
+                );
+                return;
+            }
+        };
+        self.contact_cache.write().await.refresh(
+            genesis_key,
+            sap.prefix,
+            sap.elders.values().copied(),
+        );
+        self.store_contacts().await;
+    }
+
+    /// Persist the contact cache, if we have somewhere to
+    pub(crate) async fn store_contacts(&self) {
+        if let Some(path) = &self.contact_cache_path {
+            if let Err(err) = self.contact_cache.read().await.store(path) {
+                warn!(
+                    "Failed to store contact cache at {}: {}",
+                    path.display(),
+                    err
+                );
+            }
+        }
+    }
+}
+
+// The contact cache lives next to qp2p's bootstrap cache if its location was configured,
+// otherwise in the client's default directory.
+fn contact_cache_path(qp2p_config: &QuicP2pConfig) -> Option<PathBuf> {
+    qp2p_config
+        .bootstrap_cache_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| dirs_next::home_dir().map(|home| home.join(".safe").join("client")))
+        .map(|dir| dir.join(CONTACT_CACHE_FILE))
 }
@@ -15,6 +15,7 @@ pub use crate::transfers::Error as TransfersError;
 use crate::types::{Error as DtError, PublicKey};
 use qp2p::Error as QuicP2pError;
 use std::io;
+use xor_name::XorName;
 
 use thiserror::Error;
 
@@ -44,6 +45,9 @@ pub enum Error {
     /// Client has not gone trhough qp2p bootstrap process yet
     #[error("Client has failed to bootstrap to a section yet")]
     NotBootstrapped,
+    /// None of the bootstrap contacts responded with our section info in time
+    #[error("Failed to bootstrap: no contact responded with our section info in time")]
+    BootstrapFailed,
     /// Could not connect to sufficient elder to retrieve reliable responses.
     #[error(
         "Problem connecting to sufficient elders. A supermajority of responses is unobtainable. {0} were connected to"
@@ -109,9 +113,50 @@ pub enum Error {
     /// Transfer actor did not find any events to register locally
     #[error("Transfer actor did not find any events to register locally")]
     NoTransferEventsForLocalActor,
+    /// No payment with the given credit id was found in the history of the wallet
+    #[error("No payment with the given credit id was made from this wallet")]
+    NoSuchPayment,
+    /// The payment was held in escrow, so the Replicas' signature is over the held credit and
+    /// doesn't prove the credit to the recipient
+    #[error("No receipt can be exported for a payment held in escrow")]
+    EscrowedPayment,
+    /// The owners of data whose entries this client encrypts can't be changed, as the new owners
+    /// couldn't decrypt the entries
+    #[error("Can't change the owners of data whose entries are encrypted by this client")]
+    EncryptedOwnershipTransfer,
     /// Not in testnet "simulated payout" mode
     #[error("Simulated payouts unavailable without 'simualted-payouts' feature flag at build")]
     NotBuiltWithSimulatedPayouts,
+    /// The URL can't be fetched from
+    #[error("Unsupported URL: {0}")]
+    UnsupportedUrl(String),
+    /// The requested content version is not available
+    #[error("Content version {0} not found")]
+    ContentVersionNotFound(u64),
+    /// The NRS name is not in its top name's map
+    #[error("NRS name not found: {0}")]
+    NrsNameNotFound(String),
+    /// Following NRS names led back to an NRS URL already visited
+    #[error("NRS resolution loops at {0}")]
+    NrsResolutionLoop(String),
+    /// Nothing is found at the path in the FilesContainer
+    #[error("Path not found in FilesContainer: {0}")]
+    PathNotFound(String),
+    /// A file is already at the path in the FilesContainer
+    #[error("Path already exists in FilesContainer: {0}")]
+    PathAlreadyExists(String),
+    /// A merge entry must be written on top of at least two current heads of the Register
+    #[error("Cannot merge Register branches, {0} of the given entries are current heads")]
+    InvalidRegisterMerge(usize),
+    /// Fewer than the given number of Elders gave the same response to a query
+    #[error("Fewer than {0} Elders agreed on the response")]
+    NoAgreedResponse(usize),
+    /// No verified section could be found for the given name
+    #[error("No verified section found for {0}")]
+    SectionNotFound(XorName),
+    /// SafeUrl errors
+    #[error(transparent)]
+    Url(#[from] crate::url::Error),
     /// Other types errors
     #[error(transparent)]
     NetworkDataError(#[from] DtError),
@@ -57,7 +57,10 @@ mod errors;
 
 // Export public API.
 
-pub use client_api::Client;
+pub use client_api::{
+    BlobSyncReport, Client, FileItem, FilesMap, MultimapEntries, MultimapEntry, MultimapKey,
+    MultimapValue, MultisigWallet, NrsMap, ResolvedPath, SafeContent,
+};
 pub use errors::{Error, ErrorMessage, TransfersError};
 pub use qp2p::Config as QuicP2pConfig;
 
@@ -65,6 +68,8 @@ pub use qp2p::Config as QuicP2pConfig;
 pub mod client_api;
 /// Config file handling.
 pub mod config_handler;
+/// Network topology crawling and export.
+pub mod topology;
 
 /// Utility functions.
 pub mod utils;
@@ -0,0 +1,462 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::NetworkTopology;
+use crate::types::{SectionSupplyShare, SectionTotals, SignedSectionSupply, Token, MAX_SUPPLY};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+use xor_name::Prefix;
+
+/// Token supply of the network, from the totals signed by each section of a `NetworkTopology`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SupplyAudit {
+    /// Totals signed by the verified key of each section, by prefix.
+    pub sections: BTreeMap<Prefix, SignedSectionSupply>,
+    /// Sections of the topology we could not obtain signed totals from.
+    pub failures: BTreeMap<Prefix, SupplyFailure>,
+    /// Invariants of the supply which the signed totals break.
+    pub inconsistencies: Vec<SupplyInconsistency>,
+}
+
+impl SupplyAudit {
+    /// Audits the shares of the totals received from the Elders of each section of `topology`.
+    pub fn new(
+        topology: &NetworkTopology,
+        mut shares: BTreeMap<Prefix, Vec<SectionSupplyShare>>,
+    ) -> Self {
+        let mut audit = Self::default();
+        for (prefix, section) in &topology.sections {
+            let section_shares = shares.remove(prefix).unwrap_or_default();
+            match combine_section_shares(*prefix, section.section_key, section_shares) {
+                Ok(signed) => {
+                    let _ = audit.sections.insert(*prefix, signed);
+                }
+                Err(failure) => {
+                    let _ = audit.failures.insert(*prefix, failure);
+                }
+            }
+        }
+
+        if !topology.is_complete() || !audit.failures.is_empty() {
+            audit.inconsistencies.push(SupplyInconsistency::Incomplete);
+        }
+        for (prefix, signed) in &audit.sections {
+            let inherited = signed.supply.totals.inherited;
+            let has_inherited =
+                inherited.minted != Token::zero() || inherited.burned != Token::zero();
+            if has_inherited && !SectionTotals::is_heir(*prefix) {
+                audit
+                    .inconsistencies
+                    .push(SupplyInconsistency::NotAnHeir(*prefix));
+            }
+        }
+        if audit.managed().as_nano() > MAX_SUPPLY {
+            audit
+                .inconsistencies
+                .push(SupplyInconsistency::ManagedExceedsMaxSupply(
+                    audit.managed(),
+                ));
+        }
+        if audit.circulating().as_nano() > MAX_SUPPLY {
+            audit
+                .inconsistencies
+                .push(SupplyInconsistency::CirculatingExceedsMaxSupply(
+                    audit.circulating(),
+                ));
+        }
+        // Only the whole network is expected to hold exactly what was ever minted.
+        if audit.inconsistencies.is_empty() && audit.managed() != audit.minted() {
+            audit.inconsistencies.push(SupplyInconsistency::Unbacked {
+                managed: audit.managed(),
+                minted: audit.minted(),
+            });
+        }
+
+        audit
+    }
+
+    /// Returns whether every section signed its totals and they are all consistent.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// Sum of the balances of all the wallets held by the audited sections.
+    pub fn managed(&self) -> Token {
+        self.sum(|signed| signed.supply.managed)
+    }
+
+    /// Tokens ever minted by the audited sections and those they split from.
+    pub fn minted(&self) -> Token {
+        self.sum(|signed| signed.supply.totals.sum().minted)
+    }
+
+    /// Tokens ever burned by the audited sections and those they split from.
+    pub fn burned(&self) -> Token {
+        self.sum(|signed| signed.supply.totals.sum().burned)
+    }
+
+    /// Tokens in circulation, i.e. minted and not burned.
+    pub fn circulating(&self) -> Token {
+        Token::from_nano(
+            self.minted()
+                .as_nano()
+                .saturating_sub(self.burned().as_nano()),
+        )
+    }
+
+    fn sum(&self, amount: impl Fn(&SignedSectionSupply) -> Token) -> Token {
+        Token::from_nano(
+            self.sections
+                .values()
+                .map(|signed| amount(signed).as_nano())
+                .fold(0, u64::saturating_add),
+        )
+    }
+}
+
+// Combines the shares stating the same totals, for the section and key the topology verified.
+fn combine_section_shares(
+    prefix: Prefix,
+    section_key: bls::PublicKey,
+    shares: Vec<SectionSupplyShare>,
+) -> Result<SignedSectionSupply, SupplyFailure> {
+    if shares.is_empty() {
+        return Err(SupplyFailure::Unreachable);
+    }
+
+    let mut groups: Vec<Vec<SectionSupplyShare>> = vec![];
+    for share in shares {
+        if share.supply.prefix != prefix || share.supply.section_key != section_key {
+            continue;
+        }
+        match groups
+            .iter_mut()
+            .find(|group| group[0].supply == share.supply)
+        {
+            Some(group) => group.push(share),
+            None => groups.push(vec![share]),
+        }
+    }
+    if groups.is_empty() {
+        return Err(SupplyFailure::KeyMismatch);
+    }
+
+    let mut failure = SupplyFailure::NotEnoughShares;
+    for group in groups {
+        match SignedSectionSupply::combine(&group) {
+            Ok(signed) => match signed.verify() {
+                Ok(()) => return Ok(signed),
+                Err(_) => failure = SupplyFailure::InvalidSignature,
+            },
+            Err(crate::types::Error::InvalidSignature) => failure = SupplyFailure::InvalidSignature,
+            Err(_) => (),
+        }
+    }
+    Err(failure)
+}
+
+/// Reason why a section is missing from the audited supply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupplyFailure {
+    /// None of the section's Elders answered our query.
+    Unreachable,
+    /// Too few Elders signed the same totals to combine a section signature.
+    NotEnoughShares,
+    /// The totals are for another section, or signed by another key, than the verified one.
+    KeyMismatch,
+    /// The shares did not combine into a valid section signature.
+    InvalidSignature,
+}
+
+impl Display for SupplyFailure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Unreachable => write!(f, "unreachable"),
+            Self::NotEnoughShares => write!(f, "not enough Elders signed the same totals"),
+            Self::KeyMismatch => write!(f, "totals not signed by the verified section key"),
+            Self::InvalidSignature => write!(f, "invalid section signature"),
+        }
+    }
+}
+
+/// An invariant of the token supply broken by the signed totals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupplyInconsistency {
+    /// Parts of the network are missing from the audit, so its totals are only lower bounds.
+    Incomplete,
+    /// The wallets held by the sections add up to more than the maximum supply.
+    ManagedExceedsMaxSupply(Token),
+    /// More tokens were minted and not burned than the maximum supply.
+    CirculatingExceedsMaxSupply(Token),
+    /// The section with this prefix states totals inherited from the section it split from,
+    /// which only its heir takes over, so they may be counted twice.
+    NotAnHeir(Prefix),
+    /// The wallets held by the sections don't add up to the tokens ever minted.
+    ///
+    /// Credits still propagating between sections can briefly cause this, so it is only an
+    /// error if it persists.
+    Unbacked {
+        /// Sum of the balances of all wallets.
+        managed: Token,
+        /// Tokens ever minted.
+        minted: Token,
+    },
+}
+
+impl Display for SupplyInconsistency {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Incomplete => write!(f, "not every section of the network was audited"),
+            Self::ManagedExceedsMaxSupply(managed) => {
+                write!(f, "wallets hold {} over the maximum supply", managed)
+            }
+            Self::CirculatingExceedsMaxSupply(circulating) => {
+                write!(f, "{} in circulation over the maximum supply", circulating)
+            }
+            Self::NotAnHeir(prefix) => write!(
+                f,
+                "section {:?} states totals inherited from its parent, but isn't its heir",
+                prefix
+            ),
+            Self::Unbacked { managed, minted } => write!(
+                f,
+                "wallets hold {} while {} was ever minted",
+                managed, minted
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::topology::SectionTopology;
+    use crate::messaging::NetworkParams;
+    use crate::types::{SectionSupply, SignatureShare, SupplyTotals};
+
+    fn totals(minted: u64, burned: u64) -> SupplyTotals {
+        SupplyTotals {
+            minted: Token::from_nano(minted),
+            burned: Token::from_nano(burned),
+        }
+    }
+    use anyhow::Result;
+
+    struct MockSection {
+        prefix: Prefix,
+        sk_set: bls::SecretKeySet,
+    }
+
+    impl MockSection {
+        fn new(prefix: Prefix) -> Self {
+            Self {
+                prefix,
+                sk_set: bls::SecretKeySet::random(1, &mut rand::thread_rng()),
+            }
+        }
+
+        fn topology(&self) -> SectionTopology {
+            SectionTopology {
+                prefix: self.prefix,
+                section_key: self.sk_set.public_keys().public_key(),
+                elders: BTreeMap::new(),
+                adult_count: 0,
+                chain_len: 2,
+                network_params: NetworkParams::default(),
+            }
+        }
+
+        // The shares of `count` Elders over the given own totals.
+        fn shares(
+            &self,
+            managed: u64,
+            minted: u64,
+            burned: u64,
+            count: usize,
+        ) -> Result<Vec<SectionSupplyShare>> {
+            let totals = SectionTotals {
+                own: totals(minted, burned),
+                inherited: SupplyTotals::default(),
+            };
+            self.shares_of_totals(managed, totals, count)
+        }
+
+        fn shares_of_totals(
+            &self,
+            managed: u64,
+            totals: SectionTotals,
+            count: usize,
+        ) -> Result<Vec<SectionSupplyShare>> {
+            let supply = SectionSupply {
+                prefix: self.prefix,
+                section_key: self.sk_set.public_keys().public_key(),
+                managed: Token::from_nano(managed),
+                totals,
+            };
+            let bytes = bincode::serialize(&supply)?;
+            Ok((0..count)
+                .map(|index| SectionSupplyShare {
+                    supply: supply.clone(),
+                    public_key_set: self.sk_set.public_keys(),
+                    signature_share: SignatureShare {
+                        index,
+                        share: self.sk_set.secret_key_share(index).sign(&bytes),
+                    },
+                })
+                .collect())
+        }
+    }
+
+    fn gen_network() -> (MockSection, MockSection, NetworkTopology) {
+        let section0 = MockSection::new(Prefix::default().pushed(false));
+        let section1 = MockSection::new(Prefix::default().pushed(true));
+        let mut topology = NetworkTopology::default();
+        for section in &[&section0, &section1] {
+            let _ = topology.sections.insert(section.prefix, section.topology());
+        }
+        (section0, section1, topology)
+    }
+
+    #[test]
+    fn consistent_network() -> Result<()> {
+        let (section0, section1, topology) = gen_network();
+        let mut shares = BTreeMap::new();
+        // payments left section 0 for section 1, whose rewards burned them
+        let _ = shares.insert(section0.prefix, section0.shares(60, 100, 0, 3)?);
+        let _ = shares.insert(section1.prefix, section1.shares(90, 50, 40, 3)?);
+
+        let audit = SupplyAudit::new(&topology, shares);
+
+        assert!(audit.is_consistent(), "{:?}", audit.inconsistencies);
+        assert_eq!(audit.managed(), Token::from_nano(150));
+        assert_eq!(audit.minted(), Token::from_nano(150));
+        assert_eq!(audit.circulating(), Token::from_nano(110));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sums_totals_inherited_by_the_heir() -> Result<()> {
+        let (section0, section1, topology) = gen_network();
+        let mut shares = BTreeMap::new();
+        // section 0 is the heir of the root section, which had minted 100 before splitting
+        let heir_totals = SectionTotals {
+            own: totals(20, 5),
+            inherited: totals(100, 0),
+        };
+        let _ = shares.insert(
+            section0.prefix,
+            section0.shares_of_totals(120, heir_totals, 3)?,
+        );
+        let _ = shares.insert(section1.prefix, section1.shares(10, 10, 0, 3)?);
+
+        let audit = SupplyAudit::new(&topology, shares);
+
+        assert!(audit.is_consistent(), "{:?}", audit.inconsistencies);
+        assert_eq!(audit.minted(), Token::from_nano(130));
+        assert_eq!(audit.burned(), Token::from_nano(5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_inherited_totals_of_non_heirs() -> Result<()> {
+        let (section0, section1, topology) = gen_network();
+        let mut shares = BTreeMap::new();
+        let inherited = SectionTotals {
+            own: SupplyTotals::default(),
+            inherited: totals(100, 0),
+        };
+        let _ = shares.insert(
+            section0.prefix,
+            section0.shares_of_totals(100, inherited, 3)?,
+        );
+        let _ = shares.insert(section1.prefix, section1.shares_of_totals(0, inherited, 3)?);
+
+        let audit = SupplyAudit::new(&topology, shares);
+
+        assert_eq!(
+            audit.inconsistencies,
+            vec![SupplyInconsistency::NotAnHeir(section1.prefix)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_unbacked_tokens() -> Result<()> {
+        let (section0, section1, topology) = gen_network();
+        let mut shares = BTreeMap::new();
+        let _ = shares.insert(section0.prefix, section0.shares(100, 100, 0, 3)?);
+        let _ = shares.insert(section1.prefix, section1.shares(70, 50, 0, 3)?);
+
+        let audit = SupplyAudit::new(&topology, shares);
+
+        assert_eq!(
+            audit.inconsistencies,
+            vec![SupplyInconsistency::Unbacked {
+                managed: Token::from_nano(170),
+                minted: Token::from_nano(150),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_sections_without_signed_totals() -> Result<()> {
+        let (section0, section1, topology) = gen_network();
+        let mut shares = BTreeMap::new();
+        // only one Elder of section 0 answered
+        let _ = shares.insert(section0.prefix, section0.shares(100, 100, 0, 1)?);
+        // section 1 answered with totals signed by some other key
+        let other = MockSection::new(section1.prefix);
+        let _ = shares.insert(section1.prefix, other.shares(50, 50, 0, 3)?);
+
+        let audit = SupplyAudit::new(&topology, shares);
+
+        assert!(audit.sections.is_empty());
+        assert_eq!(
+            audit.failures.get(&section0.prefix),
+            Some(&SupplyFailure::NotEnoughShares)
+        );
+        assert_eq!(
+            audit.failures.get(&section1.prefix),
+            Some(&SupplyFailure::KeyMismatch)
+        );
+        assert_eq!(audit.inconsistencies, vec![SupplyInconsistency::Incomplete]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_supply_over_the_maximum() -> Result<()> {
+        let (section0, section1, topology) = gen_network();
+        let mut shares = BTreeMap::new();
+        let _ = shares.insert(
+            section0.prefix,
+            section0.shares(MAX_SUPPLY, MAX_SUPPLY, 0, 3)?,
+        );
+        let _ = shares.insert(section1.prefix, section1.shares(1, 1, 0, 3)?);
+
+        let audit = SupplyAudit::new(&topology, shares);
+
+        let over = Token::from_nano(MAX_SUPPLY + 1);
+        assert_eq!(
+            audit.inconsistencies,
+            vec![
+                SupplyInconsistency::ManagedExceedsMaxSupply(over),
+                SupplyInconsistency::CirculatingExceedsMaxSupply(over),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,551 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{verify_section_knowledge, NetworkTopology, SectionFailure, SectionTopology};
+use crate::client::Error;
+use crate::messaging::{
+    section_info::{SectionInfoMsg, SectionKnowledge},
+    MessageType, SectionAuthorityProvider, WireMsg,
+};
+use async_trait::async_trait;
+use bls::PublicKey as BlsPublicKey;
+use log::{debug, trace, warn};
+use qp2p::{Config as QuicP2pConfig, Endpoint, IncomingMessages, QuicP2p};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt::{self, Debug, Formatter},
+    net::SocketAddr,
+    time::Duration,
+};
+use tokio::{sync::Mutex, time::timeout};
+use xor_name::{Prefix, XorName};
+
+/// Something able to ask a section's Elders for their `SectionKnowledge`.
+#[async_trait]
+pub trait KnowledgeSource: Send + Sync {
+    /// Asks the given Elders, in turn, for their section knowledge and returns the first answer.
+    async fn section_knowledge(&self, elders: &[SocketAddr]) -> Result<SectionKnowledge, Error>;
+}
+
+/// `KnowledgeSource` sending `SectionKnowledgeQuery`s over its own QUIC endpoint.
+pub struct QuicKnowledgeSource {
+    endpoint: Endpoint,
+    incoming_messages: Mutex<IncomingMessages>,
+    response_timeout: Duration,
+}
+
+impl QuicKnowledgeSource {
+    /// Creates a new source on a fresh endpoint, waiting up to `response_timeout` for each Elder
+    /// to answer.
+    pub async fn new(
+        qp2p_config: QuicP2pConfig,
+        response_timeout: Duration,
+    ) -> Result<Self, Error> {
+        let qp2p = QuicP2p::with_config(Some(qp2p_config), &[], false)?;
+        let (endpoint, _, incoming_messages, _) = qp2p.new_endpoint().await?;
+
+        Ok(Self {
+            endpoint,
+            incoming_messages: Mutex::new(incoming_messages),
+            response_timeout,
+        })
+    }
+
+    async fn query(&self, elder: &SocketAddr) -> Result<SectionKnowledge, Error> {
+        // The recipient answers with its own section key, so any key will do here.
+        let msg = SectionInfoMsg::SectionKnowledgeQuery
+            .serialize(XorName::random(), bls::SecretKey::random().public_key())?;
+
+        // Hold the lock for the whole exchange so concurrent queries don't steal each other's
+        // responses.
+        let mut incoming_messages = self.incoming_messages.lock().await;
+
+        self.endpoint.connect_to(elder).await?;
+        self.endpoint.send_message(msg, elder).await?;
+
+        let response = timeout(self.response_timeout, async {
+            while let Some((src, bytes)) = incoming_messages.next().await {
+                if src != *elder {
+                    trace!("Ignoring message from {} while crawling", src);
+                    continue;
+                }
+
+                match WireMsg::deserialize(bytes) {
+                    Ok(MessageType::SectionInfo {
+                        msg: SectionInfoMsg::SectionKnowledgeResponse(knowledge),
+                        ..
+                    }) => return Some(knowledge),
+                    Ok(msg) => trace!("Ignoring unexpected message from {}: {:?}", src, msg),
+                    Err(err) => warn!("Failed to deserialize message from {}: {:?}", src, err),
+                }
+            }
+            None
+        })
+        .await;
+
+        if let Err(err) = self.endpoint.disconnect_from(elder).await {
+            trace!("Failed to disconnect from {}: {:?}", elder, err);
+        }
+
+        response.ok().flatten().ok_or(Error::NoResponse)
+    }
+}
+
+impl Debug for QuicKnowledgeSource {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("QuicKnowledgeSource")
+            .field("endpoint", &self.endpoint)
+            .field("response_timeout", &self.response_timeout)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl KnowledgeSource for QuicKnowledgeSource {
+    async fn section_knowledge(&self, elders: &[SocketAddr]) -> Result<SectionKnowledge, Error> {
+        for elder in elders {
+            match self.query(elder).await {
+                Ok(knowledge) => return Ok(knowledge),
+                Err(err) => debug!(
+                    "Elder {} did not provide section knowledge: {:?}",
+                    elder, err
+                ),
+            }
+        }
+
+        Err(Error::NoResponse)
+    }
+}
+
+/// Walks the network section by section, starting from some seed Elders and following the
+/// other sections each section knows about, verifying every section against the genesis key.
+/// Only Elder addresses are needed to start from, so it can be driven by a client or a node alike.
+#[derive(Debug)]
+pub struct NetworkCrawler<S: KnowledgeSource> {
+    source: S,
+    genesis_key: Option<BlsPublicKey>,
+}
+
+impl<S: KnowledgeSource> NetworkCrawler<S> {
+    /// Creates a crawler trusting only sections proving back to `genesis_key`. If no genesis key
+    /// is given, the one reported by the seed Elders is trusted.
+    pub fn new(source: S, genesis_key: Option<BlsPublicKey>) -> Self {
+        Self {
+            source,
+            genesis_key,
+        }
+    }
+
+    /// Crawls the network starting from `seeds`, which should be the Elders of a single section.
+    /// Fails only if none of the seeds answered.
+    pub async fn crawl(&self, seeds: Vec<SocketAddr>) -> Result<NetworkTopology, Error> {
+        let seed_knowledge = self.source.section_knowledge(&seeds).await?;
+        let genesis_key = match self.genesis_key {
+            Some(key) => key,
+            None => {
+                warn!(
+                    "No genesis key given, trusting the one reported by the seeds: {:?}",
+                    seed_knowledge.genesis_key
+                );
+                seed_knowledge.genesis_key
+            }
+        };
+        let mut topology = NetworkTopology {
+            genesis_key: Some(genesis_key),
+            ..Default::default()
+        };
+
+        let seed_prefix = seed_knowledge.section_auth.prefix;
+        let mut visited = BTreeSet::new();
+        let _ = visited.insert(seed_prefix);
+        let mut queue: VecDeque<_> = self
+            .add_section(&mut topology, seed_prefix, seed_knowledge, &genesis_key)
+            .into();
+
+        while let Some((prefix, elders)) = queue.pop_front() {
+            if !visited.insert(prefix) {
+                continue;
+            }
+
+            trace!("Crawling section {:b}", prefix);
+            match self.source.section_knowledge(&elders).await {
+                Ok(knowledge) => {
+                    let _ = visited.insert(knowledge.section_auth.prefix);
+                    queue.extend(self.add_section(&mut topology, prefix, knowledge, &genesis_key));
+                }
+                Err(err) => {
+                    debug!("Section {:b} could not be crawled: {:?}", prefix, err);
+                    let _ = topology
+                        .failures
+                        .insert(prefix, SectionFailure::Unreachable);
+                }
+            }
+        }
+
+        Ok(topology)
+    }
+
+    /// Finds the section responsible for `name`, starting from `seeds`, which should be the Elders
+    /// of a single section, and following at each hop the closest section known to lead to `name`.
+    /// Every section on the way must verify against the genesis key.
+    pub async fn find_section(
+        &self,
+        seeds: Vec<SocketAddr>,
+        name: XorName,
+    ) -> Result<SectionAuthorityProvider, Error> {
+        let mut genesis_key = self.genesis_key;
+        let mut visited = BTreeSet::new();
+        let mut elders = seeds;
+
+        loop {
+            let knowledge = self.source.section_knowledge(&elders).await?;
+            let genesis_key = *genesis_key.get_or_insert_with(|| {
+                warn!(
+                    "No genesis key given, trusting the one reported by the seeds: {:?}",
+                    knowledge.genesis_key
+                );
+                knowledge.genesis_key
+            });
+
+            let prefix = knowledge.section_auth.prefix;
+            if let Err(failure) = verify_section_knowledge(&knowledge, &genesis_key) {
+                warn!("Section {:b} failed verification: {}", prefix, failure);
+                return Err(Error::SectionNotFound(name));
+            }
+            if prefix.matches(&name) {
+                return Ok(knowledge.section_auth);
+            }
+            if !visited.insert(prefix) {
+                debug!("Looking up {} led back to section {:b}", name, prefix);
+                return Err(Error::SectionNotFound(name));
+            }
+
+            trace!("Section {:b} is not responsible for {}", prefix, name);
+            elders = knowledge
+                .other_sections
+                .into_iter()
+                .filter(|section_auth| section_auth.prefix.matches(&name))
+                .max_by_key(|section_auth| section_auth.prefix.bit_count())
+                .map(|section_auth| section_auth.elders.values().copied().collect())
+                .ok_or(Error::SectionNotFound(name))?;
+        }
+    }
+
+    // Verifies `knowledge` received from the section we expected to have `prefix` and records
+    // the outcome in `topology`. Returns the other sections the section knows about, which are
+    // only worth following if the section itself could be verified.
+    fn add_section(
+        &self,
+        topology: &mut NetworkTopology,
+        prefix: Prefix,
+        knowledge: SectionKnowledge,
+        genesis_key: &BlsPublicKey,
+    ) -> Vec<(Prefix, Vec<SocketAddr>)> {
+        let actual_prefix = knowledge.section_auth.prefix;
+        if actual_prefix != prefix {
+            debug!("Section {:b} is now known as {:b}", prefix, actual_prefix);
+        }
+
+        if let Err(failure) = verify_section_knowledge(&knowledge, genesis_key) {
+            warn!(
+                "Section {:b} failed verification: {}",
+                actual_prefix, failure
+            );
+            let _ = topology.failures.insert(actual_prefix, failure);
+            return vec![];
+        }
+
+        let _ = topology.failures.remove(&prefix);
+        let _ = topology.failures.remove(&actual_prefix);
+
+        let section = SectionTopology::from_knowledge(&knowledge);
+        // Keep the most recent view if we already have this section from another path.
+        let is_newer = topology
+            .sections
+            .get(&actual_prefix)
+            .map(|existing| existing.chain_len < section.chain_len)
+            .unwrap_or(true);
+        if is_newer {
+            let _ = topology.sections.insert(actual_prefix, section);
+        }
+
+        knowledge
+            .other_sections
+            .into_iter()
+            .map(|section_auth| {
+                let elders = section_auth.elders.values().copied().collect();
+                (section_auth.prefix, elders)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::NetworkParams;
+    use anyhow::Result;
+    use secured_linked_list::SecuredLinkedList;
+    use std::collections::BTreeMap;
+
+    struct MockSource(BTreeMap<SocketAddr, SectionKnowledge>);
+
+    #[async_trait]
+    impl KnowledgeSource for MockSource {
+        async fn section_knowledge(
+            &self,
+            elders: &[SocketAddr],
+        ) -> Result<SectionKnowledge, Error> {
+            elders
+                .iter()
+                .find_map(|elder| self.0.get(elder).cloned())
+                .ok_or(Error::NoResponse)
+        }
+    }
+
+    // Creates the knowledge of a section with a single elder at `port`, whose key was signed by
+    // `genesis_sk`.
+    fn gen_knowledge(
+        genesis_sk: &bls::SecretKey,
+        prefix: Prefix,
+        port: u16,
+    ) -> Result<SectionKnowledge> {
+        let genesis_key = genesis_sk.public_key();
+        let poly = bls::poly::Poly::random(0, &mut rand::thread_rng());
+        let section_sk = bls::SecretKey::from_mut(&mut poly.evaluate(0));
+        let sk_set = bls::SecretKeySet::from(poly);
+        let section_key = section_sk.public_key();
+
+        let mut section_chain = SecuredLinkedList::new(genesis_key);
+        let sig = genesis_sk.sign(&bincode::serialize(&section_key)?);
+        section_chain.insert(&genesis_key, section_key, sig)?;
+
+        let mut elders = BTreeMap::new();
+        let _ = elders.insert(
+            prefix.substituted_in(XorName::random()),
+            SocketAddr::from(([127, 0, 0, 1], port)),
+        );
+        let section_auth = SectionAuthorityProvider {
+            prefix,
+            public_key_set: sk_set.public_keys(),
+            elders,
+            network_params: NetworkParams::default(),
+        };
+        let section_auth_sig = section_sk.sign(&bincode::serialize(&section_auth)?);
+
+        Ok(SectionKnowledge {
+            genesis_key,
+            section_auth,
+            section_auth_sig,
+            section_chain,
+            adult_count: 3,
+            other_sections: vec![],
+        })
+    }
+
+    fn elder_addr(knowledge: &SectionKnowledge) -> SocketAddr {
+        *knowledge.section_auth.elders.values().next().unwrap()
+    }
+
+    // Two sibling sections `0` and `1`, each knowing about the other.
+    fn gen_network(genesis_sk: &bls::SecretKey) -> Result<(SectionKnowledge, SectionKnowledge)> {
+        let mut knowledge0 = gen_knowledge(genesis_sk, Prefix::default().pushed(false), 5000)?;
+        let mut knowledge1 = gen_knowledge(genesis_sk, Prefix::default().pushed(true), 5001)?;
+        knowledge0.other_sections = vec![knowledge1.section_auth.clone()];
+        knowledge1.other_sections = vec![knowledge0.section_auth.clone()];
+        Ok((knowledge0, knowledge1))
+    }
+
+    fn mock_source(knowledges: Vec<SectionKnowledge>) -> MockSource {
+        MockSource(
+            knowledges
+                .into_iter()
+                .map(|knowledge| (elder_addr(&knowledge), knowledge))
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn crawl_finds_all_sections() -> Result<()> {
+        let genesis_sk = bls::SecretKey::random();
+        let (knowledge0, knowledge1) = gen_network(&genesis_sk)?;
+        let seeds = vec![elder_addr(&knowledge0)];
+        let crawler = NetworkCrawler::new(
+            mock_source(vec![knowledge0, knowledge1.clone()]),
+            Some(genesis_sk.public_key()),
+        );
+
+        let topology = crawler.crawl(seeds).await?;
+
+        assert_eq!(topology.sections.len(), 2);
+        assert!(topology.failures.is_empty());
+        assert!(topology.is_complete());
+        assert_eq!(topology.elder_count(), 2);
+        assert_eq!(topology.adult_count(), 6);
+
+        let section1 = &topology.sections[&Prefix::default().pushed(true)];
+        assert_eq!(
+            section1.section_key,
+            knowledge1.section_auth.public_key_set.public_key()
+        );
+        assert_eq!(section1.chain_len, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn crawl_trusts_seed_genesis_key_if_none_given() -> Result<()> {
+        let genesis_sk = bls::SecretKey::random();
+        let (knowledge0, knowledge1) = gen_network(&genesis_sk)?;
+        let seeds = vec![elder_addr(&knowledge0)];
+        let crawler = NetworkCrawler::new(mock_source(vec![knowledge0, knowledge1]), None);
+
+        let topology = crawler.crawl(seeds).await?;
+
+        assert_eq!(topology.genesis_key, Some(genesis_sk.public_key()));
+        assert!(topology.is_complete());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn crawl_rejects_section_of_other_network() -> Result<()> {
+        let genesis_sk = bls::SecretKey::random();
+        let (knowledge0, mut knowledge1) = gen_network(&genesis_sk)?;
+
+        // Section `1` claims to be part of the network, but its chain has a different root.
+        let other_genesis_sk = bls::SecretKey::random();
+        let forged = gen_knowledge(&other_genesis_sk, Prefix::default().pushed(true), 5001)?;
+        knowledge1.section_chain = forged.section_chain;
+        knowledge1.section_auth = forged.section_auth;
+        knowledge1.section_auth_sig = forged.section_auth_sig;
+
+        let seeds = vec![elder_addr(&knowledge0)];
+        let crawler = NetworkCrawler::new(
+            mock_source(vec![knowledge0, knowledge1]),
+            Some(genesis_sk.public_key()),
+        );
+
+        let topology = crawler.crawl(seeds).await?;
+
+        assert_eq!(topology.sections.len(), 1);
+        assert_eq!(
+            topology.failures.get(&Prefix::default().pushed(true)),
+            Some(&SectionFailure::UntrustedChain)
+        );
+        assert!(!topology.is_complete());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn crawl_records_unreachable_section() -> Result<()> {
+        let genesis_sk = bls::SecretKey::random();
+        let (knowledge0, _) = gen_network(&genesis_sk)?;
+        let seeds = vec![elder_addr(&knowledge0)];
+        let crawler =
+            NetworkCrawler::new(mock_source(vec![knowledge0]), Some(genesis_sk.public_key()));
+
+        let topology = crawler.crawl(seeds).await?;
+
+        assert_eq!(topology.sections.len(), 1);
+        assert_eq!(
+            topology.failures.get(&Prefix::default().pushed(true)),
+            Some(&SectionFailure::Unreachable)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn find_section_follows_other_sections() -> Result<()> {
+        let genesis_sk = bls::SecretKey::random();
+        let (knowledge0, knowledge1) = gen_network(&genesis_sk)?;
+        let seeds = vec![elder_addr(&knowledge0)];
+        let name = Prefix::default()
+            .pushed(true)
+            .substituted_in(XorName::random());
+        let crawler = NetworkCrawler::new(
+            mock_source(vec![knowledge0.clone(), knowledge1.clone()]),
+            Some(genesis_sk.public_key()),
+        );
+
+        let section_auth = crawler.find_section(seeds.clone(), name).await?;
+        assert_eq!(section_auth, knowledge1.section_auth);
+
+        // A name of the seed section is answered without leaving it.
+        let name = Prefix::default()
+            .pushed(false)
+            .substituted_in(XorName::random());
+        let crawler = NetworkCrawler::new(
+            mock_source(vec![knowledge0.clone()]),
+            Some(genesis_sk.public_key()),
+        );
+        let section_auth = crawler.find_section(seeds, name).await?;
+        assert_eq!(section_auth, knowledge0.section_auth);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn find_section_rejects_section_of_other_network() -> Result<()> {
+        let genesis_sk = bls::SecretKey::random();
+        let (knowledge0, _) = gen_network(&genesis_sk)?;
+        let mut forged = gen_knowledge(
+            &bls::SecretKey::random(),
+            Prefix::default().pushed(true),
+            5001,
+        )?;
+        forged.genesis_key = genesis_sk.public_key();
+
+        let seeds = vec![elder_addr(&knowledge0)];
+        let name = Prefix::default()
+            .pushed(true)
+            .substituted_in(XorName::random());
+        let crawler = NetworkCrawler::new(
+            mock_source(vec![knowledge0, forged]),
+            Some(genesis_sk.public_key()),
+        );
+
+        assert!(matches!(
+            crawler.find_section(seeds, name).await,
+            Err(Error::SectionNotFound(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn crawl_fails_without_responding_seeds() -> Result<()> {
+        let crawler = NetworkCrawler::new(mock_source(vec![]), None);
+        let seeds = vec![SocketAddr::from(([127, 0, 0, 1], 5000))];
+
+        assert!(matches!(crawler.crawl(seeds).await, Err(Error::NoResponse)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn verify_rejects_invalid_signature() -> Result<()> {
+        let genesis_sk = bls::SecretKey::random();
+        let mut knowledge = gen_knowledge(&genesis_sk, Prefix::default(), 5000)?;
+        assert_eq!(
+            verify_section_knowledge(&knowledge, &genesis_sk.public_key()),
+            Ok(())
+        );
+
+        knowledge.section_auth_sig = genesis_sk.sign(b"not the section authority provider");
+        assert_eq!(
+            verify_section_knowledge(&knowledge, &genesis_sk.public_key()),
+            Err(SectionFailure::InvalidSignature)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{NetworkTopology, SectionTopology};
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt::Write, net::SocketAddr};
+use xor_name::Prefix;
+
+#[derive(Serialize)]
+struct TopologyJson {
+    genesis_key: Option<String>,
+    complete: bool,
+    elder_count: usize,
+    adult_count: usize,
+    sections: Vec<SectionJson>,
+    failures: Vec<FailureJson>,
+}
+
+#[derive(Serialize)]
+struct SectionJson {
+    prefix: String,
+    section_key: String,
+    elders: Vec<ElderJson>,
+    adult_count: usize,
+    chain_len: usize,
+    elder_size: usize,
+    recommended_section_size: usize,
+}
+
+#[derive(Serialize)]
+struct ElderJson {
+    name: String,
+    addr: SocketAddr,
+}
+
+#[derive(Serialize)]
+struct FailureJson {
+    prefix: String,
+    reason: String,
+}
+
+impl NetworkTopology {
+    /// Serialises the topology to JSON. Prefixes are given as binary strings and keys and names
+    /// as hex strings.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        let json = TopologyJson {
+            genesis_key: self.genesis_key.map(|key| hex::encode(key.to_bytes())),
+            complete: self.is_complete(),
+            elder_count: self.elder_count(),
+            adult_count: self.adult_count(),
+            sections: self.sections.values().map(section_json).collect(),
+            failures: self
+                .failures
+                .iter()
+                .map(|(prefix, failure)| FailureJson {
+                    prefix: prefix_str(prefix),
+                    reason: failure.to_string(),
+                })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&json)
+    }
+
+    /// Renders the topology as a Graphviz digraph of the prefix tree, with one leaf per section.
+    /// Sections which failed to be crawled or verified are drawn dashed in red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph network {{");
+        let _ = writeln!(dot, "    node [shape=box, fontname=monospace];");
+
+        // Every prefix on the path from the root to a leaf, so the tree is connected.
+        let leaves: BTreeSet<_> = self
+            .sections
+            .keys()
+            .chain(self.failures.keys())
+            .copied()
+            .collect();
+        let mut inner = BTreeSet::new();
+        for leaf in &leaves {
+            let mut prefix = *leaf;
+            while prefix.bit_count() > 0 {
+                prefix = prefix.popped();
+                if !leaves.contains(&prefix) {
+                    let _ = inner.insert(prefix);
+                }
+            }
+        }
+
+        for prefix in &inner {
+            let _ = writeln!(
+                dot,
+                "    {} [shape=point, label=\"({})\"];",
+                node_id(prefix),
+                prefix_str(prefix)
+            );
+        }
+
+        for section in self.sections.values() {
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"({})\\nkey: {}\\nelders: {}, adults: {}\\nchain: {}\"];",
+                node_id(&section.prefix),
+                prefix_str(&section.prefix),
+                short_hex(&section.section_key.to_bytes()),
+                section.elders.len(),
+                section.adult_count,
+                section.chain_len,
+            );
+        }
+
+        for (prefix, failure) in &self.failures {
+            if self.sections.contains_key(prefix) {
+                continue;
+            }
+            let _ = writeln!(
+                dot,
+                "    {} [style=dashed, color=red, label=\"({})\\n{}\"];",
+                node_id(prefix),
+                prefix_str(prefix),
+                failure,
+            );
+        }
+
+        for prefix in leaves.iter().chain(inner.iter()) {
+            if prefix.bit_count() == 0 {
+                continue;
+            }
+            let bit = prefix.name().bit(prefix.bit_count() as u8 - 1);
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [label=\"{}\"];",
+                node_id(&prefix.popped()),
+                node_id(prefix),
+                bit as u8,
+            );
+        }
+
+        let _ = writeln!(dot, "}}");
+        dot
+    }
+}
+
+fn section_json(section: &SectionTopology) -> SectionJson {
+    SectionJson {
+        prefix: prefix_str(&section.prefix),
+        section_key: hex::encode(section.section_key.to_bytes()),
+        elders: section
+            .elders
+            .iter()
+            .map(|(name, addr)| ElderJson {
+                name: hex::encode(name.0),
+                addr: *addr,
+            })
+            .collect(),
+        adult_count: section.adult_count,
+        chain_len: section.chain_len,
+        elder_size: section.network_params.elder_size,
+        recommended_section_size: section.network_params.recommended_section_size,
+    }
+}
+
+fn prefix_str(prefix: &Prefix) -> String {
+    format!("{:b}", prefix)
+}
+
+fn node_id(prefix: &Prefix) -> String {
+    format!("\"p{}\"", prefix_str(prefix))
+}
+
+fn short_hex(bytes: &[u8]) -> String {
+    hex::encode(&bytes[..bytes.len().min(4)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::topology::SectionFailure;
+    use crate::messaging::NetworkParams;
+    use anyhow::Result;
+    use std::collections::BTreeMap;
+    use xor_name::XorName;
+
+    fn gen_topology() -> NetworkTopology {
+        let prefix0 = Prefix::default().pushed(false);
+        let prefix10 = Prefix::default().pushed(true).pushed(false);
+        let prefix11 = Prefix::default().pushed(true).pushed(true);
+
+        let mut elders = BTreeMap::new();
+        let _ = elders.insert(
+            prefix0.substituted_in(XorName::random()),
+            SocketAddr::from(([127, 0, 0, 1], 5000)),
+        );
+        let section0 = SectionTopology {
+            prefix: prefix0,
+            section_key: bls::SecretKey::random().public_key(),
+            elders,
+            adult_count: 4,
+            chain_len: 3,
+            network_params: NetworkParams::default(),
+        };
+        let section10 = SectionTopology {
+            prefix: prefix10,
+            elders: BTreeMap::new(),
+            ..section0.clone()
+        };
+
+        let mut topology = NetworkTopology {
+            genesis_key: Some(bls::SecretKey::random().public_key()),
+            ..Default::default()
+        };
+        let _ = topology.sections.insert(prefix0, section0);
+        let _ = topology.sections.insert(prefix10, section10);
+        let _ = topology
+            .failures
+            .insert(prefix11, SectionFailure::Unreachable);
+        topology
+    }
+
+    #[test]
+    fn json_export() -> Result<()> {
+        let topology = gen_topology();
+        let json: serde_json::Value = serde_json::from_str(&topology.to_json()?)?;
+
+        assert_eq!(json["complete"], false);
+        assert_eq!(json["elder_count"], 1);
+        assert_eq!(json["adult_count"], 8);
+        assert_eq!(json["sections"][0]["prefix"], "0");
+        assert_eq!(json["sections"][0]["chain_len"], 3);
+        assert_eq!(json["sections"][0]["elders"][0]["addr"], "127.0.0.1:5000");
+        assert_eq!(json["sections"][1]["prefix"], "10");
+        assert_eq!(json["failures"][0]["prefix"], "11");
+        assert_eq!(json["failures"][0]["reason"], "unreachable");
+
+        Ok(())
+    }
+
+    #[test]
+    fn dot_export() {
+        let dot = gen_topology().to_dot();
+
+        assert!(dot.starts_with("digraph network {"));
+        // The inner prefix `1` connects the root to sections `10` and `11`.
+        assert!(dot.contains("\"p\" -> \"p0\" [label=\"0\"];"));
+        assert!(dot.contains("\"p\" -> \"p1\" [label=\"1\"];"));
+        assert!(dot.contains("\"p1\" -> \"p10\" [label=\"0\"];"));
+        assert!(dot.contains("\"p1\" -> \"p11\" [label=\"1\"];"));
+        assert!(dot.contains("\"p11\" [style=dashed, color=red"));
+        assert!(dot.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod audit;
+mod crawler;
+mod export;
+
+pub use audit::{SupplyAudit, SupplyFailure, SupplyInconsistency};
+pub use crawler::{KnowledgeSource, NetworkCrawler, QuicKnowledgeSource};
+
+use crate::messaging::{section_info::SectionKnowledge, NetworkParams};
+use bls::PublicKey as BlsPublicKey;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    iter,
+    net::SocketAddr,
+};
+use xor_name::{Prefix, XorName};
+
+/// Topology of the network as discovered by a `NetworkCrawler`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkTopology {
+    /// Genesis key every section in `sections` was verified against.
+    pub genesis_key: Option<BlsPublicKey>,
+    /// Verified sections, by prefix.
+    pub sections: BTreeMap<Prefix, SectionTopology>,
+    /// Sections we learned about but could not obtain a verified view of.
+    pub failures: BTreeMap<Prefix, SectionFailure>,
+}
+
+impl NetworkTopology {
+    /// Returns whether the verified sections cover the whole xorspace, i.e. whether no part of
+    /// the network is missing from this topology.
+    pub fn is_complete(&self) -> bool {
+        !self.sections.is_empty() && Prefix::default().is_covered_by(self.sections.keys())
+    }
+
+    /// Total number of Elders across the verified sections.
+    pub fn elder_count(&self) -> usize {
+        self.sections
+            .values()
+            .map(|section| section.elders.len())
+            .sum()
+    }
+
+    /// Total number of Adults across the verified sections.
+    pub fn adult_count(&self) -> usize {
+        self.sections
+            .values()
+            .map(|section| section.adult_count)
+            .sum()
+    }
+}
+
+/// A single section of the network, as reported by its own Elders.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SectionTopology {
+    /// The section prefix.
+    pub prefix: Prefix,
+    /// The current section key.
+    pub section_key: BlsPublicKey,
+    /// The section's Elders as a map from their name to their socket address.
+    pub elders: BTreeMap<XorName, SocketAddr>,
+    /// Number of Adults in the section.
+    pub adult_count: usize,
+    /// Number of keys in the section chain, from the genesis key to `section_key`.
+    pub chain_len: usize,
+    /// Network parameters the section runs with.
+    pub network_params: NetworkParams,
+}
+
+impl SectionTopology {
+    fn from_knowledge(knowledge: &SectionKnowledge) -> Self {
+        Self {
+            prefix: knowledge.section_auth.prefix,
+            section_key: knowledge.section_auth.public_key_set.public_key(),
+            elders: knowledge.section_auth.elders.clone(),
+            adult_count: knowledge.adult_count,
+            chain_len: knowledge.section_chain.len(),
+            network_params: knowledge.section_auth.network_params,
+        }
+    }
+}
+
+/// Reason why a section is missing from the verified topology.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SectionFailure {
+    /// None of the section's Elders answered our query.
+    Unreachable,
+    /// The section chain does not prove back to the trusted genesis key.
+    UntrustedChain,
+    /// The section authority provider is not for the last key of the section chain.
+    KeyMismatch,
+    /// The signature over the section authority provider is invalid.
+    InvalidSignature,
+}
+
+impl Display for SectionFailure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Unreachable => write!(f, "unreachable"),
+            Self::UntrustedChain => write!(f, "untrusted section chain"),
+            Self::KeyMismatch => write!(f, "section key not at the end of the section chain"),
+            Self::InvalidSignature => write!(f, "invalid section authority signature"),
+        }
+    }
+}
+
+/// Verifies that `knowledge` describes a section of the network rooted at `genesis_key`: the
+/// section chain must prove back to it, end with the section key, and that key must have signed
+/// the section authority provider.
+pub fn verify_section_knowledge(
+    knowledge: &SectionKnowledge,
+    genesis_key: &BlsPublicKey,
+) -> Result<(), SectionFailure> {
+    let chain = &knowledge.section_chain;
+    if !chain.check_trust(iter::once(genesis_key)) {
+        return Err(SectionFailure::UntrustedChain);
+    }
+
+    if *chain.last_key() != knowledge.section_auth.public_key_set.public_key() {
+        return Err(SectionFailure::KeyMismatch);
+    }
+
+    let bytes = bincode::serialize(&knowledge.section_auth)
+        .map_err(|_| SectionFailure::InvalidSignature)?;
+    if !chain.last_key().verify(&knowledge.section_auth_sig, &bytes) {
+        return Err(SectionFailure::InvalidSignature);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::Client;
+use crate::types::Token;
+use anyhow::Result;
+use tokio::time::{sleep, Duration};
+
+/// Request a payout from the local testnet faucet to a client, and wait until it shows up
+/// in the client's history.
+///
+/// The Replicas of the faucet wallet cap the tokens paid out to each key, and the payouts per minute,
+/// by their own clocks.
+pub async fn fund_from_faucet(client: &Client, amount: Token) -> Result<()> {
+    let id = client.request_faucet_payout(amount).await?;
+
+    loop {
+        client.get_history().await?;
+        if client.has_credit(&id).await {
+            return Ok(());
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+}
@@ -6,18 +6,19 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod faucet;
 #[cfg(test)]
 mod test_client;
-#[cfg(feature = "simulated-payouts")]
 mod tokens;
 
 use anyhow::{anyhow, Context, Result};
 use dirs_next::home_dir;
 use std::path::Path;
 use std::{collections::HashSet, fs::File, io::BufReader, net::SocketAddr};
+
+pub use faucet::fund_from_faucet;
 #[cfg(test)]
-pub use test_client::{create_test_client, create_test_client_with, init_logger};
-#[cfg(feature = "simulated-payouts")]
+pub use test_client::{create_test_client, create_test_client_with, fund_test_client, init_logger};
 pub use tokens::{calculate_new_balance, gen_ed_keypair};
 
 // Relative path from $HOME where to read the genesis node connection information from
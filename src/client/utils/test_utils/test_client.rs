@@ -6,6 +6,8 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+#[cfg(not(feature = "simulated-payouts"))]
+use super::fund_from_faucet;
 use super::read_network_conn_info;
 use crate::client::Client;
 use crate::types::{Keypair, Token};
@@ -36,13 +38,16 @@ pub async fn create_test_client() -> Result<Client> {
 }
 
 /// Create a test client optionally providing keypair and/or bootstrap_config
-/// If no keypair is provided, a check is run that a balance has been generated for the client
+/// If no keypair is provided, the client is given 10 test tokens, by simulated payout or from the
+/// local testnet faucet, and a check is run that the balance has been generated for it
 pub async fn create_test_client_with(optional_keypair: Option<Keypair>) -> Result<Client> {
     init_logger();
     let contact_info = read_network_conn_info()?;
     let client = Client::new(optional_keypair.clone(), None, Some(contact_info)).await?;
 
     if optional_keypair.is_none() {
+        #[cfg(not(feature = "simulated-payouts"))]
+        fund_from_faucet(&client, Token::from_str("10")?).await?;
         // get history, will only be Ok when we have _some_ history, aka test tokens
         retry_loop!(client.get_history());
         // check we have some balance, 10 test coins
@@ -52,3 +57,13 @@ pub async fn create_test_client_with(optional_keypair: Option<Keypair>) -> Resul
 
     Ok(client)
 }
+
+/// Give test tokens to a client, by simulated payout or from the local testnet faucet
+pub async fn fund_test_client(client: &mut Client, amount: Token) -> Result<()> {
+    #[cfg(feature = "simulated-payouts")]
+    client.trigger_simulated_farming_payout(amount).await?;
+    #[cfg(not(feature = "simulated-payouts"))]
+    fund_from_faucet(client, amount).await?;
+
+    Ok(())
+}
@@ -16,6 +16,8 @@ use xor_name::XorName;
 pub enum ChunkRead {
     /// TODO: docs
     Get(ChunkAddress),
+    /// Check whether a chunk is stored, without fetching its content.
+    Exists(ChunkAddress),
 }
 
 /// TODO: docs
@@ -26,20 +28,38 @@ pub enum ChunkWrite {
     New(Chunk),
     /// TODO: docs
     DeletePrivate(ChunkAddress),
+    /// Store a private chunk which the Elders delete once its expiry has passed.
+    NewExpiring {
+        /// The private chunk.
+        chunk: Chunk,
+        /// Time the chunk expires at, in seconds since the Unix epoch.
+        expiry: u64,
+    },
+    /// Postpone the expiry of a private chunk stored with `NewExpiring`.
+    ExtendExpiry {
+        /// Address of the chunk.
+        address: ChunkAddress,
+        /// New time the chunk expires at, in seconds since the Unix epoch.
+        expiry: u64,
+    },
 }
 
 impl ChunkRead {
     /// Creates a Response containing an error, with the Response variant corresponding to the
     /// Request variant.
     pub fn error(&self, error: Error) -> QueryResponse {
-        QueryResponse::GetChunk(Err(error))
+        use ChunkRead::*;
+        match self {
+            Get(_) => QueryResponse::GetChunk(Err(error)),
+            Exists(_) => QueryResponse::ChunkExists(Err(error)),
+        }
     }
 
     /// Returns the address of the destination for `request`.
     pub fn dst_address(&self) -> XorName {
         use ChunkRead::*;
         match self {
-            Get(address) => *address.name(),
+            Get(address) | Exists(address) => *address.name(),
         }
     }
 }
@@ -55,16 +75,19 @@ impl ChunkWrite {
     pub fn dst_address(&self) -> XorName {
         use ChunkWrite::*;
         match self {
-            New(ref data) => *data.name(),
-            DeletePrivate(ref address) => *address.name(),
+            New(ref data)
+            | NewExpiring {
+                chunk: ref data, ..
+            } => *data.name(),
+            DeletePrivate(ref address) | ExtendExpiry { ref address, .. } => *address.name(),
         }
     }
 
     /// Returns the owner of the chunk on a new chunk write.
     pub fn owner(&self) -> Option<PublicKey> {
         match self {
-            Self::New(chunk) => chunk.owner().cloned(),
-            Self::DeletePrivate(_) => None,
+            Self::New(chunk) | Self::NewExpiring { chunk, .. } => chunk.owner().cloned(),
+            Self::DeletePrivate(_) | Self::ExtendExpiry { .. } => None,
         }
     }
 }
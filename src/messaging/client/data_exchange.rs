@@ -44,6 +44,9 @@ pub struct DataExchange {
 pub struct ChunkDataExchange {
     /// Full Adults register
     pub full_adults: BTreeSet<XorName>,
+    /// Owners and expiries, in seconds since the Unix epoch, of the private chunks stored with an
+    /// expiry.
+    pub expiries: BTreeMap<ChunkAddress, (PublicKey, u64)>,
 }
 
 /// Map data exchange.
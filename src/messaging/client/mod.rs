@@ -39,13 +39,17 @@ pub use self::{
 
 use crate::messaging::{MessageId, MessageType, WireMsg};
 use crate::types::{
-    register::{Entry, EntryHash, Permissions, Policy, Register},
+    register::{
+        BranchDiff, Entry, EntryHash, EntryNode, Permissions, Policy, Register, RegisterOp,
+    },
     ActorHistory, Chunk, Map, MapEntries, MapPermissionSet, MapValue, MapValues, PublicKey,
-    Sequence, SequenceEntries, SequenceEntry, SequencePermissions, SequencePrivatePolicy,
-    SequencePublicPolicy, Signature, Token, TransferAgreementProof, TransferValidated,
+    RewardStatement, SectionSupplyShare, Sequence, SequenceEntries, SequenceEntry, SequenceEntryOp,
+    SequencePermissions, SequencePrivatePolicy, SequencePublicPolicy, Signature, Token,
+    TransferAgreementProof, TransferValidated,
 };
 use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
+use secured_linked_list::SecuredLinkedList;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -356,6 +360,8 @@ pub enum QueryResponse {
     //
     /// Get Chunk.
     GetChunk(Result<Chunk>),
+    /// Chunk exists, or the error it wasn't found.
+    ChunkExists(Result<()>),
     //
     // ===== Map =====
     //
@@ -392,6 +398,8 @@ pub enum QueryResponse {
     GetSequencePrivatePolicy(Result<SequencePrivatePolicy>),
     /// Get Sequence permissions for a user.
     GetSequenceUserPermissions(Result<SequencePermissions>),
+    /// Get the Sequence entry insertions not covered by a clock.
+    GetSequenceOps(Result<Vec<SequenceEntryOp>>),
     //
     // ===== Register Data =====
     //
@@ -405,6 +413,14 @@ pub enum QueryResponse {
     GetRegisterPolicy(Result<Policy>),
     /// Get Register permissions for a user.
     GetRegisterUserPermissions(Result<Permissions>),
+    /// Get all Register entries along with their parents' hashes.
+    GetRegisterEntries(Result<BTreeMap<EntryHash, EntryNode>>),
+    /// Get the ancestry of a Register entry.
+    GetRegisterAncestry(Result<Vec<(EntryHash, EntryNode)>>),
+    /// Compare two branches of a Register.
+    DiffRegisterBranches(Result<BranchDiff>),
+    /// Get the signed operations of the Register entries not in the history of some heads.
+    GetRegisterOps(Result<Vec<RegisterOp<Entry>>>),
     //
     // ===== Tokens =====
     //
@@ -414,6 +430,12 @@ pub enum QueryResponse {
     GetHistory(Result<ActorHistory>),
     /// Get Store Cost.
     GetStoreCost(Result<(u64, Token, PublicKey)>),
+    /// Get the section chain proving a section key.
+    GetSectionChain(Result<SecuredLinkedList>),
+    /// Get the statement of rewards paid to a wallet.
+    GetRewardStatement(Result<RewardStatement>),
+    /// Get the token totals of a section, signed by one of its Elders.
+    GetSectionSupply(Result<SectionSupplyShare>),
 }
 
 impl QueryResponse {
@@ -422,6 +444,7 @@ impl QueryResponse {
         use QueryResponse::*;
         match self {
             GetChunk(result) => result.is_ok(),
+            ChunkExists(result) => result.is_ok(),
             GetMap(result) => result.is_ok(),
             GetMapShell(result) => result.is_ok(),
             GetMapVersion(result) => result.is_ok(),
@@ -437,14 +460,22 @@ impl QueryResponse {
             GetSequencePublicPolicy(result) => result.is_ok(),
             GetSequencePrivatePolicy(result) => result.is_ok(),
             GetSequenceUserPermissions(result) => result.is_ok(),
+            GetSequenceOps(result) => result.is_ok(),
             GetRegister(result) => result.is_ok(),
             GetRegisterOwner(result) => result.is_ok(),
             ReadRegister(result) => result.is_ok(),
             GetRegisterPolicy(result) => result.is_ok(),
             GetRegisterUserPermissions(result) => result.is_ok(),
+            GetRegisterEntries(result) => result.is_ok(),
+            GetRegisterAncestry(result) => result.is_ok(),
+            DiffRegisterBranches(result) => result.is_ok(),
+            GetRegisterOps(result) => result.is_ok(),
             GetBalance(result) => result.is_ok(),
             GetHistory(result) => result.is_ok(),
             GetStoreCost(result) => result.is_ok(),
+            GetSectionChain(result) => result.is_ok(),
+            GetRewardStatement(result) => result.is_ok(),
+            GetSectionSupply(result) => result.is_ok(),
         }
     }
 }
@@ -492,13 +523,21 @@ try_from!((u64, SequenceEntry), GetSequenceLastEntry);
 try_from!(SequencePublicPolicy, GetSequencePublicPolicy);
 try_from!(SequencePrivatePolicy, GetSequencePrivatePolicy);
 try_from!(SequencePermissions, GetSequenceUserPermissions);
+try_from!(Vec<SequenceEntryOp>, GetSequenceOps);
 try_from!(Register, GetRegister);
 try_from!(PublicKey, GetRegisterOwner);
 try_from!(BTreeSet<(EntryHash, Entry)>, ReadRegister);
 try_from!(Policy, GetRegisterPolicy);
 try_from!(Permissions, GetRegisterUserPermissions);
+try_from!(BTreeMap<EntryHash, EntryNode>, GetRegisterEntries);
+try_from!(Vec<(EntryHash, EntryNode)>, GetRegisterAncestry);
+try_from!(BranchDiff, DiffRegisterBranches);
+try_from!(Vec<RegisterOp<Entry>>, GetRegisterOps);
 try_from!(Token, GetBalance);
 try_from!(ActorHistory, GetHistory);
+try_from!(SecuredLinkedList, GetSectionChain);
+try_from!(RewardStatement, GetRewardStatement);
+try_from!(SectionSupplyShare, GetSectionSupply);
 
 #[cfg(test)]
 mod tests {
@@ -8,11 +8,11 @@
 
 use super::{CmdError, Error, QueryResponse};
 use crate::types::{
-    register::{Address, Entry, Register, RegisterOp, User},
+    register::{Address, Entry, EntryHash, PolicyUpdate, Register, RegisterOp, User},
     PublicKey,
 };
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{collections::BTreeSet, fmt};
 use xor_name::XorName;
 
 /// Register reading queries
@@ -33,6 +33,32 @@ pub enum RegisterRead {
     },
     /// Get current owner.
     GetOwner(Address),
+    /// Get all the entries of the Register along with their parents' hashes.
+    GetEntries(Address),
+    /// Walk the history of the Register back from an entry.
+    GetAncestry {
+        /// Register address.
+        address: Address,
+        /// Hash of the entry to start from.
+        hash: EntryHash,
+    },
+    /// Compare two branches of the Register.
+    DiffBranches {
+        /// Register address.
+        address: Address,
+        /// Hash of the head of the first branch.
+        first: EntryHash,
+        /// Hash of the head of the second branch.
+        second: EntryHash,
+    },
+    /// Get the signed operations of the entries of the Register which aren't in the history of
+    /// the given heads, see `Register::ops_since`.
+    GetOpsSince {
+        /// Register address.
+        address: Address,
+        /// The current entries of the requester's replica.
+        heads: BTreeSet<EntryHash>,
+    },
 }
 
 /// Register writing commands
@@ -48,6 +74,20 @@ pub enum RegisterWrite {
     /// This operation MUST return an error if applied to public Register. Only the current
     /// owner(s) can perform this action.
     Delete(Address),
+    /// Update the policy and ownership of a Register, e.g. to hand it over to another owner.
+    ///
+    /// The update must be signed by enough of the current owners.
+    UpdatePolicy(PolicyUpdate),
+    /// Postpone the expiry of a private Register.
+    ///
+    /// The Register must have been created with an expiry, and only its owner(s) can perform
+    /// this action.
+    ExtendExpiry {
+        /// Address of the Register.
+        address: Address,
+        /// New time the Register expires at, in seconds since the Unix epoch.
+        expiry: u64,
+    },
 }
 
 impl RegisterRead {
@@ -62,6 +102,10 @@ impl RegisterRead {
                 QueryResponse::GetRegisterUserPermissions(Err(error))
             }
             RegisterRead::GetOwner(_) => QueryResponse::GetRegisterOwner(Err(error)),
+            RegisterRead::GetEntries(_) => QueryResponse::GetRegisterEntries(Err(error)),
+            RegisterRead::GetAncestry { .. } => QueryResponse::GetRegisterAncestry(Err(error)),
+            RegisterRead::DiffBranches { .. } => QueryResponse::DiffRegisterBranches(Err(error)),
+            RegisterRead::GetOpsSince { .. } => QueryResponse::GetRegisterOps(Err(error)),
         }
     }
 
@@ -72,7 +116,11 @@ impl RegisterRead {
             | RegisterRead::Read(ref address)
             | RegisterRead::GetPolicy(ref address)
             | RegisterRead::GetUserPermissions { ref address, .. }
-            | RegisterRead::GetOwner(ref address) => *address.name(),
+            | RegisterRead::GetOwner(ref address)
+            | RegisterRead::GetEntries(ref address)
+            | RegisterRead::GetAncestry { ref address, .. }
+            | RegisterRead::DiffBranches { ref address, .. }
+            | RegisterRead::GetOpsSince { ref address, .. } => *address.name(),
         }
     }
 }
@@ -88,8 +136,10 @@ impl RegisterWrite {
     pub fn dst_address(&self) -> XorName {
         match self {
             RegisterWrite::New(ref data) => *data.name(),
-            RegisterWrite::Delete(ref address) => *address.name(),
+            RegisterWrite::Delete(ref address)
+            | RegisterWrite::ExtendExpiry { ref address, .. } => *address.name(),
             RegisterWrite::Edit(ref op) => *op.address.name(),
+            RegisterWrite::UpdatePolicy(ref update) => *update.address.name(),
         }
     }
 
@@ -110,7 +160,14 @@ impl fmt::Debug for RegisterWrite {
             match self {
                 RegisterWrite::New(register) => format!("New({:?})", register.address()),
                 RegisterWrite::Delete(address) => format!("Delete({:?})", address),
+                RegisterWrite::ExtendExpiry { address, expiry } => {
+                    format!("ExtendExpiry({:?}, {})", address, expiry)
+                }
                 RegisterWrite::Edit(op) => format!("Edit({:?})", op),
+                RegisterWrite::UpdatePolicy(update) => format!(
+                    "UpdatePolicy({:?}, version {})",
+                    update.address, update.version
+                ),
             }
         )
     }
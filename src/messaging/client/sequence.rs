@@ -8,8 +8,9 @@
 
 use super::{CmdError, Error, QueryResponse};
 use crate::types::{
-    PublicKey, Sequence, SequenceAddress as Address, SequenceEntry as Entry,
-    SequenceIndex as Index, SequenceOp, SequenceUser as User,
+    PublicKey, Sequence, SequenceAddress as Address, SequenceClock as Clock,
+    SequenceEntry as Entry, SequenceIndex as Index, SequenceOp,
+    SequencePolicyUpdate as PolicyUpdate, SequenceUser as User,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -49,6 +50,14 @@ pub enum SequenceRead {
         /// User to get permissions for.
         user: User,
     },
+    /// Get the entry insertions of the Sequence which aren't covered by the given clock, see
+    /// `Sequence::ops_since`.
+    GetOpsSince {
+        /// Sequence address.
+        address: Address,
+        /// The clock of the requester's replica.
+        clock: Clock,
+    },
 }
 
 /// TODO: docs
@@ -64,6 +73,20 @@ pub enum SequenceWrite {
     /// This operation MUST return an error if applied to public Sequence. Only the current
     /// owner(s) can perform this action.
     Delete(Address),
+    /// Update the policy and ownership of a Sequence, e.g. to hand it over to another owner.
+    ///
+    /// The update must be signed by enough of the current owners.
+    UpdatePolicy(PolicyUpdate),
+    /// Postpone the expiry of a private Sequence.
+    ///
+    /// The Sequence must have been created with an expiry, and only its owner(s) can perform
+    /// this action.
+    ExtendExpiry {
+        /// Address of the Sequence.
+        address: Address,
+        /// New time the Sequence expires at, in seconds since the Unix epoch.
+        expiry: u64,
+    },
 }
 
 impl SequenceRead {
@@ -78,6 +101,7 @@ impl SequenceRead {
             GetPublicPolicy(_) => QueryResponse::GetSequencePublicPolicy(Err(error)),
             GetPrivatePolicy(_) => QueryResponse::GetSequencePrivatePolicy(Err(error)),
             GetUserPermissions { .. } => QueryResponse::GetSequenceUserPermissions(Err(error)),
+            GetOpsSince { .. } => QueryResponse::GetSequenceOps(Err(error)),
         }
     }
 
@@ -90,7 +114,8 @@ impl SequenceRead {
             | GetLastEntry(ref address)
             | GetPublicPolicy(ref address)
             | GetPrivatePolicy(ref address)
-            | GetUserPermissions { ref address, .. } => *address.name(),
+            | GetUserPermissions { ref address, .. }
+            | GetOpsSince { ref address, .. } => *address.name(),
         }
     }
 }
@@ -107,8 +132,9 @@ impl SequenceWrite {
         use SequenceWrite::*;
         match self {
             New(ref data) => *data.name(),
-            Delete(ref address) => *address.name(),
+            Delete(ref address) | ExtendExpiry { ref address, .. } => *address.name(),
             Edit(ref op) => *op.address.name(),
+            UpdatePolicy(ref update) => *update.address.name(),
         }
     }
 
@@ -130,7 +156,14 @@ impl fmt::Debug for SequenceWrite {
             match self {
                 New(seq) => format!("New({:?})", seq.address()),
                 Delete(address) => format!("Delete({:?})", address),
+                ExtendExpiry { address, expiry } => {
+                    format!("ExtendExpiry({:?}, {})", address, expiry)
+                }
                 Edit(op) => format!("Edit({:?})", op),
+                UpdatePolicy(update) => format!(
+                    "UpdatePolicy({:?}, version {})",
+                    update.address, update.version
+                ),
             }
         )
     }
@@ -9,7 +9,7 @@
 use super::{CmdError, Error, QueryResponse, TransferError};
 #[cfg(feature = "simulated-payouts")]
 use crate::types::Transfer;
-use crate::types::{PublicKey, SignedTransfer, TransferAgreementProof};
+use crate::types::{DebitId, PublicKey, SignedCredit, SignedTransfer, TransferAgreementProof};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use xor_name::XorName;
@@ -25,6 +25,24 @@ pub enum TransferCmd {
     ValidateTransfer(SignedTransfer),
     /// The cmd to register the consensused transfer.
     RegisterTransfer(TransferAgreementProof),
+    /// The cmd to release the credit of a transfer held in escrow to its recipient,
+    /// revealing the secret of its condition.
+    ReleaseEscrow {
+        /// The id of the escrowed transfer.
+        id: DebitId,
+        /// The preimage of the hash lock of the condition.
+        secret: Vec<u8>,
+    },
+    /// The cmd to refund a transfer held in escrow to its sender, once its deadline has passed.
+    RefundEscrow(DebitId),
+    /// The cmd to request a payout from the faucet of a test network, which its Replicas make
+    /// within the limits of the faucet.
+    RequestFaucetPayout {
+        /// The faucet wallet.
+        faucet: PublicKey,
+        /// The credit to the recipient, signed by the recipient.
+        payout: SignedCredit,
+    },
 }
 
 /// Token query that is sent to network.
@@ -47,6 +65,27 @@ pub enum TransferQuery {
         ///
         bytes: u64,
     },
+    /// Get the chain of section keys from genesis to the given key,
+    /// f.ex. the key of the replicas which validated a transfer.
+    GetSectionChain {
+        /// The wallet key, whose section is queried.
+        at: PublicKey,
+        /// The section key to prove.
+        key: bls::PublicKey,
+    },
+    /// Get the statement of the rewards paid to a wallet by the section of a node.
+    GetRewardStatement {
+        /// The name of the node, whose section is queried.
+        node: XorName,
+        /// The wallet the rewards are paid to.
+        wallet: PublicKey,
+    },
+    /// Get the token totals of a section, signed by the Elder with its key share,
+    /// for auditing the supply of the network.
+    GetSectionSupply {
+        /// A name within the section queried.
+        section: XorName,
+    },
 }
 
 impl TransferCmd {
@@ -58,7 +97,10 @@ impl TransferCmd {
         use TransferError::*;
         match *self {
             ValidateTransfer(_) => Transfer(TransferValidation(error)),
-            RegisterTransfer(_) => Transfer(TransferRegistration(error)),
+            RegisterTransfer(_)
+            | ReleaseEscrow { .. }
+            | RefundEscrow(_)
+            | RequestFaucetPayout { .. } => Transfer(TransferRegistration(error)),
             #[cfg(feature = "simulated-payouts")]
             SimulatePayout(_) => Transfer(TransferRegistration(error)),
         }
@@ -70,6 +112,8 @@ impl TransferCmd {
         match self {
             RegisterTransfer(ref proof) => XorName::from(proof.sender()), // this is handled where the debit is made
             ValidateTransfer(ref signed_transfer) => XorName::from(signed_transfer.sender()), // this is handled where the debit is made
+            ReleaseEscrow { id, .. } | RefundEscrow(id) => XorName::from(id.actor), // this is handled where the debit was made
+            RequestFaucetPayout { faucet, .. } => XorName::from(*faucet), // this is handled where the faucet wallet is
             #[cfg(feature = "simulated-payouts")]
             SimulatePayout(ref transfer) => XorName::from(transfer.debit().sender()), // this is handled where the debit is made
         }
@@ -85,6 +129,9 @@ impl fmt::Debug for TransferCmd {
             match *self {
                 RegisterTransfer { .. } => "RegisterTransfer",
                 ValidateTransfer { .. } => "ValidateTransfer",
+                ReleaseEscrow { .. } => "ReleaseEscrow",
+                RefundEscrow { .. } => "RefundEscrow",
+                RequestFaucetPayout { .. } => "RequestFaucetPayout",
                 #[cfg(feature = "simulated-payouts")]
                 SimulatePayout { .. } => "SimulatePayout",
             }
@@ -101,6 +148,9 @@ impl TransferQuery {
             GetBalance(_) => QueryResponse::GetBalance(Err(error)),
             GetHistory { .. } => QueryResponse::GetHistory(Err(error)),
             GetStoreCost { .. } => QueryResponse::GetStoreCost(Err(error)),
+            GetSectionChain { .. } => QueryResponse::GetSectionChain(Err(error)),
+            GetRewardStatement { .. } => QueryResponse::GetRewardStatement(Err(error)),
+            GetSectionSupply { .. } => QueryResponse::GetSectionSupply(Err(error)),
         }
     }
 
@@ -108,9 +158,12 @@ impl TransferQuery {
     pub fn dst_address(&self) -> XorName {
         use TransferQuery::*;
         match self {
-            GetBalance(at) | GetHistory { at, .. } | GetStoreCost { requester: at, .. } => {
-                XorName::from(*at)
-            }
+            GetBalance(at)
+            | GetHistory { at, .. }
+            | GetStoreCost { requester: at, .. }
+            | GetSectionChain { at, .. } => XorName::from(*at),
+            GetRewardStatement { node, .. } => *node,
+            GetSectionSupply { section } => *section,
         }
     }
 }
@@ -124,6 +177,9 @@ impl fmt::Debug for TransferQuery {
             GetStoreCost { bytes, .. } => {
                 write!(formatter, "TransferQuery::GetStoreCost of {:?}", bytes)
             }
+            GetSectionChain { .. } => write!(formatter, "TransferQuery::GetSectionChain"),
+            GetRewardStatement { .. } => write!(formatter, "TransferQuery::GetRewardStatement"),
+            GetSectionSupply { .. } => write!(formatter, "TransferQuery::GetSectionSupply"),
         }
     }
 }
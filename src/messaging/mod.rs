@@ -19,6 +19,7 @@ pub mod node;
 pub mod sap;
 /// Queries and responses for section info
 pub mod section_info;
+mod trace;
 
 /// Functionality for serialising and deserialising messages
 pub mod serialisation;
@@ -27,8 +28,9 @@ pub use self::{
     errors::{Error, Result},
     location::{Aggregation, DstLocation, EndUser, Itinerary, SrcLocation},
     msg_id::{MessageId, MESSAGE_ID_LEN},
-    sap::SectionAuthorityProvider,
+    sap::{FaucetParams, NetworkParams, RewardPolicyKind, SectionAuthorityProvider},
     serialisation::WireMsg,
+    trace::{TraceContext, TraceId, TRACE_ID_LEN},
 };
 use crate::messaging::node::Variant;
 use bls::PublicKey;
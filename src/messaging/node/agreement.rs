@@ -6,7 +6,11 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{plain_message::PlainMessage, section::NodeState, signed::KeyedSig};
+use super::{
+    plain_message::PlainMessage,
+    section::{NodeState, Peer},
+    signed::KeyedSig,
+};
 use crate::messaging::{MessageId, SectionAuthorityProvider};
 use bls::PublicKey as BlsPublicKey;
 use ed25519_dalek::{PublicKey, Signature};
@@ -120,4 +124,7 @@ pub enum Proposal {
     },
     /// Proposal to change whether new nodes are allowed to join our section.
     JoinsAllowed((MessageId, bool)),
+    /// Proposal to keep an elder of our section as an adult, leaving it out of the elder
+    /// candidates for as long as it is a member.
+    Demote(Peer),
 }
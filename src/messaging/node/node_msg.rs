@@ -17,8 +17,9 @@ use crate::messaging::{
     EndUser, MessageId, MessageType, WireMsg,
 };
 use crate::types::{
-    ActorHistory, Chunk, ChunkAddress, CreditAgreementProof, NodeAge, PublicKey, ReplicaEvent,
-    SectionElders, Signature,
+    ActorHistory, Chunk, ChunkAddress, CreditAgreementProof, EscrowSettlementShare,
+    FaucetPayoutShare, NodeAge, PublicKey, ReplicaEvent, RewardRound, SectionElders, SectionTotals,
+    Signature,
 };
 use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
@@ -167,7 +168,14 @@ pub enum NodeSystemCmd {
         section: XorName,
     },
     /// Replicate a given chunk at an Adult
-    ReplicateChunk(Chunk),
+    ReplicateChunk {
+        /// The chunk.
+        chunk: Chunk,
+        /// Time the chunk expires at, in seconds since the Unix epoch, if it was stored with one.
+        expiry: Option<u64>,
+    },
+    /// Delete a private chunk which expired at an Adult
+    DeleteExpiredChunk(ChunkAddress),
     /// Tells the Elders to re-publish a chunk in the data section
     RepublishChunk(Chunk),
     /// When new section key, all propose a reward payout.
@@ -183,6 +191,10 @@ pub enum NodeSystemCmd {
         user_wallets: BTreeMap<PublicKey, ActorHistory>,
         /// Metadata
         metadata: DataExchange,
+        /// Tokens minted and burned by the section, and by those it split from
+        supply: SectionTotals,
+        /// The section's reward rounds, with the rewards of the recipient's nodes
+        reward_rounds: Vec<RewardRound>,
     },
 }
 
@@ -192,6 +204,10 @@ pub enum NodeSystemCmd {
 pub enum NodeTransferCmd {
     ///
     PropagateTransfer(CreditAgreementProof),
+    /// An Elder's share of the signature over the settlement of an escrow.
+    ProposeEscrowSettlement(EscrowSettlementShare),
+    /// An Elder's share of the signature over a payout from the faucet.
+    ProposeFaucetPayout(FaucetPayoutShare),
 }
 
 // -------------- Node Events --------------
@@ -311,6 +327,8 @@ pub enum NodeTransferQueryResponse {
 pub enum NodeDataQueryResponse {
     /// Elder to Adult Get.
     GetChunk(Result<Chunk>),
+    /// Elder to Adult existence check.
+    ChunkExists(Result<()>),
 }
 
 ///
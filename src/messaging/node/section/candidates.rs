@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::messaging::NetworkParams;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt::Debug, net::SocketAddr};
 use xor_name::{Prefix, XorName};
@@ -17,4 +18,6 @@ pub struct ElderCandidates {
     pub elders: BTreeMap<XorName, SocketAddr>,
     /// The section prefix. It matches all the members' names.
     pub prefix: Prefix,
+    /// Network parameters the resulting `SectionAuthorityProvider` will carry.
+    pub network_params: NetworkParams,
 }
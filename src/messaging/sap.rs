@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::types::{PublicKey, Token};
 use bls::PublicKeySet;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,7 @@ use std::{
     collections::BTreeMap,
     fmt::{self, Debug, Display, Formatter},
     net::SocketAddr,
+    str::FromStr,
 };
 use xor_name::{Prefix, XorName};
 
@@ -27,6 +29,139 @@ pub struct SectionAuthorityProvider {
     pub public_key_set: PublicKeySet,
     /// The section's complete set of elders as a map from their name to their socket address.
     pub elders: BTreeMap<XorName, SocketAddr>,
+    /// Network parameters set by the genesis node and inherited by every section.
+    pub network_params: NetworkParams,
+}
+
+/// Parameters of the network which are chosen when the first node starts and then carried
+/// unchanged in every `SectionAuthorityProvider` of the network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct NetworkParams {
+    /// Number of elders per section.
+    pub elder_size: usize,
+    /// Recommended section size. Nodes keep being added until the section reaches this size and
+    /// a section only splits once both halves would have at least this many adults.
+    pub recommended_section_size: usize,
+    /// How the sections of the network distribute the rewards they pay out to their nodes.
+    pub reward_policy: RewardPolicyKind,
+    /// The faucet of a local or test network, if the genesis node set one up.
+    pub faucet: Option<FaucetParams>,
+}
+
+impl NetworkParams {
+    /// Number of elders per section used unless the genesis node chooses otherwise.
+    pub const DEFAULT_ELDER_SIZE: usize = 7;
+
+    /// Creates `NetworkParams` with the given elder size and recommended section size.
+    pub fn new(elder_size: usize, recommended_section_size: usize) -> Self {
+        Self {
+            elder_size,
+            recommended_section_size,
+            reward_policy: RewardPolicyKind::default(),
+            faucet: None,
+        }
+    }
+
+    /// Returns these parameters with the given reward policy.
+    pub fn with_reward_policy(self, reward_policy: RewardPolicyKind) -> Self {
+        Self {
+            reward_policy,
+            ..self
+        }
+    }
+
+    /// Returns these parameters with the given faucet.
+    pub fn with_faucet(self, faucet: FaucetParams) -> Self {
+        Self {
+            faucet: Some(faucet),
+            ..self
+        }
+    }
+
+    /// Returns whether these parameters describe a workable network, i.e. at least one elder per
+    /// section and sections which are not smaller than their set of elders.
+    pub fn is_valid(&self) -> bool {
+        self.elder_size > 0 && self.recommended_section_size >= self.elder_size
+    }
+}
+
+impl Default for NetworkParams {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ELDER_SIZE, 2 * Self::DEFAULT_ELDER_SIZE)
+    }
+}
+
+/// A faucet for local and test networks: a wallet funded at genesis, from which its Replicas pay
+/// out on request of the recipients, a limited amount to each key, at a limited rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct FaucetParams {
+    /// Tokens credited to the faucet wallet at genesis.
+    pub supply: Token,
+    /// Most tokens the faucet pays out to any one key.
+    pub cap_per_key: Token,
+    /// Most payouts the faucet makes per minute.
+    pub payouts_per_minute: usize,
+}
+
+impl FaucetParams {
+    /// Tokens the faucet pays out to any one key unless the genesis node chooses otherwise.
+    pub const DEFAULT_CAP_PER_KEY: Token = Token::from_nano(1_000 * 1_000_000_000);
+    /// Payouts per minute unless the genesis node chooses otherwise.
+    pub const DEFAULT_PAYOUTS_PER_MINUTE: usize = 600;
+
+    /// Creates `FaucetParams` for the given supply, with the default limits.
+    pub fn new(supply: Token) -> Self {
+        Self {
+            supply,
+            cap_per_key: Self::DEFAULT_CAP_PER_KEY,
+            payouts_per_minute: Self::DEFAULT_PAYOUTS_PER_MINUTE,
+        }
+    }
+
+    /// The faucet wallet of the network with the given genesis key.
+    ///
+    /// The wallet is the genesis key itself, which no one but the genesis node ever held. Its
+    /// Replicas refuse any transfer from it, so the faucet only pays out what they sign themselves.
+    pub fn wallet(genesis_key: bls::PublicKey) -> PublicKey {
+        PublicKey::Bls(genesis_key)
+    }
+}
+
+/// The policy by which a section distributes its rewards among its nodes. Whatever the policy,
+/// only nodes which have reached the minimum reward age are rewarded.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum RewardPolicyKind {
+    /// Nodes are rewarded by age, each step up in age doubling the share.
+    #[default]
+    NodeAge,
+    /// Nodes are rewarded the same share, whatever their age.
+    Equal,
+}
+
+impl Display for RewardPolicyKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::NodeAge => write!(formatter, "node-age"),
+            Self::Equal => write!(formatter, "equal"),
+        }
+    }
+}
+
+impl FromStr for RewardPolicyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "node-age" => Ok(Self::NodeAge),
+            "equal" => Ok(Self::Equal),
+            _ => Err(format!(
+                "Unknown reward policy '{}', expected one of: node-age, equal",
+                s
+            )),
+        }
+    }
 }
 
 impl Borrow<Prefix> for SectionAuthorityProvider {
@@ -39,10 +174,11 @@ impl Debug for SectionAuthorityProvider {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(
             formatter,
-            "SectionAuthorityProvider {{ prefix: ({:b}), section_key: {:?}, elders: {{{:?}}} }}",
+            "SectionAuthorityProvider {{ prefix: ({:b}), section_key: {:?}, elders: {{{:?}}}, network_params: {:?} }}",
             self.prefix,
             self.public_key_set.public_key(),
             self.elders.iter().format(", "),
+            self.network_params,
         )
     }
 }
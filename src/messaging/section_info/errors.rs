@@ -16,7 +16,7 @@ use thiserror::Error;
 pub enum Error {
     /// Target section key provided with message is out of date. Current PK Set is provided in error
     #[error("Target section's public key is outdated. New information has been provided.")]
-    TargetSectionInfoOutdated(SectionAuthorityProvider),
+    TargetSectionInfoOutdated(Box<SectionAuthorityProvider>),
     /// Target section is undergoing churn, a new key set will be agreed upon shortly
     #[error("DKG is in process. New key set will be agreed upon shortly.")]
     DkgInProgress,
@@ -13,6 +13,7 @@ use crate::types::PublicKey;
 use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
 pub use errors::Error;
+use secured_linked_list::SecuredLinkedList;
 use serde::{Deserialize, Serialize};
 use xor_name::XorName;
 
@@ -26,6 +27,28 @@ pub enum SectionInfoMsg {
     GetSectionResponse(GetSectionResponse),
     /// Updated info related to section
     SectionInfoUpdate(ErrorResponse),
+    /// SectionInfoMsg to request everything the recipient knows about its own section and the
+    /// rest of the network, used to crawl the network topology.
+    SectionKnowledgeQuery,
+    /// Response to `SectionKnowledgeQuery`.
+    SectionKnowledgeResponse(SectionKnowledge),
+}
+
+/// A section's view of itself and of the other sections it knows about.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SectionKnowledge {
+    /// Genesis key of the network the section belongs to.
+    pub genesis_key: BlsPublicKey,
+    /// Current authority provider of the section.
+    pub section_auth: SectionAuthorityProvider,
+    /// Signature over `section_auth` by the section's current key.
+    pub section_auth_sig: bls::Signature,
+    /// Chain of the section keys, proving `section_auth`'s key back to the genesis key.
+    pub section_chain: SecuredLinkedList,
+    /// Number of adults in the section.
+    pub adult_count: usize,
+    /// Authority providers of the other sections known to the section.
+    pub other_sections: Vec<SectionAuthorityProvider>,
 }
 
 /// Infrastructure error wrapper to add correltion info for triggering message
@@ -42,7 +65,12 @@ pub struct ErrorResponse {
 pub enum GetSectionResponse {
     /// Successful response to `GetSectionQuery`. Contains information about the requested
     /// section.
-    Success(SectionAuthorityProvider),
+    Success {
+        /// Authority provider of the requested section.
+        section_auth: SectionAuthorityProvider,
+        /// Genesis key of the network the section belongs to.
+        genesis_key: BlsPublicKey,
+    },
     /// Response to `GetSectionQuery` containing addresses of nodes that are closer to the
     /// requested name than the recipient. The request should be repeated to these addresses.
     Redirect(SectionAuthorityProvider),
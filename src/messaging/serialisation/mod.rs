@@ -12,7 +12,7 @@ pub mod wire_msg_header;
 use self::wire_msg_header::{MessageKind, WireMsgHeader};
 #[cfg(not(feature = "client-only"))]
 use super::node;
-use super::{client, section_info, DstInfo, Error, MessageId, MessageType, Result};
+use super::{client, section_info, DstInfo, Error, MessageId, MessageType, Result, TraceContext};
 use crate::messaging::node::Variant;
 use bls::PublicKey;
 use bytes::Bytes;
@@ -251,6 +251,16 @@ impl WireMsg {
         self.header.src_section_pk()
     }
 
+    /// Return the trace context of this message, if it's being traced.
+    pub fn trace(&self) -> Option<TraceContext> {
+        self.header.trace()
+    }
+
+    /// Set the trace context to carry in the header of this message, or clear it with `None`.
+    pub fn set_trace(&mut self, trace: Option<TraceContext>) {
+        self.header.set_trace(trace)
+    }
+
     // The following functions are just for convenience, which allow users to
     // not need to create an instance of WireMsg beforehand.
 
@@ -403,6 +413,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn serialisation_section_info_msg_with_trace() -> Result<()> {
+        let dst = XorName::random();
+        let dst_section_pk = SecretKey::random().public_key();
+
+        let query = section_info::SectionInfoMsg::GetSectionQuery(dst_section_pk.into());
+        let mut wire_msg = WireMsg::new_section_info_msg(&query, dst, dst_section_pk)?;
+        let untraced_len = wire_msg.serialize()?.len();
+
+        let trace = TraceContext::new().next_hop();
+        wire_msg.set_trace(Some(trace));
+        let serialized = wire_msg.serialize()?;
+        assert!(serialized.len() > untraced_len);
+
+        let deserialized = WireMsg::from(serialized)?;
+        assert_eq!(deserialized, wire_msg);
+        assert_eq!(deserialized.trace(), Some(trace));
+        assert_eq!(deserialized.src_section_pk(), None);
+        assert_eq!(
+            deserialized.to_message()?,
+            MessageType::SectionInfo {
+                msg: query,
+                dst_info: DstInfo {
+                    dst,
+                    dst_section_pk
+                }
+            }
+        );
+
+        // clearing the trace context gets us back the original message
+        wire_msg.set_trace(None);
+        assert_eq!(wire_msg.serialize()?.len(), untraced_len);
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(not(feature = "client-only"))]
     fn serialisation_node_msg() -> Result<()> {
@@ -457,6 +503,29 @@ mod tests {
         assert_eq!(deserialized.src_section_pk(), Some(src_section_pk));
 
         // test deserialisation of payload
+        assert_eq!(
+            deserialized.to_message()?,
+            MessageType::Node {
+                msg: node_cmd.clone(),
+                dst_info: DstInfo {
+                    dst,
+                    dst_section_pk
+                },
+                src_section_pk: Some(src_section_pk)
+            }
+        );
+
+        // and finally with both a source section public key and a trace context
+        let trace = TraceContext::new();
+        let mut wire_msg_with_trace =
+            WireMsg::new_node_msg(&node_cmd, dst, dst_section_pk, Some(src_section_pk))?;
+        wire_msg_with_trace.set_trace(Some(trace));
+        let serialized = wire_msg_with_trace.serialize()?;
+
+        let deserialized = WireMsg::from(serialized)?;
+        assert_eq!(deserialized, wire_msg_with_trace);
+        assert_eq!(deserialized.src_section_pk(), Some(src_section_pk));
+        assert_eq!(deserialized.trace(), Some(trace));
         assert_eq!(
             deserialized.to_message()?,
             MessageType::Node {
@@ -6,7 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::messaging::{Error, MessageId, Result, MESSAGE_ID_LEN};
+use crate::messaging::{
+    Error, MessageId, Result, TraceContext, TraceId, MESSAGE_ID_LEN, TRACE_ID_LEN,
+};
 use bls::{PublicKey, PK_SIZE};
 use bytes::Bytes;
 use cookie_factory::{
@@ -32,6 +34,7 @@ pub(crate) struct WireMsgHeader {
     pub(crate) dst: XorName,
     pub(crate) dst_section_pk: PublicKey,
     src_section_pk: Option<PublicKey>,
+    trace: Option<TraceContext>,
 }
 
 // Bytes length in the header for the 'header_size' field
@@ -66,6 +69,12 @@ const HDR_SRC_PK_BYTES_START: usize = HDR_DEST_PK_BYTES_END;
 const HDR_SRC_PK_BYTES_LEN: usize = PK_SIZE;
 const HDR_SRC_PK_BYTES_END: usize = HDR_SRC_PK_BYTES_START + HDR_SRC_PK_BYTES_LEN;
 
+// Bytes size of the optional 'trace' field, which follows the 'src_section_pk' field if present,
+// or else the 'dst_section_pk' field.
+const HDR_TRACE_ID_BYTES_LEN: usize = TRACE_ID_LEN;
+const HDR_HOP_COUNT_BYTES_LEN: usize = size_of::<u8>();
+const HDR_TRACE_BYTES_LEN: usize = HDR_TRACE_ID_BYTES_LEN + HDR_HOP_COUNT_BYTES_LEN;
+
 const HEADER_MIN_SIZE: usize = HDR_SIZE_BYTES_LEN
     + HDR_VERSION_BYTES_LEN
     + HDR_MSG_ID_BYTES_LEN
@@ -84,12 +93,13 @@ impl WireMsgHeader {
     ) -> Self {
         Self {
             msg_id,
-            header_size: Self::bytes_size(src_section_pk.is_some()) as u16,
+            header_size: Self::bytes_size(src_section_pk.is_some(), false) as u16,
             version: MESSAGING_PROTO_VERSION,
             kind,
             dst,
             dst_section_pk,
             src_section_pk,
+            trace: None,
         }
     }
 
@@ -119,6 +129,18 @@ impl WireMsgHeader {
         self.src_section_pk
     }
 
+    // Return the trace context of this message, if it's being traced
+    pub fn trace(&self) -> Option<TraceContext> {
+        self.trace
+    }
+
+    // Set or clear the trace context of this message
+    pub fn set_trace(&mut self, trace: Option<TraceContext>) {
+        self.trace = trace;
+        self.header_size =
+            Self::bytes_size(self.src_section_pk.is_some(), self.trace.is_some()) as u16;
+    }
+
     // Parses the provided bytes to deserialize a WireMsgHeader,
     // returning the created WireMsgHeader, as well as the remaining bytes which
     // correspond to the message payload. The caller shall then take care of
@@ -180,12 +202,32 @@ impl WireMsgHeader {
             ))
         })?;
 
-        // ...finally, we read the source section pubic key bytes if it's a NodeMsg
-        // and if the header size has the exact number of bytes to read a PublicKey from.
+        // The optional fields present are told apart by the number of bytes left in the header,
+        // since each combination of them adds up to a different length.
         // Once we move back to fixed-length header we won't need this check.
-        let src_section_pk = if kind == MessageKind::Node
-            && HEADER_MIN_SIZE + HDR_SRC_PK_BYTES_LEN == header_size as usize
-        {
+        let optional_bytes_len = header_size as usize - HEADER_MIN_SIZE;
+        let (has_src_section_pk, has_trace) = match optional_bytes_len {
+            0 => (false, false),
+            HDR_SRC_PK_BYTES_LEN => (true, false),
+            HDR_TRACE_BYTES_LEN => (false, true),
+            len if len == HDR_SRC_PK_BYTES_LEN + HDR_TRACE_BYTES_LEN => (true, true),
+            len => {
+                return Err(Error::FailedToParse(format!(
+                    "wire message header has {} bytes of optional fields, which match no layout",
+                    len
+                )))
+            }
+        };
+        if has_src_section_pk && kind != MessageKind::Node {
+            return Err(Error::FailedToParse(format!(
+                "wire message header has a source section PublicKey but it's not a NodeMsg but a {:?}",
+                kind
+            )));
+        }
+
+        // ...we read the source section pubic key bytes if it's a NodeMsg
+        // and if the header size has the exact number of bytes to read a PublicKey from.
+        let src_section_pk = if has_src_section_pk {
             let mut src_pk_bytes = [0; HDR_SRC_PK_BYTES_LEN];
             src_pk_bytes[0..].copy_from_slice(&bytes[HDR_SRC_PK_BYTES_START..HDR_SRC_PK_BYTES_END]);
             let src_section_pk = PublicKey::from_bytes(&src_pk_bytes).map_err(|err| {
@@ -199,6 +241,27 @@ impl WireMsgHeader {
             None
         };
 
+        // ...finally, we read the trace context bytes if the message is being traced,
+        // which follow the last of the fields present before them
+        let trace = if has_trace {
+            let trace_start = if has_src_section_pk {
+                HDR_SRC_PK_BYTES_END
+            } else {
+                HDR_DEST_PK_BYTES_END
+            };
+            let hop_count_start = trace_start + HDR_TRACE_ID_BYTES_LEN;
+
+            let mut trace_id_bytes = [0; HDR_TRACE_ID_BYTES_LEN];
+            trace_id_bytes[0..].copy_from_slice(&bytes[trace_start..hop_count_start]);
+
+            Some(TraceContext {
+                trace_id: TraceId::with(trace_id_bytes),
+                hop_count: bytes[hop_count_start],
+            })
+        } else {
+            None
+        };
+
         let header = Self {
             msg_id,
             header_size,
@@ -207,6 +270,7 @@ impl WireMsgHeader {
             dst,
             dst_section_pk,
             src_section_pk,
+            trace,
         };
 
         // Get a slice for the payload bytes, i.e. the bytes after the header bytes
@@ -268,7 +332,7 @@ impl WireMsgHeader {
 
         // ...now write the source section public key if it's a NodeMsg
         // and a source section public key was provided
-        let buf_at_payload = if let Some(src_section_pk) = self.src_section_pk {
+        let buf_at_trace = if let Some(src_section_pk) = self.src_section_pk {
             if self.kind != MessageKind::Node {
                 return Err(Error::Serialisation(format!(
                     "source section public key field couldn't be serialized in header since it's not a NodeMsg but a {:?}",
@@ -288,6 +352,28 @@ impl WireMsgHeader {
             buf_at_src_pk
         };
 
+        // ...and finally the trace context if the message is being traced
+        let buf_at_payload = if let Some(trace) = self.trace {
+            let (buf_at_hop_count, _) =
+                gen(slice(trace.trace_id.as_ref()), buf_at_trace).map_err(|err| {
+                    Error::Serialisation(format!(
+                        "trace id field couldn't be serialized in header: {}",
+                        err
+                    ))
+                })?;
+
+            let (buf, _) = gen(be_u8(trace.hop_count), buf_at_hop_count).map_err(|err| {
+                Error::Serialisation(format!(
+                    "hop count field couldn't be serialized in header: {}",
+                    err
+                ))
+            })?;
+
+            buf
+        } else {
+            buf_at_trace
+        };
+
         Ok(buf_at_payload)
     }
 
@@ -297,16 +383,19 @@ impl WireMsgHeader {
     }
 
     // Size in bytes when serialized if a WireMsgHeader
-    // depending if a source section public key is included.
-    fn bytes_size(with_src_section_pk: bool) -> usize {
+    // depending if a source section public key and a trace context are included.
+    fn bytes_size(with_src_section_pk: bool, with_trace: bool) -> usize {
         // We don't use 'std::mem::size_of' since, for example, the
         // 'MessageKind' enum it reports 2 bytes mem size,
         // and we want to serialize that field using 1 byte only.
+        let mut size = HEADER_MIN_SIZE;
         if with_src_section_pk {
-            HEADER_MIN_SIZE + HDR_SRC_PK_BYTES_LEN
-        } else {
-            HEADER_MIN_SIZE
+            size += HDR_SRC_PK_BYTES_LEN;
+        }
+        if with_trace {
+            size += HDR_TRACE_BYTES_LEN;
         }
+        size
     }
 }
 
@@ -359,7 +448,93 @@ impl From<MessageKind> for u8 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use anyhow::Result;
+    use anyhow::{anyhow, Result};
+    use bls::SecretKey;
+
+    fn serialize(header: &WireMsgHeader) -> Result<Bytes> {
+        let mut buffer = vec![0u8; header.size().into()];
+        let _ = header.write(&mut buffer)?;
+        Ok(Bytes::from(buffer))
+    }
+
+    #[test]
+    fn header_roundtrip_with_optional_fields() -> Result<()> {
+        let kinds = [
+            MessageKind::SectionInfo,
+            MessageKind::Client,
+            MessageKind::Routing,
+            MessageKind::Node,
+            MessageKind::JoinRequest,
+        ];
+        for &kind in &kinds {
+            let src_section_pks = if kind == MessageKind::Node {
+                vec![None, Some(SecretKey::random().public_key())]
+            } else {
+                vec![None]
+            };
+            for src_section_pk in src_section_pks {
+                for &trace in &[None, Some(TraceContext::new().next_hop())] {
+                    let mut header = WireMsgHeader::new(
+                        MessageId::new(),
+                        kind,
+                        XorName::random(),
+                        SecretKey::random().public_key(),
+                        src_section_pk,
+                    );
+                    header.set_trace(trace);
+
+                    let (deserialized, payload) = WireMsgHeader::from(serialize(&header)?)?;
+                    assert_eq!(deserialized, header);
+                    assert_eq!(deserialized.src_section_pk(), src_section_pk);
+                    assert_eq!(deserialized.trace(), trace);
+                    assert!(payload.is_empty());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn header_with_src_section_pk_of_non_node_msg_is_rejected() -> Result<()> {
+        let mut header = WireMsgHeader::new(
+            MessageId::new(),
+            MessageKind::Node,
+            XorName::random(),
+            SecretKey::random().public_key(),
+            Some(SecretKey::random().public_key()),
+        );
+        header.set_trace(Some(TraceContext::new()));
+
+        // A client message can't carry a source section public key, so the optional bytes of
+        // this one don't match the layout of a traced client message header.
+        let mut bytes = serialize(&header)?.to_vec();
+        bytes[HDR_KIND_BYTES_START] = CLIENT_MSG_KIND;
+        match WireMsgHeader::from(Bytes::from(bytes)) {
+            Err(Error::FailedToParse(_)) => Ok(()),
+            other => Err(anyhow!("unexpected result: {:?}", other)),
+        }
+    }
+
+    #[test]
+    fn header_with_unknown_layout_is_rejected() -> Result<()> {
+        let header = WireMsgHeader::new(
+            MessageId::new(),
+            MessageKind::Client,
+            XorName::random(),
+            SecretKey::random().public_key(),
+            None,
+        );
+
+        let mut bytes = serialize(&header)?.to_vec();
+        let header_size = HEADER_MIN_SIZE as u16 + 1;
+        bytes[..HDR_SIZE_BYTES_LEN].copy_from_slice(&header_size.to_be_bytes());
+        bytes.push(0);
+        match WireMsgHeader::from(Bytes::from(bytes)) {
+            Err(Error::FailedToParse(_)) => Ok(()),
+            other => Err(anyhow!("unexpected result: {:?}", other)),
+        }
+    }
 
     #[test]
     fn message_kind_from_u8() -> Result<()> {
@@ -0,0 +1,85 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use hex_fmt::HexFmt;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Constant byte length of `TraceId`.
+pub const TRACE_ID_LEN: usize = 16;
+
+/// Identifier shared by every hop of a traced message.
+///
+/// Unlike the `MessageId`, it is printed in full so the logs of all the nodes a message went
+/// through can be searched for it.
+#[derive(Ord, PartialOrd, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub struct TraceId([u8; TRACE_ID_LEN]);
+
+impl TraceId {
+    /// Generates a new `TraceId` with random content.
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// Generates a new `TraceId` containing provided bytes
+    pub fn with(id: [u8; TRACE_ID_LEN]) -> Self {
+        Self(id)
+    }
+}
+
+impl AsRef<[u8; TRACE_ID_LEN]> for TraceId {
+    fn as_ref(&self) -> &[u8; TRACE_ID_LEN] {
+        &self.0
+    }
+}
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", HexFmt(&self.0))
+    }
+}
+
+impl fmt::Debug for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Trace context optionally carried in the header of a message, allowing its path across the
+/// network to be reconstructed from the logs of the nodes it went through.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// Identifier of the trace.
+    pub trace_id: TraceId,
+    /// Number of hops the message has travelled so far.
+    pub hop_count: u8,
+}
+
+impl TraceContext {
+    /// Starts a new trace at the node originating a message.
+    pub fn new() -> Self {
+        Self {
+            trace_id: TraceId::random(),
+            hop_count: 0,
+        }
+    }
+
+    /// Returns the context the message carries once sent on to the next hop.
+    pub fn next_hop(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            hop_count: self.hop_count.saturating_add(1),
+        }
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -47,6 +47,8 @@ const NODES_DIR: &str = "local-test-network";
 const INTERVAL: &str = "2";
 const RUST_LOG: &str = "RUST_LOG";
 const NODE_COUNT: &str = "60";
+const FAUCET: &str = "SN_NODE_FAUCET";
+const FAUCET_SUPPLY: &str = "1000000";
 
 #[tokio::main]
 async fn main() -> Result<(), String> {
@@ -54,7 +56,7 @@ async fn main() -> Result<(), String> {
     remove_dir_all(&path).unwrap_or(()); // Delete nodes directory if it exists;
     create_dir_all(&path).expect("Cannot create nodes directory");
 
-    let args: Vec<&str> = vec!["build", "--features=simulated-payouts", "--release"];
+    let args: Vec<&str> = vec!["build", "--release"];
     println!("Building current sn_node");
     let _child = Command::new("cargo")
         .args(args.clone())
@@ -116,6 +118,16 @@ pub async fn run_network() -> Result<(), String> {
 
     let node_count = std::env::var("NODE_COUNT").unwrap_or_else(|_| NODE_COUNT.to_string());
 
+    // The nodes inherit our env, so unless told otherwise the genesis node funds
+    // a faucet for the clients of the local network to get test tokens from.
+    if std::env::var(FAUCET).is_err() {
+        std::env::set_var(FAUCET, FAUCET_SUPPLY);
+    }
+    info!(
+        "Funding the faucet with {} tokens at genesis",
+        std::env::var(FAUCET).unwrap_or_default()
+    );
+
     // Let's create an args array to pass to the network launcher tool
     let mut sn_launch_tool_args = vec![
         "sn_launch_tool",
@@ -28,10 +28,15 @@
 )]
 
 use log::{self, error, info};
-use safe_network::node::{add_connection_info, set_connection_info, utils, Config, Error, Node};
+use safe_network::client::Client;
+use safe_network::node::{
+    add_connection_info, set_connection_info, state_db, utils, Config, Error, Node,
+};
+use safe_network::types::PublicKey;
 use self_update::{cargo_crate_version, Status};
 use std::{io::Write, process};
 use structopt::{clap, StructOpt};
+use xor_name::XorName;
 
 const BOOTSTRAP_RETRY_TIME: u64 = 3; // in minutes
 use safe_network::routing;
@@ -106,6 +111,17 @@ async fn run_node() {
         }
     }
 
+    if config.reward_statement() {
+        match print_reward_statement(&config).await {
+            Ok(()) => exit(0),
+            Err(e) => {
+                println!("Failed to get the reward statement: {}", e);
+                error!("Failed to get the reward statement: {}", e);
+                exit(1);
+            }
+        }
+    }
+
     let message = format!(
         "Running {} v{}",
         Config::clap().get_name(),
@@ -182,6 +198,50 @@ fn exit(exit_code: i32) {
     process::exit(exit_code);
 }
 
+async fn print_reward_statement(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let root_dir = config.root_dir()?;
+
+    // The node's own wallet is the one it registered with its section, unless another is given.
+    let wallet = match config.wallet_id() {
+        Some(wallet_id) => PublicKey::bls_from_hex(wallet_id)?,
+        None => match state_db::get_reward_pk(&root_dir).await? {
+            Some(public_key) => PublicKey::Ed25519(public_key),
+            None => return Err("No reward key found, has this node been run before?".into()),
+        },
+    };
+    let node_name = match state_db::get_network_keypair(&root_dir).await? {
+        Some(keypair) => XorName::from(PublicKey::Ed25519(keypair.public)),
+        None => return Err("No network keypair found, has this node been run before?".into()),
+    };
+
+    let contacts = if config.hard_coded_contacts.is_empty() {
+        None
+    } else {
+        Some(config.hard_coded_contacts.clone())
+    };
+    let client = Client::new(None, None, contacts).await?;
+    let statement = client.get_reward_statement(node_name, wallet).await?;
+
+    println!("Reward statement of wallet {:?}", statement.wallet);
+    for entry in &statement.entries {
+        let reward = &entry.reward;
+        print!(
+            "section {:?}, node {} (age {}): {}",
+            entry.section_key, entry.node_name, reward.age, reward.amount
+        );
+        match &reward.no_reward_reason {
+            Some(reason) => println!(" ({})", reason),
+            None => println!(),
+        }
+    }
+    match statement.total() {
+        Some(total) => println!("Total: {}", total),
+        None => println!("Total: overflow"),
+    }
+
+    Ok(())
+}
+
 fn update() -> Result<Status, Box<dyn (::std::error::Error)>> {
     info!("Checking for updates...");
     let target = self_update::get_target();
@@ -20,20 +20,27 @@ use crate::node::{
 use crate::types::{Chunk, ChunkAddress, DataAddress, PublicKey};
 use log::{error, info};
 use std::{
+    collections::BTreeMap,
     fmt::{self, Display, Formatter},
     path::Path,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 /// Storage of data chunks.
 pub(crate) struct ChunkStorage {
     chunks: ChunkDataStore,
+    // Expiries of the private chunks stored with one, in seconds since the Unix epoch.
+    expiries: BTreeMap<ChunkAddress, u64>,
 }
 
 impl ChunkStorage {
     #[allow(dead_code)]
     pub(crate) async fn new(path: &Path, max_capacity: u64) -> Result<Self> {
         let chunks = ChunkDataStore::new(path, max_capacity).await?;
-        Ok(Self { chunks })
+        Ok(Self {
+            chunks,
+            expiries: BTreeMap::new(),
+        })
     }
 
     pub async fn keys(&self) -> Result<Vec<ChunkAddress>> {
@@ -46,6 +53,48 @@ impl ChunkStorage {
         Ok(NodeDuty::NoOp)
     }
 
+    pub(crate) async fn store_expiring(&mut self, data: &Chunk, expiry: u64) -> Result<NodeDuty> {
+        self.try_store(data).await?;
+        let _ = self.expiries.insert(*data.address(), expiry);
+
+        Ok(NodeDuty::NoOp)
+    }
+
+    /// Postpones the expiry of a chunk, which the Elders checked the owner asked for.
+    pub(crate) fn extend_expiry(&mut self, address: &ChunkAddress, expiry: u64) {
+        match self.expiries.get_mut(address) {
+            Some(current) if expiry > *current => *current = expiry,
+            _ => info!(
+                "{}: Not extending the expiry of chunk {:?} to {}",
+                self, address, expiry
+            ),
+        }
+    }
+
+    pub(crate) fn expiry(&self, address: &ChunkAddress) -> Option<u64> {
+        self.expiries.get(address).copied()
+    }
+
+    // Expired chunks are as good as deleted, even before the Elders tell us to delete them.
+    fn has_expired(&self, address: &ChunkAddress) -> bool {
+        matches!(self.expiries.get(address), Some(expiry) if *expiry <= now())
+    }
+
+    /// Deletes the chunks which expired, returning how many there were.
+    pub(crate) async fn remove_expired(&mut self) -> Result<usize> {
+        let expired: Vec<_> = self
+            .expiries
+            .keys()
+            .filter(|address| self.has_expired(address))
+            .copied()
+            .collect();
+        for address in &expired {
+            info!("{}: Deleting expired chunk {:?}", self, address);
+            self.delete_chunk(address).await?;
+        }
+        Ok(expired.len())
+    }
+
     async fn try_store(&mut self, data: &Chunk) -> Result<()> {
         if self.chunks.has(data.address()).await {
             info!(
@@ -59,10 +108,14 @@ impl ChunkStorage {
     }
 
     pub(crate) async fn get_chunk(&self, address: &ChunkAddress) -> Result<Chunk> {
+        if self.has_expired(address) {
+            return Err(Error::NoSuchChunk(DataAddress::Chunk(*address)));
+        }
         self.chunks.get(address).await
     }
 
     pub(crate) async fn delete_chunk(&mut self, address: &ChunkAddress) -> Result<()> {
+        let _ = self.expiries.remove(address);
         self.chunks.delete(&address).await
     }
 
@@ -84,8 +137,33 @@ impl ChunkStorage {
         })
     }
 
-    /// Stores a chunk that Elders sent to it for replication.
-    pub async fn store_for_replication(&mut self, chunk: Chunk) -> Result<()> {
+    // Answers whether the chunk is held, sparing the transfer of its content.
+    pub(crate) async fn exists(&self, address: &ChunkAddress, msg_id: MessageId) -> NodeDuty {
+        let result = if self.chunks.has(address).await && !self.has_expired(address) {
+            Ok(())
+        } else {
+            Err(ErrorMessage::DataNotFound(DataAddress::Chunk(*address)))
+        };
+
+        NodeDuty::Send(OutgoingMsg {
+            msg: MsgType::Node(NodeMsg::NodeQueryResponse {
+                response: NodeQueryResponse::Data(NodeDataQueryResponse::ChunkExists(result)),
+                id: MessageId::in_response_to(&msg_id),
+                correlation_id: msg_id,
+            }),
+            section_source: false, // sent as single node
+            dst: DstLocation::Section(*address.name()),
+            aggregation: Aggregation::None,
+        })
+    }
+
+    /// Stores a chunk that Elders sent to it for replication, along with its expiry if any.
+    pub async fn store_for_replication(&mut self, chunk: Chunk, expiry: Option<u64>) -> Result<()> {
+        if let Some(expiry) = expiry {
+            let current = self.expiries.entry(*chunk.address()).or_insert(expiry);
+            *current = expiry.max(*current);
+        }
+
         if self.chunks.has(chunk.address()).await {
             info!(
                 "{}: Immutable chunk already exists, not storing: {:?}",
@@ -145,6 +223,14 @@ impl ChunkStorage {
     }
 }
 
+// The time of this Adult, in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
 impl Display for ChunkStorage {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(formatter, "ChunkStorage")
@@ -192,4 +278,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    pub async fn expired_chunk_is_not_served_and_gets_removed() -> Result<()> {
+        let path = PathBuf::from(temp_dir()?.path());
+        let mut storage = ChunkStorage::new(&path, u64::MAX).await?;
+        let value = "immutable data value".to_owned().into_bytes();
+        let chunk = Chunk::Private(PrivateChunk::new(value, get_random_pk()));
+        let address = *chunk.address();
+        let _ = storage.store_expiring(&chunk, now() - 1).await?;
+
+        assert!(storage.chunks.has(&address).await);
+        assert!(storage.get_chunk(&address).await.is_err());
+
+        assert_eq!(storage.remove_expired().await?, 1);
+        assert!(!storage.chunks.has(&address).await);
+        assert_eq!(storage.expiry(&address), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn expiry_can_only_be_postponed() -> Result<()> {
+        let path = PathBuf::from(temp_dir()?.path());
+        let mut storage = ChunkStorage::new(&path, u64::MAX).await?;
+        let value = "immutable data value".to_owned().into_bytes();
+        let chunk = Chunk::Private(PrivateChunk::new(value, get_random_pk()));
+        let address = *chunk.address();
+        let expiry = now() + 3600;
+        let _ = storage.store_expiring(&chunk, expiry).await?;
+
+        storage.extend_expiry(&address, expiry - 1);
+        assert_eq!(storage.expiry(&address), Some(expiry));
+        storage.extend_expiry(&address, expiry + 1);
+        assert_eq!(storage.expiry(&address), Some(expiry + 1));
+
+        assert_eq!(storage.remove_expired().await?, 0);
+        assert_eq!(storage.get_chunk(&address).await?, chunk);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn replicated_chunk_keeps_its_expiry() -> Result<()> {
+        let path = PathBuf::from(temp_dir()?.path());
+        let mut storage = ChunkStorage::new(&path, u64::MAX).await?;
+        let value = "immutable data value".to_owned().into_bytes();
+        let chunk = Chunk::Private(PrivateChunk::new(value, get_random_pk()));
+        let address = *chunk.address();
+        let expiry = now() + 3600;
+        storage
+            .store_for_replication(chunk.clone(), Some(expiry))
+            .await?;
+
+        assert_eq!(storage.expiry(&address), Some(expiry));
+        assert_eq!(storage.get_chunk(&address).await?, chunk);
+
+        Ok(())
+    }
 }
@@ -18,7 +18,7 @@ use crate::node::{
 };
 use crate::types::{Chunk, ChunkAddress, PublicKey};
 use chunk_storage::ChunkStorage;
-use log::info;
+use log::{info, warn};
 use std::{
     fmt::{self, Display, Formatter},
     path::Path,
@@ -51,9 +51,25 @@ impl Chunks {
         self.chunk_storage.get_chunk(address).await
     }
 
+    /// Returns the expiry of the chunk, if it was stored with one.
+    pub fn expiry(&self, address: &ChunkAddress) -> Option<u64> {
+        self.chunk_storage.expiry(address)
+    }
+
+    /// Deletes the private chunks which expired.
+    pub async fn remove_expired_chunks(&mut self) -> Result<()> {
+        let count = self.chunk_storage.remove_expired().await?;
+        if count > 0 {
+            info!("Deleted {} expired chunks", count);
+        }
+        Ok(())
+    }
+
     pub async fn read(&self, read: &ChunkRead, msg_id: MessageId) -> NodeDuty {
-        let ChunkRead::Get(address) = read;
-        self.chunk_storage.get(address, msg_id).await
+        match read {
+            ChunkRead::Get(address) => self.chunk_storage.get(address, msg_id).await,
+            ChunkRead::Exists(address) => self.chunk_storage.exists(address, msg_id).await,
+        }
     }
 
     pub async fn write(
@@ -63,7 +79,14 @@ impl Chunks {
         requester: PublicKey,
     ) -> Result<NodeDuty> {
         match &write {
-            ChunkWrite::New(data) => self.chunk_storage.store(&data).await,
+            ChunkWrite::New(data) => self.chunk_storage.store(data).await,
+            ChunkWrite::NewExpiring { chunk, expiry } => {
+                self.chunk_storage.store_expiring(chunk, *expiry).await
+            }
+            ChunkWrite::ExtendExpiry { address, expiry } => {
+                self.chunk_storage.extend_expiry(address, *expiry);
+                Ok(NodeDuty::NoOp)
+            }
             ChunkWrite::DeletePrivate(address) => {
                 self.chunk_storage.delete(*address, msg_id, requester).await
             }
@@ -79,9 +102,25 @@ impl Chunks {
         }
     }
 
-    /// Stores a chunk that Elders sent to it for replication.
-    pub async fn store_for_replication(&mut self, chunk: Chunk) -> Result<NodeDuty> {
-        self.chunk_storage.store_for_replication(chunk).await?;
+    /// Deletes a private chunk that Elders found expired.
+    pub async fn delete_expired_chunk(&mut self, address: &ChunkAddress) -> Result<NodeDuty> {
+        if address.is_private() {
+            self.chunk_storage.delete_chunk(address).await?;
+        } else {
+            warn!("Not deleting public chunk {:?} said to be expired", address);
+        }
+        Ok(NodeDuty::NoOp)
+    }
+
+    /// Stores a chunk that Elders sent to it for replication, along with its expiry if any.
+    pub async fn store_for_replication(
+        &mut self,
+        chunk: Chunk,
+        expiry: Option<u64>,
+    ) -> Result<NodeDuty> {
+        self.chunk_storage
+            .store_for_replication(chunk, expiry)
+            .await?;
         Ok(NodeDuty::NoOp)
     }
 }
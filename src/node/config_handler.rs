@@ -8,8 +8,12 @@
 
 #![allow(trivial_numeric_casts)] // FIXME
                                  // beep
-use crate::node::{Error, Result};
-use crate::routing::TransportConfig as NetworkConfig;
+use crate::node::{capacity::MAX_SUPPLY, Error, Result};
+use crate::routing::{
+    ElderHealthThresholds, FaucetParams, NetworkParams, RewardPolicyKind,
+    TransportConfig as NetworkConfig,
+};
+use crate::types::Token;
 use log::{debug, Level};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -18,6 +22,7 @@ use std::{
     io::{self, BufReader},
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
+    time::Duration,
 };
 use structopt::StructOpt;
 
@@ -115,6 +120,60 @@ pub struct Config {
     /// Duration of a UPnP port mapping.
     #[structopt(long)]
     pub upnp_lease_duration: Option<u32>,
+    /// Number of elders per section. Set by the first node for the whole network; a joining node
+    /// given this value refuses to join a network started with a different one.
+    #[structopt(long)]
+    pub elder_size: Option<usize>,
+    /// Recommended number of nodes per section, which also determines when sections split. Set by
+    /// the first node for the whole network; a joining node given this value refuses to join a
+    /// network started with a different one. Defaults to twice the elder size.
+    #[structopt(long)]
+    pub recommended_section_size: Option<usize>,
+    /// Policy by which sections distribute rewards among their nodes: node-age or equal. Set by
+    /// the first node for the whole network; a joining node given this value refuses to join a
+    /// network started with a different one. Defaults to node-age.
+    #[structopt(long)]
+    pub reward_policy: Option<RewardPolicyKind>,
+    /// Tokens to fund a faucet with at genesis, for clients of a local or test network to draw
+    /// from. Only used by the first node.
+    #[structopt(long, env = "SN_NODE_FAUCET")]
+    pub faucet: Option<Token>,
+    /// Attach a trace context to the messages this node originates, so their path across the
+    /// network can be followed in the logs of every node they go through.
+    #[structopt(long)]
+    pub trace_messages: bool,
+    /// Print the statement of the rewards paid to this node's wallet by its section, then exit
+    /// without starting the node.
+    #[structopt(long)]
+    pub reward_statement: bool,
+    /// Number of consecutive DKG sessions an elder candidate may fail to take part in before this
+    /// node, as an elder, proposes it offline. Defaults to 1.
+    #[structopt(long)]
+    pub max_elder_dkg_failures: Option<u32>,
+    /// Number of invalid signature shares an elder may send before this node, as an elder,
+    /// proposes it offline. Defaults to 3.
+    #[structopt(long)]
+    pub max_elder_invalid_shares: Option<u32>,
+    /// Number of consecutive agreed proposals an elder may fail to sign before this node, as an
+    /// elder, demotes it. Defaults to 10.
+    #[structopt(long)]
+    pub max_elder_missed_signatures: Option<u32>,
+    /// Number of consecutive proposals an elder may sign late before this node, as an elder,
+    /// demotes it. Defaults to 10.
+    #[structopt(long)]
+    pub max_elder_late_signatures: Option<u32>,
+    /// Delay after the first signature share of a proposal past which an elder's share counts as
+    /// late.
+    ///
+    /// The delay is in milliseconds. Defaults to 5000.
+    #[structopt(long)]
+    pub elder_late_signature_msec: Option<u64>,
+    /// Delay after the first signature share of a proposal past which the elders that haven't
+    /// signed it, once agreed, have missed it.
+    ///
+    /// The delay is in milliseconds. Defaults to 30000.
+    #[structopt(long)]
+    pub elder_signature_timeout_msec: Option<u64>,
     #[structopt(skip)]
     #[allow(missing_docs)]
     pub network_config: NetworkConfig,
@@ -162,6 +221,37 @@ impl Config {
             }
         }
 
+        if let Some(faucet) = self.faucet {
+            if faucet.as_nano() > MAX_SUPPLY {
+                return Err(Error::Configuration(format!(
+                    "Invalid faucet supply: {}. It can't exceed the maximum supply of {}",
+                    faucet,
+                    Token::from_nano(MAX_SUPPLY)
+                )));
+            }
+        }
+
+        if let Some(network_params) = self.network_params() {
+            if !network_params.is_valid() {
+                return Err(Error::Configuration(format!(
+                    "Invalid network parameters: {:?}. The elder size must be at least 1 and not exceed the recommended section size",
+                    network_params
+                )));
+            }
+        }
+
+        let elder_health = self.elder_health();
+        if elder_health.max_dkg_failures == 0
+            || elder_health.max_invalid_shares == 0
+            || elder_health.max_missed_signatures == 0
+            || elder_health.max_late_signatures == 0
+        {
+            return Err(Error::Configuration(format!(
+                "Invalid elder health thresholds: {:?}. The maximum failures must be at least 1",
+                elder_health
+            )));
+        }
+
         if self.public_addr.is_none() && self.local_addr.is_some() {
             if self.skip_igd {
                 // local_addr duplicated to public_addr so that the specified port is used (and not a random one)
@@ -246,6 +336,49 @@ impl Config {
         if let Some(upnp_lease_duration) = config.upnp_lease_duration {
             self.network_config.upnp_lease_duration = Some(upnp_lease_duration);
         }
+
+        if let Some(elder_size) = config.elder_size {
+            self.elder_size = Some(elder_size);
+        }
+
+        if let Some(recommended_section_size) = config.recommended_section_size {
+            self.recommended_section_size = Some(recommended_section_size);
+        }
+
+        if let Some(reward_policy) = config.reward_policy {
+            self.reward_policy = Some(reward_policy);
+        }
+
+        if let Some(faucet) = config.faucet {
+            self.faucet = Some(faucet);
+        }
+
+        self.trace_messages = config.trace_messages || self.trace_messages;
+        self.reward_statement = config.reward_statement || self.reward_statement;
+
+        if let Some(max_dkg_failures) = config.max_elder_dkg_failures {
+            self.max_elder_dkg_failures = Some(max_dkg_failures);
+        }
+
+        if let Some(max_invalid_shares) = config.max_elder_invalid_shares {
+            self.max_elder_invalid_shares = Some(max_invalid_shares);
+        }
+
+        if let Some(max_missed_signatures) = config.max_elder_missed_signatures {
+            self.max_elder_missed_signatures = Some(max_missed_signatures);
+        }
+
+        if let Some(max_late_signatures) = config.max_elder_late_signatures {
+            self.max_elder_late_signatures = Some(max_late_signatures);
+        }
+
+        if let Some(late_signature) = config.elder_late_signature_msec {
+            self.elder_late_signature_msec = Some(late_signature);
+        }
+
+        if let Some(signature_timeout) = config.elder_signature_timeout_msec {
+            self.elder_signature_timeout_msec = Some(signature_timeout);
+        }
     }
 
     /// The address to be credited when this node farms SafeCoin.
@@ -253,6 +386,61 @@ impl Config {
         self.wallet_id.as_ref()
     }
 
+    /// Network parameters to start the network with (if first) or to expect when joining, if any
+    /// were given. Missing values are filled in from the defaults.
+    pub fn network_params(&self) -> Option<NetworkParams> {
+        let faucet = self
+            .faucet
+            .filter(|_| self.is_first())
+            .map(FaucetParams::new);
+        if self.elder_size.is_none()
+            && self.recommended_section_size.is_none()
+            && self.reward_policy.is_none()
+            && faucet.is_none()
+        {
+            return None;
+        }
+
+        let elder_size = self.elder_size.unwrap_or(NetworkParams::DEFAULT_ELDER_SIZE);
+        let recommended_section_size = self.recommended_section_size.unwrap_or(2 * elder_size);
+        let reward_policy = self.reward_policy.unwrap_or_default();
+
+        let network_params = NetworkParams::new(elder_size, recommended_section_size)
+            .with_reward_policy(reward_policy);
+        Some(match faucet {
+            Some(faucet) => network_params.with_faucet(faucet),
+            None => network_params,
+        })
+    }
+
+    /// Thresholds past which this node, as an elder, proposes other elders offline or demotes
+    /// them. Missing values are filled in from the defaults.
+    pub fn elder_health(&self) -> ElderHealthThresholds {
+        let default = ElderHealthThresholds::default();
+        ElderHealthThresholds {
+            max_dkg_failures: self
+                .max_elder_dkg_failures
+                .unwrap_or(default.max_dkg_failures),
+            max_invalid_shares: self
+                .max_elder_invalid_shares
+                .unwrap_or(default.max_invalid_shares),
+            max_missed_signatures: self
+                .max_elder_missed_signatures
+                .unwrap_or(default.max_missed_signatures),
+            max_late_signatures: self
+                .max_elder_late_signatures
+                .unwrap_or(default.max_late_signatures),
+            late_signature: self
+                .elder_late_signature_msec
+                .map(Duration::from_millis)
+                .unwrap_or(default.late_signature),
+            signature_timeout: self
+                .elder_signature_timeout_msec
+                .map(Duration::from_millis)
+                .unwrap_or(default.signature_timeout),
+        }
+    }
+
     /// Is this the first node in a section?
     pub fn is_first(&self) -> bool {
         self.first.is_some()
@@ -324,6 +512,11 @@ impl Config {
         self.update_only
     }
 
+    /// Print the reward statement of this node's wallet without starting the node
+    pub fn reward_statement(&self) -> bool {
+        self.reward_statement
+    }
+
     /// Set the Quic-P2P `ip` configuration to 127.0.0.1.
     pub fn listen_on_loopback(&mut self) {
         self.network_config.local_ip = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
@@ -439,7 +632,7 @@ fn smoke() {
     // NOTE: IF this value is being changed due to a change in the config,
     // the change in config also be handled in Config::merge()
     // and in examples/config_handling.rs
-    let expected_size = 504;
+    let expected_size = 608;
 
     assert_eq!(std::mem::size_of::<Config>(), expected_size);
 }
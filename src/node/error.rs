@@ -9,7 +9,7 @@
 use crate::messaging::{client::Error as ErrorMessage, MessageId, MessageType};
 use crate::routing::Prefix;
 use crate::transfers::Error as TransferError;
-use crate::types::{DataAddress, Error as DtError, PublicKey};
+use crate::types::{DataAddress, Error as DtError, PublicKey, Token};
 use std::io;
 use thiserror::Error;
 use xor_name::XorName;
@@ -112,6 +112,17 @@ pub enum Error {
     /// Chunk Store Id could not be found
     #[error("Could not fetch StoreId")]
     NoStoreId,
+    /// The faucet payout would take its recipient over the cap per key.
+    #[error("The faucet pays out at most {cap} to any one key, which {recipient} would exceed")]
+    FaucetCapExceeded {
+        /// The recipient of the payout.
+        recipient: PublicKey,
+        /// The cap per key.
+        cap: Token,
+    },
+    /// The faucet already made as many payouts as it may over the last minute.
+    #[error("The faucet makes at most {0} payouts per minute")]
+    FaucetRateLimited(usize),
     /// Threshold crypto combine signatures error
     #[error("Could not combine signatures")]
     CouldNotCombineSignatures,
@@ -86,6 +86,7 @@ fn map_client_process_msg(process_msg: ProcessMsg, origin: EndUser) -> NodeDuty
             msg_id,
         },
         // TODO: Map more transfer cmds
+        #[cfg(feature = "simulated-payouts")]
         ProcessMsg::Cmd {
             cmd: Cmd::Transfer(TransferCmd::SimulatePayout(transfer)),
             ..
@@ -102,6 +103,31 @@ fn map_client_process_msg(process_msg: ProcessMsg, origin: EndUser) -> NodeDuty
             origin: SrcLocation::EndUser(origin),
             msg_id,
         },
+        ProcessMsg::Cmd {
+            cmd: Cmd::Transfer(TransferCmd::ReleaseEscrow { id, secret }),
+            ..
+        } => NodeDuty::ReleaseEscrow {
+            id,
+            secret,
+            origin: SrcLocation::EndUser(origin),
+            msg_id,
+        },
+        ProcessMsg::Cmd {
+            cmd: Cmd::Transfer(TransferCmd::RefundEscrow(id)),
+            ..
+        } => NodeDuty::RefundEscrow {
+            id,
+            origin: SrcLocation::EndUser(origin),
+            msg_id,
+        },
+        ProcessMsg::Cmd {
+            cmd: Cmd::Transfer(TransferCmd::RequestFaucetPayout { payout, .. }),
+            ..
+        } => NodeDuty::RequestFaucetPayout {
+            payout,
+            origin: SrcLocation::EndUser(origin),
+            msg_id,
+        },
         // TODO: Map more transfer queries
         ProcessMsg::Query {
             query: Query::Transfer(TransferQuery::GetHistory { at, since_version }),
@@ -128,6 +154,29 @@ fn map_client_process_msg(process_msg: ProcessMsg, origin: EndUser) -> NodeDuty
             origin: SrcLocation::EndUser(origin),
             msg_id,
         },
+        ProcessMsg::Query {
+            query: Query::Transfer(TransferQuery::GetSectionChain { key, .. }),
+            ..
+        } => NodeDuty::GetSectionChain {
+            key,
+            origin: SrcLocation::EndUser(origin),
+            msg_id,
+        },
+        ProcessMsg::Query {
+            query: Query::Transfer(TransferQuery::GetRewardStatement { wallet, .. }),
+            ..
+        } => NodeDuty::GetRewardStatement {
+            wallet,
+            origin: SrcLocation::EndUser(origin),
+            msg_id,
+        },
+        ProcessMsg::Query {
+            query: Query::Transfer(TransferQuery::GetSectionSupply { .. }),
+            ..
+        } => NodeDuty::GetSectionSupply {
+            origin: SrcLocation::EndUser(origin),
+            msg_id,
+        },
         _ => {
             let error_data = convert_to_error_message(Error::InvalidMessage(
                 msg_id,
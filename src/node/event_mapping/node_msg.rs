@@ -89,12 +89,16 @@ fn match_node_msg(msg: NodeMsg, origin: SrcLocation) -> NodeDuty {
                     node_rewards,
                     user_wallets,
                     metadata,
+                    supply,
+                    reward_rounds,
                 }),
             ..
         } => NodeDuty::SynchState {
             node_rewards,
             user_wallets,
             metadata,
+            supply,
+            reward_rounds,
         },
         NodeMsg::NodeCmd {
             cmd: NodeCmd::System(NodeSystemCmd::ProposeRewardPayout(proposal)),
@@ -125,6 +129,14 @@ fn match_node_msg(msg: NodeMsg, origin: SrcLocation) -> NodeDuty {
             msg_id: id,
             origin,
         },
+        NodeMsg::NodeCmd {
+            cmd: NodeCmd::Transfers(NodeTransferCmd::ProposeEscrowSettlement(share)),
+            ..
+        } => NodeDuty::ReceiveEscrowSettlement { share },
+        NodeMsg::NodeCmd {
+            cmd: NodeCmd::Transfers(NodeTransferCmd::ProposeFaucetPayout(share)),
+            ..
+        } => NodeDuty::ReceiveFaucetPayout { share },
         // ------ metadata ------
         NodeMsg::NodeQuery {
             query:
@@ -187,9 +199,18 @@ fn match_node_msg(msg: NodeMsg, origin: SrcLocation) -> NodeDuty {
         },
         // this cmd is accumulated, thus has authority
         NodeMsg::NodeCmd {
-            cmd: NodeCmd::System(NodeSystemCmd::ReplicateChunk(chunk)),
+            cmd: NodeCmd::System(NodeSystemCmd::ReplicateChunk { chunk, expiry }),
             id,
-        } => NodeDuty::ReplicateChunk { chunk, msg_id: id },
+        } => NodeDuty::ReplicateChunk {
+            chunk,
+            expiry,
+            msg_id: id,
+        },
+        // this cmd is accumulated, thus has authority
+        NodeMsg::NodeCmd {
+            cmd: NodeCmd::System(NodeSystemCmd::DeleteExpiredChunk(address)),
+            ..
+        } => NodeDuty::DeleteExpiredChunk(address),
         NodeMsg::NodeCmd {
             cmd: NodeCmd::System(NodeSystemCmd::RepublishChunk(chunk)),
             id,
@@ -223,6 +244,15 @@ fn match_node_msg(msg: NodeMsg, origin: SrcLocation) -> NodeDuty {
             correlation_id,
             src: origin.name(),
         },
+        NodeMsg::NodeQueryResponse {
+            response: NodeQueryResponse::Data(NodeDataQueryResponse::ChunkExists(res)),
+            correlation_id,
+            ..
+        } => NodeDuty::RecordAdultReadLiveness {
+            response: QueryResponse::ChunkExists(res),
+            correlation_id,
+            src: origin.name(),
+        },
         _ => {
             let msg_id = msg.id();
             let error = convert_to_error_message(Error::InvalidMessage(
@@ -19,11 +19,11 @@ use crate::node::{
     Error, Result,
 };
 use crate::routing::Prefix;
-use crate::types::{Chunk, ChunkAddress, PublicKey};
+use crate::types::{Chunk, ChunkAddress, DataAddress, PublicKey};
 use log::{info, warn};
 
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Display, Formatter},
 };
 use xor_name::XorName;
@@ -36,6 +36,8 @@ use super::{
 pub(super) struct ChunkRecords {
     capacity: Capacity,
     adult_liveness: AdultLiveness,
+    // Owners and expiries of the private chunks stored with an expiry.
+    expiries: BTreeMap<ChunkAddress, (PublicKey, u64)>,
 }
 
 impl ChunkRecords {
@@ -43,17 +45,31 @@ impl ChunkRecords {
         Self {
             capacity,
             adult_liveness: AdultLiveness::new(),
+            expiries: BTreeMap::new(),
         }
     }
 
     pub async fn get_data_of(&self, prefix: Prefix) -> ChunkDataExchange {
         // Prepare full_adult details
         let full_adults = self.capacity.full_adults_matching(prefix).await;
-        ChunkDataExchange { full_adults }
+        let expiries = self
+            .expiries
+            .iter()
+            .filter(|(address, _)| prefix.matches(address.name()))
+            .map(|(address, expiry)| (*address, *expiry))
+            .collect();
+        ChunkDataExchange {
+            full_adults,
+            expiries,
+        }
     }
 
-    pub async fn update(&self, chunk_data: ChunkDataExchange) {
-        let ChunkDataExchange { full_adults } = chunk_data;
+    pub async fn update(&mut self, chunk_data: ChunkDataExchange) {
+        let ChunkDataExchange {
+            full_adults,
+            expiries,
+        } = chunk_data;
+        self.expiries.extend(expiries);
         self.capacity.insert_full_adults(full_adults).await
     }
 
@@ -80,9 +96,48 @@ impl ChunkRecords {
         match write {
             New(data) => self.store(data, msg_id, client_sig, origin).await,
             DeletePrivate(address) => self.delete(address, msg_id, client_sig, origin).await,
+            NewExpiring { chunk, expiry } => {
+                self.store_expiring(chunk, expiry, msg_id, client_sig, origin)
+                    .await
+            }
+            ExtendExpiry { address, expiry } => {
+                self.extend_expiry(address, expiry, msg_id, client_sig, origin)
+                    .await
+            }
         }
     }
 
+    /// Removes the records of the chunks which expired at or before `now`, in seconds since the
+    /// Unix epoch, and asks their holders to delete them.
+    pub(super) async fn remove_expired(&mut self, now: u64) -> Result<NodeDuties> {
+        let expired: Vec<_> = self
+            .expiries
+            .iter()
+            .filter(|(_, (_, expiry))| *expiry <= now)
+            .map(|(address, _)| *address)
+            .collect();
+
+        let mut duties = vec![];
+        for address in expired {
+            let _ = self.expiries.remove(&address);
+            let targets = self.capacity.get_chunk_holder_adults(address.name()).await;
+            // deterministic msg id for aggregation
+            let msg_id = MessageId::from_content(&address)?;
+            info!("Deleting expired chunk {:?} at {:?}", address, targets);
+
+            duties.push(NodeDuty::SendToNodes {
+                targets,
+                msg: NodeMsg::NodeCmd {
+                    cmd: NodeCmd::System(NodeSystemCmd::DeleteExpiredChunk(address)),
+                    id: msg_id,
+                },
+                aggregation: Aggregation::AtDestination,
+            });
+        }
+
+        Ok(duties)
+    }
+
     /// Adds a given node to the list of full nodes.
     pub async fn increase_full_node_count(&mut self, node_id: PublicKey) {
         info!(
@@ -111,6 +166,7 @@ impl ChunkRecords {
     async fn send_chunks_to_adults(
         &mut self,
         chunk: Chunk,
+        expiry: Option<u64>,
         msg_id: MessageId,
         client_sig: ClientSig,
         origin: EndUser,
@@ -129,7 +185,11 @@ impl ChunkRecords {
                 .await;
         }
 
-        let blob_write = ChunkWrite::New(chunk);
+        // The holders keep the expiry too, so that it outlives our records.
+        let blob_write = match expiry {
+            Some(expiry) => ChunkWrite::NewExpiring { chunk, expiry },
+            None => ChunkWrite::New(chunk),
+        };
 
         Ok(NodeDuty::SendToNodes {
             targets: target_holders,
@@ -156,7 +216,7 @@ impl ChunkRecords {
             return self.send_error(error, msg_id, origin).await;
         }
 
-        self.send_chunks_to_adults(chunk, msg_id, client_sig, origin)
+        self.send_chunks_to_adults(chunk, None, msg_id, client_sig, origin)
             .await
     }
 
@@ -166,9 +226,12 @@ impl ChunkRecords {
         response: QueryResponse,
         src: XorName,
     ) -> Result<NodeDuties> {
-        if !matches!(response, QueryResponse::GetChunk(_)) {
+        if !matches!(
+            response,
+            QueryResponse::GetChunk(_) | QueryResponse::ChunkExists(_)
+        ) {
             return Err(Error::Logic(format!(
-                "Got {:?}, but only `GetChunk` query responses are supposed to exist in this flow.",
+                "Got {:?}, but only `GetChunk` and `ChunkExists` query responses are supposed to exist in this flow.",
                 response
             )));
         }
@@ -204,6 +267,71 @@ impl ChunkRecords {
         Ok(duties)
     }
 
+    async fn store_expiring(
+        &mut self,
+        chunk: Chunk,
+        expiry: u64,
+        msg_id: MessageId,
+        client_sig: ClientSig,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        if !chunk.is_private() {
+            let error = Error::InvalidMessage(msg_id, "Public chunks can't expire".to_string());
+            return self.send_error(error, msg_id, origin).await;
+        }
+        if let Err(error) = validate_chunk_owner(&chunk, &client_sig.public_key) {
+            return self.send_error(error, msg_id, origin).await;
+        }
+
+        let _ = self
+            .expiries
+            .insert(*chunk.address(), (client_sig.public_key, expiry));
+
+        self.send_chunks_to_adults(chunk, Some(expiry), msg_id, client_sig, origin)
+            .await
+    }
+
+    async fn extend_expiry(
+        &mut self,
+        address: ChunkAddress,
+        expiry: u64,
+        msg_id: MessageId,
+        client_sig: ClientSig,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let requester = client_sig.public_key;
+        let result = match self.expiries.get_mut(&address) {
+            None => Err(Error::NoSuchChunk(DataAddress::Chunk(address))),
+            Some((owner, _)) if *owner != requester => Err(Error::InvalidOwner(requester)),
+            Some((_, current)) if expiry <= *current => Err(Error::InvalidMessage(
+                msg_id,
+                "The expiry of a chunk can only be postponed".to_string(),
+            )),
+            Some((_, current)) => {
+                *current = expiry;
+                Ok(())
+            }
+        };
+
+        if let Err(error) = result {
+            return self.send_error(error, msg_id, origin).await;
+        }
+
+        let targets = self.capacity.get_chunk_holder_adults(address.name()).await;
+        Ok(NodeDuty::SendToNodes {
+            targets,
+            msg: NodeMsg::NodeCmd {
+                cmd: NodeCmd::Chunks {
+                    cmd: ChunkWrite::ExtendExpiry { address, expiry },
+                    client_sig,
+                    origin,
+                },
+                id: msg_id,
+            },
+            aggregation: Aggregation::AtDestination,
+        })
+    }
+
     async fn send_error(
         &self,
         error: Error,
@@ -225,6 +353,12 @@ impl ChunkRecords {
         client_sig: ClientSig,
         origin: EndUser,
     ) -> Result<NodeDuty> {
+        // Only the owner can delete the chunk, so the expiry isn't needed anymore.
+        if matches!(self.expiries.get(&address), Some((owner, _)) if *owner == client_sig.public_key)
+        {
+            let _ = self.expiries.remove(&address);
+        }
+
         let targets = self.capacity.get_chunk_holder_adults(address.name()).await;
 
         let msg = NodeMsg::NodeCmd {
@@ -244,6 +378,10 @@ impl ChunkRecords {
 
     pub(super) async fn republish_chunk(&mut self, chunk: Chunk) -> Result<NodeDuty> {
         let owner = chunk.owner();
+        let expiry = self
+            .expiries
+            .get(chunk.address())
+            .map(|(_, expiry)| *expiry);
         let target_holders = self.capacity.get_chunk_holder_adults(chunk.name()).await;
         // deterministic msg id for aggregation
         let msg_id = MessageId::from_content(&(*chunk.name(), owner, &target_holders))?;
@@ -258,7 +396,7 @@ impl ChunkRecords {
         Ok(NodeDuty::SendToNodes {
             targets: target_holders,
             msg: NodeMsg::NodeCmd {
-                cmd: NodeCmd::System(NodeSystemCmd::ReplicateChunk(chunk)),
+                cmd: NodeCmd::System(NodeSystemCmd::ReplicateChunk { chunk, expiry }),
                 id: msg_id,
             },
             aggregation: Aggregation::None,
@@ -271,17 +409,9 @@ impl ChunkRecords {
         msg_id: MessageId,
         origin: EndUser,
     ) -> Result<NodeDuty> {
-        match read {
-            ChunkRead::Get(address) => self.get(*address, msg_id, origin).await,
-        }
-    }
-
-    async fn get(
-        &mut self,
-        address: ChunkAddress,
-        msg_id: MessageId,
-        origin: EndUser,
-    ) -> Result<NodeDuty> {
+        let address = match read {
+            ChunkRead::Get(address) | ChunkRead::Exists(address) => *address,
+        };
         let targets = self.capacity.get_chunk_holder_adults(address.name()).await;
 
         if targets.is_empty() {
@@ -300,7 +430,7 @@ impl ChunkRecords {
         {
             let msg = NodeMsg::NodeQuery {
                 query: NodeQuery::Chunks {
-                    query: ChunkRead::Get(address),
+                    query: read.clone(),
                     origin,
                 },
                 id: msg_id,
@@ -14,10 +14,13 @@ use crate::messaging::{
     client::{ClientSig, DataCmd, DataExchange, DataQuery},
     EndUser, MessageId,
 };
-use crate::node::{node_ops::NodeDuty, Error, Result};
+use crate::node::{
+    node_ops::{NodeDuties, NodeDuty},
+    Error, Result,
+};
 use crate::routing::Prefix;
 use crate::types::PublicKey;
-use log::info;
+use log::{info, warn};
 
 /// The various data type stores,
 /// that are only managed at Elders.
@@ -102,6 +105,27 @@ impl ElderStores {
         }
     }
 
+    /// Deletes the private data which expired at or before `now`, in seconds since the Unix
+    /// epoch, returning the duties asking Adults to delete the expired chunks.
+    pub async fn remove_expired(&mut self, now: u64) -> Result<NodeDuties> {
+        let (registers, failed_registers) = self.register_storage.remove_expired(now).await?;
+        let (sequences, failed_sequences) = self.sequence_storage.remove_expired(now).await?;
+        if registers + sequences > 0 {
+            info!(
+                "Deleted {} expired Registers and {} expired Sequences",
+                registers, sequences
+            );
+        }
+        if !failed_registers.is_empty() || !failed_sequences.is_empty() {
+            warn!(
+                "Couldn't sweep the Registers {:?} and the Sequences {:?} for expiry",
+                failed_registers, failed_sequences
+            );
+        }
+
+        self.chunk_records.remove_expired(now).await
+    }
+
     pub fn chunk_records_mut(&mut self) -> &mut ChunkRecords {
         &mut self.chunk_records
     }
@@ -104,6 +104,12 @@ impl Metadata {
         self.elder_stores.write(cmd, id, client_sig, origin).await
     }
 
+    /// Deletes the private data which expired at or before `now`, in seconds since the Unix
+    /// epoch.
+    pub async fn remove_expired(&mut self, now: u64) -> Result<NodeDuties> {
+        self.elder_stores.remove_expired(now).await
+    }
+
     /// Adds a given node to the list of full nodes.
     pub async fn increase_full_node_count(&mut self, node_id: PublicKey) {
         self.elder_stores
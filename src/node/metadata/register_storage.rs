@@ -16,11 +16,12 @@ use crate::node::{
     Result,
 };
 use crate::types::{
-    register::{Action, Address, Entry, Register, RegisterOp, User},
+    register::{Action, Address, Entry, EntryHash, PolicyUpdate, Register, RegisterOp, User},
     PublicKey,
 };
-use log::info;
+use log::{info, warn};
 use std::{
+    collections::BTreeSet,
     fmt::{self, Display, Formatter},
     path::Path,
 };
@@ -57,6 +58,23 @@ impl RegisterStorage {
                     .await
             }
             GetPolicy(address) => self.get_policy(*address, msg_id, requester, origin).await,
+            GetEntries(address) => self.get_entries(*address, msg_id, requester, origin).await,
+            GetAncestry { address, hash } => {
+                self.get_ancestry(*address, *hash, msg_id, requester, origin)
+                    .await
+            }
+            DiffBranches {
+                address,
+                first,
+                second,
+            } => {
+                self.diff_branches(*address, *first, *second, msg_id, requester, origin)
+                    .await
+            }
+            GetOpsSince { address, heads } => {
+                self.get_ops_since(*address, heads, msg_id, requester, origin)
+                    .await
+            }
         }
     }
 
@@ -76,6 +94,11 @@ impl RegisterStorage {
                 self.edit(operation, msg_id, requester, origin).await
             }
             Delete(address) => self.delete(address, msg_id, requester, origin).await,
+            UpdatePolicy(update) => self.update_policy(update, msg_id, origin).await,
+            ExtendExpiry { address, expiry } => {
+                self.extend_expiry(address, expiry, msg_id, requester, origin)
+                    .await
+            }
         }
     }
 
@@ -123,6 +146,63 @@ impl RegisterStorage {
         Ok(data)
     }
 
+    async fn extend_expiry(
+        &mut self,
+        address: Address,
+        expiry: u64,
+        msg_id: MessageId,
+        requester: PublicKey,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = self
+            .edit_chunk(address, Action::Write, requester, move |mut register| {
+                if !register.ownership().is_owner(&requester) {
+                    return Err(Error::InvalidOwner(requester));
+                }
+                register.extend_expiry(expiry)?;
+                Ok(register)
+            })
+            .await;
+
+        self.ok_or_error(result, msg_id, origin).await
+    }
+
+    /// Deletes the Registers which expired at or before `now`, in seconds since the Unix epoch,
+    /// returning how many were deleted and the addresses of those which couldn't be checked or
+    /// deleted. A failing Register doesn't stop the sweep of the others.
+    pub(super) async fn remove_expired(&mut self, now: u64) -> Result<(usize, Vec<Address>)> {
+        let mut removed = 0;
+        let mut failed = vec![];
+        for address in self.chunks.keys().await? {
+            let expired = match self.chunks.get(&address).await {
+                Ok(register) => register.is_expired(now),
+                Err(error) => {
+                    warn!(
+                        "Failed to check the expiry of Register {:?}: {:?}",
+                        address, error
+                    );
+                    failed.push(address);
+                    continue;
+                }
+            };
+            if !expired {
+                continue;
+            }
+            info!("Deleting expired Register {:?}", address);
+            match self.chunks.delete(&address).await {
+                Ok(()) => removed += 1,
+                Err(error) => {
+                    warn!(
+                        "Failed to delete expired Register {:?}: {:?}",
+                        address, error
+                    );
+                    failed.push(address);
+                }
+            }
+        }
+        Ok((removed, failed))
+    }
+
     async fn delete(
         &mut self,
         address: Address,
@@ -139,7 +219,7 @@ impl RegisterStorage {
                 ));
             }
 
-            if requester != register.owner() {
+            if !register.ownership().is_owner(&requester) {
                 Err(Error::InvalidOwner(requester))
             } else {
                 Ok(())
@@ -248,6 +328,111 @@ impl RegisterStorage {
         )))
     }
 
+    async fn get_entries(
+        &self,
+        address: Address,
+        msg_id: MessageId,
+        requester: PublicKey,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = match self
+            .get_chunk(address, Action::Read, requester)
+            .await
+            .and_then(|register| register.entries(Some(requester)).map_err(Error::from))
+        {
+            Ok(res) => Ok(res),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(NodeDuty::Send(build_client_query_response(
+            QueryResponse::GetRegisterEntries(result),
+            msg_id,
+            origin,
+        )))
+    }
+
+    async fn get_ancestry(
+        &self,
+        address: Address,
+        hash: EntryHash,
+        msg_id: MessageId,
+        requester: PublicKey,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = match self
+            .get_chunk(address, Action::Read, requester)
+            .await
+            .and_then(|register| {
+                register
+                    .ancestry(hash, Some(requester))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(NodeDuty::Send(build_client_query_response(
+            QueryResponse::GetRegisterAncestry(result),
+            msg_id,
+            origin,
+        )))
+    }
+
+    async fn diff_branches(
+        &self,
+        address: Address,
+        first: EntryHash,
+        second: EntryHash,
+        msg_id: MessageId,
+        requester: PublicKey,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = match self
+            .get_chunk(address, Action::Read, requester)
+            .await
+            .and_then(|register| {
+                register
+                    .diff(first, second, Some(requester))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(NodeDuty::Send(build_client_query_response(
+            QueryResponse::DiffRegisterBranches(result),
+            msg_id,
+            origin,
+        )))
+    }
+
+    async fn get_ops_since(
+        &self,
+        address: Address,
+        heads: &BTreeSet<EntryHash>,
+        msg_id: MessageId,
+        requester: PublicKey,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = match self
+            .get_chunk(address, Action::Read, requester)
+            .await
+            .and_then(|register| {
+                register
+                    .ops_since(heads, Some(requester))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(NodeDuty::Send(build_client_query_response(
+            QueryResponse::GetRegisterOps(result),
+            msg_id,
+            origin,
+        )))
+    }
+
     async fn edit(
         &mut self,
         write_op: RegisterOp<Entry>,
@@ -273,6 +458,25 @@ impl RegisterStorage {
         self.ok_or_error(result, msg_id, origin).await
     }
 
+    // Like any other write, the update was paid for before reaching us. It is authorised by the
+    // signatures of the owners, rather than by the requester.
+    async fn update_policy(
+        &mut self,
+        update: PolicyUpdate,
+        msg_id: MessageId,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = match self.chunks.get(&update.address).await {
+            Ok(mut register) => match register.apply_policy_update(update) {
+                Ok(()) => self.chunks.put(&register).await,
+                Err(error) => Err(error.into()),
+            },
+            Err(error) => Err(error),
+        };
+
+        self.ok_or_error(result, msg_id, origin).await
+    }
+
     async fn edit_chunk<F>(
         &mut self,
         address: Address,
@@ -17,10 +17,10 @@ use crate::node::{
 };
 use crate::routing::Prefix;
 use crate::types::{
-    Error as DtError, PublicKey, Sequence, SequenceAction, SequenceAddress, SequenceEntry,
-    SequenceIndex, SequenceOp, SequenceUser,
+    Error as DtError, PublicKey, Sequence, SequenceAction, SequenceAddress, SequenceClock,
+    SequenceEntry, SequenceIndex, SequenceOp, SequencePolicyUpdate, SequenceUser,
 };
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::{
     collections::BTreeMap,
     fmt::{self, Display, Formatter},
@@ -94,6 +94,10 @@ impl SequenceStorage {
                 self.get_private_policy(*address, msg_id, requester, origin)
                     .await
             }
+            GetOpsSince { address, clock } => {
+                self.get_ops_since(*address, clock, msg_id, requester, origin)
+                    .await
+            }
         }
     }
 
@@ -113,6 +117,11 @@ impl SequenceStorage {
                 self.edit(operation, msg_id, requester, origin).await
             }
             Delete(address) => self.delete(address, msg_id, requester, origin).await,
+            UpdatePolicy(update) => self.update_policy(update, msg_id, origin).await,
+            ExtendExpiry { address, expiry } => {
+                self.extend_expiry(address, expiry, msg_id, requester, origin)
+                    .await
+            }
         }
     }
 
@@ -162,6 +171,71 @@ impl SequenceStorage {
         Ok(data)
     }
 
+    async fn extend_expiry(
+        &mut self,
+        address: SequenceAddress,
+        expiry: u64,
+        msg_id: MessageId,
+        requester: PublicKey,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = self
+            .edit_chunk(
+                address,
+                SequenceAction::Append,
+                requester,
+                move |mut sequence| {
+                    if !sequence.ownership().is_owner(&requester) {
+                        return Err(Error::InvalidOwner(requester));
+                    }
+                    sequence.extend_expiry(expiry)?;
+                    Ok(sequence)
+                },
+            )
+            .await;
+
+        self.ok_or_error(result, msg_id, origin).await
+    }
+
+    /// Deletes the Sequences which expired at or before `now`, in seconds since the Unix epoch,
+    /// returning how many were deleted and the addresses of those which couldn't be checked or
+    /// deleted. A failing Sequence doesn't stop the sweep of the others.
+    pub(super) async fn remove_expired(
+        &mut self,
+        now: u64,
+    ) -> Result<(usize, Vec<SequenceAddress>)> {
+        let mut removed = 0;
+        let mut failed = vec![];
+        for address in self.chunks.keys().await? {
+            let expired = match self.chunks.get(&address).await {
+                Ok(sequence) => sequence.is_expired(now),
+                Err(error) => {
+                    warn!(
+                        "Failed to check the expiry of Sequence {:?}: {:?}",
+                        address, error
+                    );
+                    failed.push(address);
+                    continue;
+                }
+            };
+            if !expired {
+                continue;
+            }
+            info!("Deleting expired Sequence {:?}", address);
+            match self.chunks.delete(&address).await {
+                Ok(()) => removed += 1,
+                Err(error) => {
+                    warn!(
+                        "Failed to delete expired Sequence {:?}: {:?}",
+                        address, error
+                    );
+                    failed.push(address);
+                }
+            }
+        }
+        Ok((removed, failed))
+    }
+
     async fn delete(
         &mut self,
         address: SequenceAddress,
@@ -179,8 +253,7 @@ impl SequenceStorage {
                 ));
             }
 
-            let policy = sequence.private_policy(Some(requester))?;
-            if requester != policy.owner {
+            if !sequence.ownership().is_owner(&requester) {
                 Err(Error::InvalidOwner(requester))
             } else {
                 Ok(())
@@ -245,6 +318,33 @@ impl SequenceStorage {
         )))
     }
 
+    async fn get_ops_since(
+        &self,
+        address: SequenceAddress,
+        clock: &SequenceClock,
+        msg_id: MessageId,
+        requester: PublicKey,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = match self
+            .get_chunk(address, SequenceAction::Read, requester)
+            .await
+            .and_then(|sequence| {
+                sequence
+                    .ops_since(clock, Some(requester))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(NodeDuty::Send(build_client_query_response(
+            QueryResponse::GetSequenceOps(result),
+            msg_id,
+            origin,
+        )))
+    }
+
     async fn get_user_permissions(
         &self,
         address: SequenceAddress,
@@ -360,6 +460,25 @@ impl SequenceStorage {
         self.ok_or_error(result, msg_id, origin).await
     }
 
+    // Like any other write, the update was paid for before reaching us. It is authorised by the
+    // signatures of the owners, rather than by the requester.
+    async fn update_policy(
+        &mut self,
+        update: SequencePolicyUpdate,
+        msg_id: MessageId,
+        origin: EndUser,
+    ) -> Result<NodeDuty> {
+        let result = match self.chunks.get(&update.address).await {
+            Ok(mut sequence) => match sequence.apply_policy_update(update) {
+                Ok(()) => self.chunks.put(&sequence).await,
+                Err(error) => Err(error.into()),
+            },
+            Err(error) => Err(error),
+        };
+
+        self.ok_or_error(result, msg_id, origin).await
+    }
+
     async fn edit_chunk<F>(
         &mut self,
         address: SequenceAddress,
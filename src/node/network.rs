@@ -9,8 +9,8 @@
 use crate::messaging::{Itinerary, MessageId, MessageType};
 use crate::node::{state_db::store_network_keypair, utils, Config as NodeConfig, Error, Result};
 use crate::routing::{
-    Config as RoutingConfig, Error as RoutingError, EventStream, PeerUtils, Routing as RoutingNode,
-    SectionAuthorityProviderUtils,
+    Config as RoutingConfig, Error as RoutingError, EventStream, NetworkParams, PeerUtils,
+    Routing as RoutingNode, SectionAuthorityProviderUtils,
 };
 use crate::types::{PublicKey, Signature, SignatureShare};
 use bls::PublicKeySet;
@@ -43,6 +43,9 @@ impl Network {
             first: config.is_first(),
             transport_config: config.network_config().clone(),
             keypair: None,
+            network_params: config.network_params(),
+            trace_messages: config.trace_messages,
+            elder_health: config.elder_health(),
         };
         let (routing, event_stream) = RoutingNode::new(routing_config).await?;
 
@@ -197,6 +200,14 @@ impl Network {
         self.routing.section_chain().await
     }
 
+    pub async fn genesis_key(&self) -> bls::PublicKey {
+        self.routing.genesis_key().await
+    }
+
+    pub async fn network_params(&self) -> NetworkParams {
+        self.routing.our_section().await.network_params()
+    }
+
     #[allow(unused)]
     pub async fn matches_our_prefix(&self, name: &XorName) -> bool {
         self.routing.matches_our_prefix(name).await
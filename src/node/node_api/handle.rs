@@ -21,15 +21,24 @@ use crate::node::{
     event_mapping::MsgContext,
     node_ops::{NodeDuties, NodeDuty},
     section_funds::{reward_stage::RewardStage, Credits, SectionFunds},
+    transfers::faucet::genesis_credit,
     Error, Node, Result,
 };
-use crate::routing::ELDER_SIZE;
-use log::{debug, info};
-use std::sync::Arc;
-use tokio::{sync::RwLock, task::JoinHandle};
+use log::{debug, info, warn};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    sync::RwLock,
+    task::JoinHandle,
+    time::{sleep, Duration},
+};
 use xor_name::XorName;
 
 const DATA_SECTION_TARGET_COUNT: usize = 3;
+// How often Elders delete the private data which expired.
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
 pub enum NodeTask {
@@ -45,6 +54,16 @@ impl From<NodeDuties> for NodeTask {
 }
 
 impl Node {
+    // Starts the periodic sweep of the expired data, unless it is already running.
+    fn schedule_expiry_sweep(&mut self) -> NodeTask {
+        if self.expiry_sweep_scheduled {
+            NodeTask::None
+        } else {
+            self.expiry_sweep_scheduled = true;
+            NodeTask::from(vec![NodeDuty::RemoveExpiredData])
+        }
+    }
+
     ///
     pub async fn handle(&mut self, duty: NodeDuty) -> Result<NodeTask> {
         if !matches!(duty, NodeDuty::NoOp) {
@@ -54,9 +73,25 @@ impl Node {
         match duty {
             NodeDuty::Genesis => {
                 self.level_up().await?;
+                let sweep = self.schedule_expiry_sweep();
                 let elder = self.role.as_elder_mut()?;
                 *elder.received_initial_sync.write().await = true;
-                Ok(NodeTask::None)
+                if let Some(faucet) = self.network_api.network_params().await.faucet {
+                    let credit_proof = genesis_credit(&faucet, &self.network_api).await?;
+                    elder
+                        .transfers
+                        .read()
+                        .await
+                        .fund_faucet(&credit_proof)
+                        .await?;
+                    info!(
+                        "Funded faucet wallet {} with {}",
+                        credit_proof.recipient(),
+                        faucet.supply
+                    );
+                    elder.section_funds.write().await.record_mint(faucet.supply);
+                }
+                Ok(sweep)
             }
             NodeDuty::EldersChanged {
                 our_key,
@@ -67,13 +102,15 @@ impl Node {
                 if newbie {
                     info!("Promoted to Elder on Churn");
                     self.level_up().await?;
+                    let sweep = self.schedule_expiry_sweep();
                     if self.network_api.our_prefix().await.is_empty()
-                        && self.network_api.section_chain().await.len() <= ELDER_SIZE
+                        && self.network_api.section_chain().await.len()
+                            <= self.network_api.network_params().await.elder_size
                     {
                         let elder = self.role.as_elder_mut()?;
                         *elder.received_initial_sync.write().await = true;
                     }
-                    Ok(NodeTask::None)
+                    Ok(sweep)
                 } else {
                     info!("Updating our replicas on Churn");
                     let elder = self.role.as_elder_mut()?.clone();
@@ -82,7 +119,9 @@ impl Node {
                         Self::update_replicas(&elder, &network).await?;
                         let msg_id =
                             MessageId::combine(&[our_prefix.name().0, XorName::from(our_key).0]);
-                        let ops = vec![push_state(&elder, our_prefix, msg_id, new_elders).await?];
+                        let supply = elder.section_funds.read().await.supply();
+                        let ops =
+                            vec![push_state(&elder, our_prefix, msg_id, new_elders, supply).await?];
                         let our_adults = network.our_adults().await;
                         elder
                             .meta_data
@@ -199,6 +238,7 @@ impl Node {
                     if let RewardStage::Completed(credit_proofs) = churn_process.stage().clone() {
                         let reward_sum = credit_proofs.sum();
                         ops.extend(Self::propagate_credits(credit_proofs)?);
+                        reward_wallets.record_payout(reward_sum, network_api.our_prefix().await);
                         // update state
                         *section_funds = SectionFunds::KeepingNodeWallets(reward_wallets.clone());
                         let section_key = network_api.section_public_key().await?;
@@ -283,6 +323,8 @@ impl Node {
                 node_rewards,
                 user_wallets,
                 metadata,
+                supply,
+                reward_rounds,
             } => {
                 let elder = self.role.as_elder()?.clone();
                 let network_api = self.network_api.clone();
@@ -296,6 +338,8 @@ impl Node {
                             node_rewards,
                             user_wallets,
                             metadata,
+                            supply,
+                            reward_rounds,
                         )
                         .await?,
                     ]))
@@ -364,24 +408,45 @@ impl Node {
                 });
                 Ok(NodeTask::Thread(handle))
             }
+            #[cfg(feature = "simulated-payouts")]
             NodeDuty::SimulatePayout { transfer, .. } => {
                 let elder = self.role.as_elder_mut()?.clone();
+                let handle = tokio::spawn(async move {
+                    let amount = transfer.amount;
+                    let duty = elder
+                        .transfers
+                        .write()
+                        .await
+                        .credit_without_proof(transfer)
+                        .await?;
+                    // The credit has no debit, so it creates the tokens it pays out.
+                    elder.section_funds.write().await.record_mint(amount);
+                    Ok(NodeTask::from(vec![duty]))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::GetTransfersHistory {
+                at, msg_id, origin, ..
+            } => {
+                // TODO: add limit with since_version
+                let elder = self.role.as_elder()?.clone();
                 let handle = tokio::spawn(async move {
                     Ok(NodeTask::from(vec![
                         elder
                             .transfers
-                            .write()
+                            .read()
                             .await
-                            .credit_without_proof(transfer)
+                            .history(&at, msg_id, origin)
                             .await?,
                     ]))
                 });
                 Ok(NodeTask::Thread(handle))
             }
-            NodeDuty::GetTransfersHistory {
-                at, msg_id, origin, ..
+            NodeDuty::GetSectionChain {
+                key,
+                msg_id,
+                origin,
             } => {
-                // TODO: add limit with since_version
                 let elder = self.role.as_elder()?.clone();
                 let handle = tokio::spawn(async move {
                     Ok(NodeTask::from(vec![
@@ -389,7 +454,38 @@ impl Node {
                             .transfers
                             .read()
                             .await
-                            .history(&at, msg_id, origin)
+                            .section_chain(&key, msg_id, origin)
+                            .await?,
+                    ]))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::GetRewardStatement {
+                wallet,
+                msg_id,
+                origin,
+            } => {
+                let elder = self.role.as_elder()?.clone();
+                let handle = tokio::spawn(async move {
+                    Ok(NodeTask::from(vec![elder
+                        .section_funds
+                        .read()
+                        .await
+                        .reward_statement(wallet, msg_id, origin)]))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::GetSectionSupply { msg_id, origin } => {
+                let elder = self.role.as_elder()?.clone();
+                let network_api = self.network_api.clone();
+                let handle = tokio::spawn(async move {
+                    let managed = elder.transfers.read().await.managed_amount().await?;
+                    Ok(NodeTask::from(vec![
+                        elder
+                            .section_funds
+                            .read()
+                            .await
+                            .supply_share(managed, &network_api, msg_id, origin)
                             .await?,
                     ]))
                 });
@@ -435,14 +531,93 @@ impl Node {
             } => {
                 let elder = self.role.as_elder_mut()?.clone();
                 let handle = tokio::spawn(async move {
-                    Ok(NodeTask::from(vec![
+                    Ok(NodeTask::from(
                         elder
                             .transfers
                             .read()
                             .await
                             .register(&proof, msg_id, origin)
                             .await?,
-                    ]))
+                    ))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::ReleaseEscrow {
+                id,
+                secret,
+                msg_id,
+                origin,
+            } => {
+                let elder = self.role.as_elder_mut()?.clone();
+                let handle = tokio::spawn(async move {
+                    Ok(NodeTask::from(
+                        elder
+                            .transfers
+                            .read()
+                            .await
+                            .release_escrow(id, secret, msg_id, origin)
+                            .await?,
+                    ))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::RefundEscrow { id, msg_id, origin } => {
+                let elder = self.role.as_elder_mut()?.clone();
+                let handle = tokio::spawn(async move {
+                    Ok(NodeTask::from(
+                        elder
+                            .transfers
+                            .read()
+                            .await
+                            .refund_escrow(id, msg_id, origin)
+                            .await?,
+                    ))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::ReceiveEscrowSettlement { share } => {
+                let elder = self.role.as_elder_mut()?.clone();
+                let handle = tokio::spawn(async move {
+                    Ok(NodeTask::from(
+                        elder
+                            .transfers
+                            .read()
+                            .await
+                            .receive_settlement_share(share)
+                            .await?,
+                    ))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::RequestFaucetPayout {
+                payout,
+                msg_id,
+                origin,
+            } => {
+                let elder = self.role.as_elder_mut()?.clone();
+                let handle = tokio::spawn(async move {
+                    Ok(NodeTask::from(
+                        elder
+                            .transfers
+                            .read()
+                            .await
+                            .request_faucet_payout(payout, msg_id, origin)
+                            .await?,
+                    ))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::ReceiveFaucetPayout { share } => {
+                let elder = self.role.as_elder_mut()?.clone();
+                let handle = tokio::spawn(async move {
+                    Ok(NodeTask::from(
+                        elder
+                            .transfers
+                            .read()
+                            .await
+                            .receive_payout_share(share)
+                            .await?,
+                    ))
                 });
                 Ok(NodeTask::Thread(handle))
             }
@@ -669,7 +844,21 @@ impl Node {
                 Ok(NodeTask::Thread(handle))
             }
             NodeDuty::ProcessDataPayment { .. } => Ok(NodeTask::None),
-            NodeDuty::ReplicateChunk { chunk, .. } => {
+            NodeDuty::ReplicateChunk { chunk, expiry, .. } => {
+                let adult = self.role.as_adult_mut()?.clone();
+                let handle = tokio::spawn(async move {
+                    Ok(NodeTask::from(vec![
+                        adult
+                            .chunks
+                            .write()
+                            .await
+                            .store_for_replication(chunk, expiry)
+                            .await?,
+                    ]))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
+            NodeDuty::DeleteExpiredChunk(address) => {
                 let adult = self.role.as_adult_mut()?.clone();
                 let handle = tokio::spawn(async move {
                     Ok(NodeTask::from(vec![
@@ -677,12 +866,41 @@ impl Node {
                             .chunks
                             .write()
                             .await
-                            .store_for_replication(chunk)
+                            .delete_expired_chunk(&address)
                             .await?,
                     ]))
                 });
                 Ok(NodeTask::Thread(handle))
             }
+            NodeDuty::RemoveExpiredData => {
+                // The sweep reschedules itself only while we are an Elder.
+                let elder = match self.role.as_elder() {
+                    Ok(elder) => elder.clone(),
+                    Err(_) => {
+                        info!("No longer an Elder, stopping the sweep of expired data");
+                        self.expiry_sweep_scheduled = false;
+                        return Ok(NodeTask::None);
+                    }
+                };
+                let network_api = self.network_api.clone();
+                let handle = tokio::spawn(async move {
+                    sleep(EXPIRY_SWEEP_INTERVAL).await;
+                    let mut duties = vec![NodeDuty::RemoveExpiredData];
+                    // We may have been demoted meanwhile.
+                    if network_api.is_elder().await {
+                        let now = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .map(|duration| duration.as_secs())
+                            .unwrap_or_default();
+                        match elder.meta_data.write().await.remove_expired(now).await {
+                            Ok(deletions) => duties.extend(deletions),
+                            Err(error) => warn!("Failed to delete expired data: {:?}", error),
+                        }
+                    }
+                    Ok(NodeTask::from(duties))
+                });
+                Ok(NodeTask::Thread(handle))
+            }
             NodeDuty::NoOp => Ok(NodeTask::None),
         }
     }
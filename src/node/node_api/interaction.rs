@@ -19,7 +19,7 @@ use crate::node::{
     Node, Result,
 };
 use crate::routing::{Prefix, XorName};
-use crate::types::{CreditAgreementProof, CreditId, PublicKey, SectionElders};
+use crate::types::{CreditAgreementProof, CreditId, PublicKey, SectionElders, SectionTotals};
 use std::collections::{BTreeMap, BTreeSet};
 
 use super::role::ElderRole;
@@ -115,6 +115,7 @@ pub(crate) async fn push_state(
     prefix: Prefix,
     msg_id: MessageId,
     peers: BTreeSet<XorName>,
+    supply: SectionTotals,
 ) -> Result<NodeDuty> {
     let user_wallets = elder.transfers.read().await.user_wallets().await;
     let node_rewards = elder.section_funds.read().await.node_wallets();
@@ -135,6 +136,7 @@ pub(crate) async fn push_state(
         .await
         .get_data_exchange_packet(prefix)
         .await?;
+    let reward_rounds = elder.section_funds.read().await.rounds_of(prefix);
 
     Ok(NodeDuty::SendToNodes {
         msg: NodeMsg::NodeCmd {
@@ -142,6 +144,8 @@ pub(crate) async fn push_state(
                 node_rewards,
                 user_wallets,
                 metadata,
+                supply,
+                reward_rounds,
             }),
             id: msg_id,
         },
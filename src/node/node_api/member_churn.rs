@@ -21,7 +21,7 @@ use crate::node::{
     Node, Result,
 };
 use crate::routing::XorName;
-use crate::types::{ActorHistory, NodeAge, PublicKey};
+use crate::types::{ActorHistory, NodeAge, PublicKey, RewardRound, SectionTotals};
 use log::info;
 use std::collections::BTreeMap;
 
@@ -59,10 +59,10 @@ impl Node {
 
         //
         // start handling node rewards
-        let section_funds = SectionFunds::KeepingNodeWallets(RewardWallets::new(BTreeMap::<
-            XorName,
-            (NodeAge, PublicKey),
-        >::new()));
+        let section_funds = SectionFunds::KeepingNodeWallets(RewardWallets::new(
+            BTreeMap::<XorName, (NodeAge, PublicKey)>::new(),
+            self.node_info.path(),
+        )?);
 
         self.role = Role::Elder(ElderRole::new(meta_data, transfers, section_funds, false));
 
@@ -70,6 +70,7 @@ impl Node {
     }
 
     /// Continue the level up and handle more responsibilities.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn synch_state(
         elder: &ElderRole,
         reward_key: PublicKey,
@@ -77,6 +78,8 @@ impl Node {
         node_wallets: BTreeMap<XorName, (NodeAge, PublicKey)>,
         user_wallets: BTreeMap<PublicKey, ActorHistory>,
         metadata: DataExchange,
+        supply: SectionTotals,
+        reward_rounds: Vec<RewardRound>,
     ) -> Result<NodeDuty> {
         if *elder.received_initial_sync.read().await {
             info!("We are already received the initial sync from our section. Ignoring update");
@@ -93,6 +96,13 @@ impl Node {
                 .await
                 .set_node_wallet(*key, *wallet, *age)
         }
+        // --------- take over the supply totals and reward rounds ---------
+        elder.section_funds.write().await.set_supply(supply);
+        elder
+            .section_funds
+            .write()
+            .await
+            .set_rounds(reward_rounds)?;
         // --------- merge in provided metadata ---------
         elder.meta_data.write().await.update(metadata).await?;
 
@@ -68,6 +68,8 @@ pub struct Node {
     node_info: NodeInfo,
     used_space: UsedSpace,
     role: Role,
+    // Whether the sweep of the expired data is running, which it does while we are an Elder.
+    expiry_sweep_scheduled: bool,
 }
 
 impl Node {
@@ -108,6 +110,7 @@ impl Node {
             node_info,
             used_space: UsedSpace::new(config.max_capacity()),
             network_api: network_api.clone(),
+            expiry_sweep_scheduled: false,
         };
 
         messaging::send(
@@ -38,6 +38,8 @@ impl AdultRole {
         lost_adults: BTreeSet<XorName>,
         remaining: BTreeSet<XorName>,
     ) -> Result<NodeDuties> {
+        // No need to replicate what already expired.
+        self.chunks.write().await.remove_expired_chunks().await?;
         let keys = self.chunks.read().await.keys().await?;
         let mut data_for_replication = BTreeMap::new();
         for addr in keys.iter() {
@@ -50,9 +52,9 @@ impl AdultRole {
         }
         Ok(data_for_replication
             .into_iter()
-            .map(|(data, targets)| NodeDuty::SendToNodes {
+            .map(|((chunk, expiry), targets)| NodeDuty::SendToNodes {
                 msg: NodeMsg::NodeCmd {
-                    cmd: NodeCmd::System(NodeSystemCmd::ReplicateChunk(data)),
+                    cmd: NodeCmd::System(NodeSystemCmd::ReplicateChunk { chunk, expiry }),
                     id: MessageId::new(),
                 },
                 targets,
@@ -68,7 +70,7 @@ impl AdultRole {
         new_adults: &BTreeSet<XorName>,
         lost_adults: &BTreeSet<XorName>,
         remaining: &BTreeSet<XorName>,
-    ) -> Option<(Chunk, BTreeSet<XorName>)> {
+    ) -> Option<((Chunk, Option<u64>), BTreeSet<XorName>)> {
         let old_adult_list = remaining.union(lost_adults).copied().collect();
         let new_adult_list = remaining.union(new_adults).copied().collect();
         let new_holders = self.compute_holders(address, &new_adult_list);
@@ -82,13 +84,14 @@ impl AdultRole {
             info!("Republishing chunk at {:?}", address);
             trace!("We are not a holder anymore? {}, New Adult is Holder? {}, Lost Adult was holder? {}", we_are_not_holder_anymore, new_adult_is_holder, lost_old_holder);
             let chunk = self.chunks.read().await.get_chunk(address).await.ok()?;
+            let expiry = self.chunks.read().await.expiry(address);
             if we_are_not_holder_anymore {
                 if let Err(err) = self.chunks.write().await.remove_chunk(address).await {
                     warn!("Error deleting chunk during republish: {:?}", err);
                 }
             }
             // TODO: Push to LRU cache
-            Some((chunk, new_holders))
+            Some(((chunk, expiry), new_holders))
         } else {
             None
         }
@@ -21,6 +21,7 @@ use crate::types::{NodeAge, PublicKey, Token};
 use log::debug;
 use section_funds::{
     elder_signing::ElderSigning,
+    reward_policy::reward_policy,
     reward_process::{OurSection, RewardProcess},
     reward_wallets::RewardWallets,
 };
@@ -53,7 +54,10 @@ impl Node {
         let process =
             RewardProcess::new(section, ElderSigning::new(self.network_api.clone()).await?);
 
-        let wallets = RewardWallets::new(BTreeMap::<XorName, (NodeAge, PublicKey)>::new());
+        let wallets = RewardWallets::new(
+            BTreeMap::<XorName, (NodeAge, PublicKey)>::new(),
+            self.node_info.path(),
+        )?;
 
         *elder.section_funds.write().await = SectionFunds::Churning { process, wallets };
 
@@ -76,7 +80,7 @@ impl Node {
         let info = replica_info(network_api).await?;
         elder.transfers.write().await.update_replica_info(info);
 
-        let wallets = match &*elder.section_funds.read().await {
+        let mut wallets = match &*elder.section_funds.read().await {
             SectionFunds::KeepingNodeWallets(wallets) | SectionFunds::Churning { wallets, .. } => {
                 wallets.clone()
             }
@@ -102,11 +106,19 @@ impl Node {
                 ElderSigning::new(network_api.clone()).await?,
             );
 
-            ops.push(
-                process
-                    .reward_and_mint(payments, section_managed, wallets.node_wallets())
-                    .await?,
-            );
+            let policy = reward_policy(network_api.network_params().await.reward_policy);
+            debug!("Rewarding by {} policy", policy.kind());
+
+            let (duty, round) = process
+                .reward_and_mint(
+                    payments,
+                    section_managed,
+                    wallets.node_wallets(),
+                    policy.as_ref(),
+                )
+                .await?;
+            ops.push(duty);
+            wallets.record_round(round)?;
 
             *elder.section_funds.write().await = SectionFunds::Churning {
                 process,
@@ -118,11 +130,21 @@ impl Node {
 
         // replicate state to our new elders
         let msg_id = MessageId::combine(&[our_prefix.name().0, XorName::from(our_key).0]);
-        ops.push(push_state(elder, our_prefix, msg_id, our_new_elders).await?);
+        let supply = elder
+            .section_funds
+            .read()
+            .await
+            .supply_after_split(our_prefix);
+        ops.push(push_state(elder, our_prefix, msg_id, our_new_elders, supply).await?);
 
         // replicate state to our neighbour's new elders
         let msg_id = MessageId::combine(&[sibling_prefix.name().0, XorName::from(sibling_key).0]);
-        ops.push(push_state(elder, sibling_prefix, msg_id, their_new_elders).await?);
+        let supply = elder
+            .section_funds
+            .read()
+            .await
+            .supply_after_split(sibling_prefix);
+        ops.push(push_state(elder, sibling_prefix, msg_id, their_new_elders, supply).await?);
 
         let our_adults = network_api.our_adults().await;
         // drop metadata state
@@ -142,7 +164,11 @@ impl Node {
             .await?;
 
         // drop reward wallets state
-        elder.section_funds.read().await.keep_wallets_of(our_prefix);
+        elder
+            .section_funds
+            .write()
+            .await
+            .keep_wallets_of(our_prefix)?;
 
         Ok(ops)
     }
@@ -17,9 +17,11 @@ use crate::messaging::{
 };
 use crate::routing::Prefix;
 #[cfg(feature = "simulated-payouts")]
+use crate::types::Transfer;
 use crate::types::{
-    ActorHistory, Chunk, CreditAgreementProof, NodeAge, PublicKey, RewardAccumulation,
-    RewardProposal, SignedTransfer, Transfer, TransferAgreementProof,
+    ActorHistory, Chunk, ChunkAddress, CreditAgreementProof, DebitId, EscrowSettlementShare,
+    FaucetPayoutShare, NodeAge, PublicKey, RewardAccumulation, RewardProposal, RewardRound,
+    SectionTotals, SignedCredit, SignedTransfer, TransferAgreementProof,
 };
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -76,7 +78,35 @@ pub enum NodeDuty {
         msg_id: MessageId,
         origin: SrcLocation,
     },
+    /// Release the credit of a transfer held in escrow to its recipient.
+    ReleaseEscrow {
+        id: DebitId,
+        secret: Vec<u8>,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    },
+    /// Refund a transfer held in escrow to its sender.
+    RefundEscrow {
+        id: DebitId,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    },
+    /// Another Elder has signed the settlement of an escrow.
+    ReceiveEscrowSettlement {
+        share: EscrowSettlementShare,
+    },
+    /// Pay out from the faucet to the recipient requesting it.
+    RequestFaucetPayout {
+        payout: SignedCredit,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    },
+    /// Another Elder has signed a payout from the faucet.
+    ReceiveFaucetPayout {
+        share: FaucetPayoutShare,
+    },
     /// TEMP: Simulate a transfer from a client
+    #[cfg(feature = "simulated-payouts")]
     SimulatePayout {
         transfer: Transfer,
         msg_id: MessageId,
@@ -128,6 +158,23 @@ pub enum NodeDuty {
         msg_id: MessageId,
         origin: SrcLocation,
     },
+    /// Get the section chain from genesis to a section key.
+    GetSectionChain {
+        key: bls::PublicKey,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    },
+    /// Get the statement of rewards paid to a wallet by our section.
+    GetRewardStatement {
+        wallet: PublicKey,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    },
+    /// Get the token totals of our section, signed with our key share.
+    GetSectionSupply {
+        msg_id: MessageId,
+        origin: SrcLocation,
+    },
     /// Proposal of payout of rewards.
     ReceiveRewardProposal(RewardProposal),
     /// Accumulation of payout of rewards.
@@ -175,6 +222,10 @@ pub enum NodeDuty {
         user_wallets: BTreeMap<PublicKey, ActorHistory>,
         /// The metadata stored on Elders.
         metadata: DataExchange,
+        /// Tokens minted and burned by the section, and by those it split from.
+        supply: SectionTotals,
+        /// The section's reward rounds.
+        reward_rounds: Vec<RewardRound>,
     },
     /// As members are lost for various reasons
     /// there are certain things nodes need
@@ -229,8 +280,15 @@ pub enum NodeDuty {
     /// This is run at an Adult (the new holder).
     ReplicateChunk {
         chunk: Chunk,
+        expiry: Option<u64>,
         msg_id: MessageId,
     },
+    /// Delete a private chunk which expired.
+    /// This is run at an Adult (a holder).
+    DeleteExpiredChunk(ChunkAddress),
+    /// Wait for the next sweep of the private data which expired, then delete it.
+    /// This is run at an Elder, and stops once we are no longer one.
+    RemoveExpiredData,
     /// Create proposals to vote unresponsive nodes as offline
     ProposeOffline(Vec<XorName>),
     NoOp,
@@ -256,8 +314,17 @@ impl Debug for NodeDuty {
             Self::GetTransferReplicaEvents { .. } => write!(f, "GetTransferReplicaEvents"),
             Self::ValidateClientTransfer { .. } => write!(f, "ValidateClientTransfer"),
             Self::RegisterTransfer { .. } => write!(f, "RegisterTransfer"),
+            Self::ReleaseEscrow { .. } => write!(f, "ReleaseEscrow"),
+            Self::RefundEscrow { .. } => write!(f, "RefundEscrow"),
+            Self::ReceiveEscrowSettlement { .. } => write!(f, "ReceiveEscrowSettlement"),
+            Self::RequestFaucetPayout { .. } => write!(f, "RequestFaucetPayout"),
+            Self::ReceiveFaucetPayout { .. } => write!(f, "ReceiveFaucetPayout"),
             Self::GetBalance { .. } => write!(f, "GetBalance"),
             Self::GetStoreCost { .. } => write!(f, "GetStoreCost"),
+            Self::GetSectionChain { .. } => write!(f, "GetSectionChain"),
+            Self::GetRewardStatement { .. } => write!(f, "GetRewardStatement"),
+            Self::GetSectionSupply { .. } => write!(f, "GetSectionSupply"),
+            #[cfg(feature = "simulated-payouts")]
             Self::SimulatePayout { .. } => write!(f, "SimulatePayout"),
             Self::GetTransfersHistory { .. } => write!(f, "GetTransfersHistory"),
             Self::ReadChunk { .. } => write!(f, "ReadChunk"),
@@ -303,6 +370,8 @@ impl Debug for NodeDuty {
             Self::ProcessWrite { .. } => write!(f, "ProcessWrite"),
             Self::ProcessDataPayment { .. } => write!(f, "ProcessDataPayment"),
             Self::ReplicateChunk { .. } => write!(f, "ReplicateChunk"),
+            Self::DeleteExpiredChunk(address) => write!(f, "DeleteExpiredChunk({:?})", address),
+            Self::RemoveExpiredData => write!(f, "RemoveExpiredData"),
             Self::ProposeOffline(nodes) => write!(f, "ProposeOffline({:?})", nodes),
         }
     }
@@ -8,14 +8,26 @@
 
 pub mod elder_signing;
 mod reward_calc;
+pub mod reward_policy;
 pub mod reward_process;
 pub mod reward_stage;
 pub mod reward_wallets;
 
 use self::{reward_process::RewardProcess, reward_wallets::RewardWallets};
-use crate::node::{Error, Result};
+use crate::messaging::{
+    client::{ClientMsg, ProcessMsg, QueryResponse},
+    Aggregation, MessageId, SrcLocation,
+};
+use crate::node::{
+    network::Network,
+    node_ops::{MsgType, NodeDuty, OutgoingMsg},
+    Error, Result,
+};
 use crate::routing::{Prefix, XorName};
-use crate::types::{CreditAgreementProof, CreditId, NodeAge, PublicKey, Token};
+use crate::types::{
+    CreditAgreementProof, CreditId, NodeAge, PublicKey, RewardRound, SectionSupply,
+    SectionSupplyShare, SectionTotals, Token,
+};
 use dashmap::DashMap;
 use log::info;
 use std::collections::BTreeMap;
@@ -59,6 +71,118 @@ impl SectionFunds {
         }
     }
 
+    /// Responds with the rewards paid to a wallet, over the rounds recorded by us.
+    pub fn reward_statement(
+        &self,
+        wallet: PublicKey,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    ) -> NodeDuty {
+        let statement = match &self {
+            Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => {
+                wallets.statement(wallet)
+            }
+        };
+        NodeDuty::Send(OutgoingMsg {
+            msg: MsgType::Client(ClientMsg::Process(ProcessMsg::QueryResponse {
+                id: MessageId::in_response_to(&msg_id),
+                response: QueryResponse::GetRewardStatement(Ok(statement)),
+                correlation_id: msg_id,
+            })),
+            section_source: false, // strictly this is not correct, but we don't expect responses to a response..
+            dst: origin.to_dst(),
+            aggregation: Aggregation::AtDestination,
+        })
+    }
+
+    /// The reward rounds to hand over to the Elders of a section with the given prefix.
+    pub fn rounds_of(&self, prefix: Prefix) -> Vec<RewardRound> {
+        match &self {
+            Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => {
+                wallets.rounds_of(prefix)
+            }
+        }
+    }
+
+    /// Takes over the reward rounds of the Elders handing over the section to us.
+    pub fn set_rounds(&mut self, rounds: Vec<RewardRound>) -> Result<()> {
+        match self {
+            Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => {
+                wallets.set_rounds(rounds)
+            }
+        }
+    }
+
+    /// Tokens minted and burned by our section, and by those it split from.
+    pub fn supply(&self) -> SectionTotals {
+        match &self {
+            Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => wallets.supply(),
+        }
+    }
+
+    /// The totals which go to one half of our section as it splits.
+    pub fn supply_after_split(&self, prefix: Prefix) -> SectionTotals {
+        match &self {
+            Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => {
+                wallets.supply_after_split(prefix)
+            }
+        }
+    }
+
+    /// Adds tokens created outside of reward payouts, i.e. the funding of a faucet at genesis,
+    /// or the simulated payouts of test networks.
+    pub fn record_mint(&mut self, amount: Token) {
+        match self {
+            Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => {
+                wallets.record_mint(amount)
+            }
+        }
+    }
+
+    /// Takes over the totals of the Elders handing over the section to us.
+    pub fn set_supply(&mut self, supply: SectionTotals) {
+        match self {
+            Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => {
+                wallets.set_supply(supply)
+            }
+        }
+    }
+
+    /// Responds with the token totals of our section, signed with our share of the section key,
+    /// for the auditor to combine with the shares of the other Elders.
+    pub async fn supply_share(
+        &self,
+        managed: Token,
+        network_api: &Network,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    ) -> Result<NodeDuty> {
+        let public_key_set = network_api.our_public_key_set().await?;
+        let supply = SectionSupply {
+            prefix: network_api.our_prefix().await,
+            section_key: public_key_set.public_key(),
+            managed,
+            totals: self.supply(),
+        };
+        let signature_share = network_api.sign_as_elder(&supply).await?;
+        let share = SectionSupplyShare {
+            supply,
+            public_key_set,
+            signature_share,
+        };
+
+        Ok(NodeDuty::Send(OutgoingMsg {
+            msg: MsgType::Client(ClientMsg::Process(ProcessMsg::QueryResponse {
+                id: MessageId::in_response_to(&msg_id),
+                response: QueryResponse::GetSectionSupply(Ok(share)),
+                correlation_id: msg_id,
+            })),
+            section_source: false, // strictly this is not correct, but we don't expect responses to a response..
+            dst: origin.to_dst(),
+            aggregation: Aggregation::None,
+        }))
+    }
+
     /// Nodes register/updates wallets for future reward payouts.
     pub fn set_node_wallet(&self, node_id: XorName, wallet: PublicKey, age: u8) {
         match &self {
@@ -81,8 +205,8 @@ impl SectionFunds {
 
     /// When the section becomes aware that a node has left,
     /// its reward key is removed.
-    pub fn keep_wallets_of(&self, prefix: Prefix) {
-        match &self {
+    pub fn keep_wallets_of(&mut self, prefix: Prefix) -> Result<()> {
+        match self {
             Self::Churning { wallets, .. } | Self::KeepingNodeWallets(wallets) => {
                 wallets.keep_wallets_of(prefix)
             }
@@ -7,10 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::routing::XorName;
-use crate::types::{NodeAge, PublicKey, Token};
+use crate::types::{NoRewardReason, NodeAge, NodeReward, PublicKey, RewardRound, Token};
 use std::collections::BTreeMap;
 
-const MIN_REWARD_AGE: u8 = 6;
+pub(super) const MIN_REWARD_AGE: u8 = 6;
 
 ///  -----  MINTING  -----
 /// This is the minting of new coins happening;
@@ -58,6 +58,99 @@ pub fn distribute_rewards(
     distribute(amount, reward_buckets)
 }
 
+/// Calculates reward for each public key
+/// proportional to the weight of its node,
+/// out of the total amount supplied.
+/// Nodes below the minimum reward age, or without weight, get nothing.
+pub fn distribute_by_weight(
+    amount: Token,
+    nodes: &BTreeMap<XorName, (NodeAge, PublicKey)>,
+    weight: impl Fn(&XorName, NodeAge) -> u64,
+) -> BTreeMap<XorName, (NodeAge, PublicKey, Token)> {
+    let weights: BTreeMap<XorName, u64> = nodes
+        .iter()
+        .filter(|(_, (age, _))| *age >= MIN_REWARD_AGE)
+        .map(|(node_name, (age, _))| (*node_name, weight(node_name, *age)))
+        .filter(|(_, weight)| *weight > 0)
+        .collect();
+    let total_weight = weights.values().map(|weight| *weight as u128).sum::<u128>();
+    if total_weight == 0 {
+        return BTreeMap::new();
+    }
+
+    let amount = amount.as_nano();
+    let mut to_return = BTreeMap::new();
+    let mut remaining_amount = amount;
+    for (node_name, weight) in &weights {
+        let (age, wallet) = nodes[node_name];
+        // the share is at most the amount, so it fits
+        let reward = (amount as u128 * *weight as u128 / total_weight) as u64;
+        remaining_amount -= reward;
+        let _ = to_return.insert(*node_name, (age, wallet, Token::from_nano(reward)));
+    }
+
+    // what is left from rounding goes to the heaviest node
+    if let Some((node_name, _)) = weights.iter().max_by_key(|(_, weight)| **weight) {
+        if let Some((_, _, reward)) = to_return.get_mut(node_name) {
+            *reward = Token::from_nano(reward.as_nano() + remaining_amount);
+        }
+    }
+
+    to_return
+}
+
+/// Records a round of rewards, with the reward of each node,
+/// including those not rewarded, and why.
+pub fn reward_round(
+    section_key: PublicKey,
+    payments: Token,
+    rewards: Token,
+    nodes: &BTreeMap<XorName, (NodeAge, PublicKey)>,
+    distribution: &BTreeMap<XorName, (NodeAge, PublicKey, Token)>,
+) -> RewardRound {
+    let mut age_buckets = BTreeMap::new();
+    for (age, _, _) in distribution.values() {
+        *age_buckets.entry(*age).or_insert(0) += 1;
+    }
+    let nodes = nodes
+        .iter()
+        .map(|(node_name, (age, wallet))| {
+            let amount = distribution
+                .get(node_name)
+                .map(|(_, _, amount)| *amount)
+                .unwrap_or_else(Token::zero);
+            let no_reward_reason = if *age < MIN_REWARD_AGE {
+                Some(NoRewardReason::AgeBelowMinimum(MIN_REWARD_AGE))
+            } else if rewards == Token::zero() {
+                Some(NoRewardReason::NoRewards)
+            } else if amount == Token::zero() {
+                Some(NoRewardReason::ShareTooSmall)
+            } else {
+                None
+            };
+            let reward = NodeReward {
+                age: *age,
+                wallet: *wallet,
+                amount,
+                no_reward_reason,
+            };
+            (*node_name, reward)
+        })
+        .collect();
+
+    // Rewards are either the payments with newly minted tokens on top,
+    // or what is left of them once some are burned: one of these is zero.
+    RewardRound {
+        section_key,
+        payments,
+        minted: rewards.checked_sub(payments).unwrap_or_else(Token::zero),
+        burned: payments.checked_sub(rewards).unwrap_or_else(Token::zero),
+        rewards,
+        age_buckets,
+        nodes,
+    }
+}
+
 fn get_buckets(
     nodes: BTreeMap<XorName, (NodeAge, PublicKey)>,
 ) -> BTreeMap<NodeAge, BTreeMap<XorName, PublicKey>> {
@@ -192,6 +285,53 @@ mod test {
         println!("Total rewards: {:?}", total);
     }
 
+    #[test]
+    fn records_reward_round() {
+        let young = XorName::random();
+        let old = XorName::random();
+        let wallet = get_random_pk();
+        let mut nodes = BTreeMap::<XorName, (NodeAge, PublicKey)>::new();
+        let _ = nodes.insert(young, (MIN_REWARD_AGE - 1, wallet));
+        let _ = nodes.insert(old, (MIN_REWARD_AGE, wallet));
+
+        let payments = Token::from_nano(1_000);
+        let rewards = get_reward_and_mint_amount(payments, Token::zero(), Token::from_nano(10_000));
+        let distribution = distribute_rewards(rewards, nodes.clone());
+        let round = reward_round(get_random_pk(), payments, rewards, &nodes, &distribution);
+
+        assert_eq!(round.minted, payments);
+        assert_eq!(round.burned, Token::zero());
+        assert_eq!(round.age_buckets.get(&MIN_REWARD_AGE), Some(&1));
+        assert_eq!(round.nodes[&old].amount, rewards);
+        assert_eq!(round.nodes[&old].no_reward_reason, None);
+        assert_eq!(round.nodes[&young].amount, Token::zero());
+        assert_eq!(
+            round.nodes[&young].no_reward_reason,
+            Some(NoRewardReason::AgeBelowMinimum(MIN_REWARD_AGE))
+        );
+        assert_eq!(round.entries_for(wallet).len(), 2);
+    }
+
+    #[test]
+    fn records_burned_payments() {
+        let mut nodes = BTreeMap::<XorName, (NodeAge, PublicKey)>::new();
+        let _ = nodes.insert(XorName::random(), (MIN_REWARD_AGE, get_random_pk()));
+
+        // The section manages more than its proportional supply, so payments are burned.
+        let payments = Token::from_nano(1_000);
+        let rewards = get_reward_and_mint_amount(
+            payments,
+            Token::from_nano(10_600),
+            Token::from_nano(10_000),
+        );
+        let distribution = distribute_rewards(rewards, nodes.clone());
+        let round = reward_round(get_random_pk(), payments, rewards, &nodes, &distribution);
+
+        assert_eq!(round.minted, Token::zero());
+        assert_eq!(round.burned, Token::from_nano(600));
+        assert_eq!(round.rewards, rewards);
+    }
+
     fn get_random_pk() -> PublicKey {
         PublicKey::from(bls::SecretKey::random().public_key())
     }
@@ -0,0 +1,202 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::reward_calc::{distribute_by_weight, distribute_rewards, get_reward_and_mint_amount};
+use crate::messaging::RewardPolicyKind;
+use crate::routing::XorName;
+use crate::types::{NodeAge, PublicKey, Token};
+use std::collections::BTreeMap;
+
+/// How a section pays out rewards and distributes them among its nodes.
+///
+/// Every Elder proposes the rewards on its own and the proposals must match for the credits to
+/// be signed, so a policy may only use what the section has agreed on, i.e. its nodes and their
+/// ages, and not what each Elder observed of them.
+pub trait RewardPolicy: Send + Sync {
+    /// The kind of policy, as chosen at genesis.
+    fn kind(&self) -> RewardPolicyKind;
+
+    /// The amount to pay out in rewards, i.e. the payments received
+    /// plus what is minted, or less what is burned.
+    fn reward_amount(&self, payments: Token, section_managed: Token, max_supply: Token) -> Token {
+        get_reward_and_mint_amount(payments, section_managed, max_supply)
+    }
+
+    /// The reward of each node, out of the total amount.
+    fn distribute(
+        &self,
+        amount: Token,
+        nodes: &BTreeMap<XorName, (NodeAge, PublicKey)>,
+    ) -> BTreeMap<XorName, (NodeAge, PublicKey, Token)>;
+}
+
+/// Returns the policy of the given kind.
+pub fn reward_policy(kind: RewardPolicyKind) -> Box<dyn RewardPolicy> {
+    match kind {
+        RewardPolicyKind::NodeAge => Box::new(NodeAgePolicy),
+        RewardPolicyKind::Equal => Box::new(EqualPolicy),
+    }
+}
+
+/// Rewards nodes by age, each step up in age doubling the share.
+pub struct NodeAgePolicy;
+
+impl RewardPolicy for NodeAgePolicy {
+    fn kind(&self) -> RewardPolicyKind {
+        RewardPolicyKind::NodeAge
+    }
+
+    fn distribute(
+        &self,
+        amount: Token,
+        nodes: &BTreeMap<XorName, (NodeAge, PublicKey)>,
+    ) -> BTreeMap<XorName, (NodeAge, PublicKey, Token)> {
+        distribute_rewards(amount, nodes.clone())
+    }
+}
+
+/// Rewards nodes the same share, whatever their age.
+pub struct EqualPolicy;
+
+impl RewardPolicy for EqualPolicy {
+    fn kind(&self) -> RewardPolicyKind {
+        RewardPolicyKind::Equal
+    }
+
+    fn distribute(
+        &self,
+        amount: Token,
+        nodes: &BTreeMap<XorName, (NodeAge, PublicKey)>,
+    ) -> BTreeMap<XorName, (NodeAge, PublicKey, Token)> {
+        distribute_by_weight(amount, nodes, |_, _| 1)
+    }
+}
+
+/// Simulation of the policies over synthetic sections, for comparing them.
+#[cfg(test)]
+mod simulation {
+    use super::super::reward_calc::MIN_REWARD_AGE;
+    use super::*;
+    use itertools::Itertools;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const SECTION_COUNT: usize = 20;
+    const SECTION_SIZE: usize = 30;
+
+    // Ages vary from below the minimum reward age up to old nodes.
+    fn synthetic_section(rng: &mut StdRng) -> BTreeMap<XorName, (NodeAge, PublicKey)> {
+        (0..SECTION_SIZE)
+            .map(|_| {
+                let name = XorName(rng.gen());
+                let age = rng.gen_range(4, 16);
+                let wallet = PublicKey::from(bls::SecretKey::random().public_key());
+                (name, (age, wallet))
+            })
+            .collect()
+    }
+
+    struct Outcome {
+        paid_out: u64,
+        rewarded_nodes: usize,
+        eligible_nodes: usize,
+        // share of the rewards paid to the best paid tenth of the nodes
+        top_decile_share: f64,
+    }
+
+    fn simulate(
+        policy: &dyn RewardPolicy,
+        sections: &[BTreeMap<XorName, (NodeAge, PublicKey)>],
+        amount: Token,
+    ) -> Outcome {
+        let mut rewards = vec![];
+        for nodes in sections {
+            let distribution = policy.distribute(amount, nodes);
+            rewards.extend(
+                nodes
+                    .keys()
+                    .map(|name| distribution.get(name).map_or(0, |(_, _, r)| r.as_nano())),
+            );
+        }
+        let paid_out = rewards.iter().sum::<u64>();
+        let top_decile = rewards
+            .iter()
+            .sorted()
+            .rev()
+            .take(rewards.len() / 10)
+            .sum::<u64>();
+        Outcome {
+            paid_out,
+            rewarded_nodes: rewards.iter().filter(|reward| **reward > 0).count(),
+            eligible_nodes: sections
+                .iter()
+                .flat_map(|nodes| nodes.values())
+                .filter(|(age, _)| *age >= MIN_REWARD_AGE)
+                .count(),
+            top_decile_share: top_decile as f64 / paid_out as f64,
+        }
+    }
+
+    #[test]
+    fn compares_policies_over_synthetic_sections() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let sections = (0..SECTION_COUNT)
+            .map(|_| synthetic_section(&mut rng))
+            .collect_vec();
+        let amount = Token::from_nano(1_000_000_000_000);
+
+        let mut outcomes = BTreeMap::new();
+        for kind in &[RewardPolicyKind::NodeAge, RewardPolicyKind::Equal] {
+            let policy = reward_policy(*kind);
+            assert_eq!(policy.kind(), *kind);
+
+            let outcome = simulate(policy.as_ref(), &sections, amount);
+
+            // every policy pays out exactly what each section had to reward, only to nodes old
+            // enough
+            assert_eq!(outcome.paid_out, amount.as_nano() * SECTION_COUNT as u64);
+            assert!(outcome.rewarded_nodes <= outcome.eligible_nodes);
+            let _ = outcomes.insert(*kind, outcome);
+        }
+
+        // rewarding by age pays the oldest nodes more than an equal split does
+        let by_age = &outcomes[&RewardPolicyKind::NodeAge];
+        let equal = &outcomes[&RewardPolicyKind::Equal];
+        assert!(by_age.top_decile_share > equal.top_decile_share);
+        // while an equal split leaves no node old enough out
+        assert_eq!(equal.rewarded_nodes, equal.eligible_nodes);
+    }
+
+    #[test]
+    fn equal_policy_rewards_eligible_nodes_alike() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let nodes = synthetic_section(&mut rng);
+        let amount = Token::from_nano(1_000_000_000_000);
+
+        let distribution = EqualPolicy.distribute(amount, &nodes);
+        let eligible = nodes
+            .values()
+            .filter(|(age, _)| *age >= MIN_REWARD_AGE)
+            .count() as u64;
+        assert_eq!(distribution.len() as u64, eligible);
+
+        // every node gets the same share, but for the rounding remainder going to one of them
+        let share = amount.as_nano() / eligible;
+        let remainder = amount.as_nano() % eligible;
+        for (age, _, reward) in distribution.values() {
+            assert!(*age >= MIN_REWARD_AGE);
+            assert!(reward.as_nano() == share || reward.as_nano() == share + remainder);
+        }
+        assert_eq!(
+            distribution
+                .values()
+                .map(|(_, _, reward)| reward.as_nano())
+                .sum::<u64>(),
+            amount.as_nano()
+        );
+    }
+}
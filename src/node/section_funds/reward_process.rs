@@ -8,7 +8,8 @@
 
 use super::{
     elder_signing::ElderSigning,
-    reward_calc::{distribute_rewards, get_reward_and_mint_amount},
+    reward_calc::reward_round,
+    reward_policy::RewardPolicy,
     reward_stage::{
         CreditAccumulation, CreditProposal, RewardAccumulationDetails, RewardProposalDetails,
         RewardStage,
@@ -24,7 +25,8 @@ use crate::node::{
     Error, Result,
 };
 use crate::types::{
-    Credit, NodeAge, PublicKey, RewardAccumulation, RewardProposal, Signature, Signing, Token,
+    Credit, NodeAge, PublicKey, RewardAccumulation, RewardProposal, RewardRound, Signature,
+    Signing, Token,
 };
 use log::{debug, info};
 use std::collections::BTreeMap;
@@ -73,29 +75,46 @@ impl RewardProcess {
     }
 
     /// Calculates reward for each node
-    /// proportional to the age of it,
+    /// according to the network's reward policy,
     /// out of the total payments received.
     /// Additionally adds newly minted tokens, unless max supply has been reached.
+    /// Returns the record of the round along with the proposal.
     pub async fn reward_and_mint(
         &mut self,
         payments: Token,
         section_managed: Token,
         our_nodes: BTreeMap<XorName, (NodeAge, PublicKey)>,
-    ) -> Result<NodeDuty> {
+        policy: &dyn RewardPolicy,
+    ) -> Result<(NodeDuty, RewardRound)> {
         // Max supply is the proportional supply for a section in a network of a certain size.
         // The network size is derived from the prefix len.
         let max_supply =
             Token::from_nano(MAX_SUPPLY / 2_u64.pow(self.section.our_prefix.bit_count() as u32));
         // derive an amount to pay out in rewards, i.e. payments + newly minted tokens
-        let rewards = get_reward_and_mint_amount(payments, section_managed, max_supply);
+        let rewards = policy.reward_amount(payments, section_managed, max_supply);
+        // create reward distribution
+        let distribution = policy.distribute(rewards, &our_nodes);
+        let round = reward_round(
+            self.section.our_key,
+            payments,
+            rewards,
+            &our_nodes,
+            &distribution,
+        );
+        if round.burned > Token::zero() {
+            info!(
+                "Burning {} of the {} paid, as the section exceeds its proportional supply",
+                round.burned, payments
+            );
+        }
         // generate proposal
-        let reward_credits = self.get_reward_credits(rewards, self.section.our_key, our_nodes);
+        let reward_credits = self.get_reward_credits(self.section.our_key, distribution);
         let proposal_details = self.sign_proposed_rewards(reward_credits).await?;
         let proposal = proposal_details
             .get_proposal(self.section.wallet_key(), self.signing.our_index().await?);
 
         self.stage = RewardStage::ProposingCredits(proposal_details.clone());
-        Ok(send_prop_msg(proposal, self.section.address()))
+        Ok((send_prop_msg(proposal, self.section.address()), round))
     }
 
     async fn sign_proposed_rewards(
@@ -142,12 +161,10 @@ impl RewardProcess {
 
     fn get_reward_credits(
         &self,
-        rewards: Token,
         section_key: PublicKey,
-        nodes: BTreeMap<XorName, (NodeAge, PublicKey)>,
+        distribution: BTreeMap<XorName, (NodeAge, PublicKey, Token)>,
     ) -> Vec<CreditProposal> {
-        // create reward distribution
-        distribute_rewards(rewards, nodes)
+        distribution
             .into_iter()
             .map(|(node, (age, wallet, amount))| {
                 let id = *MessageId::combine(&[node.0, XorName::from(section_key).0]).as_ref();
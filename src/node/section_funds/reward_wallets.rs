@@ -6,29 +6,56 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::node::{utils, Result};
 use crate::routing::Prefix;
-use crate::types::{NodeAge, PublicKey};
+use crate::types::{NodeAge, PublicKey, RewardRound, RewardStatement, SectionTotals, Token};
 use dashmap::DashMap;
 use log::debug;
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
 use xor_name::XorName;
 
+// Filename for storing the rounds of rewards paid out by our section
+const REWARD_ROUNDS_FILENAME: &str = "reward_rounds";
+
 /// The accumulation and paying
 /// out of rewards to nodes for
 /// their work in the network.
 #[derive(Clone)]
 pub struct RewardWallets {
     node_rewards: DashMap<XorName, (NodeAge, PublicKey)>,
+    /// The rounds of rewards paid out, in order.
+    rounds: Vec<RewardRound>,
+    /// Where the rounds are persisted.
+    rounds_path: PathBuf,
+    /// Tokens minted and burned by our section, and by those it split from.
+    supply: SectionTotals,
 }
 
 // Node age
 type Age = u8;
 
 impl RewardWallets {
-    pub fn new(node_rewards: BTreeMap<XorName, (NodeAge, PublicKey)>) -> Self {
-        Self {
+    /// Creates the wallets, with the rounds persisted under `root_dir`, if any.
+    pub fn new(
+        node_rewards: BTreeMap<XorName, (NodeAge, PublicKey)>,
+        root_dir: &Path,
+    ) -> Result<Self> {
+        let rounds_path = root_dir.join(REWARD_ROUNDS_FILENAME);
+        let rounds = if rounds_path.is_file() {
+            utils::deserialise(&fs::read(&rounds_path)?)?
+        } else {
+            vec![]
+        };
+        Ok(Self {
             node_rewards: node_rewards.into_iter().collect(),
-        }
+            rounds,
+            rounds_path,
+            supply: SectionTotals::default(),
+        })
     }
 
     /// Returns the stage of a specific node.
@@ -55,7 +82,11 @@ impl RewardWallets {
     /// Removes a subset of the nodes,
     /// more specifically those no longer
     /// part of this section, after a split.
-    pub fn keep_wallets_of(&self, prefix: Prefix) {
+    pub fn keep_wallets_of(&mut self, prefix: Prefix) -> Result<()> {
+        self.supply = self.supply_after_split(prefix);
+        let rounds = self.rounds_of(prefix);
+        self.set_rounds(rounds)?;
+
         // Removes keys that are no longer our section responsibility.
         let keys = self
             .node_rewards
@@ -70,6 +101,99 @@ impl RewardWallets {
                 }
             }
         }
+
+        Ok(())
+    }
+
+    /// Records a round of rewards, for operators to see what their nodes were paid, and why.
+    pub fn record_round(&mut self, round: RewardRound) -> Result<()> {
+        self.rounds.push(round);
+        self.store_rounds()
+    }
+
+    /// The recorded rounds, with the rewards of the nodes matching `prefix` only,
+    /// i.e. those to hand over to the Elders of a section with that prefix.
+    pub fn rounds_of(&self, prefix: Prefix) -> Vec<RewardRound> {
+        self.rounds
+            .iter()
+            .cloned()
+            .map(|mut round| {
+                round.nodes.retain(|node_name, _| prefix.matches(node_name));
+                round
+            })
+            .collect()
+    }
+
+    /// Takes over the rounds of the Elders handing over the section to us.
+    pub fn set_rounds(&mut self, rounds: Vec<RewardRound>) -> Result<()> {
+        self.rounds = rounds;
+        self.store_rounds()
+    }
+
+    fn store_rounds(&self) -> Result<()> {
+        fs::write(&self.rounds_path, utils::serialise(&self.rounds)?)?;
+        Ok(())
+    }
+
+    /// Adds a completed payout of rewards to the supply totals.
+    ///
+    /// The payments it was made from were received before the section split, by the section
+    /// both halves pay out rewards for, so they are counted as burned by its heir only.
+    pub fn record_payout(&mut self, paid: Token, our_prefix: Prefix) {
+        self.record_mint(paid);
+        if SectionTotals::is_heir(our_prefix) {
+            let payments = self
+                .rounds
+                .last()
+                .map(|round| round.payments)
+                .unwrap_or_else(Token::zero);
+            self.supply.own.burned = Token::from_nano(
+                self.supply
+                    .own
+                    .burned
+                    .as_nano()
+                    .saturating_add(payments.as_nano()),
+            );
+        }
+    }
+
+    /// Adds tokens created outside of reward payouts, i.e. the funding of a faucet at genesis,
+    /// or the simulated payouts of test networks.
+    pub fn record_mint(&mut self, amount: Token) {
+        self.supply.own.minted = Token::from_nano(
+            self.supply
+                .own
+                .minted
+                .as_nano()
+                .saturating_add(amount.as_nano()),
+        );
+    }
+
+    /// Tokens minted and burned by our section, and by those it split from.
+    pub fn supply(&self) -> SectionTotals {
+        self.supply
+    }
+
+    /// Takes over the totals of the Elders handing over the section to us.
+    pub fn set_supply(&mut self, supply: SectionTotals) {
+        self.supply = supply;
+    }
+
+    /// The totals which go to the half of our section with the given prefix as it splits.
+    pub fn supply_after_split(&self, prefix: Prefix) -> SectionTotals {
+        self.supply.after_split(prefix)
+    }
+
+    /// The rewards paid to a wallet, over the recorded rounds.
+    pub fn statement(&self, wallet: PublicKey) -> RewardStatement {
+        RewardStatement {
+            wallet,
+            entries: self
+                .rounds
+                .iter()
+                .flat_map(|round| round.entries_for(wallet))
+                .collect(),
+        }
     }
 
     /// A new node registers a wallet id for future reward payout.
@@ -84,3 +208,75 @@ impl RewardWallets {
         let _ = self.node_rewards.remove(&node_name);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::RewardWallets;
+    use crate::node::Result;
+    use crate::types::{NodeReward, PublicKey, RewardRound, Token};
+    use std::collections::BTreeMap;
+    use tempdir::TempDir;
+    use xor_name::{Prefix, XorName};
+
+    #[test]
+    fn rounds_are_persisted_and_handed_over_per_prefix() -> Result<()> {
+        let root = TempDir::new("rewards")?;
+        let prefix0 = Prefix::default().pushed(false);
+        let prefix1 = Prefix::default().pushed(true);
+        let node0 = prefix0.substituted_in(XorName::random());
+        let node1 = prefix1.substituted_in(XorName::random());
+        let round = gen_round(&[node0, node1]);
+
+        let mut wallets = RewardWallets::new(BTreeMap::new(), root.path())?;
+        wallets.record_round(round.clone())?;
+
+        // A restarted node finds its rounds again.
+        let restarted = RewardWallets::new(BTreeMap::new(), root.path())?;
+        assert_eq!(restarted.rounds_of(Prefix::default()), vec![round]);
+
+        // Each half of a split gets the rewards of its own nodes only.
+        let handed_over = wallets.rounds_of(prefix1);
+        assert_eq!(handed_over.len(), 1);
+        assert_eq!(
+            handed_over[0].nodes.keys().copied().collect::<Vec<_>>(),
+            vec![node1]
+        );
+
+        wallets.keep_wallets_of(prefix0)?;
+        let restarted = RewardWallets::new(BTreeMap::new(), root.path())?;
+        assert_eq!(
+            restarted.rounds_of(Prefix::default())[0]
+                .nodes
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![node0]
+        );
+
+        Ok(())
+    }
+
+    fn gen_round(node_names: &[XorName]) -> RewardRound {
+        let wallet = PublicKey::from(bls::SecretKey::random().public_key());
+        RewardRound {
+            section_key: PublicKey::from(bls::SecretKey::random().public_key()),
+            payments: Token::from_nano(100),
+            minted: Token::from_nano(100),
+            burned: Token::zero(),
+            rewards: Token::from_nano(200),
+            age_buckets: BTreeMap::new(),
+            nodes: node_names
+                .iter()
+                .map(|name| {
+                    let reward = NodeReward {
+                        age: 7,
+                        wallet,
+                        amount: Token::from_nano(100),
+                        no_reward_reason: None,
+                    };
+                    (*name, reward)
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::messaging::FaucetParams;
+use crate::node::{network::Network, utils, Error, Result};
+use crate::types::{
+    Credit, CreditAgreementProof, PublicKey, ReplicaEvent, Signature, SignedCredit,
+};
+use bls::PublicKeySet;
+use itertools::Itertools;
+use serde::Serialize;
+use std::iter;
+use xor_name::XorName;
+
+// Length of the window over which the rate of payouts is limited, in seconds.
+const RATE_WINDOW_SECS: u64 = 60;
+
+/// Holds the faucet wallet to the limits of the faucet, at the Replicas of the wallet.
+///
+/// Payouts are requested by their recipients, and made once enough Replicas signed them. Each
+/// Replica checks the cap per key and the rate of payouts against the payouts it signed or made,
+/// by the times of its own clock it recorded them at.
+#[derive(Clone, Debug)]
+pub struct Faucet {
+    params: FaucetParams,
+    wallet: PublicKey,
+}
+
+impl Faucet {
+    pub fn new(params: FaucetParams, genesis_key: bls::PublicKey) -> Self {
+        Self {
+            params,
+            wallet: FaucetParams::wallet(genesis_key),
+        }
+    }
+
+    pub fn wallet(&self) -> PublicKey {
+        self.wallet
+    }
+
+    /// Checks that a payout is requested by its recipient, i.e. signed by it.
+    pub fn check_request(&self, payout: &SignedCredit) -> Result<()> {
+        let recipient = payout.recipient();
+        if recipient == self.wallet || payout.amount().as_nano() == 0 {
+            return Err(Error::InvalidOperation(
+                "The faucet only pays out a non-zero amount to another key".to_string(),
+            ));
+        }
+        recipient
+            .verify(&payout.actor_signature, utils::serialise(&payout.credit)?)
+            .map_err(|_| Error::Transfer(crate::transfers::Error::InvalidSignature))
+    }
+
+    /// Checks that a payout doesn't take its recipient over the cap per key, given the events of
+    /// the faucet wallet so far.
+    pub fn check_cap(&self, payout: &SignedCredit, history: &[ReplicaEvent]) -> Result<()> {
+        let recipient = payout.recipient();
+        let paid = payouts(history)
+            .filter(|(credit, _)| credit.recipient() == recipient)
+            .map(|(credit, _)| credit.amount().as_nano())
+            .fold(0, u64::saturating_add);
+
+        let cap = self.params.cap_per_key;
+        if paid.saturating_add(payout.amount().as_nano()) > cap.as_nano() {
+            return Err(Error::FaucetCapExceeded { recipient, cap });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that fewer than the payouts per minute were made over the minute before `now`, the
+    /// time of this Replica in seconds since the Unix epoch, given the events of the faucet wallet
+    /// so far.
+    pub fn check_rate(&self, history: &[ReplicaEvent], now: u64) -> Result<()> {
+        let recent_payouts = payouts(history)
+            .filter(|(_, time)| time.saturating_add(RATE_WINDOW_SECS) > now)
+            .count();
+
+        if recent_payouts >= self.params.payouts_per_minute {
+            return Err(Error::FaucetRateLimited(self.params.payouts_per_minute));
+        }
+
+        Ok(())
+    }
+}
+
+// The payouts signed or made by the Replica, each with the time it was first recorded at.
+fn payouts(history: &[ReplicaEvent]) -> impl Iterator<Item = (&Credit, u64)> {
+    history
+        .iter()
+        .filter_map(|event| match event {
+            ReplicaEvent::FaucetPayoutProposed(event) => Some((&event.payout.credit, event.time)),
+            ReplicaEvent::FaucetPaidOut(event) => {
+                Some((&event.credit_proof.signed_credit.credit, event.time))
+            }
+            _ => None,
+        })
+        .unique_by(|(credit, _)| credit.id)
+}
+
+/// The credit funding the faucet wallet at genesis.
+///
+/// It is signed with the genesis section key, which the genesis node holds on its own.
+pub async fn genesis_credit(
+    params: &FaucetParams,
+    network: &Network,
+) -> Result<CreditAgreementProof> {
+    let public_key_set = network.our_public_key_set().await?;
+    let genesis_key = public_key_set.public_key();
+    let wallet = FaucetParams::wallet(genesis_key);
+
+    let credit = Credit {
+        id: XorName::from(wallet).0,
+        amount: params.supply,
+        recipient: wallet,
+        msg: "Faucet funding at genesis".to_string(),
+    };
+    let actor_signature = sign_as_section(network, &public_key_set, &credit).await?;
+    let signed_credit = SignedCredit {
+        credit,
+        actor_signature,
+    };
+    let debiting_replicas_sig = sign_as_section(network, &public_key_set, &signed_credit).await?;
+
+    Ok(CreditAgreementProof {
+        signed_credit,
+        debiting_replicas_sig,
+        debiting_replicas_keys: public_key_set,
+    })
+}
+
+// Signs with the section key, which takes our share alone only while we are the only Elder.
+async fn sign_as_section<T: Serialize>(
+    network: &Network,
+    public_key_set: &PublicKeySet,
+    data: &T,
+) -> Result<Signature> {
+    let share = network.sign_as_elder(data).await?;
+    let signature = public_key_set
+        .combine_signatures(iter::once((share.index, &share.share)))
+        .map_err(|_| Error::CouldNotCombineSignatures)?;
+    Ok(Signature::Bls(signature))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{FaucetPaidOut, FaucetPayoutProposed, Keypair, Token};
+
+    fn faucet() -> Faucet {
+        let genesis_key = bls::SecretKey::random().public_key();
+        Faucet::new(
+            FaucetParams {
+                cap_per_key: Token::from_nano(100),
+                payouts_per_minute: 2,
+                ..FaucetParams::new(Token::from_nano(1_000))
+            },
+            genesis_key,
+        )
+    }
+
+    fn payout(recipient: &Keypair, amount: u64) -> Result<SignedCredit> {
+        let credit = Credit {
+            id: rand::random(),
+            amount: Token::from_nano(amount),
+            recipient: recipient.public_key(),
+            msg: "Faucet payout".to_string(),
+        };
+        Ok(SignedCredit {
+            actor_signature: recipient.sign(&utils::serialise(&credit)?),
+            credit,
+        })
+    }
+
+    fn proposed(payout: SignedCredit, time: u64) -> ReplicaEvent {
+        ReplicaEvent::FaucetPayoutProposed(FaucetPayoutProposed { payout, time })
+    }
+
+    fn paid_out(payout: SignedCredit, time: u64) -> Result<ReplicaEvent> {
+        let sk_set = bls::SecretKeySet::random(0, &mut rand::thread_rng());
+        let share = sk_set.secret_key_share(0).sign(&utils::serialise(&payout)?);
+        let sig = sk_set
+            .public_keys()
+            .combine_signatures(iter::once((0, &share)))
+            .map_err(|_| Error::CouldNotCombineSignatures)?;
+        Ok(ReplicaEvent::FaucetPaidOut(FaucetPaidOut {
+            credit_proof: CreditAgreementProof {
+                signed_credit: payout,
+                debiting_replicas_sig: Signature::Bls(sig),
+                debiting_replicas_keys: sk_set.public_keys(),
+            },
+            time,
+        }))
+    }
+
+    #[test]
+    fn accepts_only_payouts_requested_by_their_recipient() -> Result<()> {
+        let faucet = faucet();
+        let recipient = Keypair::new_ed25519(&mut rand::thread_rng());
+        let other = Keypair::new_ed25519(&mut rand::thread_rng());
+
+        faucet.check_request(&payout(&recipient, 1)?)?;
+
+        let mut forged = payout(&recipient, 1)?;
+        forged.actor_signature = payout(&other, 1)?.actor_signature;
+        assert!(faucet.check_request(&forged).is_err());
+        assert!(faucet.check_request(&payout(&recipient, 0)?).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn caps_payouts_per_key() -> Result<()> {
+        let faucet = faucet();
+        let recipient = Keypair::new_ed25519(&mut rand::thread_rng());
+        let other = Keypair::new_ed25519(&mut rand::thread_rng());
+        let made = payout(&recipient, 30)?;
+        let history = vec![
+            // signed, then made: counted once
+            proposed(made.clone(), 1_000),
+            paid_out(made, 1_000)?,
+            // signed, but not made (yet)
+            proposed(payout(&recipient, 30)?, 1_000),
+        ];
+
+        faucet.check_cap(&payout(&recipient, 40)?, &history)?;
+        faucet.check_cap(&payout(&other, 100)?, &history)?;
+        assert!(matches!(
+            faucet.check_cap(&payout(&recipient, 41)?, &history),
+            Err(Error::FaucetCapExceeded { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn limits_payout_rate_by_the_time_of_the_replica() -> Result<()> {
+        let faucet = faucet();
+        let recipient = Keypair::new_ed25519(&mut rand::thread_rng());
+        let history = vec![
+            paid_out(payout(&recipient, 1)?, 1_000)?,
+            proposed(payout(&recipient, 1)?, 1_030),
+        ];
+
+        assert!(matches!(
+            faucet.check_rate(&history, 1_059),
+            Err(Error::FaucetRateLimited(2))
+        ));
+        // the first payout is a minute old
+        faucet.check_rate(&history, 1_060)?;
+
+        Ok(())
+    }
+}
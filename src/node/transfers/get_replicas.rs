@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{replica_signing::ReplicaSigningImpl, replicas::Replicas, ReplicaInfo};
+use super::{faucet::Faucet, replica_signing::ReplicaSigningImpl, replicas::Replicas, ReplicaInfo};
 use crate::node::{network::Network, node_api::NodeInfo, Error, Result};
 use crate::types::{ActorHistory, PublicKey};
 use std::collections::BTreeMap;
@@ -18,7 +18,15 @@ pub async fn transfer_replicas(
 ) -> Result<Replicas<ReplicaSigningImpl>> {
     let root_dir = node_info.root_dir.clone();
     let info = replica_info(network).await?;
-    Replicas::new(root_dir, info, user_wallets).await
+    let genesis_key = network.genesis_key().await;
+    let faucet = network
+        .network_params()
+        .await
+        .faucet
+        .map(|params| Faucet::new(params, genesis_key));
+    Ok(Replicas::new(root_dir, info, user_wallets)
+        .await?
+        .with_faucet(faucet))
 }
 
 pub async fn replica_info(network: &Network) -> Result<ReplicaInfo<ReplicaSigningImpl>> {
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+pub mod faucet;
 pub mod get_replicas;
 pub mod replica_signing;
 pub mod replicas;
@@ -33,16 +34,18 @@ use crate::node::{
 #[cfg(feature = "simulated-payouts")]
 use crate::types::Transfer;
 use crate::types::{
-    ActorHistory, CreditAgreementProof, DebitId, PublicKey, SignedTransfer, Token,
+    ActorHistory, CreditAgreementProof, DebitId, EscrowSettlement, EscrowSettlementShare,
+    FaucetPayoutShare, PublicKey, Signature, SignedCredit, SignedTransfer, Token,
     TransferAgreementProof,
 };
 use futures::lock::Mutex;
 use log::{debug, error, info, trace, warn};
 use replica_signing::ReplicaSigningImpl;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
-use xor_name::Prefix;
+use std::time::{SystemTime, UNIX_EPOCH};
+use xor_name::{Prefix, XorName};
 
 /*
 Transfers is the layer that manages
@@ -56,6 +59,11 @@ Client transfers
 3. Client-to-Elders: Cmd::RegisterTransfer
 4. Elders-to-Elders: NodeCmd::PropagateTransfer
 
+Faucet payouts (on test networks)
+1. Client-to-Elders: Cmd::RequestFaucetPayout
+2. Elders-to-Elders: NodeCmd::ProposeFaucetPayout
+3. Elders-to-Elders: NodeCmd::PropagateTransfer
+
 Section transfers (such as reward payout)
 1. Elders-to-Elders: NodeCmd::ValidateSectionPayout
 2. Elders-to-Elders: NodeEvent::RewardPayoutValidated
@@ -78,6 +86,10 @@ pub struct Transfers {
     store_cost: StoreCost,
     // TODO: limit this? where do we store it
     recently_validated_transfers: Arc<Mutex<HashSet<DebitId>>>,
+    // The shares of the Elders' signatures over the settlements of escrows.
+    escrow_settlements: Arc<Mutex<HashMap<EscrowSettlement, BTreeMap<usize, bls::SignatureShare>>>>,
+    // The shares of the Elders' signatures over the payouts from the faucet.
+    faucet_payouts: Arc<Mutex<HashMap<SignedCredit, BTreeMap<usize, bls::SignatureShare>>>>,
 }
 
 impl Transfers {
@@ -86,6 +98,8 @@ impl Transfers {
             replicas,
             store_cost,
             recently_validated_transfers: Arc::default(),
+            escrow_settlements: Arc::default(),
+            faucet_payouts: Arc::default(),
         }
     }
 
@@ -177,7 +191,9 @@ impl Transfers {
         let recipient_is_not_section = payment.recipient() != self.section_wallet_id();
 
         use TransferError::*;
-        if recipient_is_not_section {
+        // A batched transfer can't pay for data, as its credits would leave the section,
+        // nor can an escrowed one, as its credit is not ours until released.
+        if recipient_is_not_section || payment.is_batch() || payment.is_escrowed() {
             warn!("Payment: recipient is not section");
             let origin = SrcLocation::EndUser(origin);
 
@@ -338,6 +354,30 @@ impl Transfers {
         }))
     }
 
+    pub async fn section_chain(
+        &self,
+        key: &bls::PublicKey,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    ) -> Result<NodeDuty> {
+        trace!("Handling GetSectionChain");
+        let result = self
+            .replicas
+            .section_chain(key)
+            .map_err(|_e| ErrorMessage::NoSuchKey);
+
+        Ok(NodeDuty::Send(OutgoingMsg {
+            msg: MsgType::Client(ClientMsg::Process(ProcessMsg::QueryResponse {
+                id: MessageId::in_response_to(&msg_id),
+                response: QueryResponse::GetSectionChain(result),
+                correlation_id: msg_id,
+            })),
+            section_source: false, // strictly this is not correct, but we don't expect responses to a response..
+            dst: origin.to_dst(),
+            aggregation: Aggregation::None,
+        }))
+    }
+
     pub async fn history(
         &self,
         key: &PublicKey,
@@ -400,32 +440,30 @@ impl Transfers {
 
     /// Registration of a transfer is requested,
     /// with a proof of enough Elders having validated it.
+    /// Each credit of a batched transfer is propagated to its own recipient.
+    /// The credit of an escrowed transfer is held until released or refunded.
     pub async fn register(
         &self,
         proof: &TransferAgreementProof,
         msg_id: MessageId,
         origin: SrcLocation,
-    ) -> Result<NodeDuty> {
-        use NodeCmd::*;
-        use NodeTransferCmd::*;
+    ) -> Result<NodeDuties> {
         match self.replicas.register(proof).await {
-            Ok(event) => {
-                let location = event.transfer_proof.recipient().into();
-                Ok(NodeDuty::Send(OutgoingMsg {
-                    msg: MsgType::Node(NodeMsg::NodeCmd {
-                        cmd: Transfers(PropagateTransfer(event.transfer_proof.credit_proof())),
-                        id: MessageId::in_response_to(&msg_id),
-                    }),
-                    section_source: true, // i.e. errors go to our section
-                    dst: DstLocation::Section(location),
-                    aggregation: Aggregation::AtDestination,
-                }))
+            Ok(event) if event.transfer_proof.is_escrowed() => {
+                info!("Holding transfer {:?} in escrow", event.id());
+                Ok(vec![])
             }
+            Ok(event) => Ok(event
+                .transfer_proof
+                .credit_proofs()
+                .into_iter()
+                .map(|credit_proof| Self::propagate(credit_proof, msg_id))
+                .collect()),
             Err(e) => {
                 let message_error = convert_to_error_message(e);
                 let dst = origin.to_dst();
 
-                Ok(NodeDuty::Send(OutgoingMsg {
+                Ok(vec![NodeDuty::Send(OutgoingMsg {
                     msg: MsgType::Client(ClientMsg::Process(ProcessMsg::CmdError {
                         id: MessageId::in_response_to(&msg_id),
                         error: CmdError::Transfer(TransferError::TransferRegistration(
@@ -436,9 +474,272 @@ impl Transfers {
                     section_source: false, // strictly this is not correct, but we don't expect responses to an error..
                     dst,
                     aggregation: Aggregation::AtDestination,
-                }))
+                })])
+            }
+        }
+    }
+
+    /// Release of the credit of a transfer held in escrow is requested, revealing the secret.
+    /// The credit is propagated to the recipient once enough Elders agree.
+    pub async fn release_escrow(
+        &self,
+        id: DebitId,
+        secret: Vec<u8>,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    ) -> Result<NodeDuties> {
+        let settlement = EscrowSettlement::Release { id, secret };
+        self.request_settlement(settlement, msg_id, origin).await
+    }
+
+    /// Refund of a transfer held in escrow is requested.
+    /// The refund is propagated back to the sender once enough Elders agree its deadline has passed.
+    pub async fn refund_escrow(
+        &self,
+        id: DebitId,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    ) -> Result<NodeDuties> {
+        let settlement = EscrowSettlement::Refund(id);
+        self.request_settlement(settlement, msg_id, origin).await
+    }
+
+    // We vote on the requested settlement, and send our share to the other Elders.
+    async fn request_settlement(
+        &self,
+        settlement: EscrowSettlement,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    ) -> Result<NodeDuties> {
+        match self
+            .replicas
+            .propose_settlement(&settlement, now(), false)
+            .await
+        {
+            Ok(Some(share)) => self.share_settlement(share).await,
+            Ok(None) => Ok(vec![]),
+            Err(e) => {
+                warn!("Escrow could not be settled: {:?}", e);
+                Ok(vec![NodeDuty::Send(OutgoingMsg {
+                    msg: MsgType::Client(ClientMsg::Process(ProcessMsg::CmdError {
+                        id: MessageId::in_response_to(&msg_id),
+                        error: CmdError::Transfer(TransferError::TransferRegistration(
+                            convert_to_error_message(e),
+                        )),
+                        correlation_id: msg_id,
+                    })),
+                    section_source: false, // strictly this is not correct, but we don't expect responses to an error..
+                    dst: origin.to_dst(),
+                    aggregation: Aggregation::AtDestination,
+                })])
+            }
+        }
+    }
+
+    /// Another Elder has signed the settlement of an escrow.
+    /// We second a release the secret of which we hadn't seen, as long as we are within the
+    /// grace of the deadline, and settle the escrow once enough Elders signed.
+    pub async fn receive_settlement_share(
+        &self,
+        share: EscrowSettlementShare,
+    ) -> Result<NodeDuties> {
+        let credit = self.replicas.settled_credit(&share.settlement).await?;
+        let pk_set = self.replicas.replicas_pk_set();
+        let valid = pk_set
+            .public_key_share(share.credit_sig.index)
+            .verify(&share.credit_sig.share, &utils::serialise(&credit)?);
+        if !valid {
+            return Err(Error::Transfer(crate::transfers::Error::InvalidSignature));
+        }
+        let mut duties = vec![];
+        if let EscrowSettlement::Release { .. } = &share.settlement {
+            match self
+                .replicas
+                .propose_settlement(&share.settlement, now(), true)
+                .await
+            {
+                Ok(Some(our_share)) => duties.extend(self.share_settlement(our_share).await?),
+                Ok(None) => (),
+                Err(e) => debug!("Not seconding the release of an escrow: {:?}", e),
             }
         }
+        duties.extend(self.accumulate_settlement(share, credit).await?);
+        Ok(duties)
+    }
+
+    async fn share_settlement(&self, share: EscrowSettlementShare) -> Result<NodeDuties> {
+        let credit = self.replicas.settled_credit(&share.settlement).await?;
+        let dst = DstLocation::Section(XorName::from(share.settlement.id().actor));
+        let mut duties = vec![NodeDuty::Send(OutgoingMsg {
+            msg: MsgType::Node(NodeMsg::NodeCmd {
+                cmd: NodeCmd::Transfers(NodeTransferCmd::ProposeEscrowSettlement(share.clone())),
+                id: MessageId::new(),
+            }),
+            section_source: false,
+            dst,
+            aggregation: Aggregation::None,
+        })];
+        duties.extend(self.accumulate_settlement(share, credit).await?);
+        Ok(duties)
+    }
+
+    // With enough shares, the escrow is settled and the settled credit propagated.
+    async fn accumulate_settlement(
+        &self,
+        share: EscrowSettlementShare,
+        signed_credit: SignedCredit,
+    ) -> Result<NodeDuties> {
+        let pk_set = self.replicas.replicas_pk_set();
+        let sig = {
+            let mut settlements = self.escrow_settlements.lock().await;
+            let shares = settlements.entry(share.settlement.clone()).or_default();
+            let _ = shares.insert(share.credit_sig.index, share.credit_sig.share);
+            if shares.len() <= pk_set.threshold() {
+                return Ok(vec![]);
+            }
+            let sig = pk_set
+                .combine_signatures(shares.iter().map(|(index, share)| (*index, share)))
+                .map_err(|e| Error::Logic(format!("Could not combine settlement shares: {}", e)))?;
+            let id = share.settlement.id();
+            settlements.retain(|settlement, _| settlement.id() != id);
+            sig
+        };
+        let credit_proof = CreditAgreementProof {
+            signed_credit,
+            debiting_replicas_sig: Signature::Bls(sig),
+            debiting_replicas_keys: pk_set,
+        };
+        match self.replicas.settle(&share.settlement, &credit_proof).await {
+            Ok(()) => {
+                info!("Escrow settled: {:?}", share.settlement);
+                // The same msg id at every Elder, for the propagation to aggregate.
+                let msg_id = MessageId::with(*credit_proof.id());
+                Ok(vec![Self::propagate(credit_proof, msg_id)])
+            }
+            Err(Error::Transfer(crate::transfers::Error::EscrowSettled(id))) => {
+                debug!("Escrow {:?} already settled", id);
+                Ok(vec![])
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// A payout from the faucet is requested by its recipient.
+    /// The payout is propagated to the recipient once enough Elders agree it's within the limits
+    /// of the faucet, by their own clocks.
+    pub async fn request_faucet_payout(
+        &self,
+        payout: SignedCredit,
+        msg_id: MessageId,
+        origin: SrcLocation,
+    ) -> Result<NodeDuties> {
+        match self.replicas.propose_payout(&payout, now()).await {
+            Ok(Some(share)) => self.share_payout(share).await,
+            Ok(None) => Ok(vec![]),
+            Err(e) => {
+                warn!("Faucet payout refused: {:?}", e);
+                Ok(vec![NodeDuty::Send(OutgoingMsg {
+                    msg: MsgType::Client(ClientMsg::Process(ProcessMsg::CmdError {
+                        id: MessageId::in_response_to(&msg_id),
+                        error: CmdError::Transfer(TransferError::TransferRegistration(
+                            convert_to_error_message(e),
+                        )),
+                        correlation_id: msg_id,
+                    })),
+                    section_source: false, // strictly this is not correct, but we don't expect responses to an error..
+                    dst: origin.to_dst(),
+                    aggregation: Aggregation::AtDestination,
+                })])
+            }
+        }
+    }
+
+    /// Another Elder has signed a payout from the faucet.
+    /// We second the payout, if it's within the limits of the faucet by our clock,
+    /// and make it once enough Elders signed.
+    pub async fn receive_payout_share(&self, share: FaucetPayoutShare) -> Result<NodeDuties> {
+        let pk_set = self.replicas.replicas_pk_set();
+        let valid = pk_set
+            .public_key_share(share.credit_sig.index)
+            .verify(&share.credit_sig.share, &utils::serialise(&share.payout)?);
+        if !valid {
+            return Err(Error::Transfer(crate::transfers::Error::InvalidSignature));
+        }
+        let mut duties = vec![];
+        match self.replicas.propose_payout(&share.payout, now()).await {
+            Ok(Some(our_share)) => duties.extend(self.share_payout(our_share).await?),
+            Ok(None) => (),
+            Err(e) => debug!("Not seconding the faucet payout: {:?}", e),
+        }
+        duties.extend(self.accumulate_payout(share).await?);
+        Ok(duties)
+    }
+
+    async fn share_payout(&self, share: FaucetPayoutShare) -> Result<NodeDuties> {
+        let wallet = self
+            .replicas
+            .faucet_wallet()
+            .ok_or_else(|| Error::InvalidOperation("The network has no faucet".to_string()))?;
+        let mut duties = vec![NodeDuty::Send(OutgoingMsg {
+            msg: MsgType::Node(NodeMsg::NodeCmd {
+                cmd: NodeCmd::Transfers(NodeTransferCmd::ProposeFaucetPayout(share.clone())),
+                id: MessageId::new(),
+            }),
+            section_source: false,
+            dst: DstLocation::Section(XorName::from(wallet)),
+            aggregation: Aggregation::None,
+        })];
+        duties.extend(self.accumulate_payout(share).await?);
+        Ok(duties)
+    }
+
+    // With enough shares, the payout is made and propagated.
+    async fn accumulate_payout(&self, share: FaucetPayoutShare) -> Result<NodeDuties> {
+        let pk_set = self.replicas.replicas_pk_set();
+        let sig = {
+            let mut payouts = self.faucet_payouts.lock().await;
+            let shares = payouts.entry(share.payout.clone()).or_default();
+            let _ = shares.insert(share.credit_sig.index, share.credit_sig.share);
+            if shares.len() <= pk_set.threshold() {
+                return Ok(vec![]);
+            }
+            let sig = pk_set
+                .combine_signatures(shares.iter().map(|(index, share)| (*index, share)))
+                .map_err(|e| Error::Logic(format!("Could not combine payout shares: {}", e)))?;
+            let id = *share.payout.id();
+            payouts.retain(|payout, _| payout.id() != &id);
+            sig
+        };
+        let credit_proof = CreditAgreementProof {
+            signed_credit: share.payout,
+            debiting_replicas_sig: Signature::Bls(sig),
+            debiting_replicas_keys: pk_set,
+        };
+        if self.replicas.pay_out(&credit_proof, now()).await? {
+            info!("Faucet paid out: {:?}", credit_proof);
+            // The same msg id at every Elder, for the propagation to aggregate.
+            let msg_id = MessageId::with(*credit_proof.id());
+            Ok(vec![Self::propagate(credit_proof, msg_id)])
+        } else {
+            debug!("Faucet payout {:?} already made", credit_proof.id());
+            Ok(vec![])
+        }
+    }
+
+    // Aggregated at the destination, so the credit is only received with the agreement of our section.
+    fn propagate(credit_proof: CreditAgreementProof, msg_id: MessageId) -> NodeDuty {
+        use NodeCmd::*;
+        use NodeTransferCmd::*;
+        let location = credit_proof.recipient().into();
+        NodeDuty::Send(OutgoingMsg {
+            msg: MsgType::Node(NodeMsg::NodeCmd {
+                cmd: Transfers(PropagateTransfer(credit_proof)),
+                id: MessageId::in_response_to(&msg_id),
+            }),
+            section_source: true, // i.e. errors go to our section
+            dst: DstLocation::Section(location),
+            aggregation: Aggregation::AtDestination,
+        })
     }
 
     /// The only step that is triggered by a Replica.
@@ -490,12 +791,29 @@ impl Transfers {
         }))
     }
 
+    /// Credits the faucet wallet with its funding at genesis.
+    pub async fn fund_faucet(&self, credit_proof: &CreditAgreementProof) -> Result<()> {
+        let _ = self
+            .replicas
+            .receive_propagated(XorName::from(credit_proof.recipient()), credit_proof)
+            .await?;
+        Ok(())
+    }
+
     #[cfg(feature = "simulated-payouts")]
     pub async fn credit_without_proof(&self, transfer: Transfer) -> Result<NodeDuty> {
         self.replicas.credit_without_proof(transfer).await
     }
 }
 
+// The time of this Elder, in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
 impl Display for Transfers {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(formatter, "Transfers")
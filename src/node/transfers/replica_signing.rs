@@ -68,7 +68,15 @@ impl ReplicaSigning for ReplicaSigningImpl {
         signed_transfer: &SignedTransfer,
     ) -> Result<(SignatureShare, SignatureShare)> {
         let replica_debit_sig = self.sign_validated_debit(&signed_transfer.debit).await?;
-        let replica_credit_sig = self.sign_validated_credit(&signed_transfer.credit).await?;
+        // The credit of an escrowed transfer is held, i.e. signed along with its escrow.
+        let replica_credit_sig = match &signed_transfer.escrow {
+            Some(escrow) => {
+                self.network
+                    .sign_as_elder(&escrow.holding(&signed_transfer.credit))
+                    .await?
+            }
+            None => self.sign_validated_credit(&signed_transfer.credit).await?,
+        };
         Ok((replica_debit_sig, replica_credit_sig))
     }
 
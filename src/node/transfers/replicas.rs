@@ -6,12 +6,14 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{replica_signing::ReplicaSigning, store::TransferStore};
+use super::{faucet::Faucet, replica_signing::ReplicaSigning, store::TransferStore};
 use crate::node::{Error, Result};
 use crate::transfers::WalletReplica;
 use crate::types::{
-    ActorHistory, CreditAgreementProof, OwnerType, PublicKey, ReplicaEvent, SignedTransfer, Token,
-    TransferAgreementProof, TransferPropagated, TransferRegistered, TransferValidated,
+    ActorHistory, CreditAgreementProof, EscrowSettlement, EscrowSettlementShare,
+    FaucetPayoutProposed, FaucetPayoutShare, OwnerType, PublicKey, ReplicaEvent, SignedCredit,
+    SignedTransfer, Token, TransferAgreementProof, TransferPropagated, TransferRegistered,
+    TransferValidated,
 };
 use bls::PublicKeySet;
 use dashmap::DashMap;
@@ -24,7 +26,7 @@ use xor_name::Prefix;
 #[cfg(feature = "simulated-payouts")]
 use {
     crate::node::node_ops::NodeDuty,
-    crate::types::{Signature, SignedCredit, SignedDebit, Transfer},
+    crate::types::{Signature, SignedDebit, Transfer},
     bls::{SecretKey, SecretKeySet},
     log::debug,
     rand::thread_rng,
@@ -53,6 +55,7 @@ where
     root_dir: PathBuf,
     info: ReplicaInfo<T>,
     stores: Stores,
+    faucet: Option<Faucet>,
 }
 
 impl<T: ReplicaSigning> Replicas<T> {
@@ -65,11 +68,22 @@ impl<T: ReplicaSigning> Replicas<T> {
             root_dir,
             info,
             stores: DashMap::new(),
+            faucet: None,
         };
         instance.setup(user_wallets).await?;
         Ok(instance)
     }
 
+    /// Returns these Replicas holding the faucet wallet, if any, to the limits of the faucet.
+    pub(crate) fn with_faucet(self, faucet: Option<Faucet>) -> Self {
+        Self { faucet, ..self }
+    }
+
+    /// The faucet wallet, if the network has a faucet.
+    pub fn faucet_wallet(&self) -> Option<PublicKey> {
+        self.faucet.as_ref().map(Faucet::wallet)
+    }
+
     pub async fn merge(&mut self, user_wallets: BTreeMap<PublicKey, ActorHistory>) -> Result<()> {
         self.setup(user_wallets).await // TODO: fix this!!!! (this duplciates entries in db)
     }
@@ -106,6 +120,19 @@ impl<T: ReplicaSigning> Replicas<T> {
                 // Access to the specific store is now serialised!
                 store.try_insert(e.to_owned())?;
             }
+            // Settlements after the debits, so that a settled escrow is not pending again.
+            // Payouts of the faucet after its funding.
+            let settlements = wallet
+                .escrows_released
+                .into_iter()
+                .map(EscrowReleased)
+                .chain(wallet.escrows_refunded.into_iter().map(EscrowRefunded))
+                .chain(wallet.faucet_payouts.into_iter().map(FaucetPaidOut));
+            let store_ref = self.get_load_or_create_store(node).await?;
+            let mut store = store_ref.write().await;
+            for e in settlements {
+                store.try_insert(e)?;
+            }
         }
         Ok(())
     }
@@ -166,9 +193,33 @@ impl<T: ReplicaSigning> Replicas<T> {
             return Ok(ActorHistory::empty());
         }
 
+        let escrows_released = events
+            .iter()
+            .filter_map(|e| match e {
+                ReplicaEvent::EscrowReleased(e) => Some(e.clone()),
+                _ => None,
+            })
+            .collect();
+        let escrows_refunded = events
+            .iter()
+            .filter_map(|e| match e {
+                ReplicaEvent::EscrowRefunded(e) => Some(e.clone()),
+                _ => None,
+            })
+            .collect();
+        let faucet_payouts = events
+            .iter()
+            .filter_map(|e| match e {
+                ReplicaEvent::FaucetPaidOut(e) => Some(e.clone()),
+                _ => None,
+            })
+            .collect();
         let history = ActorHistory {
             credits: Self::get_credits(&events),
             debits: Self::get_debits(events),
+            escrows_released,
+            escrows_refunded,
+            faucet_payouts,
         };
 
         Ok(history)
@@ -229,6 +280,14 @@ impl<T: ReplicaSigning> Replicas<T> {
     /// -----------------------------------------------------------------
 
     ///
+    /// The chain of section keys from genesis to the given key.
+    pub fn section_chain(&self, key: &bls::PublicKey) -> Result<SecuredLinkedList> {
+        let chain = &self.info.section_chain;
+        chain
+            .get_proof_chain(chain.root_key(), key)
+            .map_err(|_| Error::UnknownSectionKey(PublicKey::Bls(*key)))
+    }
+
     pub fn update_replica_info(&mut self, info: ReplicaInfo<T>) {
         self.info = info;
     }
@@ -264,6 +323,13 @@ impl<T: ReplicaSigning> Replicas<T> {
         debug!("Replica validating transfer: {:?}", signed_transfer);
         let key = signed_transfer.sender();
 
+        // Whoever holds the key of the faucet wallet, only its Replicas pay out from it.
+        if self.faucet_wallet() == Some(key) {
+            return Err(Error::InvalidOperation(
+                "The faucet only pays out on request of the recipients".to_string(),
+            ));
+        }
+
         let store_ref = match self.stores.get(&key) {
             Some(store) => store,
             None => return Err(Error::Transfer(crate::transfers::Error::NoSuchSender)),
@@ -273,15 +339,38 @@ impl<T: ReplicaSigning> Replicas<T> {
         // write lock is on
 
         // Access to the specific wallet is now serialised!
+
+        // A transfer sent again by its Actor, f.ex. after a failure
+        // with further debits in flight, gets the validation already made.
+        let validated = store.get_all().into_iter().find_map(|event| match event {
+            ReplicaEvent::TransferValidated(event)
+                if event.signed_debit == signed_transfer.debit
+                    && event.signed_credit == signed_transfer.credit =>
+            {
+                Some(event)
+            }
+            _ => None,
+        });
+        if let Some(event) = validated {
+            debug!("Transfer already validated");
+            return Ok(event);
+        }
+
         let wallet = self.load_wallet(&store, OwnerType::Single(key)).await?;
 
         debug!("Wallet loaded");
-        let _ = wallet.validate(&signed_transfer.debit, &signed_transfer.credit)?;
+        let _ = wallet.validate(&signed_transfer)?;
 
         debug!("wallet valid");
         // signing will be serialised
         let (replica_debit_sig, replica_credit_sig) =
             self.info.signing.sign_transfer(&signed_transfer).await?;
+        let mut batch = vec![];
+        for credit in signed_transfer.batch {
+            let sig = self.info.signing.sign_validated_credit(&credit).await?;
+            batch.push((credit, sig));
+        }
+        let escrow = signed_transfer.escrow;
 
         let event = TransferValidated {
             signed_credit: signed_transfer.credit,
@@ -289,6 +378,8 @@ impl<T: ReplicaSigning> Replicas<T> {
             replica_debit_sig,
             replica_credit_sig,
             replicas: self.info.peer_replicas.clone(),
+            batch,
+            escrow,
         };
 
         // first store to disk
@@ -342,6 +433,178 @@ impl<T: ReplicaSigning> Replicas<T> {
         }
     }
 
+    /// Proposal of the settlement of a transfer held in escrow, i.e. our vote on it.
+    /// Returns our share of the signature over the settled credit, unless we proposed it before.
+    pub async fn propose_settlement(
+        &self,
+        settlement: &EscrowSettlement,
+        now: u64,
+        seconded: bool,
+    ) -> Result<Option<EscrowSettlementShare>> {
+        let id = settlement.id();
+        let store_ref = match self.stores.get(&id.actor) {
+            None => return Err(Error::Transfer(crate::transfers::Error::NoSuchSender)),
+            Some(store) => store,
+        };
+        let mut store = store_ref.write().await;
+
+        // write lock is on
+
+        let wallet = self
+            .load_wallet(&store, OwnerType::Single(id.actor))
+            .await?;
+        match wallet.propose_settlement(settlement, now, seconded)? {
+            None => Ok(None),
+            Some(event) => {
+                let credit_sig = self
+                    .info
+                    .signing
+                    .sign_validated_credit(wallet.settled_credit(settlement)?)
+                    .await?;
+                // first store to disk
+                store.try_insert(ReplicaEvent::EscrowSettlementProposed(event.clone()))?;
+                let mut wallet = wallet;
+                // then apply to inmem state
+                wallet.apply(ReplicaEvent::EscrowSettlementProposed(event))?;
+                Ok(Some(EscrowSettlementShare {
+                    settlement: settlement.clone(),
+                    credit_sig,
+                }))
+            }
+        }
+    }
+
+    /// The credit a pending escrow settles to.
+    pub async fn settled_credit(&self, settlement: &EscrowSettlement) -> Result<SignedCredit> {
+        let id = settlement.id();
+        let store_ref = match self.stores.get(&id.actor) {
+            None => return Err(Error::Transfer(crate::transfers::Error::NoSuchSender)),
+            Some(store) => store,
+        };
+        let store = store_ref.read().await;
+        let wallet = self
+            .load_wallet(&store, OwnerType::Single(id.actor))
+            .await?;
+        Ok(wallet.settled_credit(settlement)?.clone())
+    }
+
+    /// Settlement of a transfer held in escrow, once the Replicas agreed on it.
+    pub async fn settle(
+        &self,
+        settlement: &EscrowSettlement,
+        credit_proof: &CreditAgreementProof,
+    ) -> Result<()> {
+        let id = settlement.id();
+        let store_ref = match self.stores.get(&id.actor) {
+            None => return Err(Error::Transfer(crate::transfers::Error::NoSuchSender)),
+            Some(store) => store,
+        };
+        let mut store = store_ref.write().await;
+
+        // write lock is on
+
+        let wallet = self
+            .load_wallet(&store, OwnerType::Single(id.actor))
+            .await?;
+        if let Some(event) = wallet.settle(settlement, credit_proof)? {
+            // first store to disk
+            store.try_insert(event.clone())?;
+            let mut wallet = wallet;
+            // then apply to inmem state
+            wallet.apply(event)?;
+        }
+        Ok(())
+    }
+
+    /// Proposal of a payout from the faucet, requested by its recipient, i.e. our vote on it.
+    /// Returns our share of the signature over the payout, unless we proposed it before.
+    ///
+    /// `now` is the time of this Replica, in seconds since the Unix epoch, by which the rate of
+    /// payouts is limited.
+    pub async fn propose_payout(
+        &self,
+        payout: &SignedCredit,
+        now: u64,
+    ) -> Result<Option<FaucetPayoutShare>> {
+        let faucet = self
+            .faucet
+            .as_ref()
+            .ok_or_else(|| Error::InvalidOperation("The network has no faucet".to_string()))?;
+        faucet.check_request(payout)?;
+        let store_ref = match self.stores.get(&faucet.wallet()) {
+            None => return Err(Error::Transfer(crate::transfers::Error::NoSuchSender)),
+            Some(store) => store,
+        };
+        let mut store = store_ref.write().await;
+
+        // write lock is on
+
+        let history = store.get_all();
+        let proposed = history.iter().any(|event| match event {
+            ReplicaEvent::FaucetPayoutProposed(event) => event.payout.id() == payout.id(),
+            ReplicaEvent::FaucetPaidOut(event) => event.credit_proof.id() == payout.id(),
+            _ => false,
+        });
+        if proposed {
+            return Ok(None);
+        }
+        faucet.check_cap(payout, &history)?;
+        faucet.check_rate(&history, now)?;
+
+        let wallet = self
+            .load_wallet(&store, OwnerType::Single(faucet.wallet()))
+            .await?;
+        if payout.amount() > wallet.balance() {
+            return Err(Error::Transfer(
+                crate::transfers::Error::InsufficientBalance,
+            ));
+        }
+        let credit_sig = self.info.signing.sign_validated_credit(payout).await?;
+        let event = FaucetPayoutProposed {
+            payout: payout.clone(),
+            time: now,
+        };
+        // first store to disk
+        store.try_insert(ReplicaEvent::FaucetPayoutProposed(event.clone()))?;
+        let mut wallet = wallet;
+        // then apply to inmem state
+        wallet.apply(ReplicaEvent::FaucetPayoutProposed(event))?;
+        Ok(Some(FaucetPayoutShare {
+            payout: payout.clone(),
+            credit_sig,
+        }))
+    }
+
+    /// Payout from the faucet, once the Replicas agreed on it.
+    /// Returns whether it was paid out now, i.e. not before.
+    pub async fn pay_out(&self, credit_proof: &CreditAgreementProof, now: u64) -> Result<bool> {
+        let wallet_id = self
+            .faucet_wallet()
+            .ok_or_else(|| Error::InvalidOperation("The network has no faucet".to_string()))?;
+        let store_ref = match self.stores.get(&wallet_id) {
+            None => return Err(Error::Transfer(crate::transfers::Error::NoSuchSender)),
+            Some(store) => store,
+        };
+        let mut store = store_ref.write().await;
+
+        // write lock is on
+
+        let wallet = self
+            .load_wallet(&store, OwnerType::Single(wallet_id))
+            .await?;
+        match wallet.pay_out(credit_proof, now)? {
+            None => Ok(false),
+            Some(event) => {
+                // first store to disk
+                store.try_insert(ReplicaEvent::FaucetPaidOut(event.clone()))?;
+                let mut wallet = wallet;
+                // then apply to inmem state
+                wallet.apply(ReplicaEvent::FaucetPaidOut(event))?;
+                Ok(true)
+            }
+        }
+    }
+
     /// Step 3. Validation of DebitAgreementProof, and credit idempotency at credit destination.
     /// (Since this leads to a credit, there is no requirement on order.)
     pub async fn receive_propagated(
@@ -471,6 +734,8 @@ impl<T: ReplicaSigning> Replicas<T> {
             debit_sig: Signature::from(sig.clone()),
             credit_sig: Signature::from(sig),
             debiting_replicas_keys: replica_keys,
+            batch: vec![],
+            escrow: None,
         };
 
         store.try_insert(ReplicaEvent::TransferPropagated(TransferPropagated {
@@ -9,8 +9,8 @@
 use super::{delivery_group, enduser_registry::SocketId, Core};
 use crate::messaging::{
     node::{Network, NodeState, Peer, Proposal, RoutingMsg, Section, Variant},
-    DstInfo, EndUser, Itinerary, MessageId, MessageType, SectionAuthorityProvider, SrcLocation,
-    WireMsg,
+    DstInfo, EndUser, Itinerary, MessageId, MessageType, NetworkParams, SectionAuthorityProvider,
+    SrcLocation, WireMsg,
 };
 use crate::routing::{
     dkg::commands::DkgCommands,
@@ -30,8 +30,12 @@ use xor_name::{Prefix, XorName};
 
 impl Core {
     // Creates `Core` for the first node in the network
-    pub fn first_node(node: Node, event_tx: mpsc::Sender<Event>) -> Result<Self> {
-        let (section, section_key_share) = Section::first_node(node.peer())?;
+    pub fn first_node(
+        node: Node,
+        network_params: NetworkParams,
+        event_tx: mpsc::Sender<Event>,
+    ) -> Result<Self> {
+        let (section, section_key_share) = Section::first_node(node.peer(), network_params)?;
         Ok(Self::new(node, section, Some(section_key_share), event_tx))
     }
 
@@ -136,6 +140,10 @@ impl Core {
     // ----------------------------------------------------------------------------------------
 
     pub(crate) fn handle_timeout(&mut self, token: u64) -> Result<Vec<Command>> {
+        if self.elder_health_timer == Some(token) {
+            return Ok(self.check_elder_health());
+        }
+
         self.dkg_voter
             .handle_timeout(&self.node.keypair, token)
             .into_commands(&self.node, *self.section_chain().last_key())
@@ -294,7 +302,10 @@ impl Core {
     pub(crate) fn promote_and_demote_elders(&mut self) -> Result<Vec<Command>> {
         let mut commands = vec![];
 
-        for info in self.section.promote_and_demote_elders(&self.node.name()) {
+        for info in self
+            .section
+            .promote_and_demote_elders(&self.node.name(), &self.demoted)
+        {
             commands.extend(self.send_dkg_start(info)?);
         }
 
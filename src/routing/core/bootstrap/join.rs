@@ -11,7 +11,7 @@ use crate::messaging::{
         JoinRejectionReason, JoinRequest, JoinResponse, ResourceProofResponse, RoutingMsg, Section,
         Variant,
     },
-    DstInfo, DstLocation, MessageType, WireMsg,
+    DstInfo, DstLocation, MessageType, NetworkParams, SectionAuthorityProvider, WireMsg,
 };
 use crate::routing::{
     dkg::SectionSignedUtils,
@@ -47,12 +47,13 @@ pub(crate) async fn join_network(
     comm: &Comm,
     incoming_conns: &mut mpsc::Receiver<ConnectionEvent>,
     bootstrap_addr: SocketAddr,
+    network_params: Option<NetworkParams>,
 ) -> Result<(Node, Section, Vec<(RoutingMsg, SocketAddr, DstInfo)>)> {
     let (send_tx, send_rx) = mpsc::channel(1);
 
     let span = trace_span!("bootstrap", name = %node.name());
 
-    let state = Join::new(node, send_tx, incoming_conns);
+    let state = Join::new(node, send_tx, incoming_conns, network_params);
 
     future::join(state.run(bootstrap_addr), send_messages(send_rx, comm))
         .instrument(span)
@@ -68,6 +69,8 @@ struct Join<'a> {
     node: Node,
     // Backlog for unknown messages
     backlog: VecDeque<(RoutingMsg, SocketAddr, DstInfo)>,
+    // Network parameters we expect the network to have been started with, if any.
+    network_params: Option<NetworkParams>,
 }
 
 impl<'a> Join<'a> {
@@ -75,12 +78,14 @@ impl<'a> Join<'a> {
         node: Node,
         send_tx: mpsc::Sender<(MessageType, Vec<(XorName, SocketAddr)>)>,
         recv_rx: &'a mut mpsc::Receiver<ConnectionEvent>,
+        network_params: Option<NetworkParams>,
     ) -> Self {
         Self {
             send_tx,
             recv_rx,
             node,
             backlog: VecDeque::with_capacity(BACKLOG_CAPACITY),
+            network_params,
         }
     }
 
@@ -145,6 +150,8 @@ impl<'a> Join<'a> {
                     section_chain,
                     ..
                 } => {
+                    self.check_network_params(&section_auth.value)?;
+
                     return Ok((
                         self.node,
                         Section::new(genesis_key, section_chain, section_auth)?,
@@ -157,6 +164,8 @@ impl<'a> Join<'a> {
                         continue;
                     }
 
+                    self.check_network_params(&section_auth)?;
+
                     let new_recipients: Vec<(XorName, SocketAddr)> = section_auth
                         .elders
                         .iter()
@@ -216,6 +225,8 @@ impl<'a> Join<'a> {
                         continue;
                     }
 
+                    self.check_network_params(&section_auth)?;
+
                     // Ignore already used recipients
                     let new_recipients: Vec<(XorName, SocketAddr)> = section_auth
                         .elders
@@ -283,6 +294,36 @@ impl<'a> Join<'a> {
         }
     }
 
+    // Verify the network parameters carried by the section we are about to join are usable and,
+    // if we were configured with the parameters to expect, that they match.
+    fn check_network_params(&self, section_auth: &SectionAuthorityProvider) -> Result<()> {
+        let actual = section_auth.network_params;
+
+        if !actual.is_valid() {
+            error!("Section {:?} has invalid network parameters", section_auth);
+            return Err(Error::InvalidNetworkParams(Box::new(actual)));
+        }
+
+        // Only the genesis node knows the faucet it set up, if any, so joining nodes don't expect it.
+        let expected = self.network_params.map(|expected| NetworkParams {
+            faucet: actual.faucet,
+            ..expected
+        });
+        match expected {
+            Some(expected) if expected != actual => {
+                error!(
+                    "Network parameters mismatch: expected {:?}, section {:?} has {:?}",
+                    expected, section_auth.prefix, actual
+                );
+                Err(Error::NetworkParamsMismatch {
+                    expected: Box::new(expected),
+                    actual: Box::new(actual),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     async fn send_join_requests(
         &mut self,
         join_request: JoinRequest,
@@ -475,7 +516,7 @@ mod tests {
             gen_addr(),
         );
         let peer = node.peer();
-        let state = Join::new(node, send_tx, &mut recv_rx);
+        let state = Join::new(node, send_tx, &mut recv_rx, None);
 
         // Create the bootstrap task, but don't run it yet.
         let bootstrap = async move { state.run(bootstrap_addr).await.map_err(Error::from) };
@@ -575,7 +616,7 @@ mod tests {
             gen_addr(),
         );
         let name = node.name();
-        let state = Join::new(node, send_tx, &mut recv_rx);
+        let state = Join::new(node, send_tx, &mut recv_rx, None);
 
         let bootstrap_task = state.run(bootstrap_node.addr);
         let test_task = async move {
@@ -607,6 +648,7 @@ mod tests {
                     prefix: Prefix::default(),
                     public_key_set: pk_set.clone(),
                     elders: new_bootstrap_addrs.clone(),
+                    network_params: NetworkParams::default(),
                 }))),
                 &bootstrap_node,
                 section_auth.section_key(),
@@ -666,7 +708,7 @@ mod tests {
             gen_addr(),
         );
         let node_name = node.name();
-        let state = Join::new(node, send_tx, &mut recv_rx);
+        let state = Join::new(node, send_tx, &mut recv_rx, None);
 
         let bootstrap_task = state.run(bootstrap_node.addr);
         let test_task = async {
@@ -684,6 +726,7 @@ mod tests {
                     prefix: Prefix::default(),
                     public_key_set: pk_set.clone(),
                     elders: BTreeMap::new(),
+                    network_params: NetworkParams::default(),
                 }))),
                 &bootstrap_node,
                 section_auth.section_key(),
@@ -701,6 +744,7 @@ mod tests {
                     prefix: Prefix::default(),
                     public_key_set: pk_set.clone(),
                     elders: addrs,
+                    network_params: NetworkParams::default(),
                 }))),
                 &bootstrap_node,
                 section_auth.section_key(),
@@ -743,7 +787,7 @@ mod tests {
         );
 
         let node_name = node.name();
-        let state = Join::new(node, send_tx, &mut recv_rx);
+        let state = Join::new(node, send_tx, &mut recv_rx, None);
 
         let bootstrap_task = state.run(bootstrap_node.addr);
         let test_task = async {
@@ -778,6 +822,56 @@ mod tests {
         test_result
     }
 
+    #[tokio::test]
+    async fn join_network_params_mismatch() -> Result<()> {
+        let (send_tx, mut send_rx) = mpsc::channel(1);
+        let (recv_tx, mut recv_rx) = mpsc::channel(1);
+
+        let (section_auth, mut nodes, _) =
+            gen_section_authority_provider(Prefix::default(), ELDER_SIZE);
+        let bootstrap_node = nodes.remove(0);
+
+        let node = Node::new(
+            ed25519::gen_keypair(&Prefix::default().range_inclusive(), MIN_ADULT_AGE),
+            gen_addr(),
+        );
+
+        let node_name = node.name();
+        let expected = NetworkParams::new(3, 6);
+        let state = Join::new(node, send_tx, &mut recv_rx, Some(expected));
+
+        let bootstrap_task = state.run(bootstrap_node.addr);
+        let test_task = async {
+            let (message, _) = send_rx
+                .recv()
+                .await
+                .ok_or_else(|| anyhow!("JoinRequest was not received"))?;
+
+            assert_matches!(message, MessageType::Routing { msg, .. } =>
+                            assert_matches!(msg.variant, Variant::JoinRequest{..}));
+
+            send_response(
+                &recv_tx,
+                Variant::JoinResponse(Box::new(JoinResponse::Retry(section_auth.clone()))),
+                &bootstrap_node,
+                section_auth.section_key(),
+                node_name,
+            )?;
+
+            Ok(())
+        };
+
+        let (join_result, test_result) = future::join(bootstrap_task, test_task).await;
+
+        assert_matches!(
+            join_result,
+            Err(RoutingError::NetworkParamsMismatch { expected: e, actual })
+                if *e == expected && *actual == NetworkParams::default()
+        );
+
+        test_result
+    }
+
     #[tokio::test]
     async fn join_invalid_retry_prefix_response() -> Result<()> {
         let (send_tx, mut send_rx) = mpsc::channel(1);
@@ -805,7 +899,7 @@ mod tests {
             }
         };
 
-        let state = Join::new(node, send_tx, &mut recv_rx);
+        let state = Join::new(node, send_tx, &mut recv_rx, None);
 
         let section_key = bls::SecretKey::random().public_key();
         let elders = (0..ELDER_SIZE)
@@ -6,15 +6,19 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::Core;
+use super::{Core, Remedy};
 use crate::messaging::node::Proposal;
 use crate::routing::{
     error::Result,
     peer::PeerUtils,
-    routing_api::command::Command,
+    routing_api::command::{self, Command},
     section::{NodeStateUtils, SectionAuthorityProviderUtils, SectionPeersUtils, SectionUtils},
 };
-use std::{collections::BTreeSet, iter, net::SocketAddr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter,
+    net::SocketAddr,
+};
 use xor_name::XorName;
 
 impl Core {
@@ -78,4 +82,90 @@ impl Core {
         }
         Ok(result)
     }
+
+    // Schedules the next check of how our section's elders sign proposals.
+    pub(crate) fn schedule_elder_health_check(&mut self) -> Command {
+        let token = command::next_timer_token();
+        self.elder_health_timer = Some(token);
+        Command::ScheduleTimeout {
+            duration: self.elder_health.thresholds().signature_timeout,
+            token,
+        }
+    }
+
+    // Closes the signing rounds which timed out, dealing with the elders which missed them, and
+    // schedules the next check for as long as we are an elder.
+    pub(crate) fn check_elder_health(&mut self) -> Vec<Command> {
+        if !self.is_elder() {
+            self.elder_health_timer = None;
+            return vec![];
+        }
+
+        let mut commands = vec![self.schedule_elder_health_check()];
+        let unhealthy = self.elder_health.close_expired_rounds();
+        match self.handle_unhealthy_elders(unhealthy) {
+            Ok(unhealthy_commands) => commands.extend(unhealthy_commands),
+            Err(error) => warn!("Failed to handle unhealthy elders: {}", error),
+        }
+        commands
+    }
+
+    // Proposes offline or demoted the unhealthy elders, as due. Our section's elders only change
+    // once enough of the other elders find them unhealthy too and the proposals get agreed.
+    pub(crate) fn handle_unhealthy_elders(
+        &self,
+        unhealthy: BTreeMap<XorName, Remedy>,
+    ) -> Result<Vec<Command>> {
+        if unhealthy.is_empty() || !self.is_elder() {
+            return Ok(vec![]);
+        }
+
+        self.propose_unhealthy(&unhealthy)
+    }
+
+    pub(crate) fn propose_unhealthy(
+        &self,
+        unhealthy: &BTreeMap<XorName, Remedy>,
+    ) -> Result<Vec<Command>> {
+        let our_name = self.node.name();
+        let due = |due_remedy: Remedy| -> BTreeSet<XorName> {
+            unhealthy
+                .iter()
+                .filter(|(name, remedy)| **remedy == due_remedy && **name != our_name)
+                .map(|(name, _)| *name)
+                .collect()
+        };
+
+        let mut commands = vec![];
+        let offline = due(Remedy::Offline);
+        if !offline.is_empty() {
+            commands.extend(self.cast_offline_proposals(&offline)?);
+        }
+        let demoted: BTreeSet<_> = due(Remedy::Demote)
+            .difference(&self.demoted)
+            .copied()
+            .collect();
+        commands.extend(self.cast_demote_proposals(&demoted)?);
+        Ok(commands)
+    }
+
+    // Proposes to demote the given elders, not putting the proposals to the elders they are
+    // about.
+    pub(crate) fn cast_demote_proposals(&self, names: &BTreeSet<XorName>) -> Result<Vec<Command>> {
+        let elders: Vec<_> = self
+            .section
+            .authority_provider()
+            .peers()
+            .filter(|peer| !names.contains(peer.name()))
+            .collect();
+        let mut result: Vec<Command> = Vec::new();
+        for name in names.iter() {
+            if let Some(info) = self.section.members().get(name) {
+                if let Ok(commands) = self.send_proposal(&elders, Proposal::Demote(info.peer)) {
+                    result.extend(commands);
+                }
+            }
+        }
+        Ok(result)
+    }
 }
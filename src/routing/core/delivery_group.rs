@@ -17,7 +17,7 @@ use crate::routing::{
     network::NetworkUtils,
     peer::PeerUtils,
     section::{SectionAuthorityProviderUtils, SectionPeersUtils, SectionUtils},
-    supermajority, ELDER_SIZE,
+    supermajority,
 };
 use itertools::Itertools;
 use std::{cmp, iter};
@@ -113,7 +113,8 @@ fn candidates(
         .map(|info| (&info.prefix, info.elder_count(), info.peers()));
 
     // gives at least 1 honest target among recipients.
-    let min_dg_size = 1 + ELDER_SIZE - supermajority(ELDER_SIZE);
+    let elder_size = section.network_params().elder_size;
+    let min_dg_size = 1 + elder_size - supermajority(elder_size);
     let mut dg_size = min_dg_size;
     let mut candidates = Vec::new();
     for (idx, (prefix, len, connected)) in sections.enumerate() {
@@ -173,6 +174,7 @@ mod tests {
             test_utils::{gen_addr, gen_section_authority_provider},
             NodeStateUtils, SectionAuthorityProviderUtils, MIN_ADULT_AGE,
         },
+        ELDER_SIZE,
     };
     use anyhow::{Context, Result};
     use rand::seq::IteratorRandom;
@@ -470,7 +472,12 @@ mod tests {
 
         let (section_auth1, _, _) = gen_section_authority_provider(prefix1, ELDER_SIZE);
         let section_auth1 = section_signed(&sk, section_auth1)?;
-        assert!(network.update_section(section_auth1, None, section.chain()));
+        assert!(network.update_section(
+            section_auth1,
+            None,
+            section.chain(),
+            &section.network_params()
+        ));
 
         let our_name = choose_elder_name(section.authority_provider())?;
 
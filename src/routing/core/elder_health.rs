@@ -0,0 +1,398 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+use tiny_keccak::{Hasher, Sha3};
+use xor_name::XorName;
+
+type Digest256 = [u8; 32];
+
+/// Thresholds past which an elder is considered unhealthy by the other elders. Elders failing DKG
+/// or sending invalid signature shares are proposed offline, while elders not signing proposals,
+/// or signing them late, are proposed to be demoted to adults. Failures counted as consecutive are forgiven as
+/// soon as the elder behaves again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElderHealthThresholds {
+    /// Number of consecutive DKG sessions an elder candidate may fail to take part in.
+    pub max_dkg_failures: u32,
+    /// Number of invalid signature shares an elder may send.
+    pub max_invalid_shares: u32,
+    /// Number of consecutive agreed proposals an elder may fail to sign.
+    pub max_missed_signatures: u32,
+    /// Number of consecutive proposals an elder may sign late.
+    pub max_late_signatures: u32,
+    /// Delay after the first signature share of a proposal past which a share counts as late.
+    pub late_signature: Duration,
+    /// Delay after the first signature share of a proposal past which the elders that haven't
+    /// signed it, once agreed, are considered to have missed it. Also the interval at which the
+    /// signing of proposals is checked.
+    pub signature_timeout: Duration,
+}
+
+impl Default for ElderHealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_dkg_failures: 1,
+            max_invalid_shares: 3,
+            max_missed_signatures: 10,
+            max_late_signatures: 10,
+            late_signature: Duration::from_secs(5),
+            signature_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ElderRecord {
+    dkg_failures: u32,
+    invalid_shares: u32,
+    missed_signatures: u32,
+    late_signatures: u32,
+}
+
+impl ElderRecord {
+    fn remedy(&self, thresholds: &ElderHealthThresholds) -> Option<Remedy> {
+        if self.dkg_failures >= thresholds.max_dkg_failures
+            || self.invalid_shares >= thresholds.max_invalid_shares
+        {
+            Some(Remedy::Offline)
+        } else if self.missed_signatures >= thresholds.max_missed_signatures
+            || self.late_signatures >= thresholds.max_late_signatures
+        {
+            Some(Remedy::Demote)
+        } else {
+            None
+        }
+    }
+}
+
+// What to do about an elder past the health thresholds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Remedy {
+    // Propose the elder offline.
+    Offline,
+    // Keep the elder as an adult of our section, promoting another member in its place.
+    Demote,
+}
+
+// Signing of a single proposal by the elders.
+struct SigningRound {
+    started: Instant,
+    elders: BTreeSet<XorName>,
+    signers: BTreeSet<XorName>,
+    agreed: bool,
+}
+
+// Tracks the health of our section's elders from their DKG participation, the signature shares
+// they send for proposals and how fast they send them. The `record_*` and `close_*` functions
+// return the elders which are past the thresholds after a new failure, with what to do about them.
+// This is our own view only: it is acted upon by proposing, and only the agreed proposals change
+// our section.
+pub(crate) struct ElderHealth {
+    thresholds: ElderHealthThresholds,
+    records: BTreeMap<XorName, ElderRecord>,
+    rounds: HashMap<Digest256, SigningRound>,
+}
+
+impl ElderHealth {
+    pub fn new(thresholds: ElderHealthThresholds) -> Self {
+        Self {
+            thresholds,
+            records: BTreeMap::new(),
+            rounds: HashMap::new(),
+        }
+    }
+
+    pub fn thresholds(&self) -> &ElderHealthThresholds {
+        &self.thresholds
+    }
+
+    // Records the DKG session among `participants` completed, without `failed` ones.
+    pub fn record_dkg(
+        &mut self,
+        participants: &BTreeSet<XorName>,
+        failed: &BTreeSet<XorName>,
+    ) -> BTreeMap<XorName, Remedy> {
+        for name in participants {
+            let record = self.records.entry(*name).or_default();
+            if failed.contains(name) {
+                record.dkg_failures += 1;
+            } else {
+                record.dkg_failures = 0;
+            }
+        }
+
+        self.unhealthy(failed.iter().copied().collect())
+    }
+
+    // Records a signature share of `signer` failing verification.
+    pub fn record_invalid_share(&mut self, signer: XorName) -> BTreeMap<XorName, Remedy> {
+        self.records.entry(signer).or_default().invalid_shares += 1;
+        self.unhealthy(vec![signer])
+    }
+
+    // Records a valid signature share of `signer` for the proposal with the given signable bytes,
+    // which the `elders` it was put to are expected to sign.
+    pub fn record_share(
+        &mut self,
+        payload: &[u8],
+        signer: XorName,
+        elders: impl FnOnce() -> BTreeSet<XorName>,
+    ) -> BTreeMap<XorName, Remedy> {
+        self.record_share_at(payload, signer, elders, Instant::now())
+    }
+
+    fn record_share_at(
+        &mut self,
+        payload: &[u8],
+        signer: XorName,
+        elders: impl FnOnce() -> BTreeSet<XorName>,
+        now: Instant,
+    ) -> BTreeMap<XorName, Remedy> {
+        let round = self
+            .rounds
+            .entry(digest(payload))
+            .or_insert_with(|| SigningRound {
+                started: now,
+                elders: elders(),
+                signers: BTreeSet::new(),
+                agreed: false,
+            });
+        if !round.elders.contains(&signer) || !round.signers.insert(signer) {
+            // Not expected to sign, or a duplicate.
+            return BTreeMap::new();
+        }
+        let late = now.saturating_duration_since(round.started) > self.thresholds.late_signature;
+
+        let record = self.records.entry(signer).or_default();
+        record.missed_signatures = 0;
+        if late {
+            record.late_signatures += 1;
+            self.unhealthy(vec![signer])
+        } else {
+            record.late_signatures = 0;
+            BTreeMap::new()
+        }
+    }
+
+    // Records the proposal with the given signable bytes got agreed, so the elders which don't
+    // sign it in time miss it.
+    pub fn record_agreement(&mut self, payload: &[u8]) {
+        if let Some(round) = self.rounds.get_mut(&digest(payload)) {
+            round.agreed = true;
+        }
+    }
+
+    // Closes the rounds which timed out.
+    pub fn close_expired_rounds(&mut self) -> BTreeMap<XorName, Remedy> {
+        self.close_expired_rounds_at(Instant::now())
+    }
+
+    fn close_expired_rounds_at(&mut self, now: Instant) -> BTreeMap<XorName, Remedy> {
+        let timeout = self.thresholds.signature_timeout;
+        self.close_rounds(|round| now.saturating_duration_since(round.started) > timeout)
+    }
+
+    // Closes all the rounds, as no further shares are tracked for them once our section key
+    // changes.
+    pub fn close_all_rounds(&mut self) -> BTreeMap<XorName, Remedy> {
+        self.close_rounds(|_| true)
+    }
+
+    // Stops tracking the names that are no longer members of our section.
+    pub fn retain_members(&mut self, members: &BTreeSet<XorName>) {
+        self.records.retain(|name, _| members.contains(name));
+    }
+
+    // Closes the rounds matching `closing`, counting a missed signature for each elder which
+    // didn't sign an agreed one. Rounds which weren't agreed are dropped, as the elders might
+    // have rightly refused to sign them.
+    fn close_rounds(
+        &mut self,
+        closing: impl Fn(&SigningRound) -> bool,
+    ) -> BTreeMap<XorName, Remedy> {
+        let (closed, open): (HashMap<_, _>, HashMap<_, _>) =
+            self.rounds.drain().partition(|(_, round)| closing(round));
+        self.rounds = open;
+
+        let mut missed = vec![];
+        for round in closed.values().filter(|round| round.agreed) {
+            for name in round.elders.difference(&round.signers) {
+                self.records.entry(*name).or_default().missed_signatures += 1;
+                missed.push(*name);
+            }
+        }
+        self.unhealthy(missed)
+    }
+
+    // Returns those of the elders which just failed that are past the thresholds.
+    fn unhealthy(&mut self, failed: Vec<XorName>) -> BTreeMap<XorName, Remedy> {
+        let mut unhealthy = BTreeMap::new();
+        for name in failed {
+            let record = match self.records.get(&name) {
+                Some(record) => record,
+                None => continue,
+            };
+            if let Some(remedy) = record.remedy(&self.thresholds) {
+                warn!(
+                    "Elder {} is past the health thresholds ({:?}): {:?}",
+                    name, remedy, record
+                );
+                let _ = unhealthy.insert(name, remedy);
+            }
+        }
+        unhealthy
+    }
+}
+
+fn digest(payload: &[u8]) -> Digest256 {
+    let mut hasher = Sha3::v256();
+    let mut hash = Digest256::default();
+    hasher.update(payload);
+    hasher.finalize(&mut hash);
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter;
+
+    fn names(count: usize) -> Vec<XorName> {
+        (0..count).map(|_| XorName::random()).collect()
+    }
+
+    #[test]
+    fn dkg_failures() {
+        let mut health = ElderHealth::new(ElderHealthThresholds {
+            max_dkg_failures: 2,
+            ..Default::default()
+        });
+        let participants: BTreeSet<_> = names(3).into_iter().collect();
+        let failed: BTreeSet<_> = participants.iter().take(1).copied().collect();
+        let offline: BTreeMap<_, _> = failed.iter().map(|name| (*name, Remedy::Offline)).collect();
+
+        assert!(health.record_dkg(&participants, &failed).is_empty());
+        // Taking part in a DKG again is forgiving.
+        assert!(health
+            .record_dkg(&participants, &BTreeSet::new())
+            .is_empty());
+        assert!(health.record_dkg(&participants, &failed).is_empty());
+        assert_eq!(health.record_dkg(&participants, &failed), offline);
+        // Reported again on further failures.
+        assert_eq!(health.record_dkg(&participants, &failed), offline);
+    }
+
+    #[test]
+    fn invalid_shares() {
+        let mut health = ElderHealth::new(ElderHealthThresholds {
+            max_invalid_shares: 2,
+            ..Default::default()
+        });
+        let elder = XorName::random();
+
+        assert!(health.record_invalid_share(elder).is_empty());
+        assert_eq!(
+            health.record_invalid_share(elder),
+            iter::once((elder, Remedy::Offline)).collect()
+        );
+    }
+
+    #[test]
+    fn missed_and_late_signatures() {
+        let thresholds = ElderHealthThresholds {
+            max_missed_signatures: 2,
+            max_late_signatures: 2,
+            ..Default::default()
+        };
+        let mut health = ElderHealth::new(thresholds);
+        let elders = names(3);
+        let (prompt, late, silent) = (elders[0], elders[1], elders[2]);
+        let elder_set = || elders.iter().copied().collect::<BTreeSet<_>>();
+        let start = Instant::now();
+        let round_duration = thresholds.signature_timeout + Duration::from_secs(1);
+
+        for round in 0..2u32 {
+            let payload = round.to_be_bytes();
+            let started = start + round_duration * round;
+            assert!(health
+                .record_share_at(&payload, prompt, elder_set, started)
+                .is_empty());
+            let unhealthy = health.record_share_at(
+                &payload,
+                late,
+                elder_set,
+                started + thresholds.late_signature + Duration::from_secs(1),
+            );
+            health.record_agreement(&payload);
+            if round == 1 {
+                assert_eq!(unhealthy, iter::once((late, Remedy::Demote)).collect());
+            }
+
+            // The silent elder misses the round once it times out.
+            assert!(health.close_expired_rounds_at(started).is_empty());
+            let unhealthy = health.close_expired_rounds_at(started + round_duration);
+            if round == 1 {
+                assert_eq!(unhealthy, iter::once((silent, Remedy::Demote)).collect());
+            } else {
+                assert!(unhealthy.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn only_agreed_rounds_are_missed() {
+        let mut health = ElderHealth::new(ElderHealthThresholds {
+            max_missed_signatures: 1,
+            ..Default::default()
+        });
+        let elders = names(2);
+        let (signer, silent) = (elders[0], elders[1]);
+        let elder_set = || elders.iter().copied().collect::<BTreeSet<_>>();
+        let asked = || iter::once(signer).collect::<BTreeSet<_>>();
+
+        // A proposal the section never agreed on.
+        assert!(health
+            .record_share(b"unagreed", signer, elder_set)
+            .is_empty());
+        // A proposal the silent elder wasn't put to, such as the one about itself going offline.
+        assert!(health
+            .record_share(b"about silent", signer, asked)
+            .is_empty());
+        health.record_agreement(b"about silent");
+        assert!(health.close_all_rounds().is_empty());
+
+        assert!(health.record_share(b"agreed", signer, elder_set).is_empty());
+        health.record_agreement(b"agreed");
+        assert_eq!(
+            health.close_all_rounds(),
+            iter::once((silent, Remedy::Demote)).collect()
+        );
+    }
+
+    #[test]
+    fn retain_members() {
+        let mut health = ElderHealth::new(ElderHealthThresholds {
+            max_invalid_shares: 1,
+            ..Default::default()
+        });
+        let elder = XorName::random();
+
+        assert_eq!(
+            health.record_invalid_share(elder),
+            iter::once((elder, Remedy::Offline)).collect()
+        );
+        health.retain_members(&BTreeSet::new());
+        // Once the elder rejoins, it starts from a clean record.
+        health.thresholds.max_invalid_shares = 2;
+        assert!(health.record_invalid_share(elder).is_empty());
+    }
+}
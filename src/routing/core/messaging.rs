@@ -265,7 +265,7 @@ impl Core {
     pub(crate) fn return_relocate_promise(&self) -> Option<Command> {
         // TODO: keep sending this periodically until we get relocated.
         if let Some(RelocateState::Delayed(msg)) = &self.relocate_state {
-            Some(self.send_message_to_our_elders(msg.clone()))
+            Some(self.send_message_to_our_elders((**msg).clone()))
         } else {
             None
         }
@@ -10,14 +10,19 @@ mod api;
 mod bootstrap;
 mod connectivity;
 mod delivery_group;
+mod elder_health;
 mod enduser_registry;
 mod message_filter;
 mod messaging;
 mod msg_handling;
 mod split_barrier;
 
+pub use self::elder_health::ElderHealthThresholds;
 use self::{
-    enduser_registry::EndUserRegistry, message_filter::MessageFilter, split_barrier::SplitBarrier,
+    elder_health::{ElderHealth, Remedy},
+    enduser_registry::EndUserRegistry,
+    message_filter::MessageFilter,
+    split_barrier::SplitBarrier,
 };
 use crate::messaging::node::SignatureAggregator;
 use crate::messaging::{
@@ -65,6 +70,10 @@ pub(crate) struct Core {
     joins_allowed: bool,
     resource_proof: ResourceProof,
     end_users: EndUserRegistry,
+    elder_health: ElderHealth,
+    elder_health_timer: Option<u64>,
+    // Members our section agreed to demote, left out of the elder candidates.
+    demoted: BTreeSet<XorName>,
 }
 
 impl Core {
@@ -92,9 +101,22 @@ impl Core {
             joins_allowed: true,
             resource_proof: ResourceProof::new(RESOURCE_PROOF_DATA_SIZE, RESOURCE_PROOF_DIFFICULTY),
             end_users: EndUserRegistry::new(),
+            elder_health: ElderHealth::new(ElderHealthThresholds::default()),
+            elder_health_timer: None,
+            demoted: BTreeSet::new(),
         }
     }
 
+    // Sets the thresholds past which our section's elders are proposed offline or demoted.
+    pub(crate) fn with_elder_health(mut self, thresholds: ElderHealthThresholds) -> Self {
+        self.elder_health = ElderHealth::new(thresholds);
+        self
+    }
+
+    pub(crate) fn elder_health_thresholds(&self) -> ElderHealthThresholds {
+        *self.elder_health.thresholds()
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     // Miscellaneous
     ////////////////////////////////////////////////////////////////////////////
@@ -114,10 +136,12 @@ impl Core {
         section_chain: SecuredLinkedList,
     ) {
         let prefix = section_auth.value.prefix;
-        if self
-            .network
-            .update_section(section_auth, None, &section_chain)
-        {
+        if self.network.update_section(
+            section_auth,
+            None,
+            &section_chain,
+            &self.section.network_params(),
+        ) {
             info!("Neighbour section knowledge updated: {:?}", prefix);
         } else {
             warn!("Neighbour section update failed");
@@ -138,6 +162,15 @@ impl Core {
         if new.last_key != old.last_key {
             self.msg_filter.reset().await;
 
+            let members = self
+                .section
+                .active_members()
+                .map(|peer| *peer.name())
+                .collect();
+            let unhealthy = self.elder_health.close_all_rounds();
+            self.elder_health.retain_members(&members);
+            self.demoted.retain(|name| members.contains(name));
+
             if new.is_elder {
                 info!(
                     "Section updated: prefix: ({:b}), key: {:?}, elders: {}",
@@ -146,7 +179,15 @@ impl Core {
                     self.section.authority_provider().peers().format(", ")
                 );
 
+                if self.elder_health_timer.is_none() {
+                    commands.push(self.schedule_elder_health_check());
+                }
+
                 if self.section_keys_provider.has_key_share() {
+                    commands.extend(self.propose_unhealthy(&unhealthy)?);
+                    // The elders just promoted don't know about the past demotions yet, casting
+                    // them again to sync.
+                    commands.extend(self.cast_demote_proposals(&self.demoted)?);
                     commands.extend(self.promote_and_demote_elders()?);
                     // Whenever there is an elders change, casting a round of joins_allowed
                     // proposals to sync.
@@ -10,8 +10,8 @@ use std::cmp;
 
 use crate::messaging::{
     node::{
-        KeyedSig, MembershipState, NodeState, PlainMessage, Proposal, RoutingMsg, SectionSigned,
-        Variant,
+        KeyedSig, MembershipState, NodeState, Peer, PlainMessage, Proposal, RoutingMsg,
+        SectionSigned, Variant,
     },
     DstInfo, DstLocation, SectionAuthorityProvider,
 };
@@ -82,6 +82,22 @@ impl Core {
                 self.joins_allowed = joins_allowed.1;
                 Ok(vec![])
             }
+            Proposal::Demote(peer) => self.handle_demote_agreement(peer),
+        }
+    }
+
+    // Leaves the demoted member out of the elder candidates for as long as it stays a member,
+    // replacing it if it is one of our elders.
+    fn handle_demote_agreement(&mut self, peer: Peer) -> Result<Vec<Command>> {
+        if !self.section.members().is_joined(peer.name()) || !self.demoted.insert(*peer.name()) {
+            return Ok(vec![]);
+        }
+        info!("Demoting {} to adult", peer);
+
+        if self.is_elder() && self.section_keys_provider.has_key_share() {
+            self.promote_and_demote_elders()
+        } else {
+            Ok(vec![])
         }
     }
 
@@ -209,7 +225,9 @@ impl Core {
         if equal_or_extension {
             // Our section of sub-section
 
-            let infos = self.section.promote_and_demote_elders(&self.node.name());
+            let infos = self
+                .section
+                .promote_and_demote_elders(&self.node.name(), &self.demoted);
             if !infos.contains(&section_auth.value.elder_candidates()) {
                 // SectionInfo out of date, ignore.
                 return Ok(commands);
@@ -254,9 +272,12 @@ impl Core {
         } else {
             // Other section
 
-            let _ = self
-                .network
-                .update_section(section_auth, None, self.section.chain());
+            let _ = self.network.update_section(
+                section_auth,
+                None,
+                self.section.chain(),
+                &self.section.network_params(),
+            );
         }
 
         Ok(commands)
@@ -280,9 +301,12 @@ impl Core {
             if section_auth.value.prefix.matches(&self.node.name()) {
                 let _ = self.section.update_elders(section_auth, key_sig);
             } else {
-                let _ =
-                    self.network
-                        .update_section(section_auth, Some(key_sig), self.section.chain());
+                let _ = self.network.update_section(
+                    section_auth,
+                    Some(key_sig),
+                    self.section.chain(),
+                    &self.section.network_params(),
+                );
             }
         }
 
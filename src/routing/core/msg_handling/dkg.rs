@@ -61,7 +61,7 @@ impl Core {
     }
 
     pub(crate) fn handle_dkg_failure_agreement(
-        &self,
+        &mut self,
         sender: &XorName,
         failure_set: &DkgFailureSigSet,
     ) -> Result<Vec<Command>> {
@@ -75,7 +75,7 @@ impl Core {
         let generation = self.section.chain().main_branch_len() as u64;
         let elder_candidates = self
             .section
-            .promote_and_demote_elders(&self.node.name())
+            .promote_and_demote_elders(&self.node.name(), &self.demoted)
             .into_iter()
             .find(|elder_candidates| failure_set.verify(elder_candidates, generation));
         let elder_candidates = if let Some(elder_candidates) = elder_candidates {
@@ -101,7 +101,11 @@ impl Core {
                 generation,
                 elder_candidates
             );
-            self.cast_offline_proposals(&failure_set.failed_participants)
+            let participants = elder_candidates.elders.keys().copied().collect();
+            let unhealthy = self
+                .elder_health
+                .record_dkg(&participants, &failure_set.failed_participants);
+            self.handle_unhealthy_elders(unhealthy)
         }
     }
 
@@ -110,6 +114,10 @@ impl Core {
         section_auth: SectionAuthorityProvider,
         key_share: SectionKeyShare,
     ) -> Result<Vec<Command>> {
+        let _ = self
+            .elder_health
+            .record_dkg(&section_auth.names(), &BTreeSet::new());
+
         let proposal = Proposal::SectionInfo(section_auth);
         let recipients: Vec<_> = self.section.authority_provider().peers().collect();
         let result = self.send_proposal_with(&recipients, proposal, &key_share);
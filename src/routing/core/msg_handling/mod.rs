@@ -37,6 +37,7 @@ use crate::routing::{
 use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
 use std::{iter, net::SocketAddr};
+use tracing::Instrument;
 
 // Message handling
 impl Core {
@@ -56,7 +57,11 @@ impl Core {
         // all the elders in our section and the msg needs to be propagated.
         if !in_dst_location {
             info!("Relay closer to the destination");
-            if let Some(cmds) = self.relay_message(&routing_msg).await? {
+            if let Some(cmds) = self
+                .relay_message(&routing_msg)
+                .instrument(trace_span!("relay", msg_id = ?routing_msg.id))
+                .await?
+            {
                 commands.push(cmds);
             }
 
@@ -99,8 +104,10 @@ impl Core {
 
         if shall_be_handled {
             info!("Entropy check passed. Handling useful msg!");
+            let span = trace_span!("deliver", msg_id = ?routing_msg.id);
             commands.extend(
                 self.handle_useful_message(sender, routing_msg, dst_info, &known_keys)
+                    .instrument(span)
                     .await?,
             );
         } else if no_ae_commands {
@@ -328,7 +335,7 @@ impl Core {
                     commands.extend(self.check_lagging((src_name, addr), sig_share)?);
                 }
 
-                let result = self.handle_proposal(content.clone(), sig_share.clone())?;
+                let result = self.handle_proposal(content.clone(), sig_share.clone(), src_name)?;
                 commands.extend(result);
 
                 Ok(commands)
@@ -366,6 +373,7 @@ impl Core {
             // Not an aggregating message, return unchanged.
             return Ok(Some(msg));
         };
+        let _span = trace_span!("aggregate", msg_id = ?msg.id).entered();
 
         let signed_bytes =
             bincode::serialize(&msg.signable_view()).map_err(|_| Error::InvalidMessage)?;
@@ -377,7 +385,10 @@ impl Core {
                 trace!("Successfully accumulated signatures for message: {:?}", msg);
                 Ok(Some(msg.into_dst_accumulated(sig)?))
             }
-            Err(AggregatorError::NotEnoughShares) => Ok(None),
+            Err(AggregatorError::NotEnoughShares) => {
+                trace!("Not enough signature shares yet for message {:?}", msg.id);
+                Ok(None)
+            }
             Err(err) => {
                 error!("Error accumulating message at dst: {:?}", err);
                 Err(Error::InvalidSignatureShare)
@@ -8,23 +8,84 @@
 
 use super::Core;
 use crate::messaging::node::{Error as AggregatorError, Proposal, SigShare};
-use crate::routing::{dkg::ProposalError, routing_api::command::Command, Error, Result};
+use crate::routing::{
+    dkg::{ProposalError, ProposalUtils},
+    peer::PeerUtils,
+    routing_api::command::Command,
+    section::{SectionAuthorityProviderUtils, SectionUtils},
+    Error, Result,
+};
+use std::collections::BTreeMap;
+use xor_name::XorName;
 
 // Decisions
 impl Core {
-    // Insert the proposal into the proposal aggregator and handle it if aggregated.
+    // Insert the proposal into the proposal aggregator and handle it if aggregated. Shares signed
+    // with our current section key also account for the health of their `signer`, and of the
+    // elders the proposal was put to which don't sign it once agreed.
     pub(crate) fn handle_proposal(
         &mut self,
         proposal: Proposal,
         sig_share: SigShare,
+        signer: XorName,
     ) -> Result<Vec<Command>> {
-        match self.proposal_aggregator.add(proposal, sig_share) {
-            Ok((proposal, sig)) => Ok(vec![Command::HandleAgreement { proposal, sig }]),
-            Err(ProposalError::Aggregation(AggregatorError::NotEnoughShares)) => Ok(vec![]),
+        let payload = if sig_share.public_key_set.public_key() == *self.section.chain().last_key() {
+            bincode::serialize(&proposal.as_signable()).ok()
+        } else {
+            None
+        };
+        // Elders aren't put the proposals about themselves going offline or being demoted.
+        let subject = match &proposal {
+            Proposal::Offline(node_state) => Some(*node_state.peer.name()),
+            Proposal::Demote(peer) => Some(*peer.name()),
+            _ => None,
+        };
+
+        let (result, valid_share, agreed) = match self.proposal_aggregator.add(proposal, sig_share)
+        {
+            Ok((proposal, sig)) => (
+                Ok(vec![Command::HandleAgreement { proposal, sig }]),
+                true,
+                true,
+            ),
+            Err(ProposalError::Aggregation(AggregatorError::NotEnoughShares)) => {
+                (Ok(vec![]), true, false)
+            }
             Err(error) => {
-                error!("Failed to add proposal: {}", error);
-                Err(Error::InvalidSignatureShare)
+                error!("Failed to add proposal from {}: {}", signer, error);
+                let valid_share = !matches!(
+                    error,
+                    ProposalError::Aggregation(AggregatorError::InvalidShare)
+                );
+                (Err(Error::InvalidSignatureShare), valid_share, false)
+            }
+        };
+
+        let unhealthy = match payload {
+            Some(payload) if valid_share => {
+                let section = &self.section;
+                let unhealthy = self.elder_health.record_share(&payload, signer, || {
+                    let mut elders = section.authority_provider().names();
+                    if let Some(subject) = &subject {
+                        let _ = elders.remove(subject);
+                    }
+                    elders
+                });
+                if agreed {
+                    self.elder_health.record_agreement(&payload);
+                }
+                unhealthy
             }
+            Some(_) => self.elder_health.record_invalid_share(signer),
+            None => BTreeMap::new(),
+        };
+
+        if unhealthy.is_empty() {
+            return result;
         }
+
+        let mut commands = self.handle_unhealthy_elders(unhealthy)?;
+        commands.extend(result.unwrap_or_default());
+        Ok(commands)
     }
 }
@@ -19,7 +19,7 @@ use crate::routing::{
     },
     routing_api::command::Command,
     section::{NodeStateUtils, SectionAuthorityProviderUtils, SectionPeersUtils, SectionUtils},
-    Event, ELDER_SIZE,
+    Event,
 };
 use xor_name::XorName;
 
@@ -33,7 +33,9 @@ impl Core {
         let mut commands = vec![];
 
         // Do not carry out relocation when there is not enough elder nodes.
-        if self.section.authority_provider().elder_count() < ELDER_SIZE {
+        if self.section.authority_provider().elder_count()
+            < self.section.network_params().elder_size
+        {
             return Ok(commands);
         }
 
@@ -163,7 +165,7 @@ impl Core {
             match self.relocate_state {
                 None => {
                     trace!("Received RelocatePromise to section at {}", promise.dst);
-                    self.relocate_state = Some(RelocateState::Delayed(msg.clone()));
+                    self.relocate_state = Some(RelocateState::Delayed(Box::new(msg.clone())));
                     self.send_event(Event::RelocationStarted {
                         previous_name: self.node.name(),
                     })
@@ -9,7 +9,7 @@
 use super::super::Core;
 use crate::messaging::{
     node::{RoutingMsg, Variant},
-    section_info::{GetSectionResponse, SectionInfoMsg},
+    section_info::{GetSectionResponse, SectionInfoMsg, SectionKnowledge},
     DstInfo, DstLocation, MessageType, SectionAuthorityProvider,
 };
 use crate::routing::{
@@ -47,16 +47,20 @@ impl Core {
                 let response = if let (true, Ok(pk_set)) =
                     (self.section.prefix().matches(&name), self.public_key_set())
                 {
-                    GetSectionResponse::Success(SectionAuthorityProvider {
-                        prefix: self.section.authority_provider().prefix(),
-                        public_key_set: pk_set,
-                        elders: self
-                            .section
-                            .authority_provider()
-                            .peers()
-                            .map(|peer| (*peer.name(), *peer.addr()))
-                            .collect(),
-                    })
+                    GetSectionResponse::Success {
+                        section_auth: SectionAuthorityProvider {
+                            prefix: self.section.authority_provider().prefix(),
+                            public_key_set: pk_set,
+                            elders: self
+                                .section
+                                .authority_provider()
+                                .peers()
+                                .map(|peer| (*peer.name(), *peer.addr()))
+                                .collect(),
+                            network_params: self.section.network_params(),
+                        },
+                        genesis_key: *self.section.genesis_key(),
+                    }
                 } else {
                     // If we are elder, we should know a section that is closer to `name` that us.
                     // Otherwise redirect to our elders.
@@ -79,6 +83,35 @@ impl Core {
                     },
                 }]
             }
+            SectionInfoMsg::SectionKnowledgeQuery => {
+                debug!("Received SectionKnowledgeQuery from {}", sender);
+
+                let section_auth = self.section.section_signed_authority_provider();
+                let response = SectionInfoMsg::SectionKnowledgeResponse(SectionKnowledge {
+                    genesis_key: *self.section.genesis_key(),
+                    section_auth: section_auth.value.clone(),
+                    section_auth_sig: section_auth.sig.signature.clone(),
+                    section_chain: self.section.chain().clone(),
+                    adult_count: self.section.adults().count(),
+                    other_sections: self.network.all().cloned().collect(),
+                });
+
+                vec![Command::SendMessage {
+                    recipients: vec![(dst_info.dst, sender)],
+                    delivery_group_size: 1,
+                    message: MessageType::SectionInfo {
+                        msg: response,
+                        dst_info,
+                    },
+                }]
+            }
+            SectionInfoMsg::SectionKnowledgeResponse(knowledge) => {
+                error!(
+                    "SectionKnowledgeResponse unexpectedly received: {:?}",
+                    knowledge
+                );
+                vec![]
+            }
             SectionInfoMsg::GetSectionResponse(response) => {
                 error!("GetSectionResponse unexpectedly received: {:?}", response);
                 vec![]
@@ -34,7 +34,11 @@ impl Core {
             section.members()
         );
         self.section.merge(section.clone())?;
-        self.network.merge(network.clone(), self.section.chain());
+        self.network.merge(
+            network.clone(),
+            self.section.chain(),
+            &self.section.network_params(),
+        );
 
         if self.is_not_elder() {
             let current_adults: BTreeSet<_> = self
@@ -60,6 +60,7 @@ impl<'a> Serialize for SignableView<'a> {
                 message.as_signable().serialize(serializer)
             }
             Proposal::JoinsAllowed(joins_allowed) => joins_allowed.serialize(serializer),
+            Proposal::Demote(peer) => peer.serialize(serializer),
         }
     }
 }
@@ -329,6 +329,7 @@ impl Backlog {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::messaging::NetworkParams;
     use crate::routing::{
         dkg::voter::DkgVoter, dkg::DkgKeyUtils, ed25519, node::test_utils::arbitrary_unique_nodes,
         node::Node, section::section_authority_provider::ElderCandidatesUtils,
@@ -350,7 +351,11 @@ mod tests {
             ed25519::gen_keypair(&Prefix::default().range_inclusive(), MIN_ADULT_AGE),
             gen_addr(),
         );
-        let elder_candidates = ElderCandidates::new(iter::once(node.peer()), Prefix::default());
+        let elder_candidates = ElderCandidates::new(
+            iter::once(node.peer()),
+            Prefix::default(),
+            NetworkParams::default(),
+        );
         let dkg_key = DkgKey::new(&elder_candidates, 0);
 
         let commands = voter.start(&node.keypair, dkg_key, elder_candidates);
@@ -372,8 +377,11 @@ mod tests {
         let mut rng = SmallRng::seed_from_u64(seed);
         let mut messages = Vec::new();
 
-        let elder_candidates =
-            ElderCandidates::new(nodes.iter().map(Node::peer), Prefix::default());
+        let elder_candidates = ElderCandidates::new(
+            nodes.iter().map(Node::peer),
+            Prefix::default(),
+            NetworkParams::default(),
+        );
         let dkg_key = DkgKey::new(&elder_candidates, 0);
 
         let mut actors: HashMap<_, _> = nodes
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::messaging::NetworkParams;
 use crate::routing::{dkg::ProposalError, messages::ExtendSignedChainError};
 use qp2p::Error as Qp2pError;
 use secured_linked_list::error::Error as SecuredLinkedListError;
@@ -75,4 +76,11 @@ pub enum Error {
     NoMatchingElder,
     #[error("Node cannot join the network since it is not externally reachable: {0}")]
     NodeNotReachable(SocketAddr),
+    #[error("Invalid network parameters: {0:?}")]
+    InvalidNetworkParams(Box<NetworkParams>),
+    #[error("Network parameters mismatch: expected {expected:?}, section has {actual:?}")]
+    NetworkParamsMismatch {
+        expected: Box<NetworkParams>,
+        actual: Box<NetworkParams>,
+    },
 }
@@ -25,6 +25,7 @@
 // ############################################################################
 pub use self::{
     cache::Cache,
+    core::ElderHealthThresholds,
     error::{Error, Result},
     event::{Event, NodeElderChange, SendStream},
     peer::PeerUtils,
@@ -34,6 +35,7 @@ pub use self::{
         MIN_AGE,
     },
 };
+pub use crate::messaging::{FaucetParams, NetworkParams, RewardPolicyKind};
 pub use qp2p::Config as TransportConfig;
 
 pub use xor_name::{Prefix, XorName, XOR_NAME_LEN}; // TODO remove pub on API update
@@ -56,14 +58,16 @@ mod relocation;
 mod routing_api;
 mod section;
 
-/// Recommended section size. sn_routing will keep adding nodes until the section reaches this size.
+/// Default recommended section size, used unless the genesis node sets different
+/// `NetworkParams`. sn_routing will keep adding nodes until the section reaches this size.
 /// More nodes might be added if requested by the upper layers.
 /// This number also detemines when split happens - if both post-split sections would have at least
 /// this number of nodes.
 pub const RECOMMENDED_SECTION_SIZE: usize = 2 * ELDER_SIZE;
 
-/// Number of elders per section.
-pub const ELDER_SIZE: usize = 7;
+/// Default number of elders per section, used unless the genesis node sets different
+/// `NetworkParams`.
+pub const ELDER_SIZE: usize = NetworkParams::DEFAULT_ELDER_SIZE;
 
 /// SuperMajority of a given group (i.e. > 2/3)
 #[inline]
@@ -20,7 +20,7 @@ use crate::routing::{
 
 use crate::messaging::{
     node::{Network, OtherSection, Peer, PrefixMap, SectionSigned},
-    SectionAuthorityProvider,
+    NetworkParams, SectionAuthorityProvider,
 };
 use secured_linked_list::SecuredLinkedList;
 use std::iter;
@@ -43,10 +43,16 @@ pub trait NetworkUtils {
     /// Returns a `Peer` of an elder from a known section.
     fn get_elder(&self, name: &XorName) -> Option<Peer>;
 
-    /// Merge two `Network`s into one.
+    /// Merge two `Network`s into one. Sections not carrying the given `network_params`, agreed at
+    /// genesis, are skipped.
     /// TODO: make this operation commutative, associative and idempotent (CRDT)
     /// TODO: return bool indicating whether anything changed.
-    fn merge(&mut self, other: Network, section_chain: &SecuredLinkedList);
+    fn merge(
+        &mut self,
+        other: Network,
+        section_chain: &SecuredLinkedList,
+        network_params: &NetworkParams,
+    );
 
     /// Update the info about a section.
     ///
@@ -57,11 +63,14 @@ pub trait NetworkUtils {
     /// If this is for a non-sibling section, then currently we require the info to be signed by our
     /// section (so we need to accumulate the signature for it first) and so `key_sig` is not
     /// needed in that case.
+    ///
+    /// The info is rejected unless it carries the given `network_params`, agreed at genesis.
     fn update_section(
         &mut self,
         section_auth: SectionSigned<SectionAuthorityProvider>,
         key_sig: Option<KeyedSig>,
         section_chain: &SecuredLinkedList,
+        network_params: &NetworkParams,
     ) -> bool;
 
     /// Returns the known section keys.
@@ -132,10 +141,18 @@ impl NetworkUtils for Network {
     /// Merge two `Network`s into one.
     /// TODO: make this operation commutative, associative and idempotent (CRDT)
     /// TODO: return bool indicating whether anything changed.
-    fn merge(&mut self, other: Network, section_chain: &SecuredLinkedList) {
+    fn merge(
+        &mut self,
+        other: Network,
+        section_chain: &SecuredLinkedList,
+        network_params: &NetworkParams,
+    ) {
         // FIXME: these operations are not commutative:
 
         for entry in other.sections {
+            if !has_network_params(&entry.section_auth.value, network_params) {
+                continue;
+            }
             if entry.verify(section_chain) {
                 let _ = self.sections.insert(entry);
             }
@@ -156,7 +173,12 @@ impl NetworkUtils for Network {
         section_auth: SectionSigned<SectionAuthorityProvider>,
         key_sig: Option<KeyedSig>,
         section_chain: &SecuredLinkedList,
+        network_params: &NetworkParams,
     ) -> bool {
+        if !has_network_params(&section_auth.value, network_params) {
+            return false;
+        }
+
         let info = OtherSection {
             section_auth: section_auth.clone(),
             key_sig,
@@ -288,6 +310,21 @@ impl OtherSectionUtils for OtherSection {
     }
 }
 
+// Whether the section carries the network parameters agreed at genesis, logging it if not.
+fn has_network_params(
+    section_auth: &SectionAuthorityProvider,
+    network_params: &NetworkParams,
+) -> bool {
+    if section_auth.network_params == *network_params {
+        return true;
+    }
+    error!(
+        "Section {:?} has network params {:?} instead of {:?}",
+        section_auth.prefix, section_auth.network_params, network_params
+    );
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,8 +342,9 @@ mod tests {
 
         // Create map containing sections (00), (01) and (10)
         let mut map = Network::new();
-        let _ = map.update_section(gen_section_auth(&sk, p01)?, None, &chain);
-        let _ = map.update_section(gen_section_auth(&sk, p10)?, None, &chain);
+        let params = NetworkParams::default();
+        let _ = map.update_section(gen_section_auth(&sk, p01)?, None, &chain, &params);
+        let _ = map.update_section(gen_section_auth(&sk, p10)?, None, &chain, &params);
 
         let mut rng = rand::thread_rng();
         let n01 = p01.substituted_in(rng.gen());
@@ -320,6 +358,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn reject_section_with_other_network_params() -> Result<()> {
+        let sk = bls::SecretKey::random();
+        let chain = SecuredLinkedList::new(sk.public_key());
+        let prefix: Prefix = "01".parse().unwrap();
+
+        let (mut section_auth, _, _) =
+            section::test_utils::gen_section_authority_provider(prefix, 5);
+        section_auth.network_params.elder_size += 1;
+        let section_auth = dkg::test_utils::section_signed(&sk, section_auth)?;
+
+        let mut map = Network::new();
+        let params = NetworkParams::default();
+        assert!(!map.update_section(section_auth.clone(), None, &chain, &params));
+        assert!(map.get(&prefix).is_none());
+
+        let mut other = Network::new();
+        let _ = other.sections.insert(OtherSection {
+            section_auth,
+            key_sig: None,
+        });
+        map.merge(other, &chain, &params);
+        assert!(map.get(&prefix).is_none());
+
+        Ok(())
+    }
+
     fn gen_section_auth(
         sk: &bls::SecretKey,
         prefix: Prefix,
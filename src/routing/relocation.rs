@@ -188,7 +188,7 @@ pub(crate) enum RelocateState {
     // while being an elder. It must keep fulfilling its duties as elder until its demoted, then it
     // can send the bytes (which are serialized `RelocatePromise` message) back to the elders who
     // will exchange it for an actual `Relocate` message.
-    Delayed(RoutingMsg),
+    Delayed(Box<RoutingMsg>),
     // Relocation in progress.
     InProgress(Box<JoiningAsRelocated>),
 }
@@ -265,7 +265,7 @@ fn trailing_zeros(bytes: &[u8]) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::messaging::SectionAuthorityProvider;
+    use crate::messaging::{NetworkParams, SectionAuthorityProvider};
     use crate::routing::{
         dkg::test_utils::section_signed,
         peer::test_utils::arbitrary_unique_peers,
@@ -328,6 +328,7 @@ mod tests {
                 .copied(),
             Prefix::default(),
             sk_set.public_keys(),
+            NetworkParams::default(),
         );
         let section_auth = section_signed(sk, section_auth)?;
 
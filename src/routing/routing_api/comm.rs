@@ -6,8 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::messaging::MessageType;
+use crate::messaging::{MessageType, TraceContext};
 use crate::routing::error::{Error, Result};
+use crate::types::ContactCache;
 use bytes::Bytes;
 use futures::stream::{FuturesUnordered, StreamExt};
 use hex_fmt::HexFmt;
@@ -17,9 +18,20 @@ use std::{
     net::SocketAddr,
     sync::RwLock,
 };
-use tokio::{sync::mpsc, task};
+use tokio::{
+    sync::mpsc,
+    task,
+    time::{self, Duration, Instant},
+};
 use xor_name::XorName;
 
+// Overall time allowed to bootstrap to the network.
+const BOOTSTRAP_DEADLINE: Duration = Duration::from_secs(60);
+// Time given to each round of contacts to accept our connection before trying the next ones.
+const BOOTSTRAP_ROUND_TIMEOUT: Duration = Duration::from_secs(10);
+// Number of contacts raced in each bootstrap round.
+const BOOTSTRAP_PARALLELISM: usize = 4;
+
 // Communication component of the node to interact with other nodes.
 pub(crate) struct Comm {
     _quic_p2p: QuicP2p,
@@ -65,20 +77,41 @@ impl Comm {
         })
     }
 
+    // Bootstraps to the network through the contacts, racing them in rounds best scored first
+    // until one accepts our connection or `BOOTSTRAP_DEADLINE` passes. The contacts are scored in
+    // `contact_cache` as they are tried.
     pub async fn bootstrap(
         transport_config: qp2p::Config,
         event_tx: mpsc::Sender<ConnectionEvent>,
+        contact_cache: &mut ContactCache,
     ) -> Result<(Self, SocketAddr)> {
-        let quic_p2p = QuicP2p::with_config(Some(transport_config), &[], true)
+        let rounds = contact_cache
+            .bootstrap_rounds(&transport_config.hard_coded_contacts, BOOTSTRAP_PARALLELISM);
+        let mut quic_p2p = QuicP2p::with_config(Some(transport_config), &[], true)
             .map_err(|err| Error::InvalidConfig { err })?;
 
-        // Bootstrap to the network returning the connection to a node.
-        // We can use the returned channels to listen for incoming messages and disconnection events
-        let (endpoint, _incoming_connections, incoming_messages, disconnections, bootstrap_addr) =
-            quic_p2p
-                .bootstrap()
-                .await
-                .map_err(|err| Error::CannotConnectEndpoint { err })?;
+        // Create the endpoint first, then bootstrap it through the contacts, returning the
+        // connection to a node. We can use the returned channels to listen for incoming messages
+        // and disconnection events.
+        let (endpoint, _incoming_connections, incoming_messages, disconnections) = quic_p2p
+            .new_endpoint()
+            .await
+            .map_err(|err| Error::CannotConnectEndpoint { err })?;
+
+        let bootstrap_addr = time::timeout(
+            BOOTSTRAP_DEADLINE,
+            bootstrap_through(&mut quic_p2p, &endpoint, rounds, contact_cache),
+        )
+        .await
+        .map_err(|_| {
+            warn!(
+                "Could not bootstrap to the network within {:?}",
+                BOOTSTRAP_DEADLINE
+            );
+            Error::CannotConnectEndpoint {
+                err: qp2p::Error::BootstrapFailure,
+            }
+        })??;
 
         let _ = task::spawn(handle_incoming_messages(
             incoming_messages,
@@ -172,10 +205,22 @@ impl Comm {
     /// on if the minimum delivery group size is met or not. The failed recipients are sent along
     /// with the status. It returns a `SendStatus::AllRecipients` if message is sent to all the recipients.
     pub async fn send(
+        &self,
+        recipients: &[(XorName, SocketAddr)],
+        delivery_group_size: usize,
+        msg: MessageType,
+    ) -> Result<SendStatus> {
+        self.send_with_trace(recipients, delivery_group_size, msg, None)
+            .await
+    }
+
+    /// Same as `send`, but carrying the given trace context in the header of the message.
+    pub async fn send_with_trace(
         &self,
         recipients: &[(XorName, SocketAddr)],
         delivery_group_size: usize,
         mut msg: MessageType,
+        trace: Option<TraceContext>,
     ) -> Result<SendStatus> {
         trace!(
             "Sending message to {} of {:?}",
@@ -200,7 +245,19 @@ impl Comm {
         // So that only one copy of MessageType need to be constructed.
         msg.update_dst_info(None, Some(recipients[0].0));
 
-        let msg_bytes = msg.serialize().map_err(Error::Messaging)?;
+        let mut wire_msg = msg.to_wire_msg().map_err(Error::Messaging)?;
+        if let Some(trace) = trace {
+            debug!(
+                trace_id = %trace.trace_id,
+                hop = trace.hop_count,
+                "Sending traced message {:?} to {} of {:?}",
+                wire_msg.msg_id(),
+                delivery_group_size,
+                recipients
+            );
+        }
+        wire_msg.set_trace(trace);
+        let msg_bytes = wire_msg.serialize().map_err(Error::Messaging)?;
 
         // Run all the sends concurrently (using `FuturesUnordered`). If any of them fails, pick
         // the next recipient and try to send to them. Proceed until the needed number of sends
@@ -303,6 +360,46 @@ impl Debug for ConnectionEvent {
     }
 }
 
+// Races each round of contacts in turn, returning the first one we manage to connect to.
+async fn bootstrap_through(
+    quic_p2p: &mut QuicP2p,
+    endpoint: &Endpoint,
+    rounds: Vec<Vec<SocketAddr>>,
+    contact_cache: &mut ContactCache,
+) -> Result<SocketAddr> {
+    if rounds.is_empty() {
+        return Err(Error::CannotConnectEndpoint {
+            err: qp2p::Error::EmptyBootstrapNodesList,
+        });
+    }
+
+    for round in rounds {
+        trace!("Bootstrapping through contacts {:?}", round);
+        let started_at = Instant::now();
+        match time::timeout(
+            BOOTSTRAP_ROUND_TIMEOUT,
+            quic_p2p.rebootstrap(endpoint, &round),
+        )
+        .await
+        {
+            Ok(Ok(bootstrap_addr)) => {
+                contact_cache.record_success(bootstrap_addr, started_at.elapsed());
+                return Ok(bootstrap_addr);
+            }
+            Ok(Err(err)) => debug!("Failed to bootstrap through {:?}: {}", round, err),
+            Err(_) => debug!("Timeout bootstrapping through {:?}", round),
+        }
+
+        for contact in round {
+            contact_cache.record_failure(contact);
+        }
+    }
+
+    Err(Error::CannotConnectEndpoint {
+        err: qp2p::Error::BootstrapFailure,
+    })
+}
+
 async fn handle_disconnection_events(
     mut disconnections: qp2p::DisconnectionEvents,
     event_tx: mpsc::Sender<ConnectionEvent>,
@@ -6,7 +6,10 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{Comm, Command};
+use super::{
+    message_tracer::{record_trace, MessageTracer},
+    Comm, Command,
+};
 use crate::messaging::{
     node::{
         JoinAsRelocatedResponse, JoinRejectionReason, JoinResponse, RoutingMsg, Section,
@@ -26,12 +29,13 @@ use tokio::{
     sync::{watch, RwLock},
     time,
 };
-use tracing::Instrument;
+use tracing::{field, Instrument};
 
 // `Command` Dispatcher.
 pub(crate) struct Dispatcher {
     pub(super) core: RwLock<Core>,
     pub(super) comm: Comm,
+    pub(super) tracer: MessageTracer,
 
     cancel_timer_tx: watch::Sender<bool>,
     cancel_timer_rx: watch::Receiver<bool>,
@@ -43,11 +47,18 @@ impl Dispatcher {
         Self {
             core: RwLock::new(state),
             comm,
+            tracer: MessageTracer::new(false),
             cancel_timer_tx,
             cancel_timer_rx,
         }
     }
 
+    /// Sets whether to start a trace for every message we originate.
+    pub fn with_message_tracing(mut self, trace_own_messages: bool) -> Self {
+        self.tracer = MessageTracer::new(trace_own_messages);
+        self
+    }
+
     /// Send provided Event to the user which shall receive it through the EventStream
     pub async fn send_event(&self, event: Event) {
         self.core.read().await.send_event(event).await
@@ -77,9 +88,25 @@ impl Dispatcher {
                 prefix = format_args!("({:b})", state.section().prefix()),
                 age = state.node().age(),
                 elder = state.is_elder(),
+                trace_id = field::Empty,
+                hop = field::Empty,
             )
         };
 
+        // Tag the handling of traced messages with their trace, so their path can be followed
+        // across the logs of all the nodes they went through.
+        let traced_msg_id = match &command {
+            Command::HandleMessage { message, .. }
+            | Command::SendMessage {
+                message: MessageType::Routing { msg: message, .. },
+                ..
+            } => Some(message.id),
+            _ => None,
+        };
+        if let Some(msg_id) = traced_msg_id {
+            record_trace(&span, self.tracer.current(&msg_id).await);
+        }
+
         async {
             trace!(?command);
 
@@ -293,9 +320,14 @@ impl Dispatcher {
     ) -> Result<Vec<Command>> {
         let cmds = match message {
             MessageType::Node { .. } | MessageType::Routing { .. } => {
+                let trace = if let MessageType::Routing { msg, .. } = &message {
+                    self.tracer.outgoing(&msg.id).await
+                } else {
+                    None
+                };
                 let status = self
                     .comm
-                    .send(recipients, delivery_group_size, message)
+                    .send_with_trace(recipients, delivery_group_size, message, trace)
                     .await?;
                 match status {
                     SendStatus::MinDeliveryGroupSizeReached(failed_recipients)
@@ -365,7 +397,8 @@ impl Dispatcher {
 
         let mut state = self.core.write().await;
         let event_tx = state.event_tx.clone();
-        *state = Core::new(new_node, new_section, None, event_tx);
+        let elder_health = state.elder_health_thresholds();
+        *state = Core::new(new_node, new_section, None, event_tx).with_elder_health(elder_health);
 
         state
             .send_event(Event::Relocated {
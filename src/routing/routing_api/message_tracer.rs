@@ -0,0 +1,105 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::messaging::{MessageId, TraceContext};
+use crate::routing::cache::Cache;
+use std::time::Duration;
+use tracing::{field, Span};
+
+const EXPIRY_DURATION: Duration = Duration::from_secs(10 * 60);
+const MAX_ENTRIES: usize = 15_000;
+
+// Keeps the trace context of the messages being traced, so it's carried on when they are relayed
+// and can be attached to the spans of their handling. If enabled, it also starts a new trace for
+// every message we originate.
+pub(crate) struct MessageTracer {
+    trace_own_messages: bool,
+    traces: Cache<MessageId, TraceContext>,
+}
+
+impl MessageTracer {
+    pub fn new(trace_own_messages: bool) -> Self {
+        Self {
+            trace_own_messages,
+            traces: Cache::with_expiry_duration_and_capacity(EXPIRY_DURATION, MAX_ENTRIES),
+        }
+    }
+
+    // Records the trace context a message reached us with.
+    pub async fn record_incoming(&self, msg_id: MessageId, trace: TraceContext) {
+        let _ = self.traces.set(msg_id, trace, None).await;
+    }
+
+    // Returns the trace context of the message as we hold it, if it's being traced.
+    pub async fn current(&self, msg_id: &MessageId) -> Option<TraceContext> {
+        self.traces.get(msg_id).await
+    }
+
+    // Returns the trace context to send the message on with, if it's being traced. Messages not
+    // seen before are ours, and get a new trace if we trace our own messages.
+    pub async fn outgoing(&self, msg_id: &MessageId) -> Option<TraceContext> {
+        if let Some(trace) = self.traces.get(msg_id).await {
+            return Some(trace.next_hop());
+        }
+
+        if !self.trace_own_messages {
+            return None;
+        }
+
+        let trace = TraceContext::new();
+        let _ = self.traces.set(*msg_id, trace, None).await;
+        Some(trace.next_hop())
+    }
+}
+
+// Records the trace context on the `trace_id` and `hop` fields of `span`, which must have been
+// created with them (as `field::Empty`).
+pub(crate) fn record_trace(span: &Span, trace: Option<TraceContext>) {
+    if let Some(trace) = trace {
+        let _ = span.record("trace_id", &field::display(trace.trace_id));
+        let _ = span.record("hop", &trace.hop_count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn relayed_message_keeps_trace() {
+        let tracer = MessageTracer::new(false);
+        let msg_id = MessageId::new();
+        let trace = TraceContext::new().next_hop();
+
+        assert_eq!(tracer.outgoing(&msg_id).await, None);
+
+        tracer.record_incoming(msg_id, trace).await;
+        assert_eq!(tracer.current(&msg_id).await, Some(trace));
+
+        let outgoing = tracer.outgoing(&msg_id).await;
+        assert_eq!(outgoing.map(|trace| trace.trace_id), Some(trace.trace_id));
+        assert_eq!(outgoing.map(|trace| trace.hop_count), Some(2));
+    }
+
+    #[tokio::test]
+    async fn own_message_starts_trace() {
+        let tracer = MessageTracer::new(true);
+        let msg_id = MessageId::new();
+
+        let first = tracer.outgoing(&msg_id).await;
+        assert_eq!(first.map(|trace| trace.hop_count), Some(1));
+        assert_eq!(
+            tracer.current(&msg_id).await.map(|trace| trace.hop_count),
+            Some(0)
+        );
+
+        // Resending the message doesn't start a new trace.
+        assert_eq!(tracer.outgoing(&msg_id).await, first);
+        assert_ne!(tracer.outgoing(&MessageId::new()).await, first);
+    }
+}
@@ -14,17 +14,20 @@ pub(crate) mod command;
 
 mod dispatcher;
 mod event_stream;
+mod message_tracer;
 
 pub use self::event_stream::EventStream;
 use self::{
     comm::{Comm, ConnectionEvent},
     command::Command,
     dispatcher::Dispatcher,
+    message_tracer::record_trace,
 };
 use crate::messaging::{
-    node::Peer, DstLocation, EndUser, Itinerary, MessageType, SectionAuthorityProvider, WireMsg,
+    node::Peer, DstLocation, EndUser, Itinerary, MessageType, NetworkParams,
+    SectionAuthorityProvider, WireMsg,
 };
-use crate::routing::core::{join_network, Core};
+use crate::routing::core::{join_network, Core, ElderHealthThresholds};
 use crate::routing::{
     ed25519,
     error::Result,
@@ -36,6 +39,7 @@ use crate::routing::{
     section::{SectionAuthorityProviderUtils, SectionUtils},
     Error, TransportConfig, MIN_ADULT_AGE,
 };
+use crate::types::{ContactCache, CONTACT_CACHE_FILE};
 use bytes::Bytes;
 use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, KEYPAIR_LENGTH};
 use itertools::Itertools;
@@ -44,10 +48,12 @@ use std::{
     collections::BTreeSet,
     fmt::{self, Debug, Formatter},
     net::SocketAddr,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
 use tokio::{sync::mpsc, task};
+use tracing::field;
 use xor_name::{Prefix, XorName};
 
 /// Routing configuration.
@@ -59,6 +65,17 @@ pub struct Config {
     pub keypair: Option<Keypair>,
     /// Configuration for the underlying network transport.
     pub transport_config: TransportConfig,
+    /// Network parameters. When starting a new network these are the parameters every section
+    /// will use (`None` meaning `NetworkParams::default()`). When joining, the node refuses to
+    /// join a network whose parameters differ from these (`None` accepting any valid ones).
+    pub network_params: Option<NetworkParams>,
+    /// If true, every message this node originates carries a trace context in its header, which
+    /// the nodes it goes through attach to the spans of its relay, aggregation and delivery.
+    pub trace_messages: bool,
+    /// Thresholds past which this node, as an elder, proposes other elders of its section offline
+    /// for failing DKG or sending invalid signature shares, or demotes them for not signing
+    /// proposals in time.
+    pub elder_health: ElderHealthThresholds,
 }
 
 impl Default for Config {
@@ -67,6 +84,9 @@ impl Default for Config {
             first: false,
             keypair: None,
             transport_config: TransportConfig::default(),
+            network_params: None,
+            trace_messages: false,
+            elder_health: ElderHealthThresholds::default(),
         }
     }
 }
@@ -112,7 +132,9 @@ impl Routing {
 
             let comm = Comm::new(config.transport_config, connection_event_tx).await?;
             let node = Node::new(keypair, comm.our_connection_info());
-            let state = Core::first_node(node, event_tx)?;
+            let network_params = config.network_params.unwrap_or_default();
+            let state = Core::first_node(node, network_params, event_tx)?
+                .with_elder_health(config.elder_health);
 
             let section = state.section();
 
@@ -134,20 +156,55 @@ impl Routing {
             (state, comm, vec![])
         } else {
             info!("{} Bootstrapping a new node.", node_name);
-            let (comm, bootstrap_addr) =
-                Comm::bootstrap(config.transport_config, connection_event_tx).await?;
+            let contact_cache_path = contact_cache_path(&config.transport_config);
+            let mut contact_cache = contact_cache_path
+                .as_deref()
+                .map(ContactCache::load_or_default)
+                .unwrap_or_default();
+            let bootstrap_result = Comm::bootstrap(
+                config.transport_config,
+                connection_event_tx,
+                &mut contact_cache,
+            )
+            .await;
+            store_contact_cache(&contact_cache, contact_cache_path.as_deref());
+            let (comm, bootstrap_addr) = bootstrap_result?;
             let node = Node::new(keypair, comm.our_connection_info());
-            let (node, section, backlog) =
-                join_network(node, &comm, &mut connection_event_rx, bootstrap_addr).await?;
-            let state = Core::new(node, section, None, event_tx);
+            let (node, section, backlog) = join_network(
+                node,
+                &comm,
+                &mut connection_event_rx,
+                bootstrap_addr,
+                config.network_params,
+            )
+            .await?;
+
+            // Remember the Elders of the section we joined, to bootstrap through next time.
+            let section_auth = section.authority_provider();
+            contact_cache.refresh(
+                *section.genesis_key(),
+                section_auth.prefix,
+                section_auth.elders.values().copied(),
+            );
+            store_contact_cache(&contact_cache, contact_cache_path.as_deref());
+
+            let state =
+                Core::new(node, section, None, event_tx).with_elder_health(config.elder_health);
 
             (state, comm, backlog)
         };
 
-        let dispatcher = Arc::new(Dispatcher::new(state, comm));
+        let dispatcher =
+            Arc::new(Dispatcher::new(state, comm).with_message_tracing(config.trace_messages));
         let event_stream = EventStream::new(event_rx);
         info!("{} Bootstrapped!", node_name);
 
+        // The genesis node starts out as an elder, so checks its fellow elders' health from now.
+        if config.first {
+            let command = dispatcher.core.write().await.schedule_elder_health_check();
+            dispatcher.clone().handle_commands(command).await?;
+        }
+
         // Process message backlog
         for (message, sender, dst_info) in backlog {
             dispatcher
@@ -254,6 +311,11 @@ impl Routing {
         self.dispatcher.core.read().await.section_chain().clone()
     }
 
+    /// The genesis key of the network
+    pub async fn genesis_key(&self) -> bls::PublicKey {
+        *self.dispatcher.core.read().await.section().genesis_key()
+    }
+
     /// Prefix of our section
     pub async fn our_prefix(&self) -> Prefix {
         *self.dispatcher.core.read().await.section().prefix()
@@ -446,6 +508,29 @@ impl Debug for Routing {
     }
 }
 
+// The contact cache lives in the bootstrap cache directory if one was configured, otherwise in
+// the node's default directory.
+fn contact_cache_path(transport_config: &TransportConfig) -> Option<PathBuf> {
+    transport_config
+        .bootstrap_cache_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| dirs_next::home_dir().map(|home| home.join(".safe").join("node")))
+        .map(|dir| dir.join(CONTACT_CACHE_FILE))
+}
+
+fn store_contact_cache(contact_cache: &ContactCache, path: Option<&Path>) {
+    if let Some(path) = path {
+        if let Err(err) = contact_cache.store(path) {
+            warn!(
+                "Failed to store contact cache at {}: {}",
+                path.display(),
+                err
+            );
+        }
+    }
+}
+
 // Listen for incoming connection events and handle them.
 async fn handle_connection_events(
     dispatcher: Arc<Dispatcher>,
@@ -487,7 +572,21 @@ async fn handle_message(dispatcher: Arc<Dispatcher>, bytes: Bytes, sender: Socke
             return;
         }
 
-        trace_span!("handle_message", name = %state.node().name(), %sender)
+        let span = trace_span!(
+            "handle_message",
+            name = %state.node().name(),
+            %sender,
+            trace_id = field::Empty,
+            hop = field::Empty,
+        );
+        if let Some(trace) = wire_msg.trace() {
+            dispatcher
+                .tracer
+                .record_incoming(wire_msg.msg_id(), trace)
+                .await;
+            record_trace(&span, Some(trace));
+        }
+        span
     };
     let _span_guard = span.enter();
 
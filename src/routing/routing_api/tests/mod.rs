@@ -16,7 +16,8 @@ use crate::messaging::{
         ResourceProofResponse, RoutingMsg, Section, SectionSigned, SignedRelocateDetails, Variant,
     },
     section_info::{GetSectionResponse, SectionInfoMsg},
-    DstInfo, DstLocation, MessageId, MessageType, SectionAuthorityProvider, SrcLocation,
+    DstInfo, DstLocation, MessageId, MessageType, NetworkParams, SectionAuthorityProvider,
+    SrcLocation,
 };
 use crate::routing::{
     core::{RESOURCE_PROOF_DATA_SIZE, RESOURCE_PROOF_DIFFICULTY},
@@ -36,7 +37,7 @@ use crate::routing::{
         SectionKeyShare, SectionPeersUtils, SectionUtils, FIRST_SECTION_MIN_AGE, MIN_ADULT_AGE,
         MIN_AGE,
     },
-    supermajority, ELDER_SIZE,
+    supermajority, Error, ELDER_SIZE,
 };
 use crate::types::{Keypair, PublicKey};
 use anyhow::Result;
@@ -57,10 +58,43 @@ use xor_name::{Prefix, XorName};
 
 static TEST_EVENT_CHANNEL_SIZE: usize = 20;
 
+#[tokio::test]
+async fn first_node_sets_network_params() -> Result<()> {
+    let network_params = NetworkParams::new(3, 5);
+    let state = Core::first_node(
+        create_node(MIN_ADULT_AGE),
+        network_params,
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+    )?;
+
+    assert_eq!(state.section().network_params(), network_params);
+    assert_eq!(
+        state.section().authority_provider().network_params,
+        network_params
+    );
+
+    let invalid = NetworkParams::new(5, 3);
+    let result = Core::first_node(
+        create_node(MIN_ADULT_AGE),
+        invalid,
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+    );
+    assert_matches!(
+        result.err(),
+        Some(Error::InvalidNetworkParams(params)) if *params == invalid
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn receive_matching_get_section_request_as_elder() -> Result<()> {
     let node = create_node(MIN_ADULT_AGE);
-    let state = Core::first_node(node, mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0)?;
+    let state = Core::first_node(
+        node,
+        NetworkParams::default(),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+    )?;
     let dispatcher = Dispatcher::new(state, create_comm().await?);
 
     let new_node_comm = create_comm().await?;
@@ -166,6 +200,62 @@ async fn receive_mismatching_get_section_request_as_adult() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn receive_section_knowledge_query() -> Result<()> {
+    let node = create_node(MIN_ADULT_AGE);
+    let state = Core::first_node(
+        node,
+        NetworkParams::default(),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+    )?;
+    let dispatcher = Dispatcher::new(state, create_comm().await?);
+
+    let crawler_comm = create_comm().await?;
+    let crawler_addr = crawler_comm.our_connection_info();
+    let crawler_name = XorName::random();
+
+    let mut commands = dispatcher
+        .handle_command(Command::HandleSectionInfoMsg {
+            sender: crawler_addr,
+            message: SectionInfoMsg::SectionKnowledgeQuery,
+            dst_info: DstInfo {
+                dst: crawler_name,
+                dst_section_pk: bls::SecretKey::random().public_key(),
+            },
+        })
+        .await?
+        .into_iter();
+
+    let (recipients, message) = assert_matches!(
+        commands.next(),
+        Some(Command::SendMessage {
+            recipients,
+            message: MessageType::SectionInfo { msg, .. }, ..
+        }) => (recipients, msg)
+    );
+
+    assert_eq!(recipients, [(crawler_name, crawler_addr)]);
+    let knowledge = assert_matches!(
+        message,
+        SectionInfoMsg::SectionKnowledgeResponse(knowledge) => knowledge
+    );
+    assert_eq!(knowledge.section_auth.prefix, Prefix::default());
+    assert_eq!(knowledge.section_chain.root_key(), &knowledge.genesis_key);
+    assert_eq!(
+        knowledge.section_chain.last_key(),
+        &knowledge.section_auth.public_key_set.public_key()
+    );
+    let bytes = bincode::serialize(&knowledge.section_auth)?;
+    assert!(knowledge
+        .section_chain
+        .last_key()
+        .verify(&knowledge.section_auth_sig, &bytes));
+    assert_eq!(knowledge.adult_count, 0);
+    assert!(knowledge.other_sections.is_empty());
+
+    Ok(())
+}
+
 // TODO: add test `receive_mismatching_get_section_request_as_elder` - should respond with
 // `Redirect` response containing addresses of nodes in a section that is closer to the joining
 // name.
@@ -174,7 +264,11 @@ async fn receive_mismatching_get_section_request_as_adult() -> Result<()> {
 async fn receive_join_request_without_resource_proof_response() -> Result<()> {
     let node = create_node(FIRST_SECTION_MIN_AGE);
     let node_name = node.name();
-    let state = Core::first_node(node, mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0)?;
+    let state = Core::first_node(
+        node,
+        NetworkParams::default(),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+    )?;
     let dispatcher = Dispatcher::new(state, create_comm().await?);
 
     let new_node_comm = create_comm().await?;
@@ -228,7 +322,11 @@ async fn receive_join_request_without_resource_proof_response() -> Result<()> {
 async fn receive_join_request_with_resource_proof_response() -> Result<()> {
     let node = create_node(FIRST_SECTION_MIN_AGE);
     let node_name = node.name();
-    let state = Core::first_node(node, mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0)?;
+    let state = Core::first_node(
+        node,
+        NetworkParams::default(),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+    )?;
     let dispatcher = Dispatcher::new(state, create_comm().await?);
 
     let new_node = Node::new(
@@ -519,6 +617,7 @@ async fn handle_agreement_on_online_of_elder_candidate() -> Result<()> {
         nodes.iter().map(Node::peer),
         Prefix::default(),
         sk_set.public_keys(),
+        NetworkParams::default(),
     );
     let section_signed_section_auth = section_signed(sk_set.secret_key(), section_auth.clone())?;
 
@@ -864,6 +963,66 @@ async fn handle_agreement_on_offline_of_elder() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn handle_agreement_on_demote_of_elder() -> Result<()> {
+    let (section_auth, mut nodes) = create_section_auth();
+    let sk_set = SecretKeySet::random();
+
+    let (mut section, section_key_share) = create_section(&sk_set, &section_auth)?;
+
+    let existing_peer = create_peer(MIN_AGE);
+    let node_state = NodeState::joined(existing_peer);
+    let node_state = section_signed(sk_set.secret_key(), node_state)?;
+    let _ = section.update_member(node_state);
+
+    // Pick the elder to demote.
+    let demote_peer = section_auth.peers().last().expect("section_auth is empty");
+
+    // Create our node
+    let (event_tx, _event_rx) = mpsc::channel(TEST_EVENT_CHANNEL_SIZE);
+    let node = nodes.remove(0);
+    let state = Core::new(node, section, Some(section_key_share), event_tx);
+    let dispatcher = Dispatcher::new(state, create_comm().await?);
+
+    // Handle agreement on the Demote proposal
+    let proposal = Proposal::Demote(demote_peer);
+    let sig = prove(sk_set.secret_key(), &proposal.as_signable())?;
+
+    let commands = dispatcher
+        .handle_command(Command::HandleAgreement { proposal, sig })
+        .await?;
+
+    // Verify we sent a `DkgStart` message with the demoted elder replaced.
+    let expected_new_elders: BTreeSet<_> = section_auth
+        .peers()
+        .filter(|peer| *peer != demote_peer)
+        .chain(iter::once(existing_peer))
+        .collect();
+    let dkg_start_sent = commands.into_iter().any(|command| match command {
+        Command::SendMessage {
+            message: MessageType::Routing { msg, .. },
+            ..
+        } => matches!(
+            msg.variant,
+            Variant::DkgStart { elder_candidates, .. }
+                if elder_candidates.peers().eq(expected_new_elders.iter().copied())
+        ),
+        _ => false,
+    });
+    assert!(dkg_start_sent);
+
+    // The demoted elder stays a member of our section.
+    assert!(dispatcher
+        .core
+        .read()
+        .await
+        .section()
+        .members()
+        .is_joined(demote_peer.name()));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn handle_untrusted_message_from_peer() -> Result<()> {
     handle_untrusted_message(UntrustedMessageSource::Peer).await
@@ -1133,6 +1292,7 @@ async fn handle_sync() -> Result<()> {
             .chain(iter::once(new_peer)),
         old_section_auth.prefix,
         sk2_set.public_keys(),
+        NetworkParams::default(),
     );
     let new_section_elders: BTreeSet<_> = new_section_auth.names();
     let section_signed_new_section_auth = section_signed(sk2, new_section_auth)?;
@@ -1467,7 +1627,11 @@ enum MessageDst {
 async fn message_to_self(dst: MessageDst) -> Result<()> {
     let node = create_node(MIN_ADULT_AGE);
     let peer = node.peer();
-    let state = Core::first_node(node, mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0)?;
+    let state = Core::first_node(
+        node,
+        NetworkParams::default(),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+    )?;
     let dispatcher = Dispatcher::new(state, create_comm().await?);
     let section_name = XorName::random();
 
@@ -1533,6 +1697,7 @@ async fn handle_elders_update() -> Result<()> {
         iter::once(node.peer()).chain(other_elder_peers.clone()),
         Prefix::default(),
         sk_set0.public_keys(),
+        NetworkParams::default(),
     );
 
     let (mut section0, section_key_share) = create_section(&sk_set0, &section_auth0)?;
@@ -1555,6 +1720,7 @@ async fn handle_elders_update() -> Result<()> {
             .chain(iter::once(promoted_peer)),
         Prefix::default(),
         sk_set1.public_keys(),
+        NetworkParams::default(),
     );
     let elder_names1: BTreeSet<_> = section_auth1.names();
 
@@ -1654,6 +1820,7 @@ async fn handle_demote_during_split() -> Result<()> {
         iter::once(node.peer()).chain(peers_a.iter().copied()),
         Prefix::default(),
         sk_set_v0.public_keys(),
+        NetworkParams::default(),
     );
 
     let (mut section, section_key_share) = create_section(&sk_set_v0, &section_auth_v0)?;
@@ -1691,14 +1858,19 @@ async fn handle_demote_during_split() -> Result<()> {
         peers_a.iter().copied().chain(iter::once(peer_c)),
         prefix0,
         sk_set_v1_p0.public_keys(),
+        NetworkParams::default(),
     );
     let command = create_our_elders_command(sk_set_v1_p0.secret_key(), section_auth)?;
     let commands = dispatcher.handle_command(command).await?;
     assert_matches!(&commands[..], &[]);
 
     // Handle agreement on `OurElders` for prefix-1.
-    let section_auth =
-        SectionAuthorityProvider::new(peers_b.iter().copied(), prefix1, sk_set_v1_p1.public_keys());
+    let section_auth = SectionAuthorityProvider::new(
+        peers_b.iter().copied(),
+        prefix1,
+        sk_set_v1_p1.public_keys(),
+        NetworkParams::default(),
+    );
     let command = create_our_elders_command(sk_set_v1_p1.secret_key(), section_auth)?;
     let commands = dispatcher.handle_command(command).await?;
 
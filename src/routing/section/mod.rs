@@ -25,13 +25,12 @@ pub use self::{
 
 use crate::messaging::{
     node::{ElderCandidates, KeyedSig, NodeState, Peer, Section, SectionPeers, SectionSigned},
-    SectionAuthorityProvider,
+    NetworkParams, SectionAuthorityProvider,
 };
 use crate::routing::{
     dkg::SectionSignedUtils,
     error::{Error, Result},
     peer::PeerUtils,
-    ELDER_SIZE, RECOMMENDED_SECTION_SIZE,
 };
 use secured_linked_list::{error::Error as SecuredLinkedListError, SecuredLinkedList};
 use serde::Serialize;
@@ -51,8 +50,9 @@ pub trait SectionUtils {
     where
         Self: Sized;
 
-    /// Creates `Section` for the first node in the network
-    fn first_node(peer: Peer) -> Result<(Section, SectionKeyShare)>;
+    /// Creates `Section` for the first node in the network, fixing the `NetworkParams` every later
+    /// section will inherit.
+    fn first_node(peer: Peer, network_params: NetworkParams) -> Result<(Section, SectionKeyShare)>;
 
     fn genesis_key(&self) -> &bls::PublicKey;
 
@@ -81,13 +81,20 @@ pub trait SectionUtils {
 
     fn authority_provider(&self) -> &SectionAuthorityProvider;
 
+    /// Network parameters set at genesis, as carried in our `SectionAuthorityProvider`.
+    fn network_params(&self) -> NetworkParams;
+
     fn section_signed_authority_provider(&self) -> &SectionSigned<SectionAuthorityProvider>;
 
     fn is_elder(&self, name: &XorName) -> bool;
 
-    /// Generate a new section info(s) based on the current set of members.
-    /// Returns a set of candidate SectionAuthorityProviders.
-    fn promote_and_demote_elders(&self, our_name: &XorName) -> Vec<ElderCandidates>;
+    /// Generate a new section info(s) based on the current set of members, leaving out the
+    /// `excluded` ones. Returns a set of candidate SectionAuthorityProviders.
+    fn promote_and_demote_elders(
+        &self,
+        our_name: &XorName,
+        excluded: &BTreeSet<XorName>,
+    ) -> Vec<ElderCandidates>;
 
     // Prefix of our section.
     fn prefix(&self) -> &Prefix;
@@ -108,11 +115,15 @@ pub trait SectionUtils {
     // Tries to split our section.
     // If we have enough mature nodes for both subsections, returns the SectionAuthorityProviders
     // of the two subsections. Otherwise returns `None`.
-    fn try_split(&self, our_name: &XorName) -> Option<(ElderCandidates, ElderCandidates)>;
+    fn try_split(
+        &self,
+        our_name: &XorName,
+        excluded: &BTreeSet<XorName>,
+    ) -> Option<(ElderCandidates, ElderCandidates)>;
 
-    // Returns the candidates for elders out of all the nodes in the section, even out of the
-    // relocating nodes if there would not be enough instead.
-    fn elder_candidates(&self, elder_size: usize) -> Vec<Peer>;
+    // Returns the candidates for elders out of all the nodes in the section but the `excluded`
+    // ones, even out of the relocating nodes if there would not be enough instead.
+    fn elder_candidates(&self, elder_size: usize, excluded: &BTreeSet<XorName>) -> Vec<Peer>;
 }
 
 impl SectionUtils for Section {
@@ -139,14 +150,24 @@ impl SectionUtils for Section {
         })
     }
 
-    /// Creates `Section` for the first node in the network
-    fn first_node(peer: Peer) -> Result<(Section, SectionKeyShare)> {
+    /// Creates `Section` for the first node in the network, fixing the `NetworkParams` every later
+    /// section will inherit.
+    fn first_node(peer: Peer, network_params: NetworkParams) -> Result<(Section, SectionKeyShare)> {
+        if !network_params.is_valid() {
+            error!("can't create first section: invalid {:?}", network_params);
+            return Err(Error::InvalidNetworkParams(Box::new(network_params)));
+        }
+
         let secret_key_set = bls::SecretKeySet::random(0, &mut rand::thread_rng());
         let public_key_set = secret_key_set.public_keys();
         let secret_key_share = secret_key_set.secret_key_share(0);
 
-        let section_auth =
-            create_first_section_authority_provider(&public_key_set, &secret_key_share, peer)?;
+        let section_auth = create_first_section_authority_provider(
+            &public_key_set,
+            &secret_key_share,
+            peer,
+            network_params,
+        )?;
 
         let mut section = Section::new(
             section_auth.sig.public_key,
@@ -177,8 +198,21 @@ impl SectionUtils for Section {
     }
 
     /// Try to merge this `Section` with `other`. Returns `InvalidMessage` if `other` is invalid or
-    /// its chain is not compatible with the chain of `self`.
+    /// its chain is not compatible with the chain of `self`, and `NetworkParamsMismatch` if it
+    /// doesn't carry the network parameters agreed at genesis.
     fn merge(&mut self, other: Section) -> Result<()> {
+        let actual = other.section_auth.value.network_params;
+        if actual != self.network_params() {
+            error!(
+                "can't merge sections: other section has network params {:?} instead of {:?}",
+                actual,
+                self.network_params()
+            );
+            return Err(Error::NetworkParamsMismatch {
+                expected: Box::new(self.network_params()),
+                actual: Box::new(actual),
+            });
+        }
         if !other.section_auth.self_verify() {
             error!("can't merge sections: other section_auth failed self-verification");
             return Err(Error::InvalidMessage);
@@ -224,6 +258,15 @@ impl SectionUtils for Section {
             return false;
         }
 
+        if new_section_auth.value.network_params != self.network_params() {
+            error!(
+                "can't update elders: new section_auth has network params {:?} instead of {:?}",
+                new_section_auth.value.network_params,
+                self.network_params()
+            );
+            return false;
+        }
+
         if let Err(error) = self.chain.insert(
             &new_key_sig.public_key,
             new_section_auth.sig.public_key,
@@ -288,6 +331,11 @@ impl SectionUtils for Section {
         &self.section_auth.value
     }
 
+    /// Network parameters set at genesis, as carried in our `SectionAuthorityProvider`.
+    fn network_params(&self) -> NetworkParams {
+        self.section_auth.value.network_params
+    }
+
     fn section_signed_authority_provider(&self) -> &SectionSigned<SectionAuthorityProvider> {
         &self.section_auth
     }
@@ -298,12 +346,19 @@ impl SectionUtils for Section {
 
     /// Generate a new section info(s) based on the current set of members.
     /// Returns a set of candidate SectionAuthorityProviders.
-    fn promote_and_demote_elders(&self, our_name: &XorName) -> Vec<ElderCandidates> {
-        if let Some((our_elder_candidates, other_elder_candidates)) = self.try_split(our_name) {
+    fn promote_and_demote_elders(
+        &self,
+        our_name: &XorName,
+        excluded: &BTreeSet<XorName>,
+    ) -> Vec<ElderCandidates> {
+        if let Some((our_elder_candidates, other_elder_candidates)) =
+            self.try_split(our_name, excluded)
+        {
             return vec![our_elder_candidates, other_elder_candidates];
         }
 
-        let expected_peers = self.elder_candidates(ELDER_SIZE);
+        let network_params = self.network_params();
+        let expected_peers = self.elder_candidates(network_params.elder_size, excluded);
         let expected_names: BTreeSet<_> = expected_peers.iter().map(Peer::name).cloned().collect();
         let current_names: BTreeSet<_> = self.authority_provider().names();
 
@@ -313,8 +368,11 @@ impl SectionUtils for Section {
             warn!("ignore attempt to reduce the number of elders too much");
             vec![]
         } else {
-            let elder_candidates =
-                ElderCandidates::new(expected_peers, self.authority_provider().prefix());
+            let elder_candidates = ElderCandidates::new(
+                expected_peers,
+                self.authority_provider().prefix(),
+                network_params,
+            );
             vec![elder_candidates]
         }
     }
@@ -370,7 +428,11 @@ impl SectionUtils for Section {
     // Tries to split our section.
     // If we have enough mature nodes for both subsections, returns the SectionAuthorityProviders
     // of the two subsections. Otherwise returns `None`.
-    fn try_split(&self, our_name: &XorName) -> Option<(ElderCandidates, ElderCandidates)> {
+    fn try_split(
+        &self,
+        our_name: &XorName,
+        excluded: &BTreeSet<XorName>,
+    ) -> Option<(ElderCandidates, ElderCandidates)> {
         let next_bit_index = if let Ok(index) = self.prefix().bit_count().try_into() {
             index
         } else {
@@ -392,8 +454,12 @@ impl SectionUtils for Section {
                 }
             });
 
+        let network_params = self.network_params();
+
         // If none of the two new sections would contain enough entries, return `None`.
-        if our_new_size < RECOMMENDED_SECTION_SIZE || sibling_new_size < RECOMMENDED_SECTION_SIZE {
+        if our_new_size < network_params.recommended_section_size
+            || sibling_new_size < network_params.recommended_section_size
+        {
             return None;
         }
 
@@ -402,26 +468,29 @@ impl SectionUtils for Section {
 
         let our_elders = self.members.elder_candidates_matching_prefix(
             &our_prefix,
-            ELDER_SIZE,
+            network_params.elder_size,
             self.authority_provider(),
+            excluded,
         );
         let other_elders = self.members.elder_candidates_matching_prefix(
             &other_prefix,
-            ELDER_SIZE,
+            network_params.elder_size,
             self.authority_provider(),
+            excluded,
         );
 
-        let our_elder_candidates = ElderCandidates::new(our_elders, our_prefix);
-        let other_elder_candidates = ElderCandidates::new(other_elders, other_prefix);
+        let our_elder_candidates = ElderCandidates::new(our_elders, our_prefix, network_params);
+        let other_elder_candidates =
+            ElderCandidates::new(other_elders, other_prefix, network_params);
 
         Some((our_elder_candidates, other_elder_candidates))
     }
 
-    // Returns the candidates for elders out of all the nodes in the section, even out of the
-    // relocating nodes if there would not be enough instead.
-    fn elder_candidates(&self, elder_size: usize) -> Vec<Peer> {
+    // Returns the candidates for elders out of all the nodes in the section but the `excluded`
+    // ones, even out of the relocating nodes if there would not be enough instead.
+    fn elder_candidates(&self, elder_size: usize, excluded: &BTreeSet<XorName>) -> Vec<Peer> {
         self.members
-            .elder_candidates(elder_size, self.authority_provider())
+            .elder_candidates(elder_size, self.authority_provider(), excluded)
     }
 }
 
@@ -430,10 +499,15 @@ fn create_first_section_authority_provider(
     pk_set: &bls::PublicKeySet,
     sk_share: &bls::SecretKeyShare,
     mut peer: Peer,
+    network_params: NetworkParams,
 ) -> Result<SectionSigned<SectionAuthorityProvider>> {
     peer.set_reachable(true);
-    let section_auth =
-        SectionAuthorityProvider::new(iter::once(peer), Prefix::default(), pk_set.clone());
+    let section_auth = SectionAuthorityProvider::new(
+        iter::once(peer),
+        Prefix::default(),
+        pk_set.clone(),
+        network_params,
+    );
     let sig = create_first_sig(pk_set, sk_share, &section_auth)?;
     Ok(SectionSigned::new(section_auth, sig))
 }
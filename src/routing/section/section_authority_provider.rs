@@ -8,7 +8,7 @@
 
 use crate::messaging::{
     node::{ElderCandidates, Peer},
-    SectionAuthorityProvider,
+    NetworkParams, SectionAuthorityProvider,
 };
 use crate::routing::{peer::PeerUtils, Prefix, XorName};
 use crate::types::ReplicaPublicKeySet;
@@ -20,8 +20,12 @@ use std::{
 
 /// The information about elder candidates in a DKG round.
 pub trait ElderCandidatesUtils {
-    /// Creates a new `ElderCandidates` with the given members and prefix.
-    fn new<I: IntoIterator<Item = Peer>>(elders: I, prefix: Prefix) -> Self;
+    /// Creates a new `ElderCandidates` with the given members, prefix and network parameters.
+    fn new<I: IntoIterator<Item = Peer>>(
+        elders: I,
+        prefix: Prefix,
+        network_params: NetworkParams,
+    ) -> Self;
 
     fn peers(&'_ self) -> Box<dyn Iterator<Item = Peer> + '_>;
 
@@ -32,8 +36,8 @@ pub trait ElderCandidatesUtils {
 }
 
 impl ElderCandidatesUtils for ElderCandidates {
-    /// Creates a new `ElderCandidates` with the given members and prefix.
-    fn new<I>(elders: I, prefix: Prefix) -> Self
+    /// Creates a new `ElderCandidates` with the given members, prefix and network parameters.
+    fn new<I>(elders: I, prefix: Prefix, network_params: NetworkParams) -> Self
     where
         I: IntoIterator<Item = Peer>,
     {
@@ -43,6 +47,7 @@ impl ElderCandidatesUtils for ElderCandidates {
                 .map(|peer| (*peer.name(), *peer.addr()))
                 .collect(),
             prefix,
+            network_params,
         }
     }
 
@@ -68,11 +73,13 @@ impl ElderCandidatesUtils for ElderCandidates {
 /// A new `SectionAuthorityProvider` is created whenever the elders change,
 /// due to an elder being added or removed, or the section splitting or merging.
 pub trait SectionAuthorityProviderUtils {
-    /// Creates a new `SectionAuthorityProvider` with the given members, prefix and public keyset.
+    /// Creates a new `SectionAuthorityProvider` with the given members, prefix, public keyset and
+    /// network parameters.
     fn new<I: IntoIterator<Item = Peer>>(
         elders: I,
         prefix: Prefix,
         pk_set: ReplicaPublicKeySet,
+        network_params: NetworkParams,
     ) -> Self;
 
     /// Creates a new `SectionAuthorityProvider` from ElderCandidates and public keyset.
@@ -110,11 +117,20 @@ pub trait SectionAuthorityProviderUtils {
 
     /// Key of the section.
     fn section_key(&self) -> PublicKey;
+
+    /// Network parameters set at genesis.
+    fn network_params(&self) -> NetworkParams;
 }
 
 impl SectionAuthorityProviderUtils for SectionAuthorityProvider {
-    /// Creates a new `SectionAuthorityProvider` with the given members, prefix and public keyset.
-    fn new<I>(elders: I, prefix: Prefix, pk_set: ReplicaPublicKeySet) -> Self
+    /// Creates a new `SectionAuthorityProvider` with the given members, prefix, public keyset and
+    /// network parameters.
+    fn new<I>(
+        elders: I,
+        prefix: Prefix,
+        pk_set: ReplicaPublicKeySet,
+        network_params: NetworkParams,
+    ) -> Self
     where
         I: IntoIterator<Item = Peer>,
     {
@@ -127,6 +143,7 @@ impl SectionAuthorityProviderUtils for SectionAuthorityProvider {
             prefix,
             public_key_set: pk_set,
             elders,
+            network_params,
         }
     }
 
@@ -144,6 +161,7 @@ impl SectionAuthorityProviderUtils for SectionAuthorityProvider {
             prefix: elder_candidates.prefix,
             public_key_set: pk_set,
             elders,
+            network_params: elder_candidates.network_params,
         }
     }
 
@@ -152,6 +170,7 @@ impl SectionAuthorityProviderUtils for SectionAuthorityProvider {
         ElderCandidates {
             elders: self.elders(),
             prefix: self.prefix,
+            network_params: self.network_params,
         }
     }
 
@@ -206,6 +225,11 @@ impl SectionAuthorityProviderUtils for SectionAuthorityProvider {
     fn section_key(&self) -> PublicKey {
         self.public_key_set.public_key()
     }
+
+    /// Network parameters set at genesis.
+    fn network_params(&self) -> NetworkParams {
+        self.network_params
+    }
 }
 
 #[cfg(test)]
@@ -266,7 +290,11 @@ pub(crate) mod test_utils {
 
         let secret_key_set = SecretKeySet::random();
         let section_auth = SectionAuthorityProvider::from_elder_candidates(
-            ElderCandidates { elders, prefix },
+            ElderCandidates {
+                elders,
+                prefix,
+                network_params: NetworkParams::default(),
+            },
             secret_key_set.public_keys(),
         );
 
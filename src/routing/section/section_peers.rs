@@ -15,7 +15,10 @@ use crate::routing::{peer::PeerUtils, section::NodeStateUtils};
 use itertools::Itertools;
 use std::{
     cmp::Ordering,
-    collections::btree_map::{self, Entry},
+    collections::{
+        btree_map::{self, Entry},
+        BTreeSet,
+    },
     mem,
 };
 use xor_name::{Prefix, XorName};
@@ -37,19 +40,23 @@ pub trait SectionPeersUtils {
     /// Get section_signed info for the member with the given name.
     fn get_section_signed(&self, name: &XorName) -> Option<&SectionSigned<NodeState>>;
 
-    /// Returns the candidates for elders out of all the nodes in this section.
+    /// Returns the candidates for elders out of all the nodes in this section but the `excluded`
+    /// ones.
     fn elder_candidates(
         &self,
         elder_size: usize,
         current_elders: &SectionAuthorityProvider,
+        excluded: &BTreeSet<XorName>,
     ) -> Vec<Peer>;
 
-    /// Returns the candidates for elders out of all nodes matching the prefix.
+    /// Returns the candidates for elders out of all nodes matching the prefix but the `excluded`
+    /// ones.
     fn elder_candidates_matching_prefix(
         &self,
         prefix: &Prefix,
         elder_size: usize,
         current_elders: &SectionAuthorityProvider,
+        excluded: &BTreeSet<XorName>,
     ) -> Vec<Peer>;
 
     /// Returns whether the given peer is a joined member of our section.
@@ -103,6 +110,7 @@ impl SectionPeersUtils for SectionPeers {
         &self,
         elder_size: usize,
         current_elders: &SectionAuthorityProvider,
+        excluded: &BTreeSet<XorName>,
     ) -> Vec<Peer> {
         elder_candidates(
             elder_size,
@@ -110,7 +118,8 @@ impl SectionPeersUtils for SectionPeers {
             self.members
                 .values()
                 .filter(|info| is_active(&info.value, current_elders))
-                .filter(|info| info.value.peer.is_reachable()),
+                .filter(|info| info.value.peer.is_reachable())
+                .filter(|info| !excluded.contains(info.value.peer.name())),
         )
     }
 
@@ -120,6 +129,7 @@ impl SectionPeersUtils for SectionPeers {
         prefix: &Prefix,
         elder_size: usize,
         current_elders: &SectionAuthorityProvider,
+        excluded: &BTreeSet<XorName>,
     ) -> Vec<Peer> {
         elder_candidates(
             elder_size,
@@ -128,6 +138,7 @@ impl SectionPeersUtils for SectionPeers {
                 info.value.state == MembershipState::Joined
                     && prefix.matches(info.value.peer.name())
                     && info.value.peer.is_reachable()
+                    && !excluded.contains(info.value.peer.name())
             }),
         )
     }
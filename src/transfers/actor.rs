@@ -7,17 +7,16 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
-    wallet::Wallet, ActorEvent, Error, Outcome, Result, StateSynched, TernaryResult,
+    offline, wallet::Wallet, ActorEvent, Error, Outcome, Result, StateSynched, TernaryResult,
     TransferInitiated, TransferRegistrationSent, TransferValidated, TransferValidationReceived,
-    TransfersSynched,
+    TransfersAbandoned, TransfersSynched, WalletSnapshot, MAX_PENDING_DEBITS,
 };
 use crate::types::{
-    ActorHistory, Credit, CreditAgreementProof, CreditId, Debit, DebitId, OwnerType, PublicKey,
-    SectionElders, SignatureShare, SignedCredit, SignedDebit, Signing, Token,
-    TransferAgreementProof, WalletHistory,
+    ActorHistory, Credit, CreditAgreementProof, CreditId, DebitId, Escrow, OwnerType, PublicKey,
+    SectionElders, Signature, SignatureShare, SignedCredit, SignedDebit, SignedTransferShare,
+    Signing, Token, TransferAgreementProof, TransferCondition, WalletHistory,
 };
 use bls::PublicKeySet;
-use crdts::Dot;
 use itertools::Itertools;
 use log::debug;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -38,8 +37,11 @@ pub struct Actor<S: Signing> {
     /// Ensures that the actor's transfer
     /// initiations (ValidateTransfer cmd) are sequential.
     next_expected_debit: u64,
+    /// Transfers initiated but not yet registered, by debit counter.
+    /// At most `MAX_PENDING_DEBITS` of them are in flight at a time.
+    pending_transfers: BTreeMap<u64, TransferInitiated>,
     /// When a transfer is initiated, validations are accumulated here.
-    /// After quorum is reached and proof produced, the set is cleared.
+    /// After quorum is reached and proof produced, the set is removed.
     accumulating_validations: HashMap<DebitId, HashMap<usize, TransferValidated>>,
     /// The PK Set of the Replicas
     replicas: SectionElders,
@@ -61,6 +63,7 @@ impl<S: Signing> Actor<S> {
             replicas,
             wallet,
             next_expected_debit: 0,
+            pending_transfers: Default::default(),
             accumulating_validations: Default::default(),
             history: ActorHistory::empty(),
         }
@@ -90,6 +93,7 @@ impl<S: Signing> Actor<S> {
             replicas,
             wallet,
             next_expected_debit: 0,
+            pending_transfers: Default::default(),
             accumulating_validations: Default::default(),
             history: ActorHistory::empty(),
         }
@@ -114,6 +118,24 @@ impl<S: Signing> Actor<S> {
         self.wallet.balance()
     }
 
+    /// Query for the balance of the Actor, less the debits in flight.
+    /// This is what new transfers can spend.
+    pub fn available_balance(&self) -> Token {
+        self.pending_transfers
+            .values()
+            .try_fold(self.balance(), |balance, transfer| {
+                balance.checked_sub(transfer.signed_debit.amount())
+            })
+            .unwrap_or_else(Token::zero)
+    }
+
+    /// Query for the transfers initiated but not yet registered, in the order of their debits.
+    /// After a failure, f.ex. a lost connection, these are to be sent again for validation,
+    /// as Replicas return the validation they already made of a debit.
+    pub fn pending_transfers(&self) -> Vec<TransferInitiated> {
+        self.pending_transfers.values().cloned().collect()
+    }
+
     ///
     pub fn replicas_public_key(&self) -> PublicKey {
         PublicKey::Bls(self.replicas.key_set.public_key())
@@ -129,6 +151,11 @@ impl<S: Signing> Actor<S> {
         self.history.clone()
     }
 
+    /// Query for a snapshot of the wallet, f.ex. to sign a transfer offline.
+    pub fn wallet_snapshot(&self) -> WalletSnapshot {
+        self.wallet.clone().into()
+    }
+
     /// -----------------------------------------------------------------
     /// ---------------------- Cmds -------------------------------------
     /// -----------------------------------------------------------------
@@ -140,49 +167,157 @@ impl<S: Signing> Actor<S> {
         recipient: PublicKey,
         msg: String,
     ) -> Outcome<TransferInitiated> {
-        if recipient == self.id() {
-            return Outcome::rejected(Error::SameSenderAndRecipient);
-        }
-
-        let id = Dot::new(self.id(), self.wallet.next_debit());
+        self.transfer_batch(vec![(amount, recipient)], msg)
+    }
 
-        // ensures one debit is completed at a time
-        if self.next_expected_debit != self.wallet.next_debit() {
+    /// Step 1, batched. Build a valid cmd for validation of a single debit
+    /// paying each of the recipients their amount.
+    pub fn transfer_batch(
+        &self,
+        payouts: Vec<(Token, PublicKey)>,
+        msg: String,
+    ) -> Outcome<TransferInitiated> {
+        // ensures a bounded number of debits are in flight
+        if self.next_expected_debit >= self.wallet.next_debit() + MAX_PENDING_DEBITS {
             return Outcome::rejected(Error::DebitPending);
         }
-        if self.next_expected_debit != id.counter {
-            return Outcome::rejected(Error::DebitProposed);
-        }
-        if amount > self.balance() {
-            return Outcome::rejected(Error::InsufficientBalance);
-        }
 
-        if amount == Token::from_nano(0) {
-            return Outcome::rejected(Error::ZeroValueTransfer);
-        }
+        Outcome::success(offline::initiate(
+            &self.signing,
+            self.next_expected_debit,
+            self.available_balance(),
+            payouts,
+            msg,
+        )?)
+    }
 
-        let debit = Debit { id, amount };
-        let credit = Credit {
-            id: debit.credit_id()?,
-            recipient,
+    /// Step 1, conditional. Build a valid cmd for validation of a debit whose credit
+    /// is held in escrow by our Replicas, until either the secret of the condition is
+    /// revealed before its deadline, releasing the credit to the recipient,
+    /// or the deadline passes, refunding us.
+    pub fn transfer_in_escrow(
+        &self,
+        amount: Token,
+        recipient: PublicKey,
+        condition: TransferCondition,
+        msg: String,
+    ) -> Outcome<TransferInitiated> {
+        let mut initiated = match self.transfer(amount, recipient, msg.clone())? {
+            Some(initiated) => initiated,
+            None => return Outcome::no_change(),
+        };
+        let debit = &initiated.signed_debit.debit;
+        let refund = Credit {
+            id: debit.refund_credit_id(&condition)?,
             amount,
+            recipient: self.id(),
             msg,
         };
+        let actor_signature = self.signing.sign(&refund)?;
+        initiated.escrow = Some(Escrow {
+            condition,
+            refund: SignedCredit {
+                credit: refund,
+                actor_signature,
+            },
+        });
+        Outcome::success(initiated)
+    }
+
+    /// Step 1, for a wallet owned by a threshold key set, i.e. when we sign with a key share.
+    /// The returned share is handed to the co-owners, who approve it with
+    /// [`SignedTransferShare::co_sign`], and enough shares are then combined
+    /// into a transfer with [`Actor::combine_transfer_shares`].
+    pub fn propose_transfer(
+        &self,
+        amount: Token,
+        recipient: PublicKey,
+        msg: String,
+    ) -> Outcome<SignedTransferShare> {
+        let actors = match &self.id {
+            OwnerType::Multi(actors) => actors.clone(),
+            OwnerType::Single(_) => return Outcome::rejected(Error::InvalidOwner),
+        };
+        let initiated = match self.transfer(amount, recipient, msg)? {
+            Some(initiated) => initiated,
+            None => return Outcome::no_change(),
+        };
+        Outcome::success(SignedTransferShare::new(
+            initiated.signed_debit.as_share()?,
+            initiated.signed_credit.as_share()?,
+            actors,
+        )?)
+    }
 
-        let actor_signature = self.signing.sign(&debit)?;
-        let signed_debit = SignedDebit {
-            debit,
-            actor_signature,
+    /// Combines the shares of a proposed transfer into a transfer signed by our key set,
+    /// which is then validated by Replicas as any other transfer.
+    pub fn combine_transfer_shares(
+        &self,
+        shares: &[SignedTransferShare],
+    ) -> Outcome<TransferInitiated> {
+        let actors = match &self.id {
+            OwnerType::Multi(actors) => actors,
+            OwnerType::Single(_) => return Outcome::rejected(Error::InvalidOwner),
         };
-        let actor_signature = self.signing.sign(&credit)?;
-        let signed_credit = SignedCredit {
-            credit,
-            actor_signature,
+        let proposal = match shares.first() {
+            Some(share) => share,
+            None => return Outcome::rejected(Error::CannotAggregate),
         };
+        let debit = &proposal.debit().debit;
+        let credit = &proposal.credit().credit;
+
+        let mut debit_sigs = BTreeMap::new();
+        let mut credit_sigs = BTreeMap::new();
+        for share in shares {
+            if share.actors() != actors {
+                return Outcome::rejected(Error::InvalidOwner);
+            } else if &share.debit().debit != debit || &share.credit().credit != credit {
+                return Outcome::rejected(Error::InvalidOperation);
+            } else if !share.verify() {
+                return Outcome::rejected(Error::InvalidSignature);
+            }
+            let _ = debit_sigs.insert(
+                share.share_index(),
+                share.debit().actor_signature.share.clone(),
+            );
+            let _ = credit_sigs.insert(
+                share.share_index(),
+                share.credit().actor_signature.share.clone(),
+            );
+        }
+        if debit_sigs.len() <= actors.threshold() {
+            return Outcome::rejected(Error::CannotAggregate);
+        }
+
+        // ensures a bounded number of debits are in flight
+        if self.next_expected_debit >= self.wallet.next_debit() + MAX_PENDING_DEBITS {
+            return Outcome::rejected(Error::DebitPending);
+        }
+        if self.next_expected_debit != debit.id.counter {
+            return Outcome::rejected(Error::DebitProposed);
+        }
+        if debit.amount() > self.available_balance() {
+            return Outcome::rejected(Error::InsufficientBalance);
+        }
+
+        let debit_sig = actors
+            .combine_signatures(&debit_sigs)
+            .map_err(|_| Error::CannotAggregate)?;
+        let credit_sig = actors
+            .combine_signatures(&credit_sigs)
+            .map_err(|_| Error::CannotAggregate)?;
 
         Outcome::success(TransferInitiated {
-            signed_debit,
-            signed_credit,
+            signed_debit: SignedDebit {
+                debit: debit.clone(),
+                actor_signature: Signature::Bls(debit_sig),
+            },
+            signed_credit: SignedCredit {
+                credit: credit.clone(),
+                actor_signature: Signature::Bls(credit_sig),
+            },
+            batch: vec![],
+            escrow: None,
         })
     }
 
@@ -206,11 +341,14 @@ impl<S: Signing> Actor<S> {
         if self.id() != signed_debit.sender() {
             return Err(Error::WrongValidationActor);
         }
-        // check if expected this validation
-        if self.next_expected_debit != signed_debit.id().counter + 1 {
+        // check if expected this validation, i.e. the debit is in flight
+        if !self
+            .pending_transfers
+            .contains_key(&signed_debit.id().counter)
+        {
             return Err(Error::OperationOutOfOrder(
                 signed_debit.id().counter,
-                self.next_expected_debit,
+                self.wallet.next_debit(),
             ));
         }
         // check if already received
@@ -242,7 +380,12 @@ impl<S: Signing> Actor<S> {
                 Err(_) => return Err(Error::Serialisation("Serialization Error".to_string())),
                 Ok(data) => data,
             };
-            let credit_bytes = match bincode::serialize(&signed_credit) {
+            // The credit of an escrowed transfer is signed along with its escrow.
+            let credit_bytes = match &validation.escrow {
+                Some(escrow) => bincode::serialize(&escrow.holding(signed_credit)),
+                None => bincode::serialize(&signed_credit),
+            };
+            let credit_bytes = match credit_bytes {
                 Err(_) => return Err(Error::Serialisation("Serialization Error".to_string())),
                 Ok(data) => data,
             };
@@ -286,13 +429,38 @@ impl<S: Signing> Actor<S> {
                 .public_key()
                 .verify(&credit_sig, credit_bytes);
 
+            // Same for each credit of a batch.
+            let mut batch = vec![];
+            let mut valid_batch = true;
+            for (index, (signed_credit, _)) in validation.batch.iter().enumerate() {
+                let sig_shares: BTreeMap<_, _> = map
+                    .values()
+                    .chain(vec![&validation])
+                    .filter_map(|v| v.batch.get(index))
+                    .map(|(_, s)| (s.index, s.share.clone()))
+                    .collect();
+                let sig = self
+                    .replicas
+                    .key_set
+                    .combine_signatures(&sig_shares)
+                    .map_err(|_| Error::CannotAggregate)?;
+                let bytes = bincode::serialize(&signed_credit)
+                    .map_err(|_| Error::Serialisation("Serialization Error".to_string()))?;
+                valid_batch &= self.replicas.key_set.public_key().verify(&sig, bytes);
+                batch.push((signed_credit.clone(), Signature::Bls(sig)));
+            }
+
+            let escrow = validation.escrow.clone();
+
             // Validate the combined signatures. If the shares were valid, this can't fail.
-            if valid_debit && valid_credit {
+            if valid_debit && valid_credit && valid_batch {
                 proof = Some(TransferAgreementProof {
                     signed_debit: signed_debit.clone(),
-                    debit_sig: crate::types::Signature::Bls(debit_sig),
+                    debit_sig: Signature::Bls(debit_sig),
                     signed_credit: signed_credit.clone(),
-                    credit_sig: crate::types::Signature::Bls(credit_sig),
+                    credit_sig: Signature::Bls(credit_sig),
+                    batch,
+                    escrow,
                     debiting_replicas_keys: self.replicas.key_set.clone(),
                 });
             } // else, we have some corrupt data. (todo: Do we need to act on that fact?)
@@ -304,6 +472,9 @@ impl<S: Signing> Actor<S> {
     /// Step 3. Registration of an agreed transfer.
     /// (The actual sending of the registration over the wire is done by upper layer,
     /// only after that, the event is applied to the actor instance.)
+    ///
+    /// Debits are registered in order, so this is rejected with `DebitPending`
+    /// while an earlier debit in flight has not been registered.
     pub fn register(
         &self,
         transfer_proof: TransferAgreementProof,
@@ -312,8 +483,13 @@ impl<S: Signing> Actor<S> {
         if self.verify_transfer_proof(&transfer_proof).is_err() {
             return Err(Error::InvalidSignature);
         }
-        if self.wallet.next_debit() == transfer_proof.id().counter {
+        let counter = transfer_proof.id().counter;
+        if self.wallet.next_debit() == counter {
             Outcome::success(TransferRegistrationSent { transfer_proof })
+        } else if self.wallet.next_debit() < counter
+            && self.pending_transfers.contains_key(&counter)
+        {
+            Err(Error::DebitPending)
         } else {
             Err(Error::OperationOutOfOrder(
                 transfer_proof.id().counter,
@@ -322,6 +498,24 @@ impl<S: Signing> Actor<S> {
         }
     }
 
+    /// Abandons a transfer in flight which failed validation, as it was rejected by Replicas.
+    /// All later transfers in flight are abandoned with it, as their debits depend on it,
+    /// and their payouts are to be initiated again.
+    ///
+    /// Replicas never validate another debit in place of one they validated, so a transfer
+    /// failing validation is first to be sent again as is, see `WalletReplica::validate`.
+    pub fn abandon(&self, id: DebitId) -> Outcome<TransfersAbandoned> {
+        if id.actor != self.id() || !self.pending_transfers.contains_key(&id.counter) {
+            return Outcome::no_change();
+        }
+        let transfers = self
+            .pending_transfers
+            .range(id.counter..)
+            .map(|(_, transfer)| transfer.clone())
+            .collect();
+        Outcome::success(TransfersAbandoned(transfers))
+    }
+
     ///
     pub fn synch(
         &self,
@@ -363,7 +557,11 @@ impl<S: Signing> Actor<S> {
         let credits = self.validate_credits(&history.credits);
         let debits = self.validate_debits(&history.debits);
         if !credits.is_empty() || !debits.is_empty() {
-            Outcome::success(TransfersSynched(ActorHistory { credits, debits }))
+            Outcome::success(TransfersSynched(ActorHistory {
+                credits,
+                debits,
+                ..ActorHistory::empty()
+            }))
         } else {
             Err(Error::NoActorHistory) // TODO: the error is actually that credits and/or debits failed validation..
         }
@@ -432,6 +630,7 @@ impl<S: Signing> Actor<S> {
             ActorEvent::TransferInitiated(e) => {
                 self.next_expected_debit = e.id().counter + 1;
                 let _ = self.accumulating_validations.insert(e.id(), HashMap::new());
+                let _ = self.pending_transfers.insert(e.id().counter, e);
                 Ok(())
             }
             ActorEvent::TransferValidationReceived(e) => {
@@ -446,10 +645,21 @@ impl<S: Signing> Actor<S> {
             ActorEvent::TransferRegistrationSent(e) => {
                 self.wallet
                     .apply_debit(e.transfer_proof.signed_debit.debit.clone())?;
-                self.accumulating_validations.clear();
+                let _ = self.accumulating_validations.remove(&e.transfer_proof.id());
+                let _ = self
+                    .pending_transfers
+                    .remove(&e.transfer_proof.id().counter);
                 self.history.debits.push(e.transfer_proof);
                 Ok(())
             }
+            ActorEvent::TransfersAbandoned(e) => {
+                for transfer in e.0 {
+                    let _ = self.accumulating_validations.remove(&transfer.id());
+                    let _ = self.pending_transfers.remove(&transfer.id().counter);
+                    self.next_expected_debit = self.next_expected_debit.min(transfer.id().counter);
+                }
+                Ok(())
+            }
             ActorEvent::TransfersSynched(e) => {
                 for credit in e.0.credits {
                     // append credits _before_ debits
@@ -462,7 +672,7 @@ impl<S: Signing> Actor<S> {
                     self.wallet.apply_debit(debit.signed_debit.debit.clone())?;
                     self.history.debits.push(debit);
                 }
-                self.next_expected_debit = self.wallet.next_debit();
+                self.drop_registered_transfers();
                 Ok(())
             }
             ActorEvent::StateSynched(e) => {
@@ -472,13 +682,30 @@ impl<S: Signing> Actor<S> {
                     e.debit_version,
                     e.credit_ids,
                 );
-                self.next_expected_debit = self.wallet.next_debit();
+                self.drop_registered_transfers();
                 Ok(())
             }
         }
         // consider event log, to properly be able to reconstruct state from restart
     }
 
+    // Transfers in flight whose debits were registered meanwhile, are no longer in flight.
+    // (If those debits were made by another instance of the same Actor, then the later
+    // transfers in flight will be rejected by Replicas, and are to be abandoned.)
+    fn drop_registered_transfers(&mut self) {
+        let next_debit = self.wallet.next_debit();
+        let registered: Vec<_> = self
+            .pending_transfers
+            .range(..next_debit)
+            .map(|(_, t)| t.id())
+            .collect();
+        for id in registered {
+            let _ = self.accumulating_validations.remove(&id);
+            let _ = self.pending_transfers.remove(&id.counter);
+        }
+        self.next_expected_debit = self.next_expected_debit.max(next_debit);
+    }
+
     /// -----------------------------------------------------------------
     /// ---------------------- Private methods --------------------------
     /// -----------------------------------------------------------------
@@ -490,20 +717,35 @@ impl<S: Signing> Actor<S> {
     fn verify(&self, event: &TransferValidated) -> Result<()> {
         let signed_debit = &event.signed_debit;
         let signed_credit = &event.signed_credit;
+        let batch = event.batch.iter().map(|(credit, _)| credit);
 
         // Check that we signed this.
-        if let error @ Err(_) = self.verify_is_our_transfer(signed_debit, signed_credit) {
+        if let error @ Err(_) = self.verify_is_our_transfer(signed_debit, signed_credit, batch) {
             return error;
         }
 
         let valid_debit = self
             .verify_share(signed_debit, &event.replica_debit_sig, &event.replicas)
             .is_ok();
-        let valid_credit = self
-            .verify_share(signed_credit, &event.replica_credit_sig, &event.replicas)
-            .is_ok();
+        let valid_credit = match &event.escrow {
+            Some(escrow) => self.verify_share(
+                escrow.holding(signed_credit),
+                &event.replica_credit_sig,
+                &event.replicas,
+            ),
+            None => self.verify_share(signed_credit, &event.replica_credit_sig, &event.replicas),
+        }
+        .is_ok();
+        let valid_batch = event
+            .batch
+            .iter()
+            .all(|(credit, sig)| self.verify_share(credit, sig, &event.replicas).is_ok());
+        let valid_escrow = event
+            .escrow
+            .iter()
+            .all(|escrow| self.verify_is_our_refund(signed_debit, escrow).is_ok());
 
-        if valid_debit && valid_credit {
+        if valid_debit && valid_credit && valid_batch && valid_escrow {
             Ok(())
         } else {
             Err(Error::InvalidSignature)
@@ -539,8 +781,9 @@ impl<S: Signing> Actor<S> {
     fn verify_transfer_proof(&self, proof: &TransferAgreementProof) -> Result<()> {
         let signed_debit = &proof.signed_debit;
         let signed_credit = &proof.signed_credit;
+        let batch = proof.batch.iter().map(|(credit, _)| credit);
         // Check that we signed this.
-        if let error @ Err(_) = self.verify_is_our_transfer(signed_debit, signed_credit) {
+        if let error @ Err(_) = self.verify_is_our_transfer(signed_debit, signed_credit, batch) {
             return error;
         }
 
@@ -553,7 +796,11 @@ impl<S: Signing> Actor<S> {
             }
         };
 
-        let valid_credit = match bincode::serialize(&proof.signed_credit) {
+        let credit_bytes = match &proof.escrow {
+            Some(escrow) => bincode::serialize(&escrow.holding(signed_credit)),
+            None => bincode::serialize(signed_credit),
+        };
+        let valid_credit = match credit_bytes {
             Err(_) => return Err(Error::Serialisation("Could not serialise credit".into())),
             Ok(data) => {
                 let public_key = crate::types::PublicKey::Bls(self.replicas.key_set.public_key());
@@ -561,7 +808,18 @@ impl<S: Signing> Actor<S> {
             }
         };
 
-        if valid_debit && valid_credit {
+        let public_key = PublicKey::Bls(self.replicas.key_set.public_key());
+        let valid_batch = proof.batch.iter().all(|(credit, sig)| {
+            bincode::serialize(credit)
+                .map(|data| public_key.verify(sig, &data).is_ok())
+                .unwrap_or(false)
+        });
+        let valid_escrow = proof
+            .escrow
+            .iter()
+            .all(|escrow| self.verify_is_our_refund(signed_debit, escrow).is_ok());
+
+        if valid_debit && valid_credit && valid_batch && valid_escrow {
             Ok(())
         } else {
             Err(Error::InvalidSignature)
@@ -584,10 +842,11 @@ impl<S: Signing> Actor<S> {
     }
 
     /// Check that we signed this.
-    fn verify_is_our_transfer(
+    fn verify_is_our_transfer<'a>(
         &self,
         signed_debit: &SignedDebit,
         signed_credit: &SignedCredit,
+        batch: impl Iterator<Item = &'a SignedCredit>,
     ) -> Result<()> {
         debug!("Actor: Verifying is this our transfer?!");
         let valid_debit = self
@@ -602,9 +861,40 @@ impl<S: Signing> Actor<S> {
                 "Actor: Valid debit sig? {}, Valid credit sig? {}",
                 valid_debit, valid_credit
             );
-            Err(Error::InvalidSignature)
+            return Err(Error::InvalidSignature);
         } else if signed_credit.id() != &signed_debit.credit_id()? {
+            return Err(Error::CreditDebitIdMismatch);
+        }
+
+        let mut total = signed_credit.amount();
+        for (index, credit) in batch.enumerate() {
+            if !self.signing.verify(&credit.actor_signature, &credit.credit) {
+                return Err(Error::InvalidSignature);
+            } else if credit.id() != &signed_debit.debit.batch_credit_id(index)? {
+                return Err(Error::CreditDebitIdMismatch);
+            }
+            total = total
+                .checked_add(credit.amount())
+                .ok_or(Error::CreditDebitValueMismatch)?;
+        }
+        if total != signed_debit.amount() {
+            return Err(Error::CreditDebitValueMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Check that we signed the refund of an escrow, and that it refunds the debit to us.
+    fn verify_is_our_refund(&self, signed_debit: &SignedDebit, escrow: &Escrow) -> Result<()> {
+        let refund = &escrow.refund;
+        if !self.signing.verify(&refund.actor_signature, &refund.credit) {
+            Err(Error::InvalidSignature)
+        } else if refund.id() != &signed_debit.debit.refund_credit_id(&escrow.condition)?
+            || refund.recipient() != signed_debit.sender()
+        {
             Err(Error::CreditDebitIdMismatch)
+        } else if refund.amount() != signed_debit.amount() {
+            Err(Error::CreditDebitValueMismatch)
         } else {
             Ok(())
         }
@@ -615,11 +905,12 @@ impl<S: Signing + fmt::Debug> fmt::Debug for Actor<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Actor {{ id: {:?}, signing: {:?}, wallet: {:?}, next_expected_debit: {:?}, accumulating_validations: {:?}, replicas: PkSet {{ public_key: {:?} }}}}",
+            "Actor {{ id: {:?}, signing: {:?}, wallet: {:?}, next_expected_debit: {:?}, pending_transfers: {:?}, accumulating_validations: {:?}, replicas: PkSet {{ public_key: {:?} }}}}",
             self.id,
             self.signing,
             self.wallet,
             self.next_expected_debit,
+            self.pending_transfers,
             self.accumulating_validations,
             self.replicas.key_set.public_key(),
         )
@@ -749,6 +1040,67 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn multisig_transfer_needs_enough_shares() -> Result<()> {
+        let mut rng = rand::thread_rng();
+        let (_, replicas_sk_set) = get_actor_and_replicas_sk_set(0)?;
+        let owners = SecretKeySet::random(1, &mut rng);
+        let owner_share = |index| {
+            Keypair::new_bls_share(index, owners.secret_key_share(index), owners.public_keys())
+        };
+
+        let mut wallet = Wallet::new(OwnerType::Multi(owners.public_keys()));
+        let amount = Token::from_nano(10);
+        wallet.apply_credit(get_credit(
+            get_random_dot(),
+            PublicKey::Bls(owners.public_keys().public_key()),
+            amount,
+        )?)?;
+        let replicas = SectionElders {
+            prefix: Prefix::default(),
+            names: Default::default(),
+            key_set: replicas_sk_set.public_keys(),
+        };
+        let mut actor = Actor::from_snapshot(wallet, owner_share(0), replicas);
+
+        let proposal = actor
+            .propose_transfer(amount, get_random_pk(), "asdf".to_string())?
+            .ok_or(Error::TransferCreationFailed)?;
+        assert!(matches!(
+            actor.combine_transfer_shares(std::slice::from_ref(&proposal)),
+            Err(Error::CannotAggregate)
+        ));
+
+        let approval = proposal.co_sign(&owner_share(1))?;
+        let transfer = actor
+            .combine_transfer_shares(&[proposal, approval])?
+            .ok_or(Error::TransferCreationFailed)?;
+        actor.apply(ActorEvent::TransferInitiated(transfer.clone()))?;
+
+        // The combined transfer is validated as any other.
+        let mut proof = None;
+        for validation in get_transfer_validation_vec(transfer, &replicas_sk_set)? {
+            let received = actor
+                .receive(validation)?
+                .ok_or(Error::ReceiveValidationFailed)?;
+            actor.apply(ActorEvent::TransferValidationReceived(received.clone()))?;
+            if received.proof.is_some() {
+                proof = received.proof;
+                break;
+            }
+        }
+        assert!(proof.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn single_owner_cannot_propose_multisig_transfer() -> Result<()> {
+        let (actor, _sk_set) = get_actor_and_replicas_sk_set(10)?;
+        let result = actor.propose_transfer(Token::from_nano(10), get_random_pk(), "".to_string());
+        assert!(matches!(result, Err(Error::InvalidOwner)));
+        Ok(())
+    }
+
     fn get_debit(actor: &Actor<Keypair>) -> Result<TransferInitiated> {
         let event = actor
             .transfer(Token::from_nano(10), get_random_pk(), "asdf".to_string())?
@@ -806,6 +1158,8 @@ mod test {
                     share: credit_sig_share.clone(),
                 },
                 replicas: pk_set.clone(),
+                batch: vec![],
+                escrow: None,
             })
         }
 
@@ -857,6 +1211,8 @@ mod test {
             debit_sig,
             credit_sig,
             debiting_replicas_keys: pk_set,
+            batch: vec![],
+            escrow: None,
         };
 
         Ok(TransferRegistrationSent {
@@ -896,7 +1252,6 @@ mod test {
         })
     }
 
-    #[allow(unused)]
     fn get_random_dot() -> Dot<PublicKey> {
         Dot::new(get_random_pk(), 0)
     }
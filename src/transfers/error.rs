@@ -23,8 +23,9 @@ pub enum Error {
     /// Sender and receiver are the same
     #[error("Sender and recipient are the same")]
     SameSenderAndRecipient,
-    /// A debit is awaiting completion. A new debit cannot be started.
-    #[error("Current pending debit has not been completed")]
+    /// Debits are awaiting completion. A new debit cannot be started,
+    /// or a later debit cannot be registered before them.
+    #[error("Pending debits have not been completed")]
     DebitPending,
     /// The provided PublicKey does not correlate with any key in the section chain history.
     #[error("PublicKey provided by the transfer was never a part of the section chain.")]
@@ -142,6 +143,24 @@ pub enum Error {
     /// Balance key already exists.
     #[error("Key already exists")]
     KeyExists,
+    /// No transfer is held in escrow with this id, or it has already been settled.
+    #[error("No transfer held in escrow with id {0:?}")]
+    NoSuchEscrow(Dot<PublicKey>),
+    /// The secret is not a preimage of the hash lock of the escrow.
+    #[error("The secret does not release the escrow")]
+    EscrowLocked,
+    /// The deadline of the escrow has passed, so it can only be refunded.
+    #[error("The deadline of the escrow has passed")]
+    EscrowExpired,
+    /// The deadline of the escrow has not passed, so it can only be released.
+    #[error("The deadline of the escrow has not passed yet")]
+    EscrowNotExpired,
+    /// This Replica has already signed a different settlement of the escrow.
+    #[error("A different settlement of the escrow has already been signed")]
+    EscrowSettlementConflict,
+    /// The escrow has already been released or refunded.
+    #[error("The escrow with id {0:?} has already been settled")]
+    EscrowSettled(Dot<PublicKey>),
     /// Other types errors
     #[error(transparent)]
     NetworkDataError(#[from] DtError),
@@ -10,16 +10,21 @@
 
 mod actor;
 mod error;
+mod offline;
 mod test_utils;
 mod wallet;
 mod wallet_replica;
 
 pub use self::{
-    actor::Actor as TransferActor, error::Error, wallet::Wallet, wallet_replica::WalletReplica,
+    actor::Actor as TransferActor,
+    error::Error,
+    offline::{sign_transfer, WatchOnly},
+    wallet::{Wallet, WalletSnapshot},
+    wallet_replica::WalletReplica,
 };
 
 use crate::types::{
-    ActorHistory, CreditId, DebitId, PublicKey, SignedCredit, SignedDebit, Token,
+    ActorHistory, CreditId, DebitId, Escrow, PublicKey, SignedCredit, SignedDebit, Token,
     TransferAgreementProof, TransferValidated,
 };
 use serde::{Deserialize, Serialize};
@@ -28,6 +33,15 @@ use std::collections::HashSet;
 type Result<T> = std::result::Result<T, Error>;
 type Outcome<T> = Result<Option<T>>;
 
+/// The maximum number of debits of a wallet in flight at a time,
+/// i.e. initiated or validated, but not yet registered.
+pub const MAX_PENDING_DEBITS: u64 = 8;
+
+/// The time, in seconds, by which the clocks of the Replicas may differ. An escrow is
+/// refunded only this long after its deadline, and released only by a quorum of Replicas
+/// seeing the secret before it, which makes the deadline section-agreed.
+pub const ESCROW_GRACE_SECS: u64 = 120;
+
 trait TernaryResult<T> {
     fn success(item: T) -> Self;
     fn no_change() -> Self;
@@ -70,6 +84,9 @@ pub enum ActorEvent {
     /// Raised when the Actor has received
     /// unknown credits on querying Replicas.
     StateSynched(StateSynched),
+    /// Raised when transfers in flight
+    /// have been abandoned after a failure.
+    TransfersAbandoned(TransfersAbandoned),
 }
 
 /// Raised when the Actor has received
@@ -95,6 +112,19 @@ pub struct StateSynched {
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct TransfersSynched(ActorHistory);
 
+/// Raised when a transfer in flight failed validation.
+/// The later transfers in flight are abandoned with it,
+/// as their debits follow on from its debit.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct TransfersAbandoned(Vec<TransferInitiated>);
+
+impl TransfersAbandoned {
+    /// The abandoned transfers, in the order of their debits.
+    pub fn transfers(&self) -> &[TransferInitiated] {
+        &self.0
+    }
+}
+
 /// This event is raised by the Actor after having
 /// successfully created a transfer cmd to send to the
 /// Replicas for validation.
@@ -104,6 +134,10 @@ pub struct TransferInitiated {
     pub signed_debit: SignedDebit,
     /// The credit signed by the initiating Actor.
     pub signed_credit: SignedCredit,
+    /// Further credits of a batched transfer, signed by the initiating Actor.
+    pub batch: Vec<SignedCredit>,
+    /// The escrow of a conditional transfer, with the refund signed by the initiating Actor.
+    pub escrow: Option<Escrow>,
 }
 
 impl TransferInitiated {
@@ -136,19 +170,19 @@ pub struct TransferRegistrationSent {
 mod test {
     use super::{
         actor::Actor, test_utils, test_utils::*, wallet, wallet_replica::WalletReplica, ActorEvent,
-        Error, Result, TransferInitiated, Wallet,
+        Error, Result, TransferInitiated, Wallet, ESCROW_GRACE_SECS, MAX_PENDING_DEBITS,
     };
     use crate::types::{
-        ActorHistory, Credit, CreditAgreementProof, CreditId, Debit, Keypair, OwnerType, PublicKey,
-        ReplicaEvent, SectionElders, SignatureShare, SignedCredit, SignedDebit, SignedTransfer,
-        Token, Transfer, TransferAgreementProof,
+        ActorHistory, Credit, CreditAgreementProof, CreditId, Debit, EscrowSettlement, Keypair,
+        OwnerType, PublicKey, ReplicaEvent, SectionElders, Signature, SignatureShare, SignedCredit,
+        SignedDebit, SignedTransfer, Token, Transfer, TransferAgreementProof, TransferCondition,
     };
     use bls::{PublicKeySet, PublicKeyShare, SecretKey, SecretKeySet, SecretKeyShare};
     use crdts::{
         quickcheck::{quickcheck, TestResult},
         Dot,
     };
-    use std::collections::{HashMap, HashSet};
+    use std::collections::{BTreeMap, HashMap, HashSet};
     use std::sync::Arc;
 
     macro_rules! hashmap {
@@ -168,6 +202,407 @@ mod test {
         let _ = transfer_between_actors(100, 10, 3);
     }
 
+    #[test]
+    fn batched_transfer() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100, 10, 20], 3)?;
+        let mut sender = actors.remove(0);
+        let mut first = actors.remove(0);
+        let mut second = actors.remove(0);
+
+        let transfer = sender
+            .actor
+            .transfer_batch(
+                vec![
+                    (Token::from_nano(60), first.actor.id()),
+                    (Token::from_nano(40), second.actor.id()),
+                ],
+                "asdf".to_string(),
+            )?
+            .ok_or(Error::TransferCreationFailed)?;
+        sender
+            .actor
+            .apply(ActorEvent::TransferInitiated(transfer.clone()))?;
+
+        let debit_proof = validate_at_sender_replicas(transfer, &mut sender)?
+            .ok_or(Error::SenderValidationFailed)?;
+        assert!(debit_proof.is_batch());
+        register_at_debiting_replicas(&debit_proof, &mut sender.section)?;
+        let credit_proofs = debit_proof.credit_proofs();
+        assert_eq!(credit_proofs.len(), 2);
+        let _ = propagate_to_crediting_replicas(credit_proofs[0].clone(), &mut first.section);
+        let _ = propagate_to_crediting_replicas(credit_proofs[1].clone(), &mut second.section);
+        synch(&mut first)?;
+        synch(&mut second)?;
+
+        assert_balance(sender, Token::zero());
+        assert_balance(first, Token::from_nano(70));
+        assert_balance(second, Token::from_nano(60));
+        Ok(())
+    }
+
+    #[test]
+    fn batched_transfer_must_not_exceed_balance() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100], 3)?;
+        let sender = actors.remove(0);
+        let result = sender.actor.transfer_batch(
+            vec![
+                (Token::from_nano(60), get_random_pk()),
+                (Token::from_nano(41), get_random_pk()),
+            ],
+            "asdf".to_string(),
+        );
+        assert!(matches!(result, Err(Error::InsufficientBalance)));
+        Ok(())
+    }
+
+    #[test]
+    fn pipelined_transfers() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100, 10], 3)?;
+        let mut sender = actors.remove(0);
+        let mut recipient = actors.remove(0);
+
+        // Further debits are initiated while the first ones are in flight.
+        let mut transfers = vec![];
+        for _ in 0..3 {
+            let transfer = sender
+                .actor
+                .transfer(
+                    Token::from_nano(30),
+                    recipient.actor.id(),
+                    "asdf".to_string(),
+                )?
+                .ok_or(Error::TransferCreationFailed)?;
+            sender
+                .actor
+                .apply(ActorEvent::TransferInitiated(transfer.clone()))?;
+            transfers.push(transfer);
+        }
+        assert_eq!(sender.actor.available_balance(), Token::from_nano(10));
+        assert_eq!(sender.actor.pending_transfers(), transfers);
+
+        // Pending debits are accounted for in the balance.
+        let result = sender.actor.transfer(
+            Token::from_nano(30),
+            recipient.actor.id(),
+            "asdf".to_string(),
+        );
+        assert!(matches!(result, Err(Error::InsufficientBalance)));
+
+        // Replicas validate each debit before the previous is registered.
+        let mut proofs = vec![];
+        for transfer in transfers {
+            proofs.push(
+                validate_at_sender_replicas(transfer, &mut sender)?
+                    .ok_or(Error::SenderValidationFailed)?,
+            );
+        }
+        for proof in &proofs {
+            register_at_debiting_replicas(proof, &mut sender.section)?;
+            let _ = propagate_to_crediting_replicas(proof.credit_proof(), &mut recipient.section);
+        }
+        synch(&mut recipient)?;
+
+        assert!(sender.actor.pending_transfers().is_empty());
+        assert_balance(sender, Token::from_nano(10));
+        assert_balance(recipient, Token::from_nano(100));
+        Ok(())
+    }
+
+    #[test]
+    fn pipelined_transfers_are_bounded() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100], 3)?;
+        let mut sender = actors.remove(0);
+        for _ in 0..MAX_PENDING_DEBITS {
+            let transfer = sender
+                .actor
+                .transfer(Token::from_nano(1), get_random_pk(), "asdf".to_string())?
+                .ok_or(Error::TransferCreationFailed)?;
+            sender
+                .actor
+                .apply(ActorEvent::TransferInitiated(transfer))?;
+        }
+        let result =
+            sender
+                .actor
+                .transfer(Token::from_nano(1), get_random_pk(), "asdf".to_string());
+        assert!(matches!(result, Err(Error::DebitPending)));
+        Ok(())
+    }
+
+    #[test]
+    fn failed_transfer_abandons_later_transfers_in_flight() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100], 3)?;
+        let mut sender = actors.remove(0);
+        let mut transfers = vec![];
+        for _ in 0..2 {
+            let transfer = sender
+                .actor
+                .transfer(Token::from_nano(30), get_random_pk(), "asdf".to_string())?
+                .ok_or(Error::TransferCreationFailed)?;
+            sender
+                .actor
+                .apply(ActorEvent::TransferInitiated(transfer.clone()))?;
+            transfers.push(transfer);
+        }
+
+        let abandoned = sender
+            .actor
+            .abandon(transfers[0].id())?
+            .ok_or(Error::InvalidOperation)?;
+        assert_eq!(abandoned.transfers(), &transfers[..]);
+        sender
+            .actor
+            .apply(ActorEvent::TransfersAbandoned(abandoned))?;
+
+        assert!(sender.actor.pending_transfers().is_empty());
+        assert_eq!(sender.actor.available_balance(), Token::from_nano(100));
+
+        // The payouts are initiated again, from the first of the abandoned debits.
+        let transfer = sender
+            .actor
+            .transfer(Token::from_nano(30), get_random_pk(), "asdf".to_string())?
+            .ok_or(Error::TransferCreationFailed)?;
+        assert_eq!(transfer.id(), transfers[0].id());
+        Ok(())
+    }
+
+    #[test]
+    fn transfer_sent_again_is_not_replaced_at_replicas() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100], 3)?;
+        let mut sender = actors.remove(0);
+        let mut other_instance = sender.actor.clone();
+        let transfer = sender
+            .actor
+            .transfer(Token::from_nano(30), get_random_pk(), "asdf".to_string())?
+            .ok_or(Error::TransferCreationFailed)?;
+        sender
+            .actor
+            .apply(ActorEvent::TransferInitiated(transfer.clone()))?;
+        let signed_transfer = SignedTransfer {
+            debit: transfer.signed_debit.clone(),
+            credit: transfer.signed_credit.clone(),
+            batch: vec![],
+            escrow: None,
+        };
+        let other = other_instance
+            .transfer(Token::from_nano(30), get_random_pk(), "asdf".to_string())?
+            .ok_or(Error::TransferCreationFailed)?;
+        let other_transfer = SignedTransfer {
+            debit: other.signed_debit,
+            credit: other.signed_credit,
+            batch: vec![],
+            escrow: None,
+        };
+
+        // The Replicas validate the transfer, but the validations don't reach the Actor.
+        let mut validations = vec![];
+        for elder in &mut sender.section.elders {
+            let wallet_replica = elder
+                .replicas
+                .get_mut(&sender.actor.id())
+                .ok_or(Error::WalletNotFound(sender.actor.id()))?;
+            wallet_replica
+                .validate(&signed_transfer)?
+                .ok_or(Error::ValidationFailed)?;
+            let (replica_debit_sig, replica_credit_sig) =
+                elder.signing.sign_transfer(&signed_transfer)?;
+            let validation = crate::types::TransferValidated {
+                signed_debit: signed_transfer.debit.clone(),
+                signed_credit: signed_transfer.credit.clone(),
+                replica_debit_sig,
+                replica_credit_sig,
+                batch: vec![],
+                escrow: None,
+                replicas: sender.section.id.clone(),
+            };
+            wallet_replica.apply(ReplicaEvent::TransferValidated(validation.clone()))?;
+            validations.push(validation);
+        }
+
+        // No other debit is validated in its place, so the Actor sends the identical transfer
+        // again, which the Replicas answer with the validations already made.
+        for elder in &sender.section.elders {
+            let wallet_replica = elder
+                .replicas
+                .get(&sender.actor.id())
+                .ok_or(Error::WalletNotFound(sender.actor.id()))?;
+            assert!(matches!(
+                wallet_replica.validate(&other_transfer),
+                Err(Error::OperationOutOfOrder(0, 1))
+            ));
+            assert!(wallet_replica.validate(&signed_transfer)?.is_none());
+        }
+        let mut proof = None;
+        for validation in validations {
+            let received = sender
+                .actor
+                .receive(validation)?
+                .ok_or(Error::ReceiveValidationFailed)?;
+            sender
+                .actor
+                .apply(ActorEvent::TransferValidationReceived(received.clone()))?;
+            proof = proof.or(received.proof);
+        }
+        let proof = proof.ok_or(Error::SenderValidationFailed)?;
+        let registered = sender
+            .actor
+            .register(proof.clone())?
+            .ok_or(Error::RegisterProofFailed)?;
+        sender
+            .actor
+            .apply(ActorEvent::TransferRegistrationSent(registered))?;
+        register_at_debiting_replicas(&proof, &mut sender.section)?;
+
+        // The amount validated is no longer reserved once registered.
+        let transfer = sender
+            .actor
+            .transfer(Token::from_nano(70), get_random_pk(), "asdf".to_string())?
+            .ok_or(Error::TransferCreationFailed)?;
+        sender
+            .actor
+            .apply(ActorEvent::TransferInitiated(transfer.clone()))?;
+        let _ = validate_at_sender_replicas(transfer, &mut sender)?
+            .ok_or(Error::SenderValidationFailed)?;
+        assert_eq!(sender.actor.balance(), Token::zero());
+        Ok(())
+    }
+
+    #[test]
+    fn escrowed_transfer_is_released_by_its_secret() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100, 10], 3)?;
+        let mut sender = actors.remove(0);
+        let mut recipient = actors.remove(0);
+        let condition = TransferCondition::new(b"secret", 1000);
+
+        let debit_proof = escrow_transfer(&mut sender, recipient.actor.id(), condition)?;
+        assert!(debit_proof.is_escrowed());
+        assert_balance(sender.clone(), Token::zero());
+        // The validated credit does not pay the recipient before the release.
+        let recipient_replica = recipient.section.elders[0]
+            .replicas
+            .get(&recipient.actor.id())
+            .ok_or(Error::WalletNotFound(recipient.actor.id()))?;
+        assert!(matches!(
+            recipient_replica.receive_propagated(&debit_proof.credit_proof()),
+            Err(Error::InvalidSignature)
+        ));
+
+        let id = debit_proof.id();
+        let release = EscrowSettlement::Release {
+            id,
+            secret: b"secret".to_vec(),
+        };
+        let guess = EscrowSettlement::Release {
+            id,
+            secret: b"guess".to_vec(),
+        };
+        for elder in &sender.section.elders {
+            let wallet_replica = elder
+                .replicas
+                .get(&sender.actor.id())
+                .ok_or(Error::WalletNotFound(sender.actor.id()))?;
+            assert!(matches!(
+                wallet_replica.propose_settlement(&guess, 500, false),
+                Err(Error::EscrowLocked)
+            ));
+            assert!(matches!(
+                wallet_replica.propose_settlement(&release, 1001, false),
+                Err(Error::EscrowExpired)
+            ));
+            // Within the grace of the deadline, a release is seconded.
+            assert!(wallet_replica
+                .propose_settlement(&release, 1001, true)?
+                .is_some());
+            assert!(matches!(
+                wallet_replica.propose_settlement(
+                    &EscrowSettlement::Refund(id),
+                    1000 + ESCROW_GRACE_SECS - 1,
+                    false
+                ),
+                Err(Error::EscrowNotExpired)
+            ));
+        }
+
+        let credit_proof = settle_escrow(&release, 500, &mut sender.section)?;
+        for elder in &mut sender.section.elders {
+            let wallet_replica = elder
+                .replicas
+                .get_mut(&sender.actor.id())
+                .ok_or(Error::WalletNotFound(sender.actor.id()))?;
+            assert!(wallet_replica.escrowed(&id).is_none());
+            // The escrow is settled only once.
+            assert!(matches!(
+                wallet_replica.propose_settlement(
+                    &EscrowSettlement::Refund(id),
+                    1000 + ESCROW_GRACE_SECS,
+                    false
+                ),
+                Err(Error::EscrowSettled(_))
+            ));
+            assert!(matches!(
+                wallet_replica.settle(&release, &credit_proof),
+                Err(Error::EscrowSettled(_))
+            ));
+        }
+
+        let _ = propagate_to_crediting_replicas(credit_proof, &mut recipient.section);
+        synch(&mut recipient)?;
+        assert_balance(recipient, Token::from_nano(110));
+        Ok(())
+    }
+
+    #[test]
+    fn escrowed_transfer_is_refunded_after_its_deadline() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100], 3)?;
+        let mut sender = actors.remove(0);
+        let condition = TransferCondition::new(b"secret", 1000);
+
+        let debit_proof = escrow_transfer(&mut sender, get_random_pk(), condition)?;
+
+        let refund = EscrowSettlement::Refund(debit_proof.id());
+        let refund_proof = settle_escrow(&refund, 1000 + ESCROW_GRACE_SECS, &mut sender.section)?;
+        assert_eq!(refund_proof.recipient(), sender.actor.id());
+        let _ = propagate_to_crediting_replicas(refund_proof, &mut sender.section);
+        synch(&mut sender)?;
+        assert_balance(sender, Token::from_nano(100));
+        Ok(())
+    }
+
+    #[test]
+    fn escrow_settlement_is_not_changed_by_a_replica() -> Result<()> {
+        let mut actors = setup_actors_in_one_section(vec![100], 3)?;
+        let mut sender = actors.remove(0);
+        let condition = TransferCondition::new(b"secret", 1000);
+        let debit_proof = escrow_transfer(&mut sender, get_random_pk(), condition)?;
+
+        let id = debit_proof.id();
+        let release = EscrowSettlement::Release {
+            id,
+            secret: b"secret".to_vec(),
+        };
+        let wallet_replica = sender.section.elders[0]
+            .replicas
+            .get_mut(&sender.actor.id())
+            .ok_or(Error::WalletNotFound(sender.actor.id()))?;
+        let proposed = wallet_replica
+            .propose_settlement(&release, 1000 + ESCROW_GRACE_SECS - 1, true)?
+            .ok_or(Error::InvalidOperation)?;
+        wallet_replica.apply(ReplicaEvent::EscrowSettlementProposed(proposed))?;
+
+        assert!(wallet_replica
+            .propose_settlement(&release, 500, false)?
+            .is_none());
+        assert!(matches!(
+            wallet_replica.propose_settlement(
+                &EscrowSettlement::Refund(id),
+                1000 + ESCROW_GRACE_SECS,
+                false
+            ),
+            Err(Error::EscrowSettlementConflict)
+        ));
+        Ok(())
+    }
+
     // #[allow(trivial_casts)]
     // #[test]
     // fn quickcheck_basic_transfer() {
@@ -208,7 +643,7 @@ mod test {
                 println!("Found actor!");
                 if let Some(synched_event) = actor.actor.from_history(ActorHistory {
                     credits: vec![genesis_credit.clone()],
-                    debits: vec![],
+                    ..ActorHistory::empty()
                 })? {
                     actor
                         .actor
@@ -386,6 +821,69 @@ mod test {
         Ok(transfer)
     }
 
+    // 1-3. Transfer the whole balance of the sender into escrow.
+    fn escrow_transfer(
+        sender: &mut TestActor,
+        to: PublicKey,
+        condition: TransferCondition,
+    ) -> Result<TransferAgreementProof> {
+        let transfer = sender
+            .actor
+            .transfer_in_escrow(sender.actor.balance(), to, condition, "asdf".to_string())?
+            .ok_or(Error::TransferCreationFailed)?;
+        sender
+            .actor
+            .apply(ActorEvent::TransferInitiated(transfer.clone()))?;
+        let debit_proof =
+            validate_at_sender_replicas(transfer, sender)?.ok_or(Error::SenderValidationFailed)?;
+        register_at_debiting_replicas(&debit_proof, &mut sender.section)?;
+        Ok(debit_proof)
+    }
+
+    // 1-3. Settle an escrow, with each Replica proposing the settlement at `now`.
+    fn settle_escrow(
+        settlement: &EscrowSettlement,
+        now: u64,
+        section: &mut Section,
+    ) -> Result<CreditAgreementProof> {
+        let mut shares = BTreeMap::new();
+        let mut signed_credit = None;
+        for elder in &mut section.elders {
+            let wallet_replica = elder
+                .replicas
+                .get_mut(&settlement.id().actor)
+                .ok_or(Error::WalletNotFound(settlement.id().actor))?;
+            let proposed = wallet_replica
+                .propose_settlement(settlement, now, false)?
+                .ok_or(Error::InvalidOperation)?;
+            wallet_replica.apply(ReplicaEvent::EscrowSettlementProposed(proposed))?;
+            let credit = wallet_replica.settled_credit(settlement)?.clone();
+            let share = elder.signing.sign_validated_credit(&credit)?;
+            let _ = shares.insert(share.index, share.share);
+            signed_credit = Some(credit);
+        }
+        let sig = section
+            .id
+            .combine_signatures(&shares)
+            .map_err(|_| Error::CannotAggregate)?;
+        let credit_proof = CreditAgreementProof {
+            signed_credit: signed_credit.ok_or(Error::InvalidOperation)?,
+            debiting_replicas_sig: Signature::Bls(sig),
+            debiting_replicas_keys: section.id.clone(),
+        };
+        for elder in &mut section.elders {
+            let wallet_replica = elder
+                .replicas
+                .get_mut(&settlement.id().actor)
+                .ok_or(Error::WalletNotFound(settlement.id().actor))?;
+            let settled = wallet_replica
+                .settle(settlement, &credit_proof)?
+                .ok_or(Error::InvalidOperation)?;
+            wallet_replica.apply(settled)?;
+        }
+        Ok(credit_proof)
+    }
+
     // 2. Validate debit at Sender Replicas.
     fn validate_at_sender_replicas(
         transfer: TransferInitiated,
@@ -396,22 +894,33 @@ mod test {
                 Some(w) => w,
                 None => panic!("Failed the test; no such wallet."),
             };
-            let _ = wallet_replica
-                .validate(&transfer.signed_debit, &transfer.signed_credit)?
-                .ok_or(Error::ValidationFailed)?;
-
             let signed_transfer = SignedTransfer {
                 debit: transfer.signed_debit.clone(),
                 credit: transfer.signed_credit.clone(),
+                batch: transfer.batch.clone(),
+                escrow: transfer.escrow.clone(),
             };
+            let _ = wallet_replica
+                .validate(&signed_transfer)?
+                .ok_or(Error::ValidationFailed)?;
+
+            let signing = &elder.signing;
             let (replica_debit_sig, replica_credit_sig) =
-                elder.signing.sign_transfer(&signed_transfer)?;
+                signing.sign_transfer(&signed_transfer)?;
+            let batch = signed_transfer
+                .batch
+                .iter()
+                .map(|credit| Ok((credit.clone(), signing.sign_validated_credit(credit)?)))
+                .collect::<Result<Vec<_>>>()?;
+            let escrow = signed_transfer.escrow;
             let validation = crate::types::TransferValidated {
                 signed_credit: signed_transfer.credit,
                 signed_debit: signed_transfer.debit,
                 replica_debit_sig,
                 replica_credit_sig,
                 replicas: sender.section.id.clone(),
+                batch,
+                escrow,
             };
             // then apply to inmem state
             wallet_replica.apply(ReplicaEvent::TransferValidated(validation.clone()))?;
@@ -645,6 +1154,19 @@ mod test {
         })
     }
 
+    // Actors whose wallets are all held by the replicas of a single section.
+    fn setup_actors_in_one_section(
+        balances: Vec<u64>,
+        replica_count: u8,
+    ) -> Result<Vec<TestActor>> {
+        let wallets = balances
+            .into_iter()
+            .map(|balance| setup_random_wallet(balance, 0))
+            .collect::<Result<Vec<_>>>()?;
+        let section = setup_section(0, generate_section_keys(0, replica_count), wallets.clone());
+        get_test_actors(wallets, vec![section])
+    }
+
     fn get_test_actors(wallets: Vec<TestWallet>, sections: Vec<Section>) -> Result<Vec<TestActor>> {
         let mut actors = vec![];
         for wallet in wallets {
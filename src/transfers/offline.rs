@@ -0,0 +1,169 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{wallet::WalletSnapshot, Error, Result, TransferInitiated};
+use crate::types::{
+    Credit, Debit, OwnerType, PublicKey, Signature, SignedCredit, SignedDebit, SignedTransfer,
+    Signing, Token,
+};
+use crdts::Dot;
+
+/// Signs a transfer without any connection to the network,
+/// from a snapshot of the sender's wallet taken when it was online.
+///
+/// The transfer is later submitted by any client, which needs no access to the key.
+/// As debits are ordered, only one transfer can be signed from a given snapshot.
+pub fn sign_transfer<S: Signing>(
+    signing: &S,
+    wallet: &WalletSnapshot,
+    amount: Token,
+    recipient: PublicKey,
+    msg: String,
+) -> Result<SignedTransfer> {
+    let initiated = initiate(
+        signing,
+        wallet.debit_version,
+        wallet.balance,
+        vec![(amount, recipient)],
+        msg,
+    )?;
+    Ok(SignedTransfer {
+        debit: initiated.signed_debit,
+        credit: initiated.signed_credit,
+        batch: initiated.batch,
+        escrow: initiated.escrow,
+    })
+}
+
+/// Builds and signs a debit at the given version, paying each of the recipients their amount.
+pub(super) fn initiate<S: Signing>(
+    signing: &S,
+    debit_version: u64,
+    balance: Token,
+    payouts: Vec<(Token, PublicKey)>,
+    msg: String,
+) -> Result<TransferInitiated> {
+    let sender = signing.id().public_key();
+    if payouts.is_empty() {
+        return Err(Error::InvalidOperation);
+    }
+    if payouts.iter().any(|(_, recipient)| *recipient == sender) {
+        return Err(Error::SameSenderAndRecipient);
+    }
+    let amount = payouts
+        .iter()
+        .try_fold(Token::zero(), |total, (amount, _)| {
+            total.checked_add(*amount)
+        })
+        .ok_or(Error::InsufficientBalance)?;
+    if amount > balance {
+        return Err(Error::InsufficientBalance);
+    }
+    if payouts.iter().any(|(amount, _)| *amount == Token::zero()) {
+        return Err(Error::ZeroValueTransfer);
+    }
+
+    let debit = Debit {
+        id: Dot::new(sender, debit_version),
+        amount,
+    };
+    let mut credits = Vec::with_capacity(payouts.len());
+    for (index, (amount, recipient)) in payouts.into_iter().enumerate() {
+        // the first credit keeps the id of a single transfer's credit
+        let id = match index {
+            0 => debit.credit_id()?,
+            _ => debit.batch_credit_id(index - 1)?,
+        };
+        let credit = Credit {
+            id,
+            recipient,
+            amount,
+            msg: msg.clone(),
+        };
+        let actor_signature = signing.sign(&credit)?;
+        credits.push(SignedCredit {
+            credit,
+            actor_signature,
+        });
+    }
+
+    let actor_signature = signing.sign(&debit)?;
+    let signed_debit = SignedDebit {
+        debit,
+        actor_signature,
+    };
+    let signed_credit = credits.remove(0);
+
+    Ok(TransferInitiated {
+        signed_debit,
+        signed_credit,
+        batch: credits,
+        escrow: None,
+    })
+}
+
+/// The signing of a wallet whose key is kept elsewhere, f.ex. on an air-gapped machine.
+/// It verifies the owner's signatures, so that an Actor can follow the wallet
+/// and drive transfers signed offline, but it can't sign anything itself.
+#[derive(Clone, Debug)]
+pub struct WatchOnly(pub OwnerType);
+
+impl Signing for WatchOnly {
+    fn id(&self) -> OwnerType {
+        self.0.clone()
+    }
+
+    fn sign<T: serde::Serialize>(&self, _: &T) -> crate::types::Result<Signature> {
+        Err(crate::types::Error::InvalidOperation)
+    }
+
+    fn verify<T: serde::Serialize>(&self, signature: &Signature, data: &T) -> bool {
+        self.0.verify(signature, data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sign_transfer, Error, Result, WalletSnapshot};
+    use crate::types::{Keypair, PublicKey, Token};
+    use bls::SecretKey;
+
+    #[test]
+    fn signs_transfer_from_snapshot() -> Result<()> {
+        let keypair = Keypair::new_ed25519(&mut rand::thread_rng());
+        let recipient = PublicKey::from(SecretKey::random().public_key());
+        let wallet = WalletSnapshot {
+            balance: Token::from_nano(10),
+            debit_version: 3,
+            credit_ids: Default::default(),
+        };
+
+        let transfer = sign_transfer(&keypair, &wallet, Token::from_nano(4), recipient, "".into())?;
+        assert_eq!(transfer.sender(), keypair.public_key());
+        assert_eq!(transfer.id().counter, 3);
+        assert_eq!(transfer.amount(), Token::from_nano(4));
+        assert!(keypair
+            .public_key()
+            .verify(
+                &transfer.debit.actor_signature,
+                bincode::serialize(&transfer.debit.debit)
+                    .map_err(|e| Error::Serialisation(e.to_string()))?
+            )
+            .is_ok());
+
+        let result = sign_transfer(
+            &keypair,
+            &wallet,
+            Token::from_nano(11),
+            recipient,
+            "".into(),
+        );
+        assert!(matches!(result, Err(Error::InsufficientBalance)));
+        Ok(())
+    }
+}
@@ -254,7 +254,10 @@ impl ReplicaSigning {
         signed_transfer: &SignedTransfer,
     ) -> Result<(SignatureShare, SignatureShare)> {
         let replica_debit_sig = self.sign_validated_debit(&signed_transfer.debit)?;
-        let replica_credit_sig = self.sign_validated_credit(&signed_transfer.credit)?;
+        let replica_credit_sig = match &signed_transfer.escrow {
+            Some(escrow) => self.sign(&escrow.holding(&signed_transfer.credit))?,
+            None => self.sign_validated_credit(&signed_transfer.credit)?,
+        };
         Ok((replica_debit_sig, replica_credit_sig))
     }
 
@@ -269,7 +272,11 @@ impl ReplicaSigning {
     }
 
     pub fn sign_validated_credit(&self, credit: &SignedCredit) -> Result<SignatureShare> {
-        match bincode::serialize(credit) {
+        self.sign(credit)
+    }
+
+    fn sign<T: serde::Serialize>(&self, item: &T) -> Result<SignatureShare> {
+        match bincode::serialize(item) {
             Err(_) => Err(Error::Serialisation("Could not serialise credit".into())),
             Ok(data) => Ok(SignatureShare {
                 index: self.key_index,
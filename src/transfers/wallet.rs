@@ -9,12 +9,17 @@
 use super::{Error, Result};
 use crate::types::{Credit, CreditId, Debit, OwnerType, Token};
 use log::debug;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-#[derive(Debug, Clone)]
+/// The state of a wallet at some point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WalletSnapshot {
+    /// The balance of the wallet.
     pub balance: Token,
+    /// The version of the next debit from the wallet.
     pub debit_version: u64,
+    /// The ids of all credits to the wallet.
     pub credit_ids: HashSet<CreditId>,
 }
 
@@ -98,6 +103,17 @@ impl Wallet {
         }
     }
 
+    /// Mutates state, paying out from the faucet wallet of a test network,
+    /// which its Replicas debit without a debit by an Actor.
+    pub fn apply_payout(&mut self, credit: &Credit) -> Result<()> {
+        debug!("Wallet applying payout");
+        match self.balance.checked_sub(credit.amount) {
+            Some(amount) => self.balance = amount,
+            None => return Err(Error::SubtractionOverflow(credit.amount, self.balance)),
+        }
+        Ok(())
+    }
+
     /// Mutates state.
     pub fn apply_credit(&mut self, credit: Credit) -> Result<()> {
         debug!("Wallet applying credit");
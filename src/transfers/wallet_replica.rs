@@ -8,18 +8,17 @@
 
 use super::{
     wallet::{Wallet, WalletSnapshot},
-    Error, Outcome, Result, TernaryResult,
+    Error, Outcome, Result, TernaryResult, ESCROW_GRACE_SECS, MAX_PENDING_DEBITS,
 };
-#[cfg(feature = "simulated-payouts")]
-use crate::types::Credit;
 use crate::types::{
-    CreditAgreementProof, Debit, OwnerType, ReplicaEvent, Signature, SignedCredit, SignedDebit,
-    SignedTransfer, SignedTransferShare, Token, TransferAgreementProof, TransferRegistered,
-    TransferValidationProposed,
+    Credit, CreditAgreementProof, CreditId, Debit, DebitId, Escrow, EscrowRefunded, EscrowReleased,
+    EscrowSettlement, EscrowSettlementProposed, FaucetPaidOut, OwnerType, ReplicaEvent, Signature,
+    SignedCredit, SignedDebit, SignedTransfer, SignedTransferShare, Token, TransferAgreementProof,
+    TransferRegistered, TransferValidationProposed,
 };
 use bls::{PublicKeySet, PublicKeyShare};
 use log::{debug, error};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 
 macro_rules! hashmap {
@@ -54,6 +53,16 @@ pub struct WalletReplica {
     /// Ensures that invidual wallet's debit
     /// initiations (ValidateTransfer cmd) are sequential.
     pending_debit: Option<u64>,
+    /// The transfers validated but not yet registered, by debit counter.
+    validated_transfers: BTreeMap<u64, SignedTransfer>,
+    /// Registered transfers held in escrow, until released or refunded.
+    escrows: HashMap<DebitId, TransferAgreementProof>,
+    /// The settlements of escrows this Replica has signed.
+    settlement_votes: HashMap<DebitId, EscrowSettlement>,
+    /// Escrows released or refunded, which are never settled again.
+    settled_escrows: HashSet<DebitId>,
+    /// Payouts made from the faucet wallet, which are never made again.
+    faucet_payouts: HashSet<CreditId>,
 }
 
 impl WalletReplica {
@@ -101,6 +110,11 @@ impl WalletReplica {
             wallet,
             pending_proposals,
             pending_debit,
+            validated_transfers: Default::default(),
+            escrows: Default::default(),
+            settlement_votes: Default::default(),
+            settled_escrows: Default::default(),
+            faucet_payouts: Default::default(),
         }
     }
 
@@ -119,6 +133,11 @@ impl WalletReplica {
         Some(wallet.into())
     }
 
+    /// The proof of the transfer held in escrow with the given id, unless already settled.
+    pub fn escrowed(&self, id: &DebitId) -> Option<&TransferAgreementProof> {
+        self.escrows.get(id)
+    }
+
     /// -----------------------------------------------------------------
     /// ---------------------- Cmds -------------------------------------
     /// -----------------------------------------------------------------
@@ -151,11 +170,10 @@ impl WalletReplica {
     }
 
     /// Step 1. Main business logic validation of a debit.
-    pub fn validate(
-        &self,
-        signed_debit: &SignedDebit,
-        signed_credit: &SignedCredit,
-    ) -> Outcome<()> {
+    /// The credits of a batched transfer are validated along with it, as a unit.
+    pub fn validate(&self, signed_transfer: &SignedTransfer) -> Outcome<()> {
+        let signed_debit = &signed_transfer.debit;
+        let signed_credit = &signed_transfer.credit;
         let debit = &signed_debit.debit;
         let credit = &signed_credit.credit;
 
@@ -163,34 +181,99 @@ impl WalletReplica {
         if self
             .verify_actor_signature(&signed_debit, &signed_credit)
             .is_err()
+            || signed_transfer.batch.iter().any(|credit| {
+                self.verify_batch_credit_signature(signed_debit, credit)
+                    .is_err()
+            })
+            || signed_transfer.escrow.iter().any(|escrow| {
+                self.verify_batch_credit_signature(signed_debit, &escrow.refund)
+                    .is_err()
+            })
         {
             return Outcome::rejected(Error::InvalidSignature);
+        } else if self.validated_transfers.get(&debit.id.counter) == Some(signed_transfer) {
+            // Sent again by its Actor, f.ex. after validations were lost. As the debit can
+            // still be registered, no other debit is validated in its place.
+            return Outcome::no_change();
         } else if debit.sender() == credit.recipient() {
             return Outcome::rejected(Error::SameSenderAndRecipient);
         } else if credit.id() != &debit.credit_id()? {
             return Outcome::rejected(Error::CreditDebitIdMismatch);
-        } else if credit.amount() != debit.amount() {
-            return Outcome::rejected(Error::CreditDebitValueMismatch);
+        } else if let Err(error) = Self::validate_batch(debit, credit, &signed_transfer.batch) {
+            return Outcome::rejected(error);
+        } else if let Err(error) = Self::validate_escrow(debit, signed_transfer) {
+            return Outcome::rejected(error);
         } else if debit.amount() == Token::zero() {
             return Outcome::rejected(Error::ZeroValueTransfer);
         } else if self.wallet.id().public_key() != debit.sender() {
             return Outcome::rejected(Error::NoSuchSender);
         } else if self.pending_debit.is_none() && debit.id.counter != 0 {
             return Outcome::rejected(Error::ShouldBeInitialOperation);
-        } else if let Some(counter) = self.pending_debit {
-            if debit.id.counter != (counter + 1) {
-                return Outcome::rejected(Error::OperationOutOfOrder(
-                    debit.id.counter,
-                    counter + 1,
-                ));
-            }
-        } else if debit.amount() > self.balance() {
+        } else if let Some(counter) = self
+            .pending_debit
+            .filter(|counter| debit.id.counter != counter + 1)
+        {
+            return Outcome::rejected(Error::OperationOutOfOrder(debit.id.counter, counter + 1));
+        } else if debit.id.counter >= self.wallet.next_debit() + MAX_PENDING_DEBITS {
+            return Outcome::rejected(Error::DebitPending);
+        } else if debit.amount() > self.available_balance() {
             return Outcome::rejected(Error::InsufficientBalance);
         }
 
         Outcome::success(())
     }
 
+    // The balance less the debits validated but not yet registered,
+    // as those can still be registered by the Actor at any time.
+    fn available_balance(&self) -> Token {
+        self.validated_transfers
+            .values()
+            .try_fold(self.balance(), |balance, transfer| {
+                balance.checked_sub(transfer.amount())
+            })
+            .unwrap_or_else(Token::zero)
+    }
+
+    // Checks the credits of a batch, if any, correspond to the debit and add up to its amount.
+    fn validate_batch(debit: &Debit, credit: &Credit, batch: &[SignedCredit]) -> Result<()> {
+        let mut total = credit.amount();
+        for (index, signed_credit) in batch.iter().enumerate() {
+            let credit = &signed_credit.credit;
+            if debit.sender() == credit.recipient() {
+                return Err(Error::SameSenderAndRecipient);
+            } else if credit.id() != &debit.batch_credit_id(index)? {
+                return Err(Error::CreditDebitIdMismatch);
+            } else if credit.amount() == Token::zero() {
+                return Err(Error::ZeroValueTransfer);
+            }
+            total = total
+                .checked_add(credit.amount())
+                .ok_or(Error::CreditDebitValueMismatch)?;
+        }
+        if total != debit.amount() {
+            return Err(Error::CreditDebitValueMismatch);
+        }
+        Ok(())
+    }
+
+    // Checks the escrow of a conditional transfer, if any, refunds the whole debit to its sender.
+    fn validate_escrow(debit: &Debit, signed_transfer: &SignedTransfer) -> Result<()> {
+        let escrow = match &signed_transfer.escrow {
+            Some(escrow) => escrow,
+            None => return Ok(()),
+        };
+        let refund = &escrow.refund.credit;
+        if !signed_transfer.batch.is_empty() || refund.recipient() != debit.sender() {
+            Err(Error::InvalidOperation)
+        } else if refund.id() != &debit.refund_credit_id(&escrow.condition)? {
+            Err(Error::CreditDebitIdMismatch)
+        } else if refund.amount() != debit.amount() {
+            Err(Error::CreditDebitValueMismatch)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Step 2. Validation of agreement, and order at debit source.
     pub fn register(&self, transfer_proof: &TransferAgreementProof) -> Outcome<TransferRegistered> {
         debug!("Checking registered transfer");
@@ -226,6 +309,123 @@ impl WalletReplica {
         }
     }
 
+    /// Proposal of the settlement of a transfer held in escrow, i.e. this Replica's vote on it.
+    /// Once signed, the Replica never signs a different settlement of the escrow.
+    ///
+    /// `now` is the time of this Replica, in seconds since the Unix epoch.
+    /// A release needs the secret before the deadline, or, when `seconded` by another Replica
+    /// having signed the release, before the deadline plus the grace for clock differences.
+    /// A refund needs the deadline plus that grace to have passed, so that a quorum can not
+    /// sign a release and a refund of the same escrow.
+    pub fn propose_settlement(
+        &self,
+        settlement: &EscrowSettlement,
+        now: u64,
+        seconded: bool,
+    ) -> Outcome<EscrowSettlementProposed> {
+        let id = settlement.id();
+        let escrow = self.pending_escrow(id)?;
+        match self.settlement_votes.get(&id) {
+            Some(vote) if vote == settlement => return Outcome::no_change(),
+            Some(_) => return Outcome::rejected(Error::EscrowSettlementConflict),
+            None => (),
+        }
+        let deadline = escrow.condition.deadline;
+        match settlement {
+            EscrowSettlement::Release { secret, .. } => {
+                let grace = if seconded { ESCROW_GRACE_SECS } else { 0 };
+                if !escrow.condition.is_unlocked_by(secret) {
+                    return Outcome::rejected(Error::EscrowLocked);
+                } else if now >= deadline.saturating_add(grace) {
+                    return Outcome::rejected(Error::EscrowExpired);
+                }
+            }
+            EscrowSettlement::Refund(_) => {
+                if now < deadline.saturating_add(ESCROW_GRACE_SECS) {
+                    return Outcome::rejected(Error::EscrowNotExpired);
+                }
+            }
+        }
+        Outcome::success(EscrowSettlementProposed {
+            settlement: settlement.clone(),
+        })
+    }
+
+    /// The credit the escrow settles to, which the Replicas sign when proposing the settlement.
+    pub fn settled_credit(&self, settlement: &EscrowSettlement) -> Result<&SignedCredit> {
+        let id = settlement.id();
+        if self.settled_escrows.contains(&id) {
+            return Err(Error::EscrowSettled(id));
+        }
+        self.escrows
+            .get(&id)
+            .and_then(|proof| proof.settled_credit(settlement))
+            .ok_or(Error::NoSuchEscrow(id))
+    }
+
+    /// Settlement of a transfer held in escrow, with the quorum of Replica sigs over the credit
+    /// it settles to. An escrow is settled only once.
+    pub fn settle(
+        &self,
+        settlement: &EscrowSettlement,
+        credit_proof: &CreditAgreementProof,
+    ) -> Outcome<ReplicaEvent> {
+        let id = settlement.id();
+        if &credit_proof.signed_credit != self.settled_credit(settlement)? {
+            return Outcome::rejected(Error::CreditDebitIdMismatch);
+        } else if credit_proof.debiting_replicas_keys != self.peer_replicas {
+            return Outcome::rejected(Error::InvalidSignature);
+        }
+        self.verify_propagated_proof(credit_proof)?;
+        let event = match settlement {
+            EscrowSettlement::Release { secret, .. } => {
+                if !self.pending_escrow(id)?.condition.is_unlocked_by(secret) {
+                    return Outcome::rejected(Error::EscrowLocked);
+                }
+                ReplicaEvent::EscrowReleased(EscrowReleased {
+                    id,
+                    secret: secret.clone(),
+                    credit_proof: credit_proof.clone(),
+                })
+            }
+            EscrowSettlement::Refund(_) => ReplicaEvent::EscrowRefunded(EscrowRefunded {
+                id,
+                credit_proof: credit_proof.clone(),
+            }),
+        };
+        Outcome::success(event)
+    }
+
+    /// Payout from the faucet wallet of a test network, with the quorum of Replica sigs over the
+    /// credit to the recipient. A payout is made only once.
+    ///
+    /// `now` is the time of this Replica, in seconds since the Unix epoch.
+    pub fn pay_out(&self, credit_proof: &CreditAgreementProof, now: u64) -> Outcome<FaucetPaidOut> {
+        if self.faucet_payouts.contains(credit_proof.id()) {
+            return Outcome::no_change();
+        } else if credit_proof.debiting_replicas_keys != self.peer_replicas {
+            return Outcome::rejected(Error::InvalidSignature);
+        }
+        self.verify_propagated_proof(credit_proof)?;
+        if credit_proof.amount() > self.balance() {
+            return Outcome::rejected(Error::InsufficientBalance);
+        }
+        Outcome::success(FaucetPaidOut {
+            credit_proof: credit_proof.clone(),
+            time: now,
+        })
+    }
+
+    fn pending_escrow(&self, id: DebitId) -> Result<&Escrow> {
+        if self.settled_escrows.contains(&id) {
+            return Err(Error::EscrowSettled(id));
+        }
+        self.escrows
+            .get(&id)
+            .and_then(|proof| proof.escrow.as_ref())
+            .ok_or(Error::NoSuchEscrow(id))
+    }
+
     /// -----------------------------------------------------------------
     /// ---------------------- Mutation ---------------------------------
     /// -----------------------------------------------------------------
@@ -249,12 +449,23 @@ impl WalletReplica {
                 Ok(())
             }
             ReplicaEvent::TransferValidated(e) => {
-                let debit = e.signed_debit.debit;
-                self.pending_debit = Some(debit.id.counter);
+                let counter = e.signed_debit.id().counter;
+                self.pending_debit = Some(counter);
+                let transfer = SignedTransfer {
+                    debit: e.signed_debit,
+                    credit: e.signed_credit,
+                    batch: e.batch.into_iter().map(|(credit, _)| credit).collect(),
+                    escrow: e.escrow,
+                };
+                let _ = self.validated_transfers.insert(counter, transfer);
                 Ok(())
             }
             ReplicaEvent::TransferRegistered(e) => {
-                let debit = e.transfer_proof.signed_debit.debit;
+                let debit = e.transfer_proof.signed_debit.debit.clone();
+                let _ = self.validated_transfers.remove(&debit.id.counter);
+                if e.transfer_proof.is_escrowed() && !self.settled_escrows.contains(&debit.id()) {
+                    let _ = self.escrows.insert(debit.id(), e.transfer_proof);
+                }
                 self.wallet.apply_debit(Debit {
                     id: debit.id(),
                     amount: debit.amount(),
@@ -262,11 +473,48 @@ impl WalletReplica {
             }
             ReplicaEvent::TransferPropagated(e) => {
                 let credit = e.credit_proof.signed_credit.credit;
+                // A refund settles its escrow, also when the history lacks the refund event.
+                let refunded = self
+                    .escrows
+                    .iter()
+                    .find_map(|(id, proof)| match &proof.escrow {
+                        Some(escrow) if escrow.refund.id() == credit.id() => Some(*id),
+                        _ => None,
+                    });
+                if let Some(id) = refunded {
+                    self.settle_escrow(id);
+                }
                 self.wallet.apply_credit(credit)
             }
+            ReplicaEvent::EscrowSettlementProposed(e) => {
+                let _ = self
+                    .settlement_votes
+                    .insert(e.settlement.id(), e.settlement);
+                Ok(())
+            }
+            ReplicaEvent::EscrowReleased(e) => {
+                self.settle_escrow(e.id);
+                Ok(())
+            }
+            ReplicaEvent::EscrowRefunded(e) => {
+                self.settle_escrow(e.id);
+                Ok(())
+            }
+            ReplicaEvent::FaucetPayoutProposed(_) => Ok(()),
+            ReplicaEvent::FaucetPaidOut(e) => {
+                let credit = e.credit_proof.signed_credit.credit;
+                let _ = self.faucet_payouts.insert(credit.id);
+                self.wallet.apply_payout(&credit)
+            }
         }
     }
 
+    fn settle_escrow(&mut self, id: DebitId) {
+        let _ = self.escrows.remove(&id);
+        let _ = self.settlement_votes.remove(&id);
+        let _ = self.settled_escrows.insert(id);
+    }
+
     /// Test-helper API to simulate Client CREDIT Transfers.
     #[cfg(feature = "simulated-payouts")]
     pub fn credit_without_proof(&mut self, credit: Credit) -> Result<()> {
@@ -317,6 +565,21 @@ impl WalletReplica {
         }
     }
 
+    fn verify_batch_credit_signature(
+        &self,
+        signed_debit: &SignedDebit,
+        signed_credit: &SignedCredit,
+    ) -> Result<()> {
+        let credit_bytes = match bincode::serialize(&signed_credit.credit) {
+            Err(_) => return Err(Error::Serialisation("Could not serialise credit".into())),
+            Ok(bytes) => bytes,
+        };
+        signed_debit
+            .sender()
+            .verify(&signed_credit.actor_signature, credit_bytes)
+            .map_err(|_| Error::InvalidSignature)
+    }
+
     /// Verify that this is a valid _registered_
     /// TransferAgreementProof, i.e. signed by our peers.
     fn verify_registered_proof(&self, proof: &TransferAgreementProof) -> Result<()> {
@@ -328,7 +591,12 @@ impl WalletReplica {
             Ok(bytes) => bytes,
             Err(_) => return Err(Error::Serialisation("Could not serialise transfer".into())),
         };
-        let credit_bytes = match bincode::serialize(&proof.signed_credit) {
+        // The credit of an escrowed transfer is signed along with its escrow.
+        let credit_bytes = match &proof.escrow {
+            Some(escrow) => bincode::serialize(&escrow.holding(&proof.signed_credit)),
+            None => bincode::serialize(&proof.signed_credit),
+        };
+        let credit_bytes = match credit_bytes {
             Ok(bytes) => bytes,
             Err(_) => return Err(Error::Serialisation("Could not serialise transfer".into())),
         };
@@ -336,7 +604,28 @@ impl WalletReplica {
         let public_key = crate::types::PublicKey::Bls(self.peer_replicas.public_key());
         let valid_debit = public_key.verify(&proof.debit_sig, &debit_bytes).is_ok();
         let valid_credit = public_key.verify(&proof.credit_sig, &credit_bytes).is_ok();
-        if valid_debit && valid_credit {
+        let mut valid_batch = true;
+        for (index, (signed_credit, sig)) in proof.batch.iter().enumerate() {
+            if signed_credit.id() != &proof.signed_debit.debit.batch_credit_id(index)? {
+                return Err(Error::CreditDebitIdMismatch);
+            }
+            let bytes = match bincode::serialize(signed_credit) {
+                Ok(bytes) => bytes,
+                Err(_) => return Err(Error::Serialisation("Could not serialise transfer".into())),
+            };
+            valid_batch &= public_key.verify(sig, &bytes).is_ok();
+        }
+        if let Some(escrow) = &proof.escrow {
+            if escrow.refund.id()
+                != &proof
+                    .signed_debit
+                    .debit
+                    .refund_credit_id(&escrow.condition)?
+            {
+                return Err(Error::CreditDebitIdMismatch);
+            }
+        }
+        if valid_debit && valid_credit && valid_batch {
             return Ok(());
         }
 
@@ -500,6 +789,8 @@ impl WalletReplica {
                     credit: signed_credit.credit.clone(),
                     actor_signature: Signature::Bls(credit_sig),
                 },
+                batch: vec![],
+                escrow: None,
             });
             Outcome::success(proposal)
         } else {
@@ -561,14 +852,16 @@ impl fmt::Debug for WalletReplica {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "WalletReplica {{ id: {:?}, replica_id: {:?}, key_index: {:?}, peer_replicas: PkSet {{ public_key: {:?} }}, wallet: {:?}, pending_proposals: {:?}, pending_debit: {:?} }}",
+            "WalletReplica {{ id: {:?}, replica_id: {:?}, key_index: {:?}, peer_replicas: PkSet {{ public_key: {:?} }}, wallet: {:?}, pending_proposals: {:?}, pending_debit: {:?}, validated_transfers: {:?}, escrows: {:?} }}",
             self.id,
             self.replica_id,
             self.key_index,
             self.peer_replicas.public_key(),
             self.wallet,
             self.pending_proposals,
-            self.pending_debit
+            self.pending_debit,
+            self.validated_transfers,
+            self.escrows.keys().collect::<Vec<_>>()
         )
     }
 }
@@ -0,0 +1,416 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under the MIT license <LICENSE-MIT
+// https://opensource.org/licenses/MIT> or the Modified BSD license <LICENSE-BSD
+// https://opensource.org/licenses/BSD-3-Clause>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms. Please review the Licences for the
+// specific language governing permissions and limitations relating to use of the SAFE Network
+// Software.
+
+use bls::PublicKey as BlsPublicKey;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter},
+    iter,
+    net::SocketAddr,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use xor_name::Prefix;
+
+/// Name of the file a `ContactCache` is stored in, within the bootstrap cache directory.
+pub const CONTACT_CACHE_FILE: &str = "contact_cache";
+
+/// Maximum number of contacts kept in the cache.
+const MAX_CONTACTS: usize = 200;
+/// Latency assumed for contacts we never got a response from, in milliseconds.
+const DEFAULT_LATENCY_MS: u64 = 500;
+
+/// Statistics kept about a single bootstrap contact.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactStats {
+    /// Number of times bootstrapping through the contact succeeded.
+    pub successes: u32,
+    /// Number of times the contact could not be reached or did not respond in time.
+    pub failures: u32,
+    /// Smoothed response latency of the contact, in milliseconds.
+    pub latency_ms: Option<u64>,
+    /// Prefix of the section the contact was last seen an Elder of.
+    pub prefix: Option<Prefix>,
+    /// When the contact was last seen, in seconds since the UNIX epoch.
+    pub last_seen: Option<u64>,
+    /// Genesis key of the network the contact was last seen an Elder in.
+    pub genesis_key: Option<BlsPublicKey>,
+}
+
+impl ContactStats {
+    /// Score of the contact: its success rate weighted down by its latency. Contacts we know
+    /// nothing about score in the middle, so they are tried after proven ones but before failing
+    /// ones.
+    pub fn score(&self) -> f64 {
+        // Laplace smoothing, so a single outcome doesn't decide the rate.
+        let success_rate = (f64::from(self.successes) + 1.0)
+            / (f64::from(self.successes) + f64::from(self.failures) + 2.0);
+        let latency_ms = self.latency_ms.unwrap_or(DEFAULT_LATENCY_MS) as f64;
+
+        success_rate * 1000.0 / (1000.0 + latency_ms)
+    }
+}
+
+/// Persistent cache of the contacts to bootstrap to the network through, scored by how well they
+/// served us before.
+///
+/// Contacts are learned from the Elders reported in section info responses, and scored as they are
+/// used to bootstrap. Each contact is keyed to the network it was seen in by the network's genesis
+/// key, so that only contacts of the network we bootstrap to are tried.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContactCache {
+    contacts: BTreeMap<SocketAddr, ContactStats>,
+}
+
+impl ContactCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the cache from the given file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Reads the cache from the given file, starting with an empty one if the file is missing or
+    /// can't be read.
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_else(|err| {
+            if err.kind() != io::ErrorKind::NotFound {
+                warn!("Discarding contact cache at {}: {}", path.display(), err);
+            }
+            Self::default()
+        })
+    }
+
+    /// Writes the cache to the given file, creating its parent directory if needed.
+    pub fn store(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Write to a temporary file of our own first, so a concurrent reader never sees a partial
+        // cache and concurrent writers don't write over each other's.
+        let tmp_path = path.with_extension(format!("{:016x}.tmp", rand::random::<u64>()));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)?;
+        let result = serde_json::to_writer(BufWriter::new(file), self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            .and_then(|()| fs::rename(&tmp_path, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    /// Returns the statistics kept about the contact, if any.
+    pub fn get(&self, addr: &SocketAddr) -> Option<&ContactStats> {
+        self.contacts.get(addr)
+    }
+
+    /// Number of contacts in the cache.
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Returns whether the cache holds no contacts.
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+
+    /// Records a successful bootstrap through the contact, which responded after `latency`.
+    pub fn record_success(&mut self, addr: SocketAddr, latency: Duration) {
+        let stats = self.contacts.entry(addr).or_default();
+        stats.successes = stats.successes.saturating_add(1);
+
+        let sample = latency.as_millis() as u64;
+        stats.latency_ms = Some(match stats.latency_ms {
+            // Exponential moving average, so the latency follows the contact's current state.
+            Some(latency_ms) => (3 * latency_ms + sample) / 4,
+            None => sample,
+        });
+        stats.last_seen = Some(now());
+
+        self.evict();
+    }
+
+    /// Records a failed attempt to bootstrap through the contact.
+    pub fn record_failure(&mut self, addr: SocketAddr) {
+        let stats = self.contacts.entry(addr).or_default();
+        stats.failures = stats.failures.saturating_add(1);
+
+        self.evict();
+    }
+
+    /// Refreshes the cache with the Elders of the section with the given prefix, in the network
+    /// with the given genesis key, as reported in a section info response. What was known about
+    /// the contacts in another network is dropped.
+    pub fn refresh(
+        &mut self,
+        genesis_key: BlsPublicKey,
+        prefix: Prefix,
+        elders: impl IntoIterator<Item = SocketAddr>,
+    ) {
+        let now = now();
+        for addr in elders {
+            let stats = self.contacts.entry(addr).or_default();
+            if stats.genesis_key.is_some_and(|key| key != genesis_key) {
+                *stats = ContactStats::default();
+            }
+            stats.genesis_key = Some(genesis_key);
+            stats.prefix = Some(prefix);
+            stats.last_seen = Some(now);
+        }
+
+        self.evict();
+    }
+
+    /// Returns the genesis key of the network the `extra` contacts (e.g. the hard-coded ones) were
+    /// last seen in, or of the network any contact was last seen in if none of them was.
+    pub fn network(&self, extra: &HashSet<SocketAddr>) -> Option<BlsPublicKey> {
+        let last_seen = |contacts: &mut dyn Iterator<Item = &ContactStats>| {
+            contacts
+                .filter(|stats| stats.genesis_key.is_some())
+                .max_by_key(|stats| stats.last_seen)
+                .and_then(|stats| stats.genesis_key)
+        };
+
+        last_seen(&mut extra.iter().filter_map(|addr| self.contacts.get(addr)))
+            .or_else(|| last_seen(&mut self.contacts.values()))
+    }
+
+    /// Returns the contacts to bootstrap through, best first. `extra` contacts (e.g. the
+    /// hard-coded ones) are included even when not in the cache. Other contacts are only included
+    /// if of the network of the `extra` contacts, or never seen in any network.
+    pub fn ranked(&self, extra: &HashSet<SocketAddr>) -> Vec<SocketAddr> {
+        let network = self.network(extra);
+        let default_stats = ContactStats::default();
+        let mut contacts: Vec<_> = self
+            .contacts
+            .iter()
+            .filter(|(addr, stats)| {
+                !extra.contains(addr)
+                    && (stats.genesis_key.is_none() || stats.genesis_key == network)
+            })
+            .chain(extra.iter().map(|addr| {
+                let stats = self.contacts.get(addr).unwrap_or(&default_stats);
+                (addr, stats)
+            }))
+            .collect();
+        contacts.sort_by(|(_, lhs), (_, rhs)| compare(rhs, lhs));
+        contacts.into_iter().map(|(addr, _)| *addr).collect()
+    }
+
+    /// Splits the contacts into rounds of `parallelism` to be raced against each other, best
+    /// first. The `hard_coded` contacts were explicitly configured, so they all join the first
+    /// round whatever their score.
+    pub fn bootstrap_rounds(
+        &self,
+        hard_coded: &HashSet<SocketAddr>,
+        parallelism: usize,
+    ) -> Vec<Vec<SocketAddr>> {
+        let ranked = self.ranked(hard_coded);
+        let (first_round, rest): (Vec<_>, Vec<_>) = ranked
+            .into_iter()
+            .enumerate()
+            .partition(|(rank, addr)| *rank < parallelism || hard_coded.contains(addr));
+
+        let first_round = first_round.into_iter().map(|(_, addr)| addr).collect();
+        let rest: Vec<_> = rest.into_iter().map(|(_, addr)| addr).collect();
+        iter::once(first_round)
+            .chain(rest.chunks(parallelism.max(1)).map(|round| round.to_vec()))
+            .filter(|round: &Vec<_>| !round.is_empty())
+            .collect()
+    }
+
+    // Drops the worst contacts once the cache is over capacity.
+    fn evict(&mut self) {
+        while self.contacts.len() > MAX_CONTACTS {
+            let worst = self
+                .contacts
+                .iter()
+                .min_by(|(_, lhs), (_, rhs)| compare(lhs, rhs))
+                .map(|(addr, _)| *addr);
+            if let Some(addr) = worst {
+                let _ = self.contacts.remove(&addr);
+            }
+        }
+    }
+}
+
+// Orders contacts by score, then by how recently they were seen.
+fn compare(lhs: &ContactStats, rhs: &ContactStats) -> Ordering {
+    lhs.score()
+        .partial_cmp(&rhs.score())
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| lhs.last_seen.cmp(&rhs.last_seen))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use std::env::temp_dir;
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    fn genesis_key() -> BlsPublicKey {
+        bls::SecretKey::random().public_key()
+    }
+
+    #[test]
+    fn ranks_by_success_rate_and_latency() {
+        let mut cache = ContactCache::new();
+        cache.record_success(addr(1), Duration::from_millis(800));
+        cache.record_success(addr(2), Duration::from_millis(50));
+        cache.record_failure(addr(3));
+        cache.record_failure(addr(3));
+
+        let extra = vec![addr(2), addr(4)].into_iter().collect();
+        assert_eq!(
+            cache.ranked(&extra),
+            vec![addr(2), addr(1), addr(4), addr(3)]
+        );
+    }
+
+    #[test]
+    fn hard_coded_contacts_join_first_round() {
+        let mut cache = ContactCache::new();
+        for port in 1..=5 {
+            cache.record_success(addr(port), Duration::from_millis(u64::from(port)));
+        }
+        cache.record_failure(addr(6));
+
+        let hard_coded = vec![addr(6), addr(7)].into_iter().collect();
+        assert_eq!(
+            cache.bootstrap_rounds(&hard_coded, 2),
+            vec![
+                vec![addr(1), addr(2), addr(7), addr(6)],
+                vec![addr(3), addr(4)],
+                vec![addr(5)],
+            ]
+        );
+        assert!(ContactCache::new()
+            .bootstrap_rounds(&HashSet::new(), 2)
+            .is_empty());
+    }
+
+    #[test]
+    fn refresh_records_prefix() {
+        let mut cache = ContactCache::new();
+        let prefix = Prefix::default().pushed(true);
+        cache.record_failure(addr(1));
+        cache.refresh(genesis_key(), prefix, vec![addr(1), addr(2)]);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.get(&addr(1)).and_then(|stats| stats.prefix),
+            Some(prefix)
+        );
+        assert_eq!(cache.get(&addr(1)).map(|stats| stats.failures), Some(1));
+        assert!(cache
+            .get(&addr(2))
+            .and_then(|stats| stats.last_seen)
+            .is_some());
+    }
+
+    #[test]
+    fn refresh_in_another_network_drops_stats() {
+        let mut cache = ContactCache::new();
+        let (old_network, new_network) = (genesis_key(), genesis_key());
+        cache.refresh(old_network, Prefix::default(), vec![addr(1)]);
+        cache.record_success(addr(1), Duration::from_millis(20));
+        cache.refresh(new_network, Prefix::default(), vec![addr(1)]);
+
+        let stats = cache.get(&addr(1)).copied().unwrap_or_default();
+        assert_eq!(stats.genesis_key, Some(new_network));
+        assert_eq!(stats.successes, 0);
+        assert_eq!(stats.latency_ms, None);
+    }
+
+    #[test]
+    fn ranks_only_contacts_of_the_network_of_the_hard_coded_ones() {
+        let mut cache = ContactCache::new();
+        let (network, other_network) = (genesis_key(), genesis_key());
+        cache.refresh(other_network, Prefix::default(), vec![addr(3), addr(4)]);
+        cache.refresh(network, Prefix::default(), vec![addr(1), addr(2)]);
+        cache.record_success(addr(5), Duration::from_millis(20));
+
+        let hard_coded = vec![addr(1)].into_iter().collect();
+        assert_eq!(cache.network(&hard_coded), Some(network));
+        let mut ranked = cache.ranked(&hard_coded);
+        ranked.sort();
+        assert_eq!(ranked, vec![addr(1), addr(2), addr(5)]);
+
+        // hard-coded contacts are tried whatever their network
+        let hard_coded = vec![addr(3), addr(6)].into_iter().collect();
+        assert_eq!(cache.network(&hard_coded), Some(other_network));
+        let mut ranked = cache.ranked(&hard_coded);
+        ranked.sort();
+        assert_eq!(ranked, vec![addr(3), addr(4), addr(5), addr(6)]);
+    }
+
+    #[test]
+    fn evicts_worst_contacts() {
+        let mut cache = ContactCache::new();
+        cache.record_failure(addr(0));
+        for port in 1..=MAX_CONTACTS as u16 {
+            cache.record_success(addr(port), Duration::from_millis(10));
+        }
+
+        assert_eq!(cache.len(), MAX_CONTACTS);
+        assert!(cache.get(&addr(0)).is_none());
+    }
+
+    #[test]
+    fn store_and_load() -> Result<()> {
+        let path = temp_dir()
+            .join(format!("contact_cache_{}", rand::random::<u64>()))
+            .join(CONTACT_CACHE_FILE);
+
+        let mut cache = ContactCache::new();
+        cache.record_success(addr(1), Duration::from_millis(20));
+        cache.refresh(
+            genesis_key(),
+            Prefix::default().pushed(false),
+            vec![addr(2)],
+        );
+        cache.store(&path)?;
+        cache.store(&path)?;
+
+        assert_eq!(ContactCache::load(&path)?, cache);
+        // no temporary file is left behind
+        if let Some(dir) = path.parent() {
+            assert_eq!(fs::read_dir(dir)?.count(), 1);
+        }
+        assert_eq!(
+            ContactCache::load_or_default(&path.with_extension("missing")),
+            ContactCache::new()
+        );
+
+        Ok(())
+    }
+}
@@ -103,6 +103,12 @@ pub enum Error {
     /// The CRDT operation cannot be applied as it targets a different content address.
     #[error("The CRDT operation cannot be applied as it targets a different content address.")]
     CrdtWrongAddress(Address),
+    /// Not enough owners have signed the update. Contains the number of signatures required.
+    #[error("The update needs the signatures of {0} owners")]
+    NotEnoughSignatures(usize),
+    /// The proof is not signed by a section the verifier trusts.
+    #[error("The proof is not signed by a trusted section")]
+    UntrustedSectionChain,
 }
 
 pub(crate) fn convert_bincode_error(err: bincode::Error) -> Error {
@@ -10,14 +10,17 @@
 //! SAFE network data types.
 
 mod chunk;
+mod contacts;
 mod errors;
 mod keys;
 mod map;
+mod ownership;
 /// Register data type
 pub mod register;
 mod rewards;
 mod section;
 mod sequence;
+mod supply;
 mod token;
 mod transfer;
 mod utils;
@@ -26,6 +29,7 @@ pub use chunk::{
     Address as ChunkAddress, Chunk, Kind as ChunkKind, PrivateChunk, PublicChunk,
     MAX_CHUNK_SIZE_IN_BYTES,
 };
+pub use contacts::{ContactCache, ContactStats, CONTACT_CACHE_FILE};
 pub use errors::{Error, Result};
 pub use keys::{
     BlsKeypairShare, Keypair, NodeKeypairs, OwnerType, PublicKey, SecretKey, Signature,
@@ -39,16 +43,25 @@ pub use map::{
     UnseqEntries as MapUnseqEntries, UnseqEntryAction as MapUnseqEntryAction,
     UnseqEntryActions as MapUnseqEntryActions, Value as MapValue, Values as MapValues,
 };
+pub use ownership::{Ownership, PolicyUpdate};
 pub use register::Address as RegisterAddress;
-pub use rewards::{AccumulatingReward, NodeAge, RewardAccumulation, RewardProposal};
+pub use rewards::{
+    AccumulatingReward, NoRewardReason, NodeAge, NodeReward, RewardAccumulation, RewardProposal,
+    RewardRound, RewardStatement, RewardStatementEntry,
+};
 pub use section::SectionElders;
 pub use sequence::{
-    Action as SequenceAction, Address as SequenceAddress, Data as Sequence, DataOp as SequenceOp,
-    Entries as SequenceEntries, Entry as SequenceEntry, Index as SequenceIndex,
-    Kind as SequenceKind, Permissions as SequencePermissions, Policy as SequencePolicy,
-    PrivatePermissions as SequencePrivatePermissions, PrivatePolicy as SequencePrivatePolicy,
-    PrivateSeqData, PublicPermissions as SequencePublicPermissions,
-    PublicPolicy as SequencePublicPolicy, PublicSeqData, User as SequenceUser,
+    Action as SequenceAction, Address as SequenceAddress, Clock as SequenceClock, Data as Sequence,
+    DataOp as SequenceOp, Entries as SequenceEntries, Entry as SequenceEntry,
+    EntryOp as SequenceEntryOp, Index as SequenceIndex, Kind as SequenceKind,
+    Permissions as SequencePermissions, Policy as SequencePolicy,
+    PolicyUpdate as SequencePolicyUpdate, PrivatePermissions as SequencePrivatePermissions,
+    PrivatePolicy as SequencePrivatePolicy, PrivateSeqData,
+    PublicPermissions as SequencePublicPermissions, PublicPolicy as SequencePublicPolicy,
+    PublicSeqData, User as SequenceUser,
+};
+pub use supply::{
+    SectionSupply, SectionSupplyShare, SectionTotals, SignedSectionSupply, SupplyTotals, MAX_SUPPLY,
 };
 pub use token::Token;
 pub use transfer::*;
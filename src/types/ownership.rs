@@ -0,0 +1,167 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under the MIT license <LICENSE-MIT
+// https://opensource.org/licenses/MIT> or the Modified BSD license <LICENSE-BSD
+// https://opensource.org/licenses/BSD-3-Clause>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms. Please review the Licences for the
+// specific language governing permissions and limitations relating to use of the SAFE Network
+// Software.
+
+use super::{utils, Error, Keypair, PublicKey, Result, Signature};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The owners of a Register or Sequence, i.e. the keys allowed to update its policy, along with
+/// the number of them which must sign an update.
+#[derive(Clone, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize, Debug)]
+pub struct Ownership {
+    owners: BTreeSet<PublicKey>,
+    threshold: usize,
+}
+
+impl Ownership {
+    /// Ownership by a single key.
+    pub fn single(owner: PublicKey) -> Self {
+        Self {
+            owners: vec![owner].into_iter().collect(),
+            threshold: 1,
+        }
+    }
+
+    /// Ownership by several keys, `threshold` of which must sign policy updates.
+    pub fn multi(owners: BTreeSet<PublicKey>, threshold: usize) -> Result<Self> {
+        if threshold == 0 || threshold > owners.len() {
+            return Err(Error::InvalidOperation);
+        }
+        Ok(Self { owners, threshold })
+    }
+
+    /// Returns the owners.
+    pub fn owners(&self) -> &BTreeSet<PublicKey> {
+        &self.owners
+    }
+
+    /// Returns the number of owners which must sign policy updates.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns true if the key is one of the owners.
+    pub fn is_owner(&self, key: &PublicKey) -> bool {
+        self.owners.contains(key)
+    }
+
+    // Checks the signatures are valid signatures of `bytes` by at least `threshold` owners.
+    fn verify(&self, bytes: &[u8], signatures: &BTreeMap<PublicKey, Signature>) -> Result<()> {
+        for (key, signature) in signatures {
+            if !self.is_owner(key) {
+                return Err(Error::AccessDenied(*key));
+            }
+            key.verify(signature, bytes)?;
+        }
+
+        if signatures.len() < self.threshold {
+            return Err(Error::NotEnoughSignatures(self.threshold));
+        }
+
+        Ok(())
+    }
+}
+
+/// An update of the policy and ownership of a Register or Sequence, which must be signed by
+/// enough of its current owners to be applied.
+#[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub struct PolicyUpdate<A, P> {
+    /// Address of the data.
+    pub address: A,
+    /// Version of the policy the update replaces, i.e. the number of updates applied before.
+    pub version: u64,
+    /// The new policy.
+    pub policy: P,
+    /// The new ownership.
+    pub ownership: Ownership,
+    /// Signatures of the update by current owners.
+    pub signatures: BTreeMap<PublicKey, Signature>,
+}
+
+impl<A: Serialize, P: Serialize> PolicyUpdate<A, P> {
+    /// Constructs an unsigned update.
+    pub fn new(address: A, version: u64, policy: P, ownership: Ownership) -> Self {
+        Self {
+            address,
+            version,
+            policy,
+            ownership,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the bytes owners sign, i.e. the update without its signatures.
+    pub fn bytes_to_sign(&self) -> Result<Vec<u8>> {
+        utils::serialise(&(&self.address, self.version, &self.policy, &self.ownership))
+    }
+
+    /// Signs the update with the keypair of an owner.
+    pub fn sign(&mut self, keypair: &Keypair) -> Result<()> {
+        let signature = keypair.sign(&self.bytes_to_sign()?);
+        self.add_signature(keypair.public_key(), signature);
+        Ok(())
+    }
+
+    /// Adds the signature of an owner, e.g. one made on another device with `bytes_to_sign`.
+    pub fn add_signature(&mut self, owner: PublicKey, signature: Signature) {
+        let _ = self.signatures.insert(owner, signature);
+    }
+
+    /// Checks the update is signed by enough of the current owners.
+    pub fn verify(&self, current: &Ownership) -> Result<()> {
+        current.verify(&self.bytes_to_sign()?, &self.signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+    use xor_name::XorName;
+
+    #[test]
+    fn threshold_signatures() -> Result<()> {
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::new_ed25519(&mut OsRng)).collect();
+        let owners = keypairs.iter().map(Keypair::public_key).collect();
+        let ownership = Ownership::multi(owners, 2)?;
+        assert!(Ownership::multi(BTreeSet::new(), 1).is_err());
+
+        let new_owner = Keypair::new_ed25519(&mut OsRng).public_key();
+        let mut update = PolicyUpdate::new(
+            XorName::random(),
+            0,
+            "policy".to_string(),
+            Ownership::single(new_owner),
+        );
+
+        update.sign(&keypairs[0])?;
+        assert_eq!(
+            update.verify(&ownership),
+            Err(Error::NotEnoughSignatures(2))
+        );
+
+        update.sign(&keypairs[1])?;
+        update.verify(&ownership)?;
+
+        // a signature from a key which isn't an owner is rejected
+        let mut forged = update.clone();
+        forged.sign(&Keypair::new_ed25519(&mut OsRng))?;
+        assert!(matches!(
+            forged.verify(&ownership),
+            Err(Error::AccessDenied(_))
+        ));
+
+        // signatures don't carry over to a different update
+        let mut tampered = update;
+        tampered.version = 1;
+        assert_eq!(tampered.verify(&ownership), Err(Error::InvalidSignature));
+
+        Ok(())
+    }
+}
@@ -11,13 +11,13 @@ mod metadata;
 mod policy;
 mod reg_crdt;
 
-use super::{Error, PublicKey, Result};
+use super::{ownership, Error, Ownership, PublicKey, Result};
 pub use metadata::{Action, Address, Entry, Kind};
 pub use policy::{
     Permissions, Policy, PrivatePermissions, PrivatePolicy, PublicPermissions, PublicPolicy, User,
 };
-pub use reg_crdt::EntryHash;
 use reg_crdt::{CrdtOperation, RegisterCrdt};
+pub use reg_crdt::{EntryHash, EntryNode};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -29,6 +29,9 @@ use xor_name::XorName;
 /// Register mutation operation to apply to Register.
 pub type RegisterOp<T> = CrdtOperation<T>;
 
+/// Update of the policy and ownership of a Register, signed by its current owners.
+pub type PolicyUpdate = ownership::PolicyUpdate<Address, Policy>;
+
 impl Debug for RegisterCrdt {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(
@@ -44,12 +47,25 @@ impl Debug for RegisterCrdt {
     }
 }
 
+/// The entries of two branches of a Register which aren't in the history of the other one.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BranchDiff {
+    /// Entries only in the history of the first branch, including its head.
+    pub first: BTreeMap<EntryHash, EntryNode>,
+    /// Entries only in the history of the second branch, including its head.
+    pub second: BTreeMap<EntryHash, EntryNode>,
+}
+
 /// Object storing the Register
 #[derive(Clone, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize, Debug)]
 pub struct Register {
     authority: PublicKey,
     crdt: RegisterCrdt,
     policy: Policy,
+    ownership: Ownership,
+    // The number of policy updates applied.
+    policy_version: u64,
+    expiry: Option<u64>,
 }
 
 impl Register {
@@ -72,7 +88,10 @@ impl Register {
         Self {
             authority,
             crdt: RegisterCrdt::new(Address::Public { name, tag }),
+            ownership: Ownership::single(policy.owner),
             policy: policy.into(),
+            policy_version: 0,
+            expiry: None,
         }
     }
 
@@ -95,7 +114,10 @@ impl Register {
         Self {
             authority,
             crdt: RegisterCrdt::new(Address::Private { name, tag }),
+            ownership: Ownership::single(policy.owner),
             policy: policy.into(),
+            policy_version: 0,
+            expiry: None,
         }
     }
 
@@ -149,6 +171,66 @@ impl Register {
         Ok(self.crdt.get(hash))
     }
 
+    /// Return the entries the entry corresponding to the provided 'hash' was written on top of.
+    /// Together with `read`, this allows walking the whole history of the register.
+    pub fn parents(
+        &self,
+        hash: EntryHash,
+        requester: Option<PublicKey>,
+    ) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        self.check_permission(Action::Read, requester)?;
+
+        Ok(self.crdt.parents(hash))
+    }
+
+    /// Return all the entries of the register along with their parents' hashes, i.e. the whole
+    /// history of the register.
+    pub fn entries(&self, requester: Option<PublicKey>) -> Result<BTreeMap<EntryHash, EntryNode>> {
+        self.check_permission(Action::Read, requester)?;
+
+        let heads = self.crdt.read().into_iter().map(|(hash, _)| hash).collect();
+        Ok(self.crdt.ancestry(heads).into_iter().collect())
+    }
+
+    /// Walk the history of the register from the entry corresponding to the provided 'hash' back
+    /// to the first entries, breadth-first, starting with the entry itself.
+    pub fn ancestry(
+        &self,
+        hash: EntryHash,
+        requester: Option<PublicKey>,
+    ) -> Result<Vec<(EntryHash, EntryNode)>> {
+        self.check_permission(Action::Read, requester)?;
+
+        if self.crdt.node(hash).is_none() {
+            return Err(Error::NoSuchEntry);
+        }
+        Ok(self.crdt.ancestry(vec![hash].into_iter().collect()))
+    }
+
+    /// Compare the branches ending at the entries corresponding to the provided hashes, returning
+    /// the entries in the history of one of them but not in the other's.
+    pub fn diff(
+        &self,
+        first: EntryHash,
+        second: EntryHash,
+        requester: Option<PublicKey>,
+    ) -> Result<BranchDiff> {
+        let mut first: BTreeMap<_, _> = self.ancestry(first, requester)?.into_iter().collect();
+        let mut second: BTreeMap<_, _> = self.ancestry(second, requester)?.into_iter().collect();
+
+        let common: Vec<_> = first
+            .keys()
+            .filter(|hash| second.contains_key(*hash))
+            .copied()
+            .collect();
+        for hash in common {
+            let _ = first.remove(&hash);
+            let _ = second.remove(&hash);
+        }
+
+        Ok(BranchDiff { first, second })
+    }
+
     /// Read the last entry, or entries when there are branches, if the register is not empty.
     pub fn read(&self, requester: Option<PublicKey>) -> Result<BTreeSet<(EntryHash, Entry)>> {
         self.check_permission(Action::Read, requester)?;
@@ -176,6 +258,30 @@ impl Register {
         self.crdt.apply_op(op)
     }
 
+    /// Return the signed operations of the entries of this replica which aren't in the history
+    /// of the provided 'heads', e.g. the current entries of another replica, parents first.
+    /// Merging them into that replica with `merge_ops` brings it up to date.
+    pub fn ops_since(
+        &self,
+        heads: &BTreeSet<EntryHash>,
+        requester: Option<PublicKey>,
+    ) -> Result<Vec<RegisterOp<Entry>>> {
+        self.check_permission(Action::Read, requester)?;
+
+        Ok(self.crdt.ops_since(heads.clone()))
+    }
+
+    /// Merge the operations obtained from a network replica with `ops_since`. Each of them is
+    /// applied with `apply_op`, so its signature and the write permission of its source are
+    /// checked; merging stops at the first invalid one. Entries this replica already has are
+    /// ignored.
+    pub fn merge_ops(&mut self, ops: Vec<RegisterOp<Entry>>) -> Result<()> {
+        for op in ops {
+            self.apply_op(op)?;
+        }
+        Ok(())
+    }
+
     /// Return user permissions, if applicable.
     pub fn permissions(&self, user: User, requester: Option<PublicKey>) -> Result<Permissions> {
         self.check_permission(Action::Read, requester)?;
@@ -190,6 +296,70 @@ impl Register {
         Ok(&self.policy)
     }
 
+    /// Return the time the Register expires at, in seconds since the Unix epoch, if any.
+    pub fn expiry(&self) -> Option<u64> {
+        self.expiry
+    }
+
+    /// Set the time a private Register expires at, in seconds since the Unix epoch, after
+    /// which the Elders holding it delete it.
+    pub fn set_expiry(&mut self, expiry: u64) -> Result<()> {
+        if self.is_public() {
+            return Err(Error::InvalidOperation);
+        }
+        self.expiry = Some(expiry);
+        Ok(())
+    }
+
+    /// Postpone the expiry of the Register. It must have been set already, and the new expiry
+    /// must be later than the current one.
+    pub fn extend_expiry(&mut self, expiry: u64) -> Result<()> {
+        match self.expiry {
+            Some(current) if expiry > current => self.set_expiry(expiry),
+            _ => Err(Error::InvalidOperation),
+        }
+    }
+
+    /// Return true if the Register expired at or before `now`, in seconds since the Unix epoch.
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expiry, Some(expiry) if expiry <= now)
+    }
+
+    /// Return the owners, and how many of them must sign policy updates.
+    pub fn ownership(&self) -> &Ownership {
+        &self.ownership
+    }
+
+    /// Return the number of policy updates applied, i.e. the version the next update replaces.
+    pub fn policy_version(&self) -> u64 {
+        self.policy_version
+    }
+
+    /// Apply an update of the policy and ownership, which must be signed by enough of the current
+    /// owners. The owner of the new policy must be one of the new owners.
+    pub fn apply_policy_update(&mut self, update: PolicyUpdate) -> Result<()> {
+        if update.address != *self.address() {
+            return Err(Error::InvalidOperation);
+        }
+        if update.version != self.policy_version {
+            return Err(Error::InvalidSuccessor(self.policy_version));
+        }
+        let same_kind = matches!(
+            (&update.policy, &self.policy),
+            (Policy::Public(_), Policy::Public(_)) | (Policy::Private(_), Policy::Private(_))
+        );
+        if !same_kind || !update.ownership.is_owner(update.policy.owner()) {
+            return Err(Error::InvalidOperation);
+        }
+        update.verify(&self.ownership)?;
+
+        self.policy = update.policy;
+        self.ownership = update.ownership;
+        self.policy_version += 1;
+
+        Ok(())
+    }
+
     /// Helper to check permissions for given `action`
     /// for the given requester's public key.
     ///
@@ -197,7 +367,9 @@ impl Register {
     /// `Ok(())` if the permissions are valid,
     /// `Err::AccessDenied` if the action is not allowed.
     pub fn check_permission(&self, action: Action, requester: Option<PublicKey>) -> Result<()> {
-        let requester = requester.unwrap_or(self.authority);
+        // The policy is authoritative for every requester, co-owners included, and its owner is
+        // the current one, which may no longer be the replica's authority.
+        let requester = requester.unwrap_or_else(|| self.owner());
         self.policy.is_action_allowed(requester, action)
     }
 
@@ -216,10 +388,10 @@ impl Register {
 mod tests {
     use super::super::{
         register::{
-            Address, Entry, EntryHash, Kind, Permissions, PrivatePermissions, PrivatePolicy,
-            PublicPermissions, PublicPolicy, Register, RegisterOp, User,
+            Action, Address, Entry, EntryHash, Kind, Permissions, PolicyUpdate, PrivatePermissions,
+            PrivatePolicy, PublicPermissions, PublicPolicy, Register, RegisterOp, User,
         },
-        utils, Error, Keypair, Result,
+        utils, Error, Keypair, Ownership, Result,
     };
     use anyhow::anyhow;
     use proptest::prelude::*;
@@ -372,6 +544,271 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn register_get_parents() -> anyhow::Result<()> {
+        let (_, register) = &mut create_public_reg_replicas(1)[0];
+
+        let entry1 = b"value0".to_vec();
+        let entry2 = b"value1".to_vec();
+
+        let (entry1_hash, _) = register.write(entry1.clone(), BTreeSet::new())?;
+        let (entry2_hash, _) = register.write(entry2.clone(), BTreeSet::new())?;
+
+        // entry3 merges the fork created by entry1 and entry2
+        let parents = vec![entry1_hash, entry2_hash].into_iter().collect();
+        let (entry3_hash, _) = register.write(b"value2".to_vec(), parents)?;
+
+        let expected: BTreeSet<_> = vec![(entry1_hash, entry1), (entry2_hash, entry2)]
+            .into_iter()
+            .collect();
+        assert_eq!(register.parents(entry3_hash, None)?, expected);
+        assert!(register.parents(entry1_hash, None)?.is_empty());
+        assert!(register.parents(EntryHash::default(), None)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_ops_since_heads() -> anyhow::Result<()> {
+        let (keypair, mut replica1) = create_public_reg_replicas(1).remove(0);
+        let mut replica3 = replica1.clone();
+
+        let (hash1, op1) = replica1.write(b"value1".to_vec(), BTreeSet::new())?;
+        replica1.apply_op(sign_register_op(op1, &keypair)?)?;
+        let mut replica2 = replica1.clone();
+
+        let (_, op2) = replica1.write(b"value2".to_vec(), vec![hash1].into_iter().collect())?;
+        replica1.apply_op(sign_register_op(op2, &keypair)?)?;
+
+        // only the entry replica2 is missing is returned
+        let heads = replica2
+            .read(None)?
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        let ops = replica1.ops_since(&heads, None)?;
+        assert_eq!(ops.len(), 1);
+        assert!(ops.iter().all(|op| op.signature.is_some()));
+        replica2.merge_ops(ops.clone())?;
+        assert_eq!(replica2, replica1);
+
+        // merging the same entries again changes nothing
+        replica2.merge_ops(ops)?;
+        assert_eq!(replica2, replica1);
+        assert_eq!(replica2.size(None)?, 2);
+
+        // from no heads at all, every entry is returned, parents first
+        let ops = replica1.ops_since(&BTreeSet::new(), None)?;
+        assert_eq!(ops.len(), 2);
+        assert!(ops[0].crdt_op.parents.is_empty());
+        replica3.merge_ops(ops)?;
+        assert_eq!(replica3, replica1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_ops_since_are_topologically_ordered() -> anyhow::Result<()> {
+        let (keypair, mut replica1) = create_public_reg_replicas(1).remove(0);
+        let mut replica2 = replica1.clone();
+
+        let mut write = |entry: &[u8], parents: Vec<EntryHash>| -> anyhow::Result<EntryHash> {
+            let (hash, op) = replica1.write(entry.to_vec(), parents.into_iter().collect())?;
+            replica1.apply_op(sign_register_op(op, &keypair)?)?;
+            Ok(hash)
+        };
+
+        // a long branch and a short one, merged back together
+        let root = write(b"root", vec![])?;
+        let long1 = write(b"long1", vec![root])?;
+        let long2 = write(b"long2", vec![long1])?;
+        let long3 = write(b"long3", vec![long2])?;
+        let short = write(b"short", vec![root])?;
+        let _ = write(b"merge", vec![long3, short])?;
+
+        let ops = replica1.ops_since(&BTreeSet::new(), None)?;
+        assert_eq!(ops.len(), 6);
+        let mut seen = BTreeSet::new();
+        for op in &ops {
+            assert!(op.crdt_op.parents.is_subset(&seen));
+            let _ = seen.insert(op.crdt_op.hash());
+        }
+
+        replica2.merge_ops(ops)?;
+        assert_eq!(replica2, replica1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_merge_ops_checks_signature_and_permissions() -> anyhow::Result<()> {
+        let (keypair, mut replica1) = create_public_reg_replicas(1).remove(0);
+        let mut replica2 = replica1.clone();
+
+        let (_, op) = replica1.write(b"value1".to_vec(), BTreeSet::new())?;
+        replica1.apply_op(sign_register_op(op, &keypair)?)?;
+        let ops = replica1.ops_since(&BTreeSet::new(), None)?;
+
+        // an op whose signature isn't its source's is refused
+        let other_keypair = Keypair::new_ed25519(&mut OsRng);
+        let forged = sign_register_op(ops[0].clone(), &other_keypair)?;
+        assert!(replica2.merge_ops(vec![forged]).is_err());
+
+        // an op validly signed by a source without write permission is refused
+        let mut unpermitted = ops[0].clone();
+        unpermitted.source = other_keypair.public_key();
+        let unpermitted = sign_register_op(unpermitted, &other_keypair)?;
+        check_op_not_allowed_failure(replica2.merge_ops(vec![unpermitted]))?;
+
+        assert_eq!(replica2.size(None)?, 0);
+        replica2.merge_ops(ops)?;
+        assert_eq!(replica2, replica1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_expiry() -> Result<()> {
+        let authority = Keypair::new_ed25519(&mut OsRng).public_key();
+        let mut register = Register::new_private(authority, XorName::random(), 43_000, None);
+        assert_eq!(register.extend_expiry(100), Err(Error::InvalidOperation));
+
+        register.set_expiry(100)?;
+        assert!(!register.is_expired(99));
+        assert!(register.is_expired(100));
+
+        // the expiry can only be postponed
+        assert_eq!(register.extend_expiry(50), Err(Error::InvalidOperation));
+        register.extend_expiry(200)?;
+        assert_eq!(register.expiry(), Some(200));
+        assert!(!register.is_expired(100));
+
+        // public Registers don't expire
+        let mut register = Register::new_public(authority, XorName::random(), 43_000, None);
+        assert_eq!(register.set_expiry(100), Err(Error::InvalidOperation));
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_policy_updates() -> anyhow::Result<()> {
+        let (keypair, mut register) = create_public_reg_replicas(1).remove(0);
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::new_ed25519(&mut OsRng)).collect();
+        let owners = keypairs.iter().map(Keypair::public_key).collect();
+
+        // an update of another Register doesn't apply
+        let mut update = PolicyUpdate::new(
+            Address::Public {
+                name: XorName::random(),
+                tag: register.tag(),
+            },
+            register.policy_version(),
+            PublicPolicy {
+                owner: keypair.public_key(),
+                permissions: BTreeMap::new(),
+            }
+            .into(),
+            Ownership::single(keypair.public_key()),
+        );
+        update.sign(&keypair)?;
+        assert_eq!(
+            register.apply_policy_update(update),
+            Err(Error::InvalidOperation)
+        );
+
+        // the single owner hands the Register over to 2-of-3 owners
+        let mut update = PolicyUpdate::new(
+            *register.address(),
+            register.policy_version(),
+            PublicPolicy {
+                owner: keypairs[0].public_key(),
+                permissions: BTreeMap::new(),
+            }
+            .into(),
+            Ownership::multi(owners, 2)?,
+        );
+        update.sign(&keypair)?;
+        register.apply_policy_update(update.clone())?;
+        assert_eq!(register.policy_version(), 1);
+        assert_eq!(register.owner(), keypairs[0].public_key());
+        // the owner of the new policy may write, as may the current owner by default, while
+        // neither a co-owner not granted it by the policy nor the former owner may
+        register.check_permission(Action::Write, Some(keypairs[0].public_key()))?;
+        register.check_permission(Action::Write, None)?;
+        assert!(register
+            .check_permission(Action::Write, Some(keypairs[2].public_key()))
+            .is_err());
+        assert!(register
+            .check_permission(Action::Write, Some(keypair.public_key()))
+            .is_err());
+
+        // the update can't be replayed
+        assert_eq!(
+            register.apply_policy_update(update),
+            Err(Error::InvalidSuccessor(1))
+        );
+
+        // handing it over to a single owner takes two of them
+        let mut update = PolicyUpdate::new(
+            *register.address(),
+            1,
+            PublicPolicy {
+                owner: keypairs[2].public_key(),
+                permissions: BTreeMap::new(),
+            }
+            .into(),
+            Ownership::single(keypairs[2].public_key()),
+        );
+        update.sign(&keypairs[0])?;
+        assert_eq!(
+            register.apply_policy_update(update.clone()),
+            Err(Error::NotEnoughSignatures(2))
+        );
+        update.sign(&keypairs[1])?;
+        register.apply_policy_update(update)?;
+        assert_eq!(register.owner(), keypairs[2].public_key());
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_history_and_diff() -> anyhow::Result<()> {
+        let (_, register) = &mut create_public_reg_replicas(1)[0];
+
+        // root <- left1 <- left2, and root <- right1
+        let (root, _) = register.write(b"root".to_vec(), BTreeSet::new())?;
+        let (left1, _) = register.write(b"left1".to_vec(), vec![root].into_iter().collect())?;
+        let (left2, _) = register.write(b"left2".to_vec(), vec![left1].into_iter().collect())?;
+        let (right1, _) = register.write(b"right1".to_vec(), vec![root].into_iter().collect())?;
+
+        let entries = register.entries(None)?;
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries.get(&left1).map(|node| &node.parents),
+            Some(&vec![root].into_iter().collect())
+        );
+
+        let ancestry: Vec<_> = register
+            .ancestry(left2, None)?
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        assert_eq!(ancestry, vec![left2, left1, root]);
+        assert!(register.ancestry(EntryHash::default(), None).is_err());
+
+        let diff = register.diff(left2, right1, None)?;
+        assert_eq!(
+            diff.first.keys().copied().collect::<BTreeSet<_>>(),
+            vec![left1, left2].into_iter().collect()
+        );
+        assert_eq!(
+            diff.second.keys().copied().collect::<Vec<_>>(),
+            vec![right1]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn register_query_public_policy() -> anyhow::Result<()> {
         let register_name = XorName::random();
@@ -18,9 +18,10 @@ use crdts::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeSet,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, VecDeque},
     fmt::{self, Debug, Display},
-    hash::Hash,
+    hash::{Hash, Hasher},
 };
 
 /// CRDT Data operation applicable to other Register replica.
@@ -36,13 +37,48 @@ pub struct CrdtOperation<T> {
     pub signature: Option<Signature>,
 }
 
+/// An entry of a Register along with the hashes of the entries it was written on top of.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryNode {
+    /// The entry.
+    pub entry: Entry,
+    /// The hashes of the entries it was written on top of, empty for the first entries.
+    pub parents: BTreeSet<EntryHash>,
+}
+
 /// Register data type as a CRDT with Access Control
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RegisterCrdt {
     /// Address on the network of this piece of data
     address: Address,
     /// CRDT to store the actual data, i.e. the items of the Register.
     data: MerkleReg<Entry>,
+    /// Source and signature of the operation each entry was applied with, so the entries can
+    /// be handed to other replicas as the signed operations they came from.
+    signatures: BTreeMap<EntryHash, (PublicKey, Signature)>,
+}
+
+// Replicas holding the same entries are equal whether or not they kept the signatures of the
+// operations which wrote them, e.g. a replica an entry was written to locally.
+impl PartialEq for RegisterCrdt {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.address, &self.data) == (&other.address, &other.data)
+    }
+}
+
+impl Eq for RegisterCrdt {}
+
+impl PartialOrd for RegisterCrdt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (&self.address, &self.data).partial_cmp(&(&other.address, &other.data))
+    }
+}
+
+impl Hash for RegisterCrdt {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+        self.data.hash(state);
+    }
 }
 
 impl Display for RegisterCrdt {
@@ -64,6 +100,7 @@ impl RegisterCrdt {
         Self {
             address,
             data: MerkleReg::new(),
+            signatures: BTreeMap::new(),
         }
     }
 
@@ -120,7 +157,11 @@ impl RegisterCrdt {
             return Err(Error::CrdtWrongAddress(op.address));
         }
 
-        // Apply the CRDT operation to the Register
+        // Apply the CRDT operation to the Register, keeping the signature it was applied with
+        let _ = self
+            .signatures
+            .entry(op.crdt_op.hash())
+            .or_insert((op.source, sig));
         self.data.apply(op.crdt_op);
 
         Ok(())
@@ -131,6 +172,102 @@ impl RegisterCrdt {
         self.data.node(hash).map(|node| &node.value)
     }
 
+    /// Get the parents of the entry corresponding to the provided `hash`, i.e. the entries it was
+    /// written on top of. Empty if the entry doesn't exist or is one of the first entries.
+    pub fn parents(&self, hash: EntryHash) -> BTreeSet<(EntryHash, Entry)> {
+        self.data
+            .parents(hash)
+            .hashes_and_nodes()
+            .map(|(hash, node)| (hash, node.value.clone()))
+            .collect()
+    }
+
+    /// Get the entry corresponding to the provided `hash` along with its parents' hashes, if it
+    /// exists.
+    pub fn node(&self, hash: EntryHash) -> Option<EntryNode> {
+        self.data.node(hash).map(|node| EntryNode {
+            entry: node.value.clone(),
+            parents: node.parents.clone(),
+        })
+    }
+
+    /// Walk the history from the provided `hashes` back to the first entries, breadth-first,
+    /// returning each entry once. Hashes which don't exist are skipped.
+    pub fn ancestry(&self, hashes: BTreeSet<EntryHash>) -> Vec<(EntryHash, EntryNode)> {
+        let mut visited = BTreeSet::new();
+        let mut pending: VecDeque<EntryHash> = hashes.into_iter().collect();
+        let mut ancestry = Vec::new();
+        while let Some(hash) = pending.pop_front() {
+            if !visited.insert(hash) {
+                continue;
+            }
+            if let Some(node) = self.node(hash) {
+                pending.extend(node.parents.iter().copied());
+                ancestry.push((hash, node));
+            }
+        }
+        ancestry
+    }
+
+    /// Get the signed operations of the entries of this replica which aren't in the history of
+    /// the provided `heads`, e.g. the current entries of another replica, in topological order,
+    /// i.e. every entry comes after all of its parents. Entries which were only written locally,
+    /// and so were never applied with a signature, are left out.
+    pub fn ops_since(&self, heads: BTreeSet<EntryHash>) -> Vec<CrdtOperation<Entry>> {
+        let known: BTreeSet<_> = self
+            .ancestry(heads)
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        let missing: BTreeMap<_, _> = self
+            .ancestry(self.data.read().hashes())
+            .into_iter()
+            .filter(|(hash, _)| !known.contains(hash))
+            .collect();
+
+        // Depth-first, emitting each entry once all of its missing parents have been emitted.
+        let mut ordered = Vec::new();
+        let mut done = BTreeSet::new();
+        for hash in missing.keys() {
+            let mut stack = vec![(*hash, false)];
+            while let Some((hash, parents_done)) = stack.pop() {
+                if parents_done {
+                    if done.insert(hash) {
+                        ordered.push(hash);
+                    }
+                } else if !done.contains(&hash) {
+                    stack.push((hash, true));
+                    stack.extend(
+                        missing[&hash]
+                            .parents
+                            .iter()
+                            .filter(|parent| {
+                                missing.contains_key(*parent) && !done.contains(*parent)
+                            })
+                            .map(|parent| (*parent, false)),
+                    );
+                }
+            }
+        }
+
+        ordered
+            .into_iter()
+            .filter_map(|hash| {
+                let (source, signature) = self.signatures.get(&hash)?;
+                let node = &missing[&hash];
+                Some(CrdtOperation {
+                    address: self.address,
+                    crdt_op: Node {
+                        parents: node.parents.clone(),
+                        value: node.entry.clone(),
+                    },
+                    source: *source,
+                    signature: Some(signature.clone()),
+                })
+            })
+            .collect()
+    }
+
     /// Read the last entry, or entries if there are branches.
     pub fn read(&self) -> BTreeSet<(EntryHash, Entry)> {
         self.data
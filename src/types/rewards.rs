@@ -7,8 +7,11 @@
 // specific language governing permissions and limitations relating to use of the SAFE Network
 // Software.
 
-use super::{CreditId, SignatureShare, SignedCredit, SignedCreditShare};
+use super::{CreditId, PublicKey, SignatureShare, SignedCredit, SignedCreditShare, Token};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use xor_name::XorName;
 
 /// Node age, the number of times
 /// it has been relocated between sections.
@@ -18,7 +21,7 @@ pub type NodeAge = u8;
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct RewardProposal {
     /// The section paying out the rewards.
-    pub section_key: super::PublicKey,
+    pub section_key: PublicKey,
     /// Any proposed rewards
     pub rewards: Vec<SignedCreditShare>,
 }
@@ -27,7 +30,7 @@ pub struct RewardProposal {
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct RewardAccumulation {
     /// The section paying out the rewards.
-    pub section_key: super::PublicKey,
+    pub section_key: PublicKey,
     /// Any agreed rewards
     pub rewards: Vec<AccumulatingReward>,
 }
@@ -47,3 +50,106 @@ impl AccumulatingReward {
         self.signed_credit.id()
     }
 }
+
+/// The record of a round of rewards paid out by a section to its nodes.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RewardRound {
+    /// The section paying out the rewards, which also identifies the round.
+    pub section_key: PublicKey,
+    /// The payments received by the section since the previous round.
+    pub payments: Token,
+    /// The tokens newly minted on top of the payments.
+    pub minted: Token,
+    /// The payments burned instead of paid out, as the section exceeded its proportional supply.
+    pub burned: Token,
+    /// The tokens paid out in rewards, i.e. the payments and newly minted tokens,
+    /// less any payments burned as the section exceeded its proportional supply.
+    pub rewards: Token,
+    /// The number of rewarded nodes of each age.
+    pub age_buckets: BTreeMap<NodeAge, usize>,
+    /// The reward of each node of the section.
+    pub nodes: BTreeMap<XorName, NodeReward>,
+}
+
+impl RewardRound {
+    /// The statement entries of the nodes rewarded to the given wallet in this round.
+    pub fn entries_for(&self, wallet: PublicKey) -> Vec<RewardStatementEntry> {
+        self.nodes
+            .iter()
+            .filter(|(_, reward)| reward.wallet == wallet)
+            .map(|(node_name, reward)| RewardStatementEntry {
+                section_key: self.section_key,
+                round_rewards: self.rewards,
+                node_name: *node_name,
+                reward: reward.clone(),
+            })
+            .collect()
+    }
+}
+
+/// The reward of a node in a round.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct NodeReward {
+    /// The age of the node at the round.
+    pub age: NodeAge,
+    /// The wallet the reward is paid to.
+    pub wallet: PublicKey,
+    /// The amount paid to the node, its share of the rewards.
+    pub amount: Token,
+    /// Why nothing was paid to the node, if so.
+    pub no_reward_reason: Option<NoRewardReason>,
+}
+
+/// The reason for a node not being rewarded in a round.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub enum NoRewardReason {
+    /// The age of the node is below the minimum age for rewards.
+    AgeBelowMinimum(NodeAge),
+    /// There were no rewards to pay out in the round.
+    NoRewards,
+    /// The share of the node was too small to pay out.
+    ShareTooSmall,
+}
+
+impl Display for NoRewardReason {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::AgeBelowMinimum(min_age) => {
+                write!(f, "age below the minimum reward age of {}", min_age)
+            }
+            Self::NoRewards => write!(f, "no rewards to pay out"),
+            Self::ShareTooSmall => write!(f, "share too small to pay out"),
+        }
+    }
+}
+
+/// The rewards paid to a wallet, over the rounds recorded by a section.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RewardStatement {
+    /// The wallet the rewards are paid to.
+    pub wallet: PublicKey,
+    /// An entry per node and round, in the order of the rounds.
+    pub entries: Vec<RewardStatementEntry>,
+}
+
+impl RewardStatement {
+    /// The total paid to the wallet over all the entries.
+    pub fn total(&self) -> Option<Token> {
+        self.entries.iter().try_fold(Token::zero(), |total, entry| {
+            total.checked_add(entry.reward.amount)
+        })
+    }
+}
+
+/// The reward of a node paying to a wallet, in a round.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RewardStatementEntry {
+    /// The section paying out the rewards.
+    pub section_key: PublicKey,
+    /// The tokens paid out in the round, to all nodes.
+    pub round_rewards: Token,
+    /// The node rewarded.
+    pub node_name: XorName,
+    /// The reward of the node.
+    pub reward: NodeReward,
+}
@@ -317,6 +317,16 @@ pub enum Policy {
     Private(PrivatePolicy),
 }
 
+impl Policy {
+    /// Returns the owner.
+    pub fn owner(&self) -> &PublicKey {
+        match self {
+            Policy::Public(policy) => &policy.owner,
+            Policy::Private(policy) => &policy.owner,
+        }
+    }
+}
+
 impl From<PrivatePolicy> for Policy {
     fn from(policy: PrivatePolicy) -> Self {
         Policy::Private(policy)
@@ -10,12 +10,13 @@
 mod metadata;
 mod seq_crdt;
 
-use super::{Error, PublicKey, Result};
+use super::{ownership, Error, Ownership, PublicKey, Result};
+use crdts::VClock;
 pub use metadata::{
     Action, Address, Entries, Entry, Index, Kind, Perm, Permissions, Policy, PrivatePermissions,
     PrivatePolicy, PublicPermissions, PublicPolicy, User,
 };
-use seq_crdt::{CrdtOperation, SequenceCrdt};
+use seq_crdt::{CrdtOperation, Op, SequenceCrdt};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::{fmt::Debug, hash::Hash};
@@ -27,6 +28,15 @@ type ActorType = String;
 /// Data mutation operation to apply to Sequence.
 pub type DataOp<T> = CrdtOperation<ActorType, T>;
 
+/// Insertion of an entry into a Sequence, as held by a replica, see `Data::ops_since`.
+pub type EntryOp = Op<Entry, ActorType>;
+
+/// Clock of the entries of a Sequence replica, see `Data::clock`.
+pub type Clock = VClock<ActorType>;
+
+/// Update of the policy and ownership of a Sequence, signed by its current owners.
+pub type PolicyUpdate = ownership::PolicyUpdate<Address, Policy>;
+
 /// Public Sequence.
 pub type PublicSeqData = SequenceCrdt<ActorType, PublicPolicy>;
 /// Private Sequence.
@@ -46,6 +56,10 @@ enum SeqData {
 pub struct Data {
     authority: PublicKey,
     data: SeqData,
+    ownership: Ownership,
+    // The number of policy updates applied.
+    policy_version: u64,
+    expiry: Option<u64>,
 }
 
 #[allow(clippy::len_without_is_empty)]
@@ -70,11 +84,14 @@ impl Data {
 
         Self {
             authority,
+            ownership: Ownership::single(policy.owner),
+            policy_version: 0,
             data: SeqData::Public(PublicSeqData::new(
                 actor,
                 Address::Public { name, tag },
                 policy,
             )),
+            expiry: None,
         }
     }
 
@@ -98,11 +115,14 @@ impl Data {
 
         Self {
             authority,
+            ownership: Ownership::single(policy.owner),
+            policy_version: 0,
             data: SeqData::Private(PrivateSeqData::new(
                 actor,
                 Address::Private { name, tag },
                 policy,
             )),
+            expiry: None,
         }
     }
 
@@ -215,6 +235,38 @@ impl Data {
         }
     }
 
+    /// Returns the clock of the entries of this replica, i.e. the last counter of each actor
+    /// which appended to it. It's the same on every replica holding the same entries.
+    pub fn clock(&self, requester: Option<PublicKey>) -> Result<Clock> {
+        self.check_permission(Action::Read, requester)?;
+
+        Ok(match &self.data {
+            SeqData::Public(data) => data.clock(),
+            SeqData::Private(data) => data.clock(),
+        })
+    }
+
+    /// Returns the insertions of the entries of this replica which aren't covered by the given
+    /// clock, e.g. the clock of another replica. Merging them into that replica with `merge_ops`
+    /// brings it up to date.
+    pub fn ops_since(&self, clock: &Clock, requester: Option<PublicKey>) -> Result<Vec<EntryOp>> {
+        self.check_permission(Action::Read, requester)?;
+
+        Ok(match &self.data {
+            SeqData::Public(data) => data.ops_since(clock),
+            SeqData::Private(data) => data.ops_since(clock),
+        })
+    }
+
+    /// Merge the insertions obtained from a network replica with `ops_since`. Entries this
+    /// replica already has are ignored.
+    pub fn merge_ops(&mut self, ops: Vec<EntryOp>) {
+        match &mut self.data {
+            SeqData::Public(data) => data.merge_ops(ops),
+            SeqData::Private(data) => data.merge_ops(ops),
+        }
+    }
+
     /// Returns user permissions, if applicable.
     pub fn permissions(&self, user: User, requester: Option<PublicKey>) -> Result<Permissions> {
         self.check_permission(Action::Read, requester)?;
@@ -244,6 +296,74 @@ impl Data {
         }
     }
 
+    /// Returns the time the Sequence expires at, in seconds since the Unix epoch, if any.
+    pub fn expiry(&self) -> Option<u64> {
+        self.expiry
+    }
+
+    /// Sets the time a private Sequence expires at, in seconds since the Unix epoch, after
+    /// which the Elders holding it delete it.
+    pub fn set_expiry(&mut self, expiry: u64) -> Result<()> {
+        if self.is_public() {
+            return Err(Error::InvalidOperation);
+        }
+        self.expiry = Some(expiry);
+        Ok(())
+    }
+
+    /// Postpones the expiry of the Sequence. It must have been set already, and the new expiry
+    /// must be later than the current one.
+    pub fn extend_expiry(&mut self, expiry: u64) -> Result<()> {
+        match self.expiry {
+            Some(current) if expiry > current => self.set_expiry(expiry),
+            _ => Err(Error::InvalidOperation),
+        }
+    }
+
+    /// Returns true if the Sequence expired at or before `now`, in seconds since the Unix epoch.
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expiry, Some(expiry) if expiry <= now)
+    }
+
+    /// Returns the owners, and how many of them must sign policy updates.
+    pub fn ownership(&self) -> &Ownership {
+        &self.ownership
+    }
+
+    /// Returns the number of policy updates applied, i.e. the version the next update replaces.
+    pub fn policy_version(&self) -> u64 {
+        self.policy_version
+    }
+
+    /// Applies an update of the policy and ownership, which must be signed by enough of the
+    /// current owners. The owner of the new policy must be one of the new owners.
+    pub fn apply_policy_update(&mut self, update: PolicyUpdate) -> Result<()> {
+        if update.address != *self.address() {
+            return Err(Error::InvalidOperation);
+        }
+        if update.version != self.policy_version {
+            return Err(Error::InvalidSuccessor(self.policy_version));
+        }
+        let new_owner = match &update.policy {
+            Policy::Public(policy) => policy.owner,
+            Policy::Private(policy) => policy.owner,
+        };
+        if !update.ownership.is_owner(&new_owner) {
+            return Err(Error::InvalidOperation);
+        }
+        update.verify(&self.ownership)?;
+
+        match (&mut self.data, update.policy) {
+            (SeqData::Public(data), Policy::Public(policy)) => data.set_policy(policy),
+            (SeqData::Private(data), Policy::Private(policy)) => data.set_policy(policy),
+            _ => return Err(Error::InvalidOperation),
+        }
+        self.ownership = update.ownership;
+        self.policy_version += 1;
+
+        Ok(())
+    }
+
     /// Helper to check permissions for given `action`
     /// for the given requester's public key.
     ///
@@ -251,7 +371,9 @@ impl Data {
     /// `Ok(())` if the permissions are valid,
     /// `Err::AccessDenied` if the action is not allowed.
     pub fn check_permission(&self, action: Action, requester: Option<PublicKey>) -> Result<()> {
-        let requester = requester.unwrap_or(self.authority);
+        // The policy is authoritative for every requester, co-owners included, and its owner is
+        // the current one, which may no longer be the replica's authority.
+        let requester = requester.unwrap_or_else(|| self.owner());
         match &self.data {
             SeqData::Public(data) => data.policy().is_action_allowed(requester, action),
             SeqData::Private(data) => data.policy().is_action_allowed(requester, action),
@@ -275,9 +397,10 @@ impl Data {
 #[cfg(test)]
 mod tests {
     use super::super::{
-        utils, Error, Keypair, Result, Sequence, SequenceAddress, SequenceEntry, SequenceIndex,
-        SequenceKind, SequenceOp, SequencePermissions, SequencePrivatePermissions,
-        SequencePrivatePolicy, SequencePublicPermissions, SequencePublicPolicy, SequenceUser,
+        utils, Error, Keypair, Ownership, Result, Sequence, SequenceAction, SequenceAddress,
+        SequenceEntry, SequenceIndex, SequenceKind, SequenceOp, SequencePermissions,
+        SequencePolicyUpdate, SequencePrivatePermissions, SequencePrivatePolicy,
+        SequencePublicPermissions, SequencePublicPolicy, SequenceUser,
     };
     use anyhow::anyhow;
     use proptest::prelude::*;
@@ -319,6 +442,139 @@ mod tests {
         assert_eq!(*sequence.address(), sequence_address);
     }
 
+    #[test]
+    fn sequence_ops_since_clock() -> Result<()> {
+        let mut replicas = create_public_seq_replicas(1);
+        let (keypair, ref mut sequence) = &mut replicas[0];
+        let mut cached = sequence.clone();
+        let mut other = sequence.clone();
+
+        let mut ops = Vec::new();
+        for item in &[b"item1", b"item2", b"item3"] {
+            let op = sign_sequence_op(sequence.create_unsigned_append_op(item.to_vec())?, keypair)?;
+            sequence.apply_op(op.clone())?;
+            ops.push(op);
+        }
+
+        // a replica receiving every op twice holds the same entries, with the same clock
+        for op in ops.iter().chain(ops.iter()) {
+            other.apply_op(op.clone())?;
+        }
+        assert_eq!(other.len(None)?, 3);
+        assert_eq!(other.clock(None)?, sequence.clock(None)?);
+
+        // the cached replica catches up with either replica by merging the insertions after its
+        // clock
+        let ops = other.ops_since(&cached.clock(None)?, None)?;
+        assert_eq!(ops, sequence.ops_since(&cached.clock(None)?, None)?);
+        assert_eq!(ops.len(), 3);
+        cached.merge_ops(ops.clone());
+        assert_eq!(cached.len(None)?, 3);
+        assert_eq!(cached.last_entry(None)?, Some(&b"item3".to_vec()));
+        assert_eq!(cached.clock(None)?, sequence.clock(None)?);
+
+        // merging the same insertions again changes nothing
+        cached.merge_ops(ops);
+        assert_eq!(cached.len(None)?, 3);
+        assert!(sequence.ops_since(&cached.clock(None)?, None)?.is_empty());
+
+        // only the entry appended since is missing from the cached replica
+        let op = sign_sequence_op(other.create_unsigned_append_op(b"item4".to_vec())?, keypair)?;
+        other.apply_op(op)?;
+        let ops = other.ops_since(&cached.clock(None)?, None)?;
+        assert_eq!(ops.len(), 1);
+        cached.merge_ops(ops);
+        assert_eq!(cached.len(None)?, 4);
+        assert_eq!(cached.last_entry(None)?, other.last_entry(None)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_policy_updates() -> Result<()> {
+        let mut replicas = create_public_seq_replicas(1);
+        let (keypair, ref mut sequence) = &mut replicas[0];
+        let keypairs = [
+            Keypair::new_ed25519(&mut OsRng),
+            Keypair::new_ed25519(&mut OsRng),
+        ];
+        let owners = keypairs.iter().map(Keypair::public_key).collect();
+        let ownership = Ownership::multi(owners, 2)?;
+        let new_owner = keypairs[0].public_key();
+
+        // an update of another Sequence doesn't apply
+        let mut update = SequencePolicyUpdate::new(
+            SequenceAddress::Public {
+                name: XorName::random(),
+                tag: sequence.tag(),
+            },
+            0,
+            SequencePublicPolicy {
+                owner: new_owner,
+                permissions: BTreeMap::new(),
+            }
+            .into(),
+            ownership.clone(),
+        );
+        update.sign(keypair)?;
+        assert_eq!(
+            sequence.apply_policy_update(update),
+            Err(Error::InvalidOperation)
+        );
+
+        // the kind of the policy can't be changed
+        let mut update = SequencePolicyUpdate::new(
+            *sequence.address(),
+            0,
+            SequencePrivatePolicy {
+                owner: new_owner,
+                permissions: BTreeMap::new(),
+            }
+            .into(),
+            ownership.clone(),
+        );
+        update.sign(keypair)?;
+        assert_eq!(
+            sequence.apply_policy_update(update),
+            Err(Error::InvalidOperation)
+        );
+
+        let mut update = SequencePolicyUpdate::new(
+            *sequence.address(),
+            0,
+            SequencePublicPolicy {
+                owner: new_owner,
+                permissions: BTreeMap::new(),
+            }
+            .into(),
+            ownership.clone(),
+        );
+        update.sign(&keypairs[0])?;
+        assert!(matches!(
+            sequence.apply_policy_update(update.clone()),
+            Err(Error::AccessDenied(_))
+        ));
+        update.signatures.clear();
+        update.sign(keypair)?;
+        sequence.apply_policy_update(update)?;
+
+        assert_eq!(sequence.owner(), new_owner);
+        assert_eq!(sequence.ownership(), &ownership);
+        assert_eq!(sequence.policy_version(), 1);
+        // the owner of the new policy may append, as may the current owner by default, while
+        // neither the other co-owner, not granted it by the policy, nor the former owner may
+        sequence.check_permission(SequenceAction::Append, Some(new_owner))?;
+        sequence.check_permission(SequenceAction::Append, None)?;
+        assert!(sequence
+            .check_permission(SequenceAction::Append, Some(keypairs[1].public_key()))
+            .is_err());
+        assert!(sequence
+            .check_permission(SequenceAction::Append, Some(keypair.public_key()))
+            .is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn sequence_concurrent_append_ops() -> Result<()> {
         let authority_keypair1 = Keypair::new_ed25519(&mut OsRng);
@@ -11,7 +11,7 @@ use super::super::{utils, Error, PublicKey, Result, Signature};
 use super::metadata::Entries;
 use super::metadata::{Address, Entry, Index, Perm};
 pub use crdts::list::Op;
-use crdts::{list::List, CmRDT};
+use crdts::{list::List, CmRDT, Dot, VClock};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::{self, Debug, Display},
@@ -126,6 +126,39 @@ where
         Ok(())
     }
 
+    /// Returns the clock of the entries of this replica, i.e. the last counter of each actor which
+    /// appended to it.
+    pub fn clock(&self) -> VClock<A> {
+        self.data
+            .iter_entries()
+            .map(|(id, _)| Dot::from(id.value().clone()))
+            .collect()
+    }
+
+    /// Returns the insertions of the entries which aren't covered by the provided clock, e.g.
+    /// the clock of another replica, in the order of their counters.
+    pub fn ops_since(&self, clock: &VClock<A>) -> Vec<Op<Entry, A>> {
+        let mut ops: Vec<_> = self
+            .data
+            .iter_entries()
+            .filter(|(id, _)| id.value().counter > clock.get(&id.value().actor))
+            .map(|(id, entry)| Op::Insert {
+                id: id.clone(),
+                val: entry.clone(),
+            })
+            .collect();
+        ops.sort_by_key(|op| op.dot().counter);
+        ops
+    }
+
+    /// Merge the insertions obtained from another replica with `ops_since`. Entries this replica
+    /// already has are ignored.
+    pub fn merge_ops(&mut self, ops: Vec<Op<Entry, A>>) {
+        for op in ops {
+            self.data.apply(op);
+        }
+    }
+
     /// Gets the entry at `index` if it exists.
     pub fn get(&self, index: Index) -> Option<&Entry> {
         let i = to_absolute_index(index, self.len() as usize)?;
@@ -142,6 +175,11 @@ where
         &self.policy
     }
 
+    /// Replaces the Policy of the object.
+    pub fn set_policy(&mut self, policy: P) {
+        self.policy = policy;
+    }
+
     /// Gets a list of items which are within the given indices.
     /// Note the range of items is [start, end), i.e. the end index is not inclusive.
     pub fn in_range(&self, start: Index, end: Index) -> Option<Entries> {
@@ -0,0 +1,272 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{utils, Error, Result, SignatureShare, Token};
+use bls::PublicKeySet;
+use serde::{Deserialize, Serialize};
+use xor_name::Prefix;
+
+/// The maximum supply of tokens in the network, in nanos.
+pub const MAX_SUPPLY: u64 = u32::MAX as u64 * 1_000_000_000_u64;
+
+/// The tokens a section has created and retired by paying out rewards.
+///
+/// Rewards are credited without any debit, so they create tokens, while the payments they are
+/// made from stay behind in the retired section wallet, out of circulation. The funding of a
+/// faucet at genesis is the only other credit creating tokens.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SupplyTotals {
+    /// Tokens credited to node wallets as rewards, or to a faucet at genesis.
+    pub minted: Token,
+    /// Payments to the section left in its retired wallets.
+    pub burned: Token,
+}
+
+impl Default for SupplyTotals {
+    fn default() -> Self {
+        Self {
+            minted: Token::zero(),
+            burned: Token::zero(),
+        }
+    }
+}
+
+impl SupplyTotals {
+    /// Adds up both totals, saturating at the numeric bounds.
+    pub fn saturating_add(&self, other: &Self) -> Self {
+        Self {
+            minted: Token::from_nano(self.minted.as_nano().saturating_add(other.minted.as_nano())),
+            burned: Token::from_nano(self.burned.as_nano().saturating_add(other.burned.as_nano())),
+        }
+    }
+}
+
+/// The totals a section accounts for: those of its own reward payouts and mints, and those it
+/// took over from the sections it split from.
+///
+/// As a section splits, its totals go to one of its halves only, its heir, so that the audit
+/// of the network counts them once.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SectionTotals {
+    /// Tokens created and retired by the section itself.
+    pub own: SupplyTotals,
+    /// Tokens created and retired by the sections it split from, if it is their heir.
+    pub inherited: SupplyTotals,
+}
+
+impl SectionTotals {
+    /// The half of the section with the `parent` prefix which takes over its totals as it
+    /// splits: the one whose prefix extends the parent's with a 0 bit.
+    pub fn heir_of(parent: Prefix) -> Prefix {
+        parent.pushed(false)
+    }
+
+    /// Returns whether the section with the given prefix is the heir of the one it split from.
+    pub fn is_heir(prefix: Prefix) -> bool {
+        !prefix.is_empty() && prefix == Self::heir_of(prefix.popped())
+    }
+
+    /// The totals which go to the half of our section with the `child` prefix as it splits: all
+    /// of them, as inherited, if it is the heir, none otherwise.
+    pub fn after_split(&self, child: Prefix) -> Self {
+        if Self::is_heir(child) {
+            Self {
+                own: SupplyTotals::default(),
+                inherited: self.sum(),
+            }
+        } else {
+            Self::default()
+        }
+    }
+
+    /// The own and inherited totals added up.
+    pub fn sum(&self) -> SupplyTotals {
+        self.own.saturating_add(&self.inherited)
+    }
+}
+
+/// The token totals of a section, as stated by its Elders.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SectionSupply {
+    /// The section prefix.
+    pub prefix: Prefix,
+    /// The section key, which the Elders sign the totals with.
+    pub section_key: bls::PublicKey,
+    /// The sum of the balances of all the wallets held by the section.
+    pub managed: Token,
+    /// Tokens created and retired by the section, and by the sections it split from if it is
+    /// their heir.
+    pub totals: SectionTotals,
+}
+
+/// The totals of a section, signed by one of its Elders with its share of the section key.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SectionSupplyShare {
+    /// The totals.
+    pub supply: SectionSupply,
+    /// The public key set of the section.
+    pub public_key_set: PublicKeySet,
+    /// The Elder's signature share over the totals.
+    pub signature_share: SignatureShare,
+}
+
+impl SectionSupplyShare {
+    /// Returns true if the share is a valid signature over the totals, by the section key.
+    pub fn is_valid(&self) -> bool {
+        if self.public_key_set.public_key() != self.supply.section_key {
+            return false;
+        }
+        match utils::serialise(&self.supply) {
+            Ok(bytes) => self
+                .public_key_set
+                .public_key_share(self.signature_share.index)
+                .verify(&self.signature_share.share, bytes),
+            Err(_) => false,
+        }
+    }
+}
+
+/// The totals of a section, signed by the section key.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedSectionSupply {
+    /// The totals.
+    pub supply: SectionSupply,
+    /// The section signature over the totals.
+    pub signature: bls::Signature,
+}
+
+impl SignedSectionSupply {
+    /// Combines the shares of the Elders which signed the same totals as the first share.
+    ///
+    /// Fails with `NotEnoughSignatures` unless more shares than the threshold of the section key
+    /// set are valid.
+    pub fn combine(shares: &[SectionSupplyShare]) -> Result<Self> {
+        let first = shares.first().ok_or(Error::NotEnoughSignatures(1))?;
+        let agreeing: Vec<_> = shares
+            .iter()
+            .filter(|share| {
+                share.supply == first.supply
+                    && share.public_key_set == first.public_key_set
+                    && share.is_valid()
+            })
+            .map(|share| (share.signature_share.index, &share.signature_share.share))
+            .collect();
+
+        let required = first.public_key_set.threshold() + 1;
+        if agreeing.len() < required {
+            return Err(Error::NotEnoughSignatures(required));
+        }
+
+        let signature = first
+            .public_key_set
+            .combine_signatures(agreeing)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok(Self {
+            supply: first.supply.clone(),
+            signature,
+        })
+    }
+
+    /// Verifies that the section key signed the totals.
+    pub fn verify(&self) -> Result<()> {
+        let bytes = utils::serialise(&self.supply)?;
+        if self.supply.section_key.verify(&self.signature, bytes) {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    fn supply_shares(sk_set: &bls::SecretKeySet, managed: u64) -> Result<Vec<SectionSupplyShare>> {
+        let supply = SectionSupply {
+            prefix: Prefix::default(),
+            section_key: sk_set.public_keys().public_key(),
+            managed: Token::from_nano(managed),
+            totals: SectionTotals::default(),
+        };
+        let bytes = utils::serialise(&supply)?;
+        Ok((0..3)
+            .map(|index| SectionSupplyShare {
+                supply: supply.clone(),
+                public_key_set: sk_set.public_keys(),
+                signature_share: SignatureShare {
+                    index,
+                    share: sk_set.secret_key_share(index).sign(&bytes),
+                },
+            })
+            .collect())
+    }
+
+    #[test]
+    fn combines_shares_into_section_signature() -> Result<()> {
+        let sk_set = bls::SecretKeySet::random(1, &mut rand::thread_rng());
+        let shares = supply_shares(&sk_set, 10)?;
+
+        let signed = SignedSectionSupply::combine(&shares)?;
+        signed.verify()?;
+        assert_eq!(signed.supply.managed, Token::from_nano(10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_too_few_agreeing_shares() -> Result<()> {
+        let sk_set = bls::SecretKeySet::random(1, &mut rand::thread_rng());
+        let mut shares = supply_shares(&sk_set, 10)?;
+        // the other Elders state different totals
+        shares.truncate(1);
+        shares.extend(supply_shares(&sk_set, 20)?.into_iter().skip(1));
+
+        assert!(matches!(
+            SignedSectionSupply::combine(&shares),
+            Err(Error::NotEnoughSignatures(2))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn totals_go_to_the_heir_on_split() {
+        let parent = Prefix::default().pushed(true);
+        let totals = SectionTotals {
+            own: SupplyTotals {
+                minted: Token::from_nano(10),
+                burned: Token::from_nano(2),
+            },
+            inherited: SupplyTotals {
+                minted: Token::from_nano(5),
+                burned: Token::zero(),
+            },
+        };
+
+        let heir = SectionTotals::heir_of(parent);
+        assert_eq!(heir, parent.pushed(false));
+        assert!(SectionTotals::is_heir(heir));
+        assert!(!SectionTotals::is_heir(heir.sibling()));
+        assert!(!SectionTotals::is_heir(Prefix::default()));
+
+        assert_eq!(
+            totals.after_split(heir),
+            SectionTotals {
+                own: SupplyTotals::default(),
+                inherited: SupplyTotals {
+                    minted: Token::from_nano(15),
+                    burned: Token::from_nano(2),
+                },
+            }
+        );
+        assert_eq!(totals.after_split(heir.sibling()), SectionTotals::default());
+    }
+}
@@ -8,12 +8,13 @@
 // Software.
 
 use super::{
-    keys::{PublicKey, Signature, SignatureShare},
+    keys::{Keypair, PublicKey, Signature, SignatureShare},
     token::Token,
     utils, Error, Result, SectionElders,
 };
 use bls::PublicKeySet;
 use crdts::Dot;
+use secured_linked_list::SecuredLinkedList;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug, Display, Formatter};
 use tiny_keccak::{Hasher, Sha3};
@@ -106,12 +107,28 @@ impl Debit {
 
     ///
     pub fn credit_id(&self) -> Result<CreditId> {
-        let id_bytes = &utils::serialise(&self.id)?;
+        Ok(Self::hash(&utils::serialise(&self.id)?))
+    }
+
+    /// Get the id of the credit at `index` in the batch of a batched transfer,
+    /// i.e. the hash of the DebitId along with the index.
+    pub fn batch_credit_id(&self, index: usize) -> Result<CreditId> {
+        Ok(Self::hash(&utils::serialise(&(self.id, index as u64))?))
+    }
+
+    /// Get the id of the credit refunding an escrowed transfer to its sender,
+    /// i.e. the hash of the DebitId along with the condition of the escrow,
+    /// which binds the condition to the signatures over the refund.
+    pub fn refund_credit_id(&self, condition: &TransferCondition) -> Result<CreditId> {
+        Ok(Self::hash(&utils::serialise(&(self.id, condition))?))
+    }
+
+    fn hash(bytes: &[u8]) -> CreditId {
         let mut hasher = Sha3::v256();
         let mut output = [0; 32];
-        hasher.update(&id_bytes);
+        hasher.update(bytes);
         hasher.finalize(&mut output);
-        Ok(output)
+        output
     }
 }
 
@@ -152,6 +169,12 @@ pub struct ActorHistory {
     pub credits: Vec<CreditAgreementProof>,
     /// All the debits.
     pub debits: Vec<TransferAgreementProof>,
+    /// The escrowed debits released to their recipients.
+    pub escrows_released: Vec<EscrowReleased>,
+    /// The escrowed debits refunded to the actor.
+    pub escrows_refunded: Vec<EscrowRefunded>,
+    /// The payouts made, when the actor is the faucet of a test network.
+    pub faucet_payouts: Vec<FaucetPaidOut>,
 }
 
 impl ActorHistory {
@@ -160,6 +183,9 @@ impl ActorHistory {
         Self {
             credits: vec![],
             debits: vec![],
+            escrows_released: vec![],
+            escrows_refunded: vec![],
+            faucet_payouts: vec![],
         }
     }
 
@@ -225,6 +251,156 @@ impl CreditAgreementProof {
     }
 }
 
+/// A self-contained receipt of a payment, which a third party can verify offline.
+///
+/// It holds the proof of the credit, along with the chain of section keys leading
+/// from the genesis key to the key of the section which validated the debit.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct PaymentReceipt {
+    /// The proof of the credit to the recipient.
+    pub credit_proof: CreditAgreementProof,
+    /// The chain of section keys proving the key of the debiting replicas.
+    pub section_chain: SecuredLinkedList,
+}
+
+impl PaymentReceipt {
+    /// Get the amount paid.
+    pub fn amount(&self) -> Token {
+        self.credit_proof.amount()
+    }
+
+    /// Get the recipient of the payment.
+    pub fn recipient(&self) -> PublicKey {
+        self.credit_proof.recipient()
+    }
+
+    /// Verify the receipt against the section keys the verifier trusts, f.ex. the genesis key.
+    ///
+    /// A trusted key must be the key of the debiting replicas, or one of its ancestors,
+    /// since only those vouch for it.
+    pub fn verify<'a, I>(&self, trusted_keys: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a bls::PublicKey>,
+    {
+        let replicas_key = self.credit_proof.debiting_replicas_keys.public_key();
+        let credit_bytes = utils::serialise(&self.credit_proof.signed_credit)?;
+        PublicKey::Bls(replicas_key)
+            .verify(&self.credit_proof.debiting_replicas_sig, &credit_bytes)?;
+
+        let proof_chain = self
+            .section_chain
+            .minimize(vec![self.section_chain.root_key(), &replicas_key])
+            .map_err(|_| Error::UntrustedSectionChain)?;
+        if proof_chain.last_key() != &replicas_key || !proof_chain.check_trust(trusted_keys) {
+            return Err(Error::UntrustedSectionChain);
+        }
+        Ok(())
+    }
+}
+
+/// The condition on which the Replicas of the sender hold a transfer in escrow.
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct TransferCondition {
+    /// The Sha3-256 hash of the secret which releases the credit to the recipient.
+    pub hash_lock: [u8; 32],
+    /// The time, in seconds since the Unix epoch, after which the sender can be refunded instead.
+    pub deadline: u64,
+}
+
+impl TransferCondition {
+    /// A condition released by the given secret, until the deadline.
+    pub fn new(secret: &[u8], deadline: u64) -> Self {
+        Self {
+            hash_lock: Debit::hash(secret),
+            deadline,
+        }
+    }
+
+    /// Returns true if the secret is a preimage of the hash lock.
+    pub fn is_unlocked_by(&self, secret: &[u8]) -> bool {
+        Debit::hash(secret) == self.hash_lock
+    }
+
+    /// Returns true if the deadline has passed at the given time.
+    pub fn has_expired(&self, now: u64) -> bool {
+        now >= self.deadline
+    }
+}
+
+/// The escrow of a conditional transfer.
+#[derive(Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Escrow {
+    /// The condition for releasing the credit to the recipient.
+    pub condition: TransferCondition,
+    /// The credit back to the sender, signed by the sender,
+    /// in case the deadline passes without the credit being released.
+    pub refund: SignedCredit,
+}
+
+impl Escrow {
+    /// The credit held in this escrow, as the Replicas sign it when validating the transfer.
+    pub fn holding<'a>(&'a self, credit: &'a SignedCredit) -> HeldCredit<'a> {
+        HeldCredit {
+            credit,
+            escrow: self,
+        }
+    }
+}
+
+/// A credit bound to its escrow. The Replicas sign this instead of the credit when
+/// validating an escrowed transfer, so that the signature does not credit the recipient
+/// until the escrow is settled.
+#[derive(Serialize, Debug)]
+pub struct HeldCredit<'a> {
+    /// The credit to the recipient.
+    pub credit: &'a SignedCredit,
+    /// The escrow holding the credit.
+    pub escrow: &'a Escrow,
+}
+
+/// The settlement of a transfer held in escrow.
+#[derive(Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum EscrowSettlement {
+    /// The credit is released to the recipient by the secret of the condition.
+    Release {
+        /// The id of the escrowed transfer.
+        id: DebitId,
+        /// The secret unlocking the condition.
+        secret: Vec<u8>,
+    },
+    /// The credit is refunded to the sender after the deadline.
+    Refund(DebitId),
+}
+
+impl EscrowSettlement {
+    /// Get the id of the escrowed transfer.
+    pub fn id(&self) -> DebitId {
+        match self {
+            Self::Release { id, .. } | Self::Refund(id) => *id,
+        }
+    }
+}
+
+/// A Replica's approval of the settlement of an escrow,
+/// i.e. its share of the signature over the credit the escrow settles to.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct EscrowSettlementShare {
+    /// The settlement.
+    pub settlement: EscrowSettlement,
+    /// Replica signature over the settled credit.
+    pub credit_sig: SignatureShare,
+}
+
+/// A Replica's approval of a payout from the faucet of a test network,
+/// i.e. its share of the signature over the credit to the recipient.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FaucetPayoutShare {
+    /// The payout, signed by its recipient when requesting it.
+    pub payout: SignedCredit,
+    /// Replica signature over the payout.
+    pub credit_sig: SignatureShare,
+}
+
 /// The aggregated Replica signatures of the Actor debit cmd.
 #[derive(Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TransferAgreementProof {
@@ -236,6 +412,10 @@ pub struct TransferAgreementProof {
     pub debit_sig: Signature,
     /// Quorum of Replica sigs over the credit.
     pub credit_sig: Signature,
+    /// Further credits of a batched transfer, along with the quorum of Replica sigs over each.
+    pub batch: Vec<(SignedCredit, Signature)>,
+    /// The escrow of a conditional transfer. The credit sig is then over the held credit.
+    pub escrow: Option<Escrow>,
     /// PublicKeySet of the replica when it validated the transfer.
     pub debiting_replicas_keys: ReplicaPublicKeySet,
 }
@@ -246,7 +426,7 @@ impl TransferAgreementProof {
         self.signed_debit.id()
     }
 
-    /// Get the amount of this transfer
+    /// Get the amount of this transfer, i.e. the sum of all its credits.
     pub fn amount(&self) -> Token {
         self.signed_debit.amount()
     }
@@ -256,17 +436,42 @@ impl TransferAgreementProof {
         self.signed_debit.sender()
     }
 
-    /// Get the recipient of this transfer
+    /// Get the recipient of this transfer, or of its first credit if batched.
     pub fn recipient(&self) -> PublicKey {
         self.signed_credit.recipient()
     }
 
+    /// Returns true if the debit pays several credits.
+    pub fn is_batch(&self) -> bool {
+        !self.batch.is_empty()
+    }
+
+    /// Returns true if the credit is held in escrow until a condition is met.
+    pub fn is_escrowed(&self) -> bool {
+        self.escrow.is_some()
+    }
+
     /// Get the PublicKeySet of the replica that validated this transfer
     pub fn replica_keys(&self) -> ReplicaPublicKeySet {
         self.debiting_replicas_keys.clone()
     }
 
+    /// Get the credit an escrowed transfer settles to, i.e. the credit to the recipient
+    /// on release, or the refund to the sender. The Replicas sign it only on settlement.
+    pub fn settled_credit(&self, settlement: &EscrowSettlement) -> Option<&SignedCredit> {
+        if settlement.id() != self.id() {
+            return None;
+        }
+        let escrow = self.escrow.as_ref()?;
+        match settlement {
+            EscrowSettlement::Release { .. } => Some(&self.signed_credit),
+            EscrowSettlement::Refund(_) => Some(&escrow.refund),
+        }
+    }
+
     /// Get the corresponding credit agreement proof.
+    /// For an escrowed transfer, the sig is over the held credit, so the proof does not credit
+    /// the recipient. The credit proof is then given by the settlement of the escrow.
     pub fn credit_proof(&self) -> CreditAgreementProof {
         CreditAgreementProof {
             signed_credit: self.signed_credit.clone(),
@@ -274,17 +479,33 @@ impl TransferAgreementProof {
             debiting_replicas_keys: self.replica_keys(),
         }
     }
+
+    /// Get the credit agreement proofs of all the credits, for propagation to each recipient.
+    /// As with `credit_proof`, they don't credit the recipient of an escrowed transfer.
+    pub fn credit_proofs(&self) -> Vec<CreditAgreementProof> {
+        let batch = self
+            .batch
+            .iter()
+            .map(|(signed_credit, sig)| CreditAgreementProof {
+                signed_credit: signed_credit.clone(),
+                debiting_replicas_sig: sig.clone(),
+                debiting_replicas_keys: self.replica_keys(),
+            });
+        Some(self.credit_proof()).into_iter().chain(batch).collect()
+    }
 }
 
 impl Debug for TransferAgreementProof {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "TransferAgreementProof {{ signed_debit: {:?}, signed_credit: {:?}, debit_sig: {:?}, credit_sig: {:?}, debiting_replicas_keys: PkSet {{ public_key: {:?} }} }}",
+            "TransferAgreementProof {{ signed_debit: {:?}, signed_credit: {:?}, debit_sig: {:?}, credit_sig: {:?}, batch: {:?}, escrow: {:?}, debiting_replicas_keys: PkSet {{ public_key: {:?} }} }}",
             self.signed_debit,
             self.signed_credit,
             self.debit_sig,
             self.credit_sig,
+            self.batch,
+            self.escrow,
             self.debiting_replicas_keys.public_key()
         )
     }
@@ -297,6 +518,10 @@ pub struct SignedTransfer {
     pub debit: SignedDebit,
     /// The credit.
     pub credit: SignedCredit,
+    /// Further credits paid by the debit, for batched transfers.
+    pub batch: Vec<SignedCredit>,
+    /// The escrow of a conditional transfer.
+    pub escrow: Option<Escrow>,
 }
 
 impl SignedTransfer {
@@ -481,6 +706,40 @@ impl SignedTransferShare {
     pub fn actors(&self) -> &PublicKeySet {
         &self.actors
     }
+
+    /// Verifies the signature shares over the debit and credit, against the key share of the signer.
+    pub fn verify(&self) -> bool {
+        let key_share = self.actors.public_key_share(self.share_index());
+        let valid_debit = utils::serialise(&self.debit.debit)
+            .map(|bytes| key_share.verify(&self.debit.actor_signature.share, bytes))
+            .unwrap_or(false);
+        let valid_credit = utils::serialise(&self.credit.credit)
+            .map(|bytes| key_share.verify(&self.credit.actor_signature.share, bytes))
+            .unwrap_or(false);
+        valid_debit && valid_credit
+    }
+
+    /// Signs the same transfer with another share of the actors' key set.
+    /// This is how co-owners of a multisig wallet approve a transfer proposed by one of them,
+    /// and only needs their keypair share, so it can be done offline.
+    pub fn co_sign(&self, keypair: &Keypair) -> Result<Self> {
+        match keypair {
+            Keypair::BlsShare(share) if share.public_key_set == self.actors => (),
+            _ => return Err(Error::InvalidOwnerNotPublicKeySet),
+        }
+        if !self.verify() {
+            return Err(Error::InvalidSignature);
+        }
+        let debit = SignedDebit {
+            debit: self.debit.debit.clone(),
+            actor_signature: keypair.sign(&utils::serialise(&self.debit.debit)?),
+        };
+        let credit = SignedCredit {
+            credit: self.credit.credit.clone(),
+            actor_signature: keypair.sign(&utils::serialise(&self.credit.credit)?),
+        };
+        Self::new(debit.as_share()?, credit.as_share()?, self.actors.clone())
+    }
 }
 
 impl Debug for SignedTransferShare {
@@ -582,6 +841,73 @@ pub enum ReplicaEvent {
     /// The event raised when
     /// PropagateTransfer cmd has been successful.
     TransferPropagated(TransferPropagated),
+    /// The event raised when
+    /// this Replica has signed the settlement of an escrow.
+    EscrowSettlementProposed(EscrowSettlementProposed),
+    /// The event raised when
+    /// ReleaseEscrow cmd has been successful.
+    EscrowReleased(EscrowReleased),
+    /// The event raised when
+    /// RefundEscrow cmd has been successful.
+    EscrowRefunded(EscrowRefunded),
+    /// The event raised when
+    /// this Replica has signed a payout from the faucet.
+    FaucetPayoutProposed(FaucetPayoutProposed),
+    /// The event raised when
+    /// RequestFaucetPayout cmd has been successful.
+    FaucetPaidOut(FaucetPaidOut),
+}
+
+/// The debiting Replica event raised when
+/// it has signed the settlement of an escrow.
+/// The Replica never signs a different settlement of the same escrow.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct EscrowSettlementProposed {
+    /// The settlement signed by the Replica.
+    pub settlement: EscrowSettlement,
+}
+
+/// The debiting Replica event raised when
+/// ReleaseEscrow cmd has been successful, i.e. the Replicas agreed on the release.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct EscrowReleased {
+    /// The id of the escrowed transfer.
+    pub id: DebitId,
+    /// The secret which released the credit to the recipient.
+    pub secret: Vec<u8>,
+    /// The credit to the recipient, with the quorum of Replica sigs over it.
+    pub credit_proof: CreditAgreementProof,
+}
+
+/// The debiting Replica event raised when
+/// RefundEscrow cmd has been successful, i.e. the Replicas agreed on the refund.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct EscrowRefunded {
+    /// The id of the escrowed transfer.
+    pub id: DebitId,
+    /// The refund to the sender, with the quorum of Replica sigs over it.
+    pub credit_proof: CreditAgreementProof,
+}
+
+/// The debiting Replica event raised when
+/// it has signed a payout from the faucet of a test network.
+/// The payout counts towards the limits of the faucet from then on, whether or not it is made.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FaucetPayoutProposed {
+    /// The payout signed by the Replica.
+    pub payout: SignedCredit,
+    /// The time of the Replica when signing, in seconds since the Unix epoch.
+    pub time: u64,
+}
+
+/// The debiting Replica event raised when
+/// RequestFaucetPayout cmd has been successful, i.e. the Replicas agreed on the payout.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FaucetPaidOut {
+    /// The payout to the recipient, with the quorum of Replica sigs over it.
+    pub credit_proof: CreditAgreementProof,
+    /// The time of the Replica when paying out, in seconds since the Unix epoch.
+    pub time: u64,
 }
 
 /// The debiting Replica event raised when
@@ -630,6 +956,10 @@ pub struct TransferValidated {
     pub replica_debit_sig: SignatureShare,
     /// Replica signature over the signed credit.
     pub replica_credit_sig: SignatureShare,
+    /// Further credits of a batched transfer, along with the Replica signature over each.
+    pub batch: Vec<(SignedCredit, SignatureShare)>,
+    /// The escrow of a conditional transfer. The credit sig is then over the held credit.
+    pub escrow: Option<Escrow>,
     /// The PK Set of the Replicas
     pub replicas: PublicKeySet,
 }
@@ -638,11 +968,13 @@ impl Debug for TransferValidated {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "TransferValidated {{ signed_debit: {:?}, signed_credit: {:?}, replica_debit_sig: {:?}, replica_credit_sig: {:?}, replicas: PkSet {{ public_key: {:?} }} }}",
+            "TransferValidated {{ signed_debit: {:?}, signed_credit: {:?}, replica_debit_sig: {:?}, replica_credit_sig: {:?}, batch: {:?}, escrow: {:?}, replicas: PkSet {{ public_key: {:?} }} }}",
             self.signed_debit,
             self.signed_credit,
             self.replica_debit_sig,
             self.replica_credit_sig,
+            self.batch,
+            self.escrow,
             self.replicas.public_key()
         )
     }
@@ -748,3 +1080,95 @@ impl Debug for KnownGroupAdded {
 /// Notification of a credit sent to a recipient.
 #[derive(Eq, PartialEq, Clone, Serialize, Deserialize, Debug)]
 pub struct CreditNotification(pub CreditAgreementProof);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::SecretKeySet;
+    use rand::rngs::OsRng;
+
+    // Signs as a section of one Elder.
+    fn section_sign(section: &SecretKeySet, bytes: &[u8]) -> Result<bls::Signature> {
+        let share = section.secret_key_share(0).sign(bytes);
+        section
+            .public_keys()
+            .combine_signatures(vec![(0, &share)])
+            .map_err(|_| Error::InvalidSignature)
+    }
+
+    fn signed_child(chain: &mut SecuredLinkedList, parent: &SecretKeySet) -> Result<SecretKeySet> {
+        let child = SecretKeySet::random(0, &mut OsRng);
+        let child_key = child.public_keys().public_key();
+        let signature = section_sign(parent, &utils::serialise(&child_key)?)?;
+        chain
+            .insert(&parent.public_keys().public_key(), child_key, signature)
+            .map_err(|e| Error::Serialisation(e.to_string()))?;
+        Ok(child)
+    }
+
+    fn credit_proof(section: &SecretKeySet) -> Result<CreditAgreementProof> {
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let credit = Credit {
+            id: Default::default(),
+            recipient: Keypair::new_ed25519(&mut OsRng).public_key(),
+            amount: Token::from_nano(10),
+            msg: "".to_string(),
+        };
+        let signed_credit = SignedCredit {
+            actor_signature: keypair.sign(&utils::serialise(&credit)?),
+            credit,
+        };
+        let debiting_replicas_sig =
+            Signature::Bls(section_sign(section, &utils::serialise(&signed_credit)?)?);
+        Ok(CreditAgreementProof {
+            signed_credit,
+            debiting_replicas_sig,
+            debiting_replicas_keys: section.public_keys(),
+        })
+    }
+
+    #[test]
+    fn payment_receipt_is_verified_against_ancestor_keys() -> Result<()> {
+        let genesis = SecretKeySet::random(0, &mut OsRng);
+        let mut section_chain = SecuredLinkedList::new(genesis.public_keys().public_key());
+        let section = signed_child(&mut section_chain, &genesis)?;
+        let successor = signed_child(&mut section_chain, &section)?;
+
+        let receipt = PaymentReceipt {
+            credit_proof: credit_proof(&section)?,
+            section_chain,
+        };
+        receipt.verify(vec![&genesis.public_keys().public_key()])?;
+        receipt.verify(vec![&section.public_keys().public_key()])?;
+
+        // A later key doesn't vouch for its ancestors.
+        assert!(matches!(
+            receipt.verify(vec![&successor.public_keys().public_key()]),
+            Err(Error::UntrustedSectionChain)
+        ));
+        // Nor does the key of another network.
+        let foreign = SecretKeySet::random(0, &mut OsRng);
+        assert!(matches!(
+            receipt.verify(vec![&foreign.public_keys().public_key()]),
+            Err(Error::UntrustedSectionChain)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn payment_receipt_with_forged_credit_fails() -> Result<()> {
+        let genesis = SecretKeySet::random(0, &mut OsRng);
+        let mut section_chain = SecuredLinkedList::new(genesis.public_keys().public_key());
+        let section = signed_child(&mut section_chain, &genesis)?;
+
+        let mut receipt = PaymentReceipt {
+            credit_proof: credit_proof(&section)?,
+            section_chain,
+        };
+        receipt.credit_proof.signed_credit.credit.amount = Token::from_nano(1000);
+        assert!(receipt
+            .verify(vec![&genesis.public_keys().public_key()])
+            .is_err());
+        Ok(())
+    }
+}
@@ -13,7 +13,7 @@ mod errors;
 mod url_parts;
 mod xorurl_media_types;
 
-use crate::types::register;
+use crate::types::{register, ChunkAddress};
 pub use errors::{Error, Result};
 use log::{info, trace, warn};
 use multibase::{decode as base_decode, encode as base_encode, Base};
@@ -24,9 +24,12 @@ use url_parts::SafeUrlParts;
 use xor_name::{XorName, XOR_NAME_LEN};
 use xorurl_media_types::{MEDIA_TYPE_CODES, MEDIA_TYPE_STR};
 
-/// Type tag to use for the NrsMapContainer stored on Sequence
+/// Type tag to use for the NrsMapContainer stored on Register
 pub const NRS_MAP_TYPE_TAG: u64 = 1_500;
 
+/// Type tag to use for the FilesContainer stored on Register
+pub const FILES_CONTAINER_TYPE_TAG: u64 = 1_100;
+
 /// Default base encoding used for XOR URLs
 pub const DEFAULT_XORURL_BASE: XorUrlBase = XorUrlBase::Base32z;
 
@@ -441,7 +444,7 @@ impl SafeUrl {
             hashed_name,
             Some(&parts.public_name),
             NRS_MAP_TYPE_TAG,
-            SafeDataType::PublicSequence,
+            SafeDataType::PublicRegister,
             SafeContentType::NrsMapContainer,
             Some(&parts.path),
             Some(parts.sub_names_vec),
@@ -590,6 +593,19 @@ impl SafeUrl {
         self.xor_name
     }
 
+    /// Attempts to create a Blob address, i.e. the address of its head chunk.
+    pub fn blob_address(&self) -> Result<ChunkAddress> {
+        let name = self.xor_name;
+        match self.data_type {
+            SafeDataType::PrivateBlob => Ok(ChunkAddress::Private(name)),
+            SafeDataType::PublicBlob => Ok(ChunkAddress::Public(name)),
+            _ => Err(Error::InvalidInput(format!(
+                "Attempting to create a blob address for wrong datatype {}",
+                self.data_type
+            ))),
+        }
+    }
+
     /// Attempts to create a Register address.
     pub fn register_address(&self) -> Result<register::Address> {
         let name = self.xor_name;
@@ -1403,6 +1419,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_safeurl_blob_address() -> Result<()> {
+        let xor_name = XorName(*b"12345678901234567890123456789012");
+        let xorurl = SafeUrl::encode_blob(xor_name, SafeContentType::Raw, DEFAULT_XORURL_BASE)?;
+        let public = SafeUrl::from_url(&xorurl)?;
+        assert_eq!(public.blob_address()?, ChunkAddress::Public(xor_name));
+
+        let private = SafeUrl::new(
+            xor_name,
+            None,
+            0,
+            SafeDataType::PrivateBlob,
+            SafeContentType::Raw,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        assert_eq!(private.blob_address()?, ChunkAddress::Private(xor_name));
+
+        let register = SafeUrl::new(
+            xor_name,
+            None,
+            1_100,
+            SafeDataType::PublicRegister,
+            SafeContentType::FilesContainer,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        assert!(register.blob_address().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_safeurl_base32z_encoding() -> Result<()> {
         let xor_name = XorName(*b"12345678901234567890123456789012");
@@ -1770,7 +1823,7 @@ mod tests {
     fn test_safeurl_to_string() -> Result<()> {
         // These two are equivalent.  ie, the xorurl is the result of nrs.to_xorurl_string()
         let nrsurl = "safe://my.sub.domain/path/my%20dir/my%20file.txt?this=that&this=other&color=blue&v=5&name=John+Doe#somefragment";
-        let xorurl = "safe://my.sub.hyryygbmk9cke7k99tyhp941od8q375wxgaqeyiag8za1jnpzbw9pb61sccn7a/path/my%20dir/my%20file.txt?this=that&this=other&color=blue&v=5&name=John+Doe#somefragment";
+        let xorurl = "safe://my.sub.hyryygy5k9cke7k99tyhp941od8q375wxgaqeyiag8za1jnpzbw9pb61sccn7a/path/my%20dir/my%20file.txt?this=that&this=other&color=blue&v=5&name=John+Doe#somefragment";
 
         let nrs = SafeUrl::from_url(nrsurl)?;
         let xor = SafeUrl::from_url(xorurl)?;